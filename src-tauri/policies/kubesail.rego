@@ -0,0 +1,46 @@
+# Default policies installed into the app data directory on first use.
+# Edit them there, or point the policy gate at your own directory.
+package main
+
+import rego.v1
+
+workload_kinds := {"Deployment", "StatefulSet", "DaemonSet", "Job", "ReplicaSet"}
+
+pod_spec := input.spec.template.spec if workload_kinds[input.kind]
+
+pod_spec := input.spec.jobTemplate.spec.template.spec if input.kind == "CronJob"
+
+pod_spec := input.spec if input.kind == "Pod"
+
+deny contains msg if {
+	some container in pod_spec.containers
+	endswith(container.image, ":latest")
+	msg := sprintf("%s/%s: container %q uses the :latest tag", [input.kind, input.metadata.name, container.name])
+}
+
+deny contains msg if {
+	some container in pod_spec.containers
+	not contains(container.image, ":")
+	not contains(container.image, "@")
+	msg := sprintf("%s/%s: container %q has no image tag", [input.kind, input.metadata.name, container.name])
+}
+
+deny contains msg if {
+	some container in pod_spec.containers
+	container.securityContext.privileged
+	msg := sprintf("%s/%s: container %q is privileged", [input.kind, input.metadata.name, container.name])
+}
+
+warn contains msg if {
+	some container in pod_spec.containers
+	not container.resources.limits.memory
+	msg := sprintf("%s/%s: container %q has no memory limit", [input.kind, input.metadata.name, container.name])
+}
+
+warn contains msg if {
+	some container in pod_spec.containers
+	not container.readinessProbe
+	input.kind != "Job"
+	input.kind != "CronJob"
+	msg := sprintf("%s/%s: container %q has no readiness probe", [input.kind, input.metadata.name, container.name])
+}
@@ -0,0 +1,62 @@
+use anyhow::Result;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::types::AuditEntry;
+
+const AUDIT_LOG_FILE: &str = "audit.log";
+
+/// Serializes appends so concurrent sessions don't interleave lines
+static AUDIT_LOCK: Mutex<()> = Mutex::new(());
+
+fn audit_log_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf> {
+    let dir = app.path().app_data_dir()?;
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir.join(AUDIT_LOG_FILE))
+}
+
+/// Append an entry to the audit log, one JSON object per line
+pub fn record<R: Runtime>(app: &AppHandle<R>, entry: &AuditEntry) -> Result<()> {
+    let path = audit_log_path(app)?;
+    let line = serde_json::to_string(entry)?;
+
+    let _guard = AUDIT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", line)?;
+    Ok(())
+}
+
+/// Wait for any append in progress, then sync the log to disk so nothing is lost at exit
+pub fn flush<R: Runtime>(app: &AppHandle<R>) -> Result<()> {
+    let path = audit_log_path(app)?;
+    let _guard = AUDIT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    match std::fs::OpenOptions::new().append(true).open(path) {
+        Ok(file) => Ok(file.sync_all()?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// The most recent `limit` entries, newest first
+pub fn read_entries<R: Runtime>(app: &AppHandle<R>, limit: usize) -> Result<Vec<AuditEntry>> {
+    let path = audit_log_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let file = std::fs::File::open(path)?;
+    let mut entries: Vec<AuditEntry> = BufReader::new(file)
+        .lines()
+        .map_while(|line| line.ok())
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect();
+
+    entries.reverse();
+    entries.truncate(limit);
+    Ok(entries)
+}
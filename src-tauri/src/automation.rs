@@ -0,0 +1,428 @@
+use anyhow::{Context, Result};
+use k8s_openapi::api::batch::v1::{CronJob, Job, JobSpec};
+use k8s_openapi::api::core::v1::{Container, PodSpec, PodTemplateSpec};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::api::{Api, PostParams};
+use kube::{Client, ResourceExt};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::{oneshot, Mutex};
+use uuid::Uuid;
+
+use crate::database::ConnectionManager;
+use crate::kube::rollout::check_rollout;
+use crate::portforward::PortForwardManager;
+use crate::types::{AutomationRunResult, AutomationStepResult};
+
+const POLL_INTERVAL_SECS: u64 = 2;
+
+/// Used for rollout and job waits that don't set `timeout_secs`
+const DEFAULT_TIMEOUT_SECS: u64 = 600;
+
+/// A runbook: steps run in order, stopping at the first failure unless the step allows it
+#[derive(Debug, Clone, Deserialize)]
+pub struct AutomationScript {
+    pub name: String,
+    #[serde(default)]
+    pub namespace: Option<String>,  // Default for steps that don't name one
+    pub steps: Vec<AutomationStep>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AutomationStep {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub namespace: Option<String>,
+    #[serde(default)]
+    pub continue_on_error: bool,
+    #[serde(flatten)]
+    pub action: AutomationAction,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "action", rename_all = "kebab-case")]
+pub enum AutomationAction {
+    Scale {
+        kind: String,  // Deployment or StatefulSet
+        resource: String,
+        replicas: i32,
+    },
+    WaitForRollout {
+        kind: String,  // Deployment, StatefulSet or DaemonSet
+        resource: String,
+        #[serde(default)]
+        timeout_secs: Option<u64>,
+    },
+    /// A Job from a CronJob's template, or from an image and command
+    RunJob {
+        #[serde(default)]
+        from_cronjob: Option<String>,
+        #[serde(default)]
+        image: Option<String>,
+        #[serde(default)]
+        command: Vec<String>,
+        #[serde(default = "default_true")]
+        wait: bool,
+        #[serde(default)]
+        timeout_secs: Option<u64>,
+    },
+    /// Left running after the script ends, like one started from the UI
+    PortForward {
+        resource_type: String,  // pod, service or deployment
+        resource: String,
+        local_port: u16,
+        remote_port: u16,
+    },
+    Sql {
+        connection_id: String,  // An open database connection
+        query: String,
+    },
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl AutomationAction {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Scale { .. } => "scale",
+            Self::WaitForRollout { .. } => "wait-for-rollout",
+            Self::RunJob { .. } => "run-job",
+            Self::PortForward { .. } => "port-forward",
+            Self::Sql { .. } => "sql",
+        }
+    }
+
+    /// Whether the step changes the cluster or a database; SQL is assumed to, since it isn't parsed
+    fn is_mutating(&self) -> bool {
+        !matches!(self, Self::WaitForRollout { .. } | Self::PortForward { .. })
+    }
+}
+
+impl AutomationScript {
+    /// Whether any step needs the protected-context confirmation
+    pub fn is_mutating(&self) -> bool {
+        self.steps.iter().any(|step| step.action.is_mutating())
+    }
+}
+
+/// Parse and check a script before anything runs, so a typo in step 5 doesn't strand steps 1-4
+pub fn parse_script(yaml: &str) -> Result<AutomationScript> {
+    let script: AutomationScript = serde_yaml::from_str(yaml).context("Invalid automation script")?;
+    if script.steps.is_empty() {
+        anyhow::bail!("Script '{}' has no steps", script.name);
+    }
+    for (index, step) in script.steps.iter().enumerate() {
+        let needs_namespace = !matches!(step.action, AutomationAction::Sql { .. });
+        if needs_namespace && step.namespace.as_ref().or(script.namespace.as_ref()).is_none() {
+            anyhow::bail!("Step {} needs a namespace, on the step or the script", index + 1);
+        }
+        if let AutomationAction::RunJob { from_cronjob, image, .. } = &step.action {
+            if from_cronjob.is_some() == image.is_some() {
+                anyhow::bail!("Step {}: run-job needs exactly one of from_cronjob and image", index + 1);
+            }
+        }
+    }
+    Ok(script)
+}
+
+/// Runs automation scripts in the background, reporting each step as it finishes
+pub struct AutomationManager {
+    runs: Arc<Mutex<HashMap<String, oneshot::Sender<()>>>>,
+}
+
+impl AutomationManager {
+    pub fn new() -> Self {
+        Self {
+            runs: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Start `yaml`, emitting `automation-step-{run_id}` with an
+    /// [`AutomationStepResult`] per step and `automation-complete-{run_id}`
+    /// with the [`AutomationRunResult`]
+    pub async fn start_run(&self, app: AppHandle, client: Client, script: AutomationScript) -> Result<String> {
+        let run_id = Uuid::new_v4().to_string();
+        let (cancel_tx, mut cancel_rx) = oneshot::channel::<()>();
+        self.runs.lock().await.insert(run_id.clone(), cancel_tx);
+
+        let runs = self.runs.clone();
+        let id = run_id.clone();
+        tokio::spawn(async move {
+            let step_event = format!("automation-step-{}", id);
+            let started = Instant::now();
+            let mut results = Vec::new();
+            let mut stopped = false;
+
+            for (index, step) in script.steps.iter().enumerate() {
+                let name = step.name.clone().unwrap_or_else(|| step.action.label().to_string());
+                if stopped {
+                    results.push(step_result(index, &name, step, "skipped", None, None, Duration::ZERO));
+                    continue;
+                }
+
+                let namespace = step.namespace.clone().or_else(|| script.namespace.clone()).unwrap_or_default();
+                let step_started = Instant::now();
+                let (outcome, cancelled) = tokio::select! {
+                    outcome = run_step(&app, &client, &namespace, &step.action) => (outcome, false),
+                    _ = &mut cancel_rx => (Err(anyhow::anyhow!("Cancelled")), true),
+                };
+                let elapsed = step_started.elapsed();
+                let result = match outcome {
+                    Ok(output) => step_result(index, &name, step, "succeeded", None, output, elapsed),
+                    Err(e) => {
+                        // Cancelling stops the run whatever the step allows
+                        stopped = cancelled || !step.continue_on_error;
+                        step_result(index, &name, step, "failed", Some(e.to_string()), None, elapsed)
+                    }
+                };
+                let _ = app.emit(&step_event, &result);
+                results.push(result);
+            }
+
+            let run = AutomationRunResult {
+                run_id: id.clone(),
+                name: script.name.clone(),
+                succeeded: !stopped,
+                steps: results,
+                duration_ms: started.elapsed().as_millis() as u64,
+            };
+            let _ = app.emit(&format!("automation-complete-{}", id), &run);
+            runs.lock().await.remove(&id);
+        });
+
+        Ok(run_id)
+    }
+
+    pub async fn stop_run(&self, run_id: &str) -> Result<()> {
+        if let Some(cancel) = self.runs.lock().await.remove(run_id) {
+            let _ = cancel.send(());
+        }
+        Ok(())
+    }
+}
+
+impl Default for AutomationManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn step_result(
+    index: usize,
+    name: &str,
+    step: &AutomationStep,
+    status: &str,
+    message: Option<String>,
+    output: Option<Value>,
+    duration: Duration,
+) -> AutomationStepResult {
+    AutomationStepResult {
+        index,
+        name: name.to_string(),
+        action: step.action.label().to_string(),
+        status: status.to_string(),
+        message,
+        output,
+        continue_on_error: step.continue_on_error,
+        duration_ms: duration.as_millis() as u64,
+    }
+}
+
+/// Run one step, returning whatever is worth showing next to it
+async fn run_step(app: &AppHandle, client: &Client, namespace: &str, action: &AutomationAction) -> Result<Option<Value>> {
+    match action {
+        AutomationAction::Scale { kind, resource, replicas } => {
+            match kind.as_str() {
+                "Deployment" => crate::kube::scale_deployment(client.clone(), namespace, resource, *replicas).await?,
+                "StatefulSet" => crate::kube::scale_statefulset(client.clone(), namespace, resource, *replicas).await?,
+                other => anyhow::bail!("Cannot scale a {}", other),
+            }
+            Ok(None)
+        }
+        AutomationAction::WaitForRollout { kind, resource, timeout_secs } => {
+            let started = Instant::now();
+            loop {
+                let status = check_rollout(client, kind, namespace, resource).await?;
+                if status.progress.done {
+                    return Ok(Some(Value::String(status.progress.message)));
+                }
+                if let Some(failure) = status.progress.failure {
+                    anyhow::bail!(failure);
+                }
+                let timeout = timeout_secs.or(status.deadline_secs).unwrap_or(DEFAULT_TIMEOUT_SECS);
+                if started.elapsed() >= Duration::from_secs(timeout) {
+                    anyhow::bail!("Rollout did not finish within {}s: {}", timeout, status.progress.message);
+                }
+                tokio::time::sleep(Duration::from_secs(POLL_INTERVAL_SECS)).await;
+            }
+        }
+        AutomationAction::RunJob { from_cronjob, image, command, wait, timeout_secs } => {
+            let job = match (from_cronjob, image) {
+                (Some(cronjob), _) => job_from_cronjob(client, namespace, cronjob).await?,
+                (None, Some(image)) => job_from_image(image, command),
+                (None, None) => anyhow::bail!("run-job needs from_cronjob or image"),
+            };
+            let jobs: Api<Job> = Api::namespaced(client.clone(), namespace);
+            let created = jobs.create(&PostParams::default(), &job).await?;
+            let name = created.name_any();
+            if !*wait {
+                return Ok(Some(Value::String(name)));
+            }
+            wait_for_job(&jobs, &name, timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS)).await?;
+            Ok(Some(Value::String(name)))
+        }
+        AutomationAction::PortForward { resource_type, resource, local_port, remote_port } => {
+            let info = app
+                .state::<PortForwardManager>()
+                .start_port_forward(resource_type, resource, namespace, *local_port, *remote_port)
+                .await?;
+            Ok(Some(serde_json::to_value(info)?))
+        }
+        AutomationAction::Sql { connection_id, query } => {
+            // The pool is a cheap handle; cloned out so a long query doesn't hold the manager lock
+            let pool = app
+                .state::<ConnectionManager>()
+                .read()
+                .await
+                .get(connection_id)
+                .map(|connection| connection.pool.clone())
+                .ok_or_else(|| anyhow::anyhow!("Connection not found: {}", connection_id))?;
+            let client = pool.get().await?;
+            let result = crate::database::queries::run_custom_query(&client, query).await?;
+            Ok(Some(serde_json::to_value(result)?))
+        }
+    }
+}
+
+/// Same as `kubectl create job --from=cronjob/<name>`
+async fn job_from_cronjob(client: &Client, namespace: &str, cronjob: &str) -> Result<Job> {
+    let cronjob = Api::<CronJob>::namespaced(client.clone(), namespace).get(cronjob).await?;
+    let template = cronjob
+        .spec
+        .as_ref()
+        .map(|spec| spec.job_template.clone())
+        .ok_or_else(|| anyhow::anyhow!("CronJob {} has no spec", cronjob.name_any()))?;
+
+    let mut annotations = template.metadata.as_ref().and_then(|m| m.annotations.clone()).unwrap_or_default();
+    annotations.insert("cronjob.kubernetes.io/instantiate".to_string(), "manual".to_string());
+    Ok(Job {
+        metadata: ObjectMeta {
+            generate_name: Some(format!("{}-manual-", cronjob.name_any())),
+            labels: template.metadata.as_ref().and_then(|m| m.labels.clone()),
+            annotations: Some(annotations),
+            ..Default::default()
+        },
+        spec: template.spec,
+        ..Default::default()
+    })
+}
+
+fn job_from_image(image: &str, command: &[String]) -> Job {
+    // Image name without registry or tag, e.g. "migrate" for "ghcr.io/acme/migrate:1.2"
+    let base = image.rsplit('/').next().unwrap_or(image).split([':', '@']).next().unwrap_or("job");
+    let base = base.replace(['_', '.'], "-");
+    Job {
+        metadata: ObjectMeta {
+            generate_name: Some(format!("automation-{}-", base)),
+            ..Default::default()
+        },
+        spec: Some(JobSpec {
+            backoff_limit: Some(0),
+            template: PodTemplateSpec {
+                metadata: None,
+                spec: Some(PodSpec {
+                    restart_policy: Some("Never".to_string()),
+                    containers: vec![Container {
+                        name: "job".to_string(),
+                        image: Some(image.to_string()),
+                        command: (!command.is_empty()).then(|| command.to_vec()),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }),
+            },
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+async fn wait_for_job(jobs: &Api<Job>, name: &str, timeout_secs: u64) -> Result<()> {
+    let started = Instant::now();
+    loop {
+        let status = jobs.get(name).await?.status.unwrap_or_default();
+        for condition in status.conditions.iter().flatten() {
+            if condition.status != "True" {
+                continue;
+            }
+            match condition.type_.as_str() {
+                "Complete" => return Ok(()),
+                "Failed" => anyhow::bail!(
+                    "Job {} failed: {}",
+                    name,
+                    condition.message.as_deref().or(condition.reason.as_deref()).unwrap_or("no reason given")
+                ),
+                _ => {}
+            }
+        }
+        if started.elapsed() >= Duration::from_secs(timeout_secs) {
+            anyhow::bail!("Job {} did not finish within {}s", name, timeout_secs);
+        }
+        tokio::time::sleep(Duration::from_secs(POLL_INTERVAL_SECS)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_script() {
+        let script = parse_script(
+            r#"
+name: Run migrations
+namespace: shop
+steps:
+  - action: scale
+    kind: Deployment
+    resource: worker
+    replicas: 0
+  - name: Migrate
+    action: run-job
+    from_cronjob: migrate
+    timeout_secs: 900
+  - action: wait-for-rollout
+    kind: Deployment
+    resource: api
+    continue_on_error: true
+  - action: sql
+    connection_id: shop-db
+    query: select count(*) from orders
+"#,
+        )
+        .unwrap();
+        assert_eq!(script.steps.len(), 4);
+        assert!(matches!(script.steps[0].action, AutomationAction::Scale { replicas: 0, .. }));
+        assert!(matches!(
+            script.steps[1].action,
+            AutomationAction::RunJob { wait: true, timeout_secs: Some(900), .. }
+        ));
+        assert!(script.steps[2].continue_on_error);
+        assert_eq!(script.steps[3].action.label(), "sql");
+        assert!(script.is_mutating());
+        assert!(!script.steps[2].action.is_mutating());
+
+        // No namespace anywhere, and an ambiguous job
+        let no_namespace = "name: x\nsteps:\n  - action: scale\n    kind: Deployment\n    resource: a\n    replicas: 1\n";
+        assert!(parse_script(no_namespace).is_err());
+        assert!(parse_script("name: x\nnamespace: a\nsteps:\n  - action: run-job\n").is_err());
+        assert!(parse_script("name: x\nsteps: []\n").is_err());
+    }
+}
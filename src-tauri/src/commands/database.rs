@@ -1,14 +1,18 @@
 use crate::database::{
-    queries, ConnectionManager, DatabaseConnection, DatabaseError, DbConnectionInfo, DbDatabase,
-    DbSchema, DbTable, DbColumn, QueryResult, QueryRequest, TableDataRequest,
+    dump, queries, supervisor, ConnectionManager, DatabaseConnection, DatabaseError, DbActivity,
+    DbConnectionInfo, DbDatabase, DbDumpResult, DbLock, DbSchema, DbTable, DbColumn, QueryResult, QueryRequest,
+    TableDataRequest,
 };
+use crate::kube::KubeClientManager;
 use crate::portforward::PortForwardManager;
 use std::sync::Arc;
-use tauri::State;
+use tauri::{AppHandle, State};
 
 /// Connect to a CloudNativePG database cluster
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn db_connect(
+    app: AppHandle,
     cluster_name: String,
     namespace: String,
     database: String,
@@ -40,8 +44,13 @@ pub async fn db_connect(
     let connection_id = info.connection_id.clone();
 
     // Store the connection
-    let mut manager = connection_manager.write().await;
-    manager.insert(connection_id.clone(), connection);
+    {
+        let mut manager = connection_manager.write().await;
+        manager.insert(connection_id.clone(), connection);
+    }
+
+    // Keep the connection alive across port-forward drops
+    supervisor::spawn_supervisor(app, connection_manager.inner().clone(), connection_id.clone());
 
     tracing::info!("Database connection created: {}", connection_id);
 
@@ -237,3 +246,91 @@ pub async fn db_version(
         .await
         .map_err(|e| format!("Failed to get database version: {}", e))
 }
+
+/// List backend sessions (pg_stat_activity)
+#[tauri::command]
+pub async fn db_list_activity(
+    connection_id: String,
+    connection_manager: State<'_, ConnectionManager>,
+) -> Result<Vec<DbActivity>, String> {
+    let manager = connection_manager.read().await;
+    let connection = manager
+        .get(&connection_id)
+        .ok_or_else(|| format!("Connection not found: {}", connection_id))?;
+
+    queries::list_activity(connection)
+        .await
+        .map_err(|e| format!("Failed to list activity: {}", e))
+}
+
+/// List held and awaited locks (pg_locks)
+#[tauri::command]
+pub async fn db_list_locks(
+    connection_id: String,
+    connection_manager: State<'_, ConnectionManager>,
+) -> Result<Vec<DbLock>, String> {
+    let manager = connection_manager.read().await;
+    let connection = manager
+        .get(&connection_id)
+        .ok_or_else(|| format!("Connection not found: {}", connection_id))?;
+
+    queries::list_locks(connection)
+        .await
+        .map_err(|e| format!("Failed to list locks: {}", e))
+}
+
+/// Terminate a backend session by PID
+#[tauri::command]
+pub async fn db_terminate_backend(
+    connection_id: String,
+    pid: i32,
+    connection_manager: State<'_, ConnectionManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<bool, String> {
+    tracing::info!("Terminate backend request: {} on {}", pid, connection_id);
+
+    let manager = connection_manager.read().await;
+    let connection = manager
+        .get(&connection_id)
+        .ok_or_else(|| format!("Connection not found: {}", connection_id))?;
+
+    // The database lives in the connection's cluster, which may not be the current context
+    let context = Some(connection.info().context.as_str()).filter(|c| !c.is_empty());
+    crate::preferences::ensure_mutation_allowed(&app, context, confirm.unwrap_or(false))
+        .map_err(|e| e.to_string())?;
+
+    queries::terminate_backend(connection, pid)
+        .await
+        .map_err(|e| format!("Failed to terminate backend: {}", e))
+}
+
+/// Dump a database to a local file with pg_dump
+#[tauri::command]
+pub async fn db_dump_database(
+    connection_id: String,
+    path: String,
+    format: String,
+    client_manager: State<'_, KubeClientManager>,
+    connection_manager: State<'_, ConnectionManager>,
+) -> Result<DbDumpResult, String> {
+    tracing::info!("Database dump request: {} -> {} ({})", connection_id, path, format);
+
+    // Copied out so a dump that takes minutes doesn't hold the manager lock
+    let connection = connection_manager
+        .read()
+        .await
+        .get(&connection_id)
+        .cloned()
+        .ok_or_else(|| format!("Connection not found: {}", connection_id))?;
+
+    // The connection's own context, even if the current one changed since connecting
+    let client = client_manager
+        .get_client_for(&connection.info().context)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    dump::dump_database(client, &connection, &path, &format)
+        .await
+        .map_err(|e| format!("Failed to dump database: {}", e))
+}
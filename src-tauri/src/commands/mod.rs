@@ -1,1245 +1,4080 @@
 pub mod database;
 
 use crate::kube::{get_current_context, load_kubeconfig, KubeClientManager};
+use crate::metrics::{MetricsSampler, TopStreamManager};
+use crate::plugins::PluginManager;
+use crate::preferences::{ClusterIntegrations, ContextTag, PolicyGate, ShellPolicy};
 use crate::shell::ShellManager;
 use crate::types::*;
-use tauri::{AppHandle, State};
+use k8s_openapi::api::apps::v1::Deployment;
+use k8s_openapi::api::core::v1::{Pod, Service};
+use tauri::{AppHandle, Manager, State};
 
 pub use database::*;
 
 #[tauri::command]
 pub async fn get_kubeconfig_contexts(
+    app: AppHandle,
 ) -> Result<Vec<ContextInfo>, String> {
     let config = load_kubeconfig().map_err(|e| e.to_string())?;
+    let tags = crate::preferences::get_context_tags(&app).map_err(|e| e.to_string())?;
 
     let contexts: Vec<ContextInfo> = config
         .contexts
         .iter()
-        .map(|ctx_entry| ContextInfo {
-            name: ctx_entry.name.clone(),
-            cluster: ctx_entry.context.cluster.clone(),
-            namespace: ctx_entry.context.namespace.clone(),
-            user: ctx_entry.context.user.clone(),
-            current: ctx_entry.name == config.current_context,
-        })
+        .map(|ctx_entry| context_info(ctx_entry, ctx_entry.name == config.current_context, &tags))
         .collect();
 
     Ok(contexts)
 }
 
-#[tauri::command]
-pub async fn get_clusters() -> Result<Vec<ClusterInfo>, String> {
-    let config = load_kubeconfig().map_err(|e| e.to_string())?;
-
-    let current_ctx = get_current_context(&config);
-    let current_cluster = current_ctx.map(|ctx| ctx.context.cluster.as_str());
-
-    let clusters: Vec<ClusterInfo> = config
-        .clusters
-        .iter()
-        .map(|cluster_entry| ClusterInfo {
-            name: cluster_entry.name.clone(),
-            server: cluster_entry.cluster.server.clone(),
-            current: Some(cluster_entry.name.as_str()) == current_cluster,
-        })
-        .collect();
-
-    Ok(clusters)
+fn context_info(
+    ctx_entry: &crate::kube::config::ContextEntry,
+    current: bool,
+    tags: &std::collections::HashMap<String, ContextTag>,
+) -> ContextInfo {
+    let tag = tags.get(&ctx_entry.name).cloned().unwrap_or_default();
+    ContextInfo {
+        name: ctx_entry.name.clone(),
+        cluster: ctx_entry.context.cluster.clone(),
+        namespace: ctx_entry.context.namespace.clone(),
+        user: ctx_entry.context.user.clone(),
+        current,
+        protected: tag.is_protected(),
+        environment: tag.environment,
+        color: tag.color,
+        group: tag.group,
+    }
 }
 
+/// Environment/color/group tags for every context, keyed by context name
 #[tauri::command]
-pub async fn get_namespaces(
-    client_manager: State<'_, KubeClientManager>,
-) -> Result<Vec<NamespaceInfo>, String> {
-    let client = client_manager
-        .get_client()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    crate::kube::list_namespaces(client)
-        .await
-        .map_err(|e| e.to_string())
+pub async fn get_context_tags(app: AppHandle) -> Result<std::collections::HashMap<String, ContextTag>, String> {
+    crate::preferences::get_context_tags(&app).map_err(|e| e.to_string())
 }
 
+/// Tag a context; passing no tag clears it
 #[tauri::command]
-pub async fn get_pods(
-    namespace: String,
-    client_manager: State<'_, KubeClientManager>,
-) -> Result<Vec<PodInfo>, String> {
-    let client = client_manager
-        .get_client()
-        .await
-        .map_err(|e| e.to_string())?;
+pub async fn set_context_tag(
+    app: AppHandle,
+    context: String,
+    tag: Option<ContextTag>,
+) -> Result<std::collections::HashMap<String, ContextTag>, String> {
+    crate::preferences::set_context_tag(&app, &context, tag).map_err(|e| e.to_string())
+}
 
-    crate::kube::list_pods(client, &namespace)
-        .await
+/// Reject mutations on a protected current context unless the caller confirmed
+fn ensure_mutation_allowed(app: &AppHandle, confirm: Option<bool>) -> Result<(), String> {
+    crate::preferences::ensure_mutation_allowed(app, None, confirm.unwrap_or(false))
         .map_err(|e| e.to_string())
 }
 
+/// Reachability, version and node readiness for every kubeconfig context
 #[tauri::command]
-pub async fn get_deployments(
-    namespace: String,
-    client_manager: State<'_, KubeClientManager>,
-) -> Result<Vec<DeploymentInfo>, String> {
-    let client = client_manager
-        .get_client()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    crate::kube::list_deployments(client, &namespace)
+pub async fn get_all_contexts_health() -> Result<Vec<ContextHealth>, String> {
+    crate::kube::get_all_contexts_health()
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Request latency, inflight requests and etcd object counts from the API server
 #[tauri::command]
-pub async fn get_services(
-    namespace: String,
+pub async fn get_apiserver_metrics(
     client_manager: State<'_, KubeClientManager>,
-) -> Result<Vec<ServiceInfo>, String> {
+) -> Result<ApiServerMetricsSummary, String> {
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::list_services(client, &namespace)
+    crate::kube::get_apiserver_metrics(client)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// HTTP GET to a Service through the API server proxy, e.g. /healthz, without port-forwarding
 #[tauri::command]
-pub async fn get_pod_logs(
+pub async fn proxy_service_request(
     namespace: String,
-    pod_name: String,
-    container: Option<String>,
-    tail_lines: Option<i64>,
+    service: String,
+    port: Option<String>,
+    path: String,
+    https: Option<bool>,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<Vec<LogEntry>, String> {
+) -> Result<ProxyResponse, String> {
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::get_pod_logs(
+    crate::kube::proxy_service_request(
         client,
         &namespace,
-        &pod_name,
-        container.as_deref(),
-        tail_lines,
+        &service,
+        port.as_deref().unwrap_or_default(),
+        &path,
+        https.unwrap_or(false),
     )
     .await
     .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn delete_pod(
+pub async fn proxy_pod_request(
     namespace: String,
-    pod_name: String,
+    pod: String,
+    port: Option<String>,
+    path: String,
+    https: Option<bool>,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<(), String> {
+) -> Result<ProxyResponse, String> {
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::delete_pod(client, &namespace, &pod_name)
-        .await
-        .map_err(|e| e.to_string())
+    crate::kube::proxy_pod_request(
+        client,
+        &namespace,
+        &pod,
+        port.as_deref().unwrap_or_default(),
+        &path,
+        https.unwrap_or(false),
+    )
+    .await
+    .map_err(|e| e.to_string())
 }
 
+/// Dry-run admission latency per namespace, correlated with webhook configurations
 #[tauri::command]
-pub async fn scale_deployment(
-    namespace: String,
-    deployment_name: String,
-    replicas: i32,
+pub async fn detect_admission_problems(
+    namespaces: Option<Vec<String>>,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<(), String> {
+) -> Result<AdmissionReport, String> {
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::scale_deployment(client, &namespace, &deployment_name, replicas)
+    crate::kube::detect_admission_problems(client, namespaces)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// kube-system component readiness, componentstatuses and recent restarts
 #[tauri::command]
-pub async fn restart_deployment(
-    namespace: String,
-    deployment_name: String,
+pub async fn get_control_plane_health(
     client_manager: State<'_, KubeClientManager>,
-) -> Result<(), String> {
+) -> Result<ControlPlaneHealth, String> {
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::restart_deployment(client, &namespace, &deployment_name)
+    crate::kube::get_control_plane_health(client)
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn delete_deployment(
-    namespace: String,
-    deployment_name: String,
-    client_manager: State<'_, KubeClientManager>,
-) -> Result<(), String> {
-    let client = client_manager
-        .get_client()
-        .await
-        .map_err(|e| e.to_string())?;
+pub async fn get_clusters() -> Result<Vec<ClusterInfo>, String> {
+    let config = load_kubeconfig().map_err(|e| e.to_string())?;
 
-    crate::kube::delete_deployment(client, &namespace, &deployment_name)
-        .await
-        .map_err(|e| e.to_string())
+    let current_ctx = get_current_context(&config);
+    let current_cluster = current_ctx.map(|ctx| ctx.context.cluster.as_str());
+
+    let clusters: Vec<ClusterInfo> = config
+        .clusters
+        .iter()
+        .map(|cluster_entry| ClusterInfo {
+            name: cluster_entry.name.clone(),
+            server: cluster_entry.cluster.server.clone(),
+            current: Some(cluster_entry.name.as_str()) == current_cluster,
+        })
+        .collect();
+
+    Ok(clusters)
 }
 
 #[tauri::command]
-pub async fn delete_service(
-    namespace: String,
-    service_name: String,
+pub async fn get_namespaces(
+    context: Option<String>,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<(), String> {
+) -> Result<Vec<NamespaceInfo>, String> {
     let client = client_manager
-        .get_client()
+        .get_client_in(context.as_deref())
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::delete_service(client, &namespace, &service_name)
+    crate::kube::list_namespaces(client)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// What a terminating namespace is still waiting on
 #[tauri::command]
-pub async fn delete_configmap(
-    namespace: String,
-    configmap_name: String,
+pub async fn get_namespace_termination_status(
+    name: String,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<(), String> {
+) -> Result<NamespaceTerminationStatus, String> {
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::delete_configmap(client, &namespace, &configmap_name)
+    crate::kube::get_namespace_termination_status(client, &name)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Follow a namespace until it is gone; returns the watch id for its events
 #[tauri::command]
-pub async fn delete_secret(
-    namespace: String,
-    secret_name: String,
+pub async fn watch_namespace_termination(
+    name: String,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<(), String> {
+    termination_manager: State<'_, crate::kube::NamespaceTerminationManager>,
+    app: AppHandle,
+) -> Result<String, String> {
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::delete_secret(client, &namespace, &secret_name)
+    termination_manager
+        .start_watch(app, client, &name)
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn reinit_kube_client(
-    client_manager: State<'_, KubeClientManager>,
+pub async fn stop_namespace_termination_watch(
+    watch_id: String,
+    termination_manager: State<'_, crate::kube::NamespaceTerminationManager>,
 ) -> Result<(), String> {
-    client_manager
-        .reinit_client()
+    termination_manager
+        .stop_watch(&watch_id)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Remove a known-safe finalizer from an object holding up namespace deletion
 #[tauri::command]
-pub async fn switch_kube_context(
-    context_name: String,
+pub async fn remove_stuck_finalizer(
+    namespace: String,
+    kind: String,
+    name: String,
+    finalizer: String,
     client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
 ) -> Result<(), String> {
-    crate::kube::switch_context(&context_name)
-        .map_err(|e| e.to_string())?;
+    ensure_mutation_allowed(&app, confirm)?;
 
-    client_manager
-        .reinit_client()
+    let client = client_manager
+        .get_client()
         .await
-        .map_err(|e| e.to_string())
-}
-
-#[tauri::command]
-pub async fn load_custom_kubeconfig_file(
-    path: String,
-    client_manager: State<'_, KubeClientManager>,
-) -> Result<(), String> {
-    crate::kube::set_kubeconfig_path(&path)
         .map_err(|e| e.to_string())?;
 
-    client_manager
-        .reinit_client()
+    crate::kube::remove_stuck_finalizer(client, &namespace, &kind, &name, &finalizer)
         .await
         .map_err(|e| e.to_string())
 }
 
-#[tauri::command]
-pub async fn get_current_context_info() -> Result<Option<ContextInfo>, String> {
-    let config = crate::kube::load_kubeconfig()
-        .map_err(|e| e.to_string())?;
-
-    let current = crate::kube::get_current_context(&config);
+/// Context a cache entry belongs to: the given one, or the current context
+fn cache_context(context: Option<&str>) -> String {
+    match context {
+        Some(context) => context.to_string(),
+        None => load_kubeconfig().map(|c| c.current_context).unwrap_or_default(),
+    }
+}
 
-    Ok(current.map(|ctx| ContextInfo {
-        name: ctx.name.clone(),
-        cluster: ctx.context.cluster.clone(),
-        namespace: ctx.context.namespace.clone(),
-        user: ctx.context.user.clone(),
-        current: true,
-    }))
+/// Reflector caches hold a whole namespace, so lists narrowed by a selector go to the API server
+fn has_selector(label_selector: Option<&str>) -> bool {
+    label_selector.is_some_and(|s| !s.trim().is_empty())
 }
 
 #[tauri::command]
-pub async fn get_ingresses(
+pub async fn get_pods(
     namespace: String,
+    label_selector: Option<String>,
+    field_selector: Option<String>,
+    query: Option<ListQuery>,
+    context: Option<String>,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<Vec<IngressInfo>, String> {
+    cache: State<'_, crate::kube::ResourceCache>,
+) -> Result<Vec<PodInfo>, String> {
     let client = client_manager
-        .get_client()
+        .get_client_in(context.as_deref())
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::list_ingresses(client, &namespace)
-        .await
-        .map_err(|e| e.to_string())
+    let cached = if has_selector(label_selector.as_deref()) || has_selector(field_selector.as_deref()) {
+        None
+    } else {
+        let context = cache_context(context.as_deref());
+        cache.list::<Pod>(&client, client_manager.generation(&context), &context, &namespace).await
+    };
+    let items = match cached {
+        Some(pods) => pods.into_iter().map(crate::kube::operations::pod_info).collect(),
+        None => crate::kube::list_pods(
+            client,
+            &namespace,
+            label_selector.as_deref(),
+            field_selector.as_deref(),
+        )
+        .await
+        .map_err(|e| e.to_string())?,
+    };
+
+    match query {
+        Some(query) => crate::kube::query_pods(items, &query).map_err(|e| e.to_string()),
+        None => Ok(items),
+    }
+}
+
+/// One page of pods; pass the returned `continue_token` back for the next
+#[tauri::command]
+pub async fn get_pods_page(
+    namespace: String,
+    label_selector: Option<String>,
+    field_selector: Option<String>,
+    limit: Option<u32>,
+    continue_token: Option<String>,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<ListPage<PodInfo>, String> {
+    let client = client_manager.get_client().await.map_err(|e| e.to_string())?;
+    crate::kube::list_pods_page(
+        client,
+        &namespace,
+        label_selector.as_deref(),
+        field_selector.as_deref(),
+        limit,
+        continue_token.as_deref(),
+    )
+    .await
+    .map_err(|e| e.to_string())
 }
 
+/// Stream pod changes in a namespace (all when empty); returns the event name to listen on
 #[tauri::command]
-pub async fn get_istio_virtual_services(
+pub async fn start_pod_watch(
+    app: AppHandle,
     namespace: String,
+    context: Option<String>,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<Vec<IstioVirtualServiceInfo>, String> {
+    watch_manager: State<'_, crate::kube::PodWatchManager>,
+) -> Result<String, String> {
     let client = client_manager
-        .get_client()
+        .get_client_in(context.as_deref())
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::list_istio_virtual_services(client, &namespace)
+    watch_manager
+        .start_watch(app, client, &cache_context(context.as_deref()), &namespace)
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn get_istio_gateways(
+pub async fn stop_pod_watch(
     namespace: String,
-    client_manager: State<'_, KubeClientManager>,
-) -> Result<Vec<IstioGatewayInfo>, String> {
-    let client = client_manager
-        .get_client()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    crate::kube::list_istio_gateways(client, &namespace)
+    context: Option<String>,
+    watch_manager: State<'_, crate::kube::PodWatchManager>,
+) -> Result<(), String> {
+    watch_manager
+        .stop_watch(&cache_context(context.as_deref()), &namespace)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// List any kind, answering from the last list at once when there is one and
+/// revalidating in the background (see `resource-list-updated`)
 #[tauri::command]
-pub async fn get_resource_yaml(
+pub async fn get_resource_list(
+    app: AppHandle,
     resource_type: String,
     namespace: Option<String>,
-    name: String,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<String, String> {
+    list_cache: State<'_, crate::kube::ListCache>,
+) -> Result<ResourceListSnapshot, String> {
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::get_resource_yaml(client, &resource_type, namespace.as_deref().unwrap_or(""), &name)
+    list_cache
+        .list(app, client, &cache_context(None), &resource_type, namespace.as_deref().unwrap_or(""))
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Stream changes to any kind, built-in or custom; events arrive on `resource-watch-{watch_id}`
 #[tauri::command]
-pub async fn get_configmaps(
-    namespace: String,
+pub async fn start_resource_watch(
+    app: AppHandle,
+    resource_type: String,
+    namespace: Option<String>,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<Vec<ConfigMapInfo>, String> {
+    watch_manager: State<'_, crate::kube::ResourceWatchManager>,
+) -> Result<ResourceWatchStarted, String> {
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::list_configmaps(client, &namespace)
+    watch_manager
+        .start_watch(app, client, &resource_type, namespace.as_deref().unwrap_or(""))
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn get_secrets(
-    namespace: String,
-    client_manager: State<'_, KubeClientManager>,
-) -> Result<Vec<SecretInfo>, String> {
-    let client = client_manager
-        .get_client()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    crate::kube::list_secrets(client, &namespace)
+pub async fn stop_resource_watch(
+    watch_id: String,
+    watch_manager: State<'_, crate::kube::ResourceWatchManager>,
+) -> Result<(), String> {
+    watch_manager
+        .stop_watch(&watch_id)
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn get_statefulsets(
+pub async fn get_deployments(
     namespace: String,
+    label_selector: Option<String>,
+    query: Option<ListQuery>,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<Vec<StatefulSetInfo>, String> {
+    cache: State<'_, crate::kube::ResourceCache>,
+) -> Result<Vec<DeploymentInfo>, String> {
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::list_statefulsets(client, &namespace)
-        .await
-        .map_err(|e| e.to_string())
+    let cached = if has_selector(label_selector.as_deref()) {
+        None
+    } else {
+        let context = cache_context(None);
+        cache.list::<Deployment>(&client, client_manager.generation(&context), &context, &namespace).await
+    };
+    let items = match cached {
+        Some(deployments) => deployments.into_iter().map(crate::kube::operations::deployment_info).collect(),
+        None => crate::kube::list_deployments(client, &namespace, label_selector.as_deref())
+            .await
+            .map_err(|e| e.to_string())?,
+    };
+
+    match query {
+        Some(query) => crate::kube::query_deployments(items, &query).map_err(|e| e.to_string()),
+        None => Ok(items),
+    }
 }
 
 #[tauri::command]
-pub async fn get_daemonsets(
+pub async fn get_deployments_page(
     namespace: String,
+    label_selector: Option<String>,
+    limit: Option<u32>,
+    continue_token: Option<String>,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<Vec<DaemonSetInfo>, String> {
-    let client = client_manager
-        .get_client()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    crate::kube::list_daemonsets(client, &namespace)
-        .await
-        .map_err(|e| e.to_string())
+) -> Result<ListPage<DeploymentInfo>, String> {
+    let client = client_manager.get_client().await.map_err(|e| e.to_string())?;
+    crate::kube::list_deployments_page(
+        client,
+        &namespace,
+        label_selector.as_deref(),
+        limit,
+        continue_token.as_deref(),
+    )
+    .await
+    .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn get_jobs(
+pub async fn get_services(
     namespace: String,
+    label_selector: Option<String>,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<Vec<JobInfo>, String> {
+    cache: State<'_, crate::kube::ResourceCache>,
+) -> Result<Vec<ServiceInfo>, String> {
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::list_jobs(client, &namespace)
-        .await
-        .map_err(|e| e.to_string())
+    let cached = if has_selector(label_selector.as_deref()) {
+        None
+    } else {
+        let context = cache_context(None);
+        cache.list::<Service>(&client, client_manager.generation(&context), &context, &namespace).await
+    };
+    match cached {
+        Some(services) => Ok(services.into_iter().map(crate::kube::operations::service_info).collect()),
+        None => crate::kube::list_services(client, &namespace, label_selector.as_deref())
+            .await
+            .map_err(|e| e.to_string()),
+    }
 }
 
 #[tauri::command]
-pub async fn get_cronjobs(
+pub async fn get_services_page(
     namespace: String,
+    label_selector: Option<String>,
+    limit: Option<u32>,
+    continue_token: Option<String>,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<Vec<CronJobInfo>, String> {
-    let client = client_manager
-        .get_client()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    crate::kube::list_cronjobs(client, &namespace)
-        .await
-        .map_err(|e| e.to_string())
-}
-
-#[tauri::command]
-pub async fn get_nodes(
-    client_manager: State<'_, KubeClientManager>,
-) -> Result<Vec<NodeInfo>, String> {
-    let client = client_manager
-        .get_client()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    crate::kube::list_nodes(client)
-        .await
-        .map_err(|e| e.to_string())
+) -> Result<ListPage<ServiceInfo>, String> {
+    let client = client_manager.get_client().await.map_err(|e| e.to_string())?;
+    crate::kube::list_services_page(
+        client,
+        &namespace,
+        label_selector.as_deref(),
+        limit,
+        continue_token.as_deref(),
+    )
+    .await
+    .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn get_events(
+pub async fn get_pod_logs(
     namespace: String,
+    pod_name: String,
+    container: Option<String>,
+    tail_lines: Option<i64>,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<Vec<EventInfo>, String> {
+) -> Result<Vec<LogEntry>, String> {
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::list_events(client, &namespace)
-        .await
-        .map_err(|e| e.to_string())
+    crate::kube::get_pod_logs(
+        client,
+        &namespace,
+        &pod_name,
+        container.as_deref(),
+        tail_lines,
+    )
+    .await
+    .map_err(|e| e.to_string())
 }
 
+/// Search logs through the cluster's log provider (kubelet or Loki)
 #[tauri::command]
-pub async fn get_persistent_volumes(
+pub async fn search_logs(
+    app: AppHandle,
+    search: LogSearch,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<Vec<PersistentVolumeInfo>, String> {
+) -> Result<Vec<LogEntry>, String> {
+    let integrations = crate::preferences::get_cluster_integrations(&app, None).map_err(|e| e.to_string())?;
+    let provider = crate::kube::LogProvider::for_cluster(&integrations, search.provider.as_deref())
+        .map_err(|e| e.to_string())?;
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::list_persistent_volumes(client)
-        .await
-        .map_err(|e| e.to_string())
+    provider.search(client, &search).await.map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn get_persistent_volume_claims(
+pub async fn get_all_container_logs(
     namespace: String,
+    pod_name: String,
+    tail_lines: Option<i64>,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<Vec<PersistentVolumeClaimInfo>, String> {
+) -> Result<std::collections::HashMap<String, ContainerLogs>, String> {
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::list_persistent_volume_claims(client, &namespace)
+    crate::kube::get_all_container_logs(client, &namespace, &pod_name, tail_lines)
         .await
         .map_err(|e| e.to_string())
 }
 
-// RBAC Commands
+/// Restart one container in place by signalling its main process
 #[tauri::command]
-pub async fn get_roles(
+pub async fn restart_container(
     namespace: String,
+    pod_name: String,
+    container: String,
+    signal: Option<String>,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<Vec<RoleInfo>, String> {
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<i32, String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::list_roles(client, &namespace)
+    crate::kube::restart_container(client, &namespace, &pod_name, &container, signal.as_deref())
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Run a command in a container and return its output, without a terminal
 #[tauri::command]
-pub async fn get_role_bindings(
+#[allow(clippy::too_many_arguments)]
+pub async fn exec_command(
     namespace: String,
+    pod_name: String,
+    container: Option<String>,
+    command: Vec<String>,
+    timeout_secs: Option<u64>,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<Vec<RoleBindingInfo>, String> {
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<CommandOutput, String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::list_role_bindings(client, &namespace)
+    crate::kube::exec_command(client, &namespace, &pod_name, container.as_deref(), command, timeout_secs)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Follow a log file inside a container; lines arrive as `file-tail-{id}` events
 #[tauri::command]
-pub async fn get_cluster_roles(
+#[allow(clippy::too_many_arguments)]
+pub async fn tail_container_file(
+    app: AppHandle,
+    namespace: String,
+    pod_name: String,
+    container: Option<String>,
+    path: String,
+    lines: Option<u32>,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<Vec<ClusterRoleInfo>, String> {
+    tail_manager: State<'_, crate::kube::FileTailManager>,
+) -> Result<String, String> {
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::list_cluster_roles(client)
+    tail_manager
+        .start_tail(app, client, &namespace, &pod_name, container.as_deref(), &path, lines)
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn get_cluster_role_bindings(
-    client_manager: State<'_, KubeClientManager>,
-) -> Result<Vec<ClusterRoleBindingInfo>, String> {
-    let client = client_manager
-        .get_client()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    crate::kube::list_cluster_role_bindings(client)
+pub async fn stop_container_file_tail(
+    tail_id: String,
+    tail_manager: State<'_, crate::kube::FileTailManager>,
+) -> Result<(), String> {
+    tail_manager
+        .stop_tail(&tail_id)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Capture a Java or Go runtime dump from a container into the app's diagnostics folder
+///
+/// `go-stacks` restarts the container, so it counts as a mutation on protected contexts.
 #[tauri::command]
-pub async fn get_service_accounts(
+pub async fn capture_runtime_diagnostic(
+    app: AppHandle,
     namespace: String,
+    pod_name: String,
+    container: String,
+    kind: String,
+    confirm: Option<bool>,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<Vec<ServiceAccountInfo>, String> {
+) -> Result<RuntimeDiagnosticResult, String> {
+    if kind == "go-stacks" {
+        ensure_mutation_allowed(&app, confirm)?;
+    }
+
+    let output_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("diagnostics");
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::list_service_accounts(client, &namespace)
+    crate::kube::capture_runtime_diagnostic(client, &namespace, &pod_name, &container, &kind, &output_dir)
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn apply_resource_yaml(
-    resource_type: String,
-    namespace: Option<String>,
-    yaml_content: String,
+pub async fn delete_pod(
+    namespace: String,
+    pod_name: String,
     client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
 ) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::apply_resource_yaml(client, &resource_type, namespace.as_deref().unwrap_or(""), &yaml_content)
+    crate::kube::delete_pod(client, &namespace, &pod_name)
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn scale_statefulset(
+pub async fn scale_deployment(
     namespace: String,
-    statefulset_name: String,
+    deployment_name: String,
     replicas: i32,
     client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
 ) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::scale_statefulset(client, &namespace, &statefulset_name, replicas)
+    crate::kube::scale_deployment(client, &namespace, &deployment_name, replicas)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Scale Deployments and StatefulSets matching a selector and/or names; `dry_run` only previews
 #[tauri::command]
-pub async fn restart_statefulset(
+#[allow(clippy::too_many_arguments)]
+pub async fn bulk_scale(
     namespace: String,
-    statefulset_name: String,
+    label_selector: Option<String>,
+    names: Option<Vec<String>>,
+    replicas: i32,
+    dry_run: Option<bool>,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<(), String> {
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<Vec<BulkScaleResult>, String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::restart_statefulset(client, &namespace, &statefulset_name)
-        .await
-        .map_err(|e| e.to_string())
+    crate::kube::bulk_scale(
+        client,
+        &namespace,
+        label_selector.as_deref(),
+        names,
+        replicas,
+        dry_run.unwrap_or(false),
+    )
+    .await
+    .map_err(|e| e.to_string())
 }
 
+/// Scale back what a bulk scale to zero recorded in the previous-replicas annotation
 #[tauri::command]
-pub async fn delete_statefulset(
+pub async fn restore_bulk_scale(
     namespace: String,
-    statefulset_name: String,
+    label_selector: Option<String>,
+    names: Option<Vec<String>>,
+    dry_run: Option<bool>,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<(), String> {
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<Vec<BulkScaleResult>, String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::delete_statefulset(client, &namespace, &statefulset_name)
-        .await
-        .map_err(|e| e.to_string())
+    crate::kube::restore_bulk_scale(
+        client,
+        &namespace,
+        label_selector.as_deref(),
+        names,
+        dry_run.unwrap_or(false),
+    )
+    .await
+    .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn restart_daemonset(
+pub async fn restart_deployment(
     namespace: String,
-    daemonset_name: String,
+    deployment_name: String,
     client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
 ) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::restart_daemonset(client, &namespace, &daemonset_name)
+    crate::kube::restart_deployment(client, &namespace, &deployment_name)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Workloads running an image matching `image_pattern`; the dry run for `restart_workloads_using_image`
 #[tauri::command]
-pub async fn delete_daemonset(
-    namespace: String,
-    daemonset_name: String,
+pub async fn preview_image_restart(
+    image_pattern: String,
+    namespaces: Option<Vec<String>>,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<(), String> {
+) -> Result<Vec<ImageRestartTarget>, String> {
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::delete_daemonset(client, &namespace, &daemonset_name)
+    crate::kube::preview_image_restart(client, &image_pattern, &namespaces.unwrap_or_default())
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Rolling-restart every workload running a matching image, `max_parallel` at a time; returns the restart id for its events
 #[tauri::command]
-pub async fn delete_job(
-    namespace: String,
-    job_name: String,
+pub async fn restart_workloads_using_image(
+    image_pattern: String,
+    namespaces: Option<Vec<String>>,
+    max_parallel: Option<usize>,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<(), String> {
+    restart_manager: State<'_, crate::kube::ImageRestartManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<String, String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::delete_job(client, &namespace, &job_name)
+    restart_manager
+        .start_restart(app, client, &image_pattern, &namespaces.unwrap_or_default(), max_parallel)
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn suspend_cronjob(
-    namespace: String,
-    cronjob_name: String,
-    client_manager: State<'_, KubeClientManager>,
-) -> Result<(), String> {
-    let client = client_manager.get_client().await.map_err(|e| e.to_string())?;
-    crate::kube::suspend_cronjob(client, &namespace, &cronjob_name)
-        .await.map_err(|e| e.to_string())
-}
-
-#[tauri::command]
-pub async fn resume_cronjob(
-    namespace: String,
-    cronjob_name: String,
-    client_manager: State<'_, KubeClientManager>,
-) -> Result<(), String> {
-    let client = client_manager.get_client().await.map_err(|e| e.to_string())?;
-    crate::kube::resume_cronjob(client, &namespace, &cronjob_name)
-        .await.map_err(|e| e.to_string())
-}
-
-#[tauri::command]
-pub async fn delete_cronjob(
-    namespace: String,
-    cronjob_name: String,
-    client_manager: State<'_, KubeClientManager>,
+pub async fn stop_image_restart(
+    restart_id: String,
+    restart_manager: State<'_, crate::kube::ImageRestartManager>,
 ) -> Result<(), String> {
-    let client = client_manager.get_client().await.map_err(|e| e.to_string())?;
-    crate::kube::delete_cronjob(client, &namespace, &cronjob_name)
-        .await.map_err(|e| e.to_string())
+    restart_manager
+        .stop_restart(&restart_id)
+        .await
+        .map_err(|e| e.to_string())
 }
 
+/// Follow a Deployment, StatefulSet or DaemonSet rollout, emitting progress and a final outcome
 #[tauri::command]
-pub async fn get_pods_for_resource(
-    resource_type: String,
-    resource_name: String,
+pub async fn watch_rollout(
+    app: AppHandle,
+    kind: String,
     namespace: String,
+    name: String,
+    timeout_secs: Option<u64>,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<Vec<PodInfo>, String> {
-    let client = client_manager.get_client().await.map_err(|e| e.to_string())?;
-    crate::kube::get_pods_for_resource(client, &resource_type, &resource_name, &namespace)
+    rollout_manager: State<'_, crate::kube::RolloutWatchManager>,
+) -> Result<String, String> {
+    let client = client_manager
+        .get_client()
         .await
-        .map_err(|e| e.to_string())
-}
+        .map_err(|e| e.to_string())?;
 
-// Port Forward Commands
-#[tauri::command]
-pub async fn start_port_forward(
-    resource_type: String,
-    resource_name: String,
-    namespace: String,
-    local_port: u16,
-    remote_port: u16,
-    portforward_manager: State<'_, crate::portforward::PortForwardManager>,
-) -> Result<crate::types::PortForwardInfo, String> {
-    portforward_manager
-        .start_port_forward(&resource_type, &resource_name, &namespace, local_port, remote_port)
+    rollout_manager
+        .start_watch(app, client, &kind, &namespace, &name, timeout_secs)
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn stop_port_forward(
-    id: String,
-    portforward_manager: State<'_, crate::portforward::PortForwardManager>,
+pub async fn stop_rollout_watch(
+    watch_id: String,
+    rollout_manager: State<'_, crate::kube::RolloutWatchManager>,
 ) -> Result<(), String> {
-    portforward_manager
-        .stop_port_forward(&id)
+    rollout_manager
+        .stop_watch(&watch_id)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Pod distribution over nodes and zones versus the workload's spread constraints and anti-affinity
 #[tauri::command]
-pub async fn list_port_forwards(
-    portforward_manager: State<'_, crate::portforward::PortForwardManager>,
-) -> Result<Vec<crate::types::PortForwardInfo>, String> {
-    Ok(portforward_manager.list_port_forwards().await)
-}
-
-// Node Operations
-#[tauri::command]
-pub async fn cordon_node(
-    node_name: String,
+pub async fn analyze_spread(
+    kind: String,
+    namespace: String,
+    name: String,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<(), String> {
+) -> Result<SpreadAnalysis, String> {
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::cordon_node(client, &node_name)
+    crate::kube::analyze_spread(client, &kind, &namespace, &name)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Run part of a Deployment's pods on a new image behind the same Service
 #[tauri::command]
-pub async fn uncordon_node(
-    node_name: String,
+#[allow(clippy::too_many_arguments)]
+pub async fn canary_deploy(
+    namespace: String,
+    deployment: String,
+    new_image: String,
+    percent: u8,
+    container: Option<String>,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<(), String> {
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<CanaryStatus, String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::uncordon_node(client, &node_name)
+    crate::kube::canary_deploy(client, &namespace, &deployment, &new_image, percent, container.as_deref())
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn drain_node(
-    node_name: String,
+pub async fn promote_canary(
+    namespace: String,
+    deployment: String,
     client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
 ) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::drain_node(client, &node_name)
+    crate::kube::promote_canary(client, &namespace, &deployment)
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn delete_node(
-    node_name: String,
+pub async fn rollback_canary(
+    namespace: String,
+    deployment: String,
     client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
 ) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::delete_node(client, &node_name)
+    crate::kube::rollback_canary(client, &namespace, &deployment)
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn describe_node(
-    node_name: String,
+pub async fn delete_deployment(
+    namespace: String,
+    deployment_name: String,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<String, String> {
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::describe_node(client, &node_name)
+    crate::kube::delete_deployment(client, &namespace, &deployment_name)
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn describe_resource(
-    resource_type: String,
-    namespace: Option<String>,
+pub async fn patch_service_type(
+    namespace: String,
     name: String,
+    service_type: String,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<String, String> {
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::describe_resource(
-        client,
-        &resource_type,
-        namespace.as_deref(),
-        &name,
-    )
-    .await
-    .map_err(|e| e.to_string())
+    crate::kube::patch_service_type(client, &namespace, &name, &service_type)
+        .await
+        .map_err(|e| e.to_string())
 }
 
-// Shell commands
+/// Set Service annotations; keys mapped to null are removed
 #[tauri::command]
-pub async fn start_shell_session(
-    app: AppHandle,
-    pod_name: String,
+pub async fn set_service_annotations(
     namespace: String,
-    container: Option<String>,
-    shell: Option<String>,
+    name: String,
+    annotations: std::collections::HashMap<String, Option<String>>,
     client_manager: State<'_, KubeClientManager>,
-    shell_manager: State<'_, ShellManager>,
-) -> Result<String, String> {
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    shell_manager
-        .start_session(app, client, pod_name, namespace, container, shell)
+    crate::kube::set_service_annotations(client, &namespace, &name, annotations)
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn send_shell_input(
-    session_id: String,
-    data: String,
-    shell_manager: State<'_, ShellManager>,
-) -> Result<(), String> {
-    shell_manager
-        .send_input(&session_id, data)
-        .await
-        .map_err(|e| e.to_string())
+pub async fn get_load_balancer_annotation_presets() -> Result<Vec<LoadBalancerAnnotationPreset>, String> {
+    Ok(crate::kube::get_load_balancer_annotation_presets())
 }
 
 #[tauri::command]
-pub async fn close_shell_session(
-    session_id: String,
-    shell_manager: State<'_, ShellManager>,
+pub async fn delete_service(
+    namespace: String,
+    service_name: String,
+    client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
 ) -> Result<(), String> {
-    shell_manager
-        .close_session(&session_id)
+    ensure_mutation_allowed(&app, confirm)?;
+
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::delete_service(client, &namespace, &service_name)
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn get_pod_containers(
-    pod_name: String,
+pub async fn delete_configmap(
     namespace: String,
+    configmap_name: String,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<Vec<String>, String> {
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::get_pod_containers(client, &namespace, &pod_name)
+    crate::kube::delete_configmap(client, &namespace, &configmap_name)
         .await
         .map_err(|e| e.to_string())
 }
 
-// CRD Commands
 #[tauri::command]
-pub async fn get_crds(
+pub async fn delete_secret(
+    namespace: String,
+    secret_name: String,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<Vec<CRDInfo>, String> {
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::list_crds(client)
+    crate::kube::delete_secret(client, &namespace, &secret_name)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Create an image pull Secret for a registry, like `kubectl create secret docker-registry`
 #[tauri::command]
-pub async fn get_custom_resources(
+#[allow(clippy::too_many_arguments)]
+pub async fn create_docker_registry_secret(
+    namespace: String,
+    name: String,
+    server: String,
+    username: String,
+    password: String,
+    email: Option<String>,
     client_manager: State<'_, KubeClientManager>,
-    group: String,
-    version: String,
-    plural: String,
-    namespace: Option<String>,
-) -> Result<Vec<CustomResourceInfo>, String> {
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::list_custom_resources(
+    crate::kube::create_docker_registry_secret(
         client,
-        &group,
-        &version,
-        &plural,
-        namespace.as_deref(),
+        &namespace,
+        &name,
+        &server,
+        &username,
+        &password,
+        email.as_deref(),
     )
     .await
     .map_err(|e| e.to_string())
 }
 
+/// Add a pull secret to a ServiceAccount; returns false if it was already there
 #[tauri::command]
-pub async fn delete_custom_resource(
+pub async fn attach_pull_secret_to_serviceaccount(
+    namespace: String,
+    service_account: String,
+    secret_name: String,
     client_manager: State<'_, KubeClientManager>,
-    group: String,
-    version: String,
-    plural: String,
-    name: String,
-    namespace: Option<String>,
-) -> Result<(), String> {
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<bool, String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::delete_custom_resource(
-        client,
-        &group,
-        &version,
-        &plural,
-        &name,
-        namespace.as_deref(),
-    )
-    .await
-    .map_err(|e| e.to_string())
+    crate::kube::attach_pull_secret_to_serviceaccount(client, &namespace, &service_account, &secret_name)
+        .await
+        .map_err(|e| e.to_string())
 }
 
+/// Search ConfigMap keys/values and Secret keys; Secret values only when `include_secret_values`
 #[tauri::command]
-pub async fn get_custom_resource_yaml(
+pub async fn search_config_data(
+    namespace: String,
+    query: String,
+    include_secret_values: Option<bool>,
     client_manager: State<'_, KubeClientManager>,
-    group: String,
-    version: String,
-    plural: String,
-    name: String,
-    namespace: Option<String>,
-) -> Result<String, String> {
+) -> Result<Vec<ConfigDataMatch>, String> {
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::get_custom_resource_yaml(
-        client,
-        &group,
-        &version,
-        &plural,
-        &name,
-        namespace.as_deref(),
-    )
-    .await
-    .map_err(|e| e.to_string())
+    crate::kube::search_config_data(client, &namespace, &query, include_secret_values.unwrap_or(false))
+        .await
+        .map_err(|e| e.to_string())
 }
 
+/// Every container image in use, with pull details and `:latest`/registry problems flagged
 #[tauri::command]
-pub async fn update_custom_resource_yaml(
-    client_manager: State<'_, KubeClientManager>,
-    group: String,
-    version: String,
-    plural: String,
-    name: String,
+pub async fn get_image_inventory(
     namespace: Option<String>,
-    yaml: String,
-) -> Result<(), String> {
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<ImageInventory, String> {
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::update_custom_resource_yaml(
-        client,
-        &group,
-        &version,
-        &plural,
-        &name,
-        namespace.as_deref(),
-        &yaml,
-    )
-    .await
-    .map_err(|e| e.to_string())
+    crate::kube::get_image_inventory(client, namespace.as_deref())
+        .await
+        .map_err(|e| e.to_string())
 }
 
+/// Versions of CNI, CSI, ingress, cert-manager, mesh and other addons against the latest known releases
 #[tauri::command]
-pub async fn describe_custom_resource(
+pub async fn get_addon_versions(
     client_manager: State<'_, KubeClientManager>,
-    group: String,
-    version: String,
-    plural: String,
-    name: String,
-    namespace: Option<String>,
-) -> Result<String, String> {
+) -> Result<Vec<AddonVersion>, String> {
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::describe_custom_resource(
-        client,
-        &group,
-        &version,
-        &plural,
-        &name,
-        namespace.as_deref(),
-    )
-    .await
-    .map_err(|e| e.to_string())
+    crate::kube::get_addon_versions(client)
+        .await
+        .map_err(|e| e.to_string())
 }
 
+/// Registry tags and digests for an image, compared with the pods running it
 #[tauri::command]
-pub async fn sync_argocd_app(
+pub async fn list_image_tags(
+    image: String,
+    namespace: Option<String>,
+    pull_secret: Option<String>,
     client_manager: State<'_, KubeClientManager>,
-    name: String,
-    namespace: String,
-) -> Result<(), String> {
+) -> Result<RegistryTags, String> {
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::sync_argocd_app(client, &name, &namespace)
+    crate::kube::list_image_tags(client, &image, namespace.as_deref(), pull_secret.as_deref())
         .await
         .map_err(|e| e.to_string())
 }
 
-// ==================== Helm Commands ====================
-
+/// Forget cached exec-plugin tokens (e.g. after `aws sso login`) and rebuild the client
 #[tauri::command]
-pub async fn helm_check_installed() -> Result<bool, String> {
-    crate::helm::check_helm_installed()
+pub async fn clear_credential_cache(
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<usize, String> {
+    let cleared = crate::kube::clear_credential_cache();
+
+    client_manager
+        .reinit_client()
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+
+    Ok(cleared)
 }
 
 #[tauri::command]
-pub async fn helm_list_releases(
-    namespace: Option<String>,
-    all_namespaces: bool,
-) -> Result<Vec<crate::helm::HelmRelease>, String> {
-    crate::helm::list_releases(namespace.as_deref(), all_namespaces)
+pub async fn reinit_kube_client(
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<(), String> {
+    client_manager
+        .reinit_client()
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Transient API failures retried since startup, so the UI can flag a flaky connection
 #[tauri::command]
-pub async fn helm_get_release(
-    name: String,
-    namespace: String,
-) -> Result<crate::helm::HelmReleaseDetail, String> {
-    crate::helm::get_release(&name, &namespace)
-        .await
-        .map_err(|e| e.to_string())
+pub async fn get_api_retry_stats() -> Result<ApiRetryStats, String> {
+    Ok(crate::kube::retry::retry_stats())
 }
 
 #[tauri::command]
-pub async fn helm_get_manifest(name: String, namespace: String) -> Result<String, String> {
-    crate::helm::get_manifest(&name, &namespace)
+pub async fn switch_kube_context(
+    context_name: String,
+    client_manager: State<'_, KubeClientManager>,
+    watch_manager: State<'_, crate::kube::PodWatchManager>,
+    app: AppHandle,
+) -> Result<(), String> {
+    crate::kube::switch_context(&context_name)
+        .map_err(|e| e.to_string())?;
+    // Views re-subscribe for the new context; the old watches would keep streaming the old cluster
+    watch_manager.stop_all().await;
+
+    client_manager
+        .reinit_client()
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    let _ = crate::tray::refresh(&app).await;
+    Ok(())
 }
 
+/// Drop cached per-context clients so the next request rebuilds them, e.g. after
+/// re-authenticating; `None` drops them all
 #[tauri::command]
-pub async fn helm_get_values(name: String, namespace: String) -> Result<String, String> {
-    crate::helm::get_values(&name, &namespace)
-        .await
-        .map_err(|e| e.to_string())
+pub async fn reset_context_clients(
+    context: Option<String>,
+    client_manager: State<'_, KubeClientManager>,
+    cache: State<'_, crate::kube::ResourceCache>,
+    list_cache: State<'_, crate::kube::ListCache>,
+) -> Result<(), String> {
+    client_manager.evict(context.as_deref()).await;
+    // Reflectors hold clients built with the old credentials
+    cache.evict(context.as_deref()).await;
+    list_cache.evict(context.as_deref()).await;
+    Ok(())
 }
 
 #[tauri::command]
-pub async fn helm_uninstall_release(name: String, namespace: String) -> Result<String, String> {
-    crate::helm::uninstall_release(&name, &namespace)
+pub async fn load_custom_kubeconfig_file(
+    path: String,
+    client_manager: State<'_, KubeClientManager>,
+    cache: State<'_, crate::kube::ResourceCache>,
+    list_cache: State<'_, crate::kube::ListCache>,
+) -> Result<(), String> {
+    crate::kube::set_kubeconfig_path(&path)
+        .map_err(|e| e.to_string())?;
+
+    // Same context names may now point at different clusters
+    crate::kube::clear_credential_cache();
+    client_manager.evict(None).await;
+    cache.evict(None).await;
+    list_cache.evict(None).await;
+    client_manager
+        .reinit_client()
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn helm_rollback_release(
-    name: String,
-    namespace: String,
-    revision: u32,
-) -> Result<String, String> {
-    crate::helm::rollback_release(&name, &namespace, revision)
-        .await
-        .map_err(|e| e.to_string())
+pub async fn get_current_context_info(app: AppHandle) -> Result<Option<ContextInfo>, String> {
+    let config = crate::kube::load_kubeconfig()
+        .map_err(|e| e.to_string())?;
+    let tags = crate::preferences::get_context_tags(&app).map_err(|e| e.to_string())?;
+
+    let current = crate::kube::get_current_context(&config);
+
+    Ok(current.map(|ctx| context_info(ctx, true, &tags)))
 }
 
+/// Which actions the current user may take on a kind, so the UI can disable buttons up front
 #[tauri::command]
-pub async fn helm_get_history(
-    name: String,
+pub async fn get_allowed_actions(
+    kind: String,
+    namespace: Option<String>,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<AllowedActions, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::get_allowed_actions(client, &kind, namespace.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Short-lived kubeconfig for a ServiceAccount limited to one namespace, for sharing with a teammate
+#[tauri::command]
+pub async fn generate_scoped_kubeconfig(
     namespace: String,
-) -> Result<Vec<serde_json::Value>, String> {
-    crate::helm::get_history(&name, &namespace)
+    service_account: String,
+    duration: Option<String>,
+    role: Option<String>,
+    client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<ScopedKubeconfig, String> {
+    // Only creating the ServiceAccount and RoleBinding changes the cluster
+    if role.is_some() {
+        ensure_mutation_allowed(&app, confirm)?;
+    }
+
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::generate_scoped_kubeconfig(
+        client,
+        &namespace,
+        &service_account,
+        duration.as_deref(),
+        role.as_deref(),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_ingresses(
+    namespace: String,
+    label_selector: Option<String>,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<IngressInfo>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::list_ingresses(client, &namespace, label_selector.as_deref())
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn helm_get_chart_values(chart: String) -> Result<String, String> {
-    crate::helm::get_chart_values(&chart)
+pub async fn get_istio_virtual_services(
+    namespace: String,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<IstioVirtualServiceInfo>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::list_istio_virtual_services(client, &namespace)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Atomically update the traffic split of a VirtualService HTTP route
 #[tauri::command]
-pub async fn helm_upgrade_release(
+pub async fn set_virtualservice_weights(
+    namespace: String,
     name: String,
-    chart: String,
+    http_route_index: Option<usize>,
+    weights: Vec<VirtualServiceWeight>,
+    client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::set_virtualservice_weights(
+        client,
+        &namespace,
+        &name,
+        http_route_index.unwrap_or(0),
+        weights,
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Clusters, listeners, certificates and key stats from a pod's istio-proxy
+#[tauri::command]
+pub async fn get_proxy_diagnostics(
     namespace: String,
-    values: Option<String>,
-    create_namespace: bool,
-    version: Option<String>,
-) -> Result<String, String> {
-    crate::helm::upgrade_release(&name, &chart, &namespace, values.as_deref(), create_namespace, version.as_deref())
+    pod_name: String,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<ProxyDiagnostics, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::get_proxy_diagnostics(client, &namespace, &pod_name)
         .await
         .map_err(|e| e.to_string())
 }
 
-// ==================== Metrics Commands ====================
+#[tauri::command]
+pub async fn get_proxy_stats(
+    namespace: String,
+    pod_name: String,
+    filter: Option<String>,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<std::collections::HashMap<String, String>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::get_proxy_stats(client, &namespace, &pod_name, filter.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
 
 #[tauri::command]
-pub async fn detect_metrics_capabilities(
+pub async fn get_proxy_config_dump(
+    namespace: String,
+    pod_name: String,
+    resource: Option<String>,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<crate::metrics::MetricsCapabilities, String> {
+) -> Result<serde_json::Value, String> {
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::metrics::detect_metrics_capabilities(client)
+    crate::kube::get_proxy_config_dump(client, &namespace, &pod_name, resource.as_deref())
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn get_cluster_metrics_data(
+pub async fn get_istio_gateways(
+    namespace: String,
     client_manager: State<'_, KubeClientManager>,
-) -> Result<crate::metrics::ClusterMetricsData, String> {
+) -> Result<Vec<IstioGatewayInfo>, String> {
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::metrics::get_cluster_metrics(client)
+    crate::kube::list_istio_gateways(client, &namespace)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Any object as YAML, CRs included; `resource_type` takes the forms [`crate::kube::get_resource_yaml`] does
 #[tauri::command]
-pub async fn get_namespace_pod_metrics(
+pub async fn get_resource_yaml(
+    resource_type: String,
     namespace: Option<String>,
-) -> Result<Vec<crate::metrics::PodMetrics>, String> {
-    crate::metrics::get_pod_metrics(namespace.as_deref())
+    name: String,
+    client_manager: State<'_, KubeClientManager>,
+    list_cache: State<'_, crate::kube::ListCache>,
+) -> Result<String, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let (resource, scope) = list_cache
+        .resolve_kind(&client, &cache_context(None), &resource_type)
+        .await
+        .map_err(|e| e.to_string())?;
+    crate::kube::get_object_yaml(client, &resource, &scope, namespace.as_deref().unwrap_or(""), &name)
         .await
         .map_err(|e| e.to_string())
 }
 
-// ==================== CloudNativePG Commands ====================
+#[tauri::command]
+pub async fn get_configmaps(
+    namespace: String,
+    label_selector: Option<String>,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<ConfigMapInfo>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::list_configmaps(client, &namespace, label_selector.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
 
 #[tauri::command]
-pub async fn get_cnpg_cluster_connection(
+pub async fn get_secrets(
+    namespace: String,
+    label_selector: Option<String>,
     client_manager: State<'_, KubeClientManager>,
-    cluster_name: String,
+) -> Result<Vec<SecretInfo>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::list_secrets(client, &namespace, label_selector.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_statefulsets(
     namespace: String,
-) -> Result<crate::types::CNPGConnectionDetails, String> {
+    label_selector: Option<String>,
+    query: Option<ListQuery>,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<StatefulSetInfo>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let items = crate::kube::list_statefulsets(client, &namespace, label_selector.as_deref())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    match query {
+        Some(query) => crate::kube::query_statefulsets(items, &query).map_err(|e| e.to_string()),
+        None => Ok(items),
+    }
+}
+
+#[tauri::command]
+pub async fn get_statefulsets_page(
+    namespace: String,
+    label_selector: Option<String>,
+    limit: Option<u32>,
+    continue_token: Option<String>,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<ListPage<StatefulSetInfo>, String> {
+    let client = client_manager.get_client().await.map_err(|e| e.to_string())?;
+    crate::kube::list_statefulsets_page(
+        client,
+        &namespace,
+        label_selector.as_deref(),
+        limit,
+        continue_token.as_deref(),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_daemonsets(
+    namespace: String,
+    label_selector: Option<String>,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<DaemonSetInfo>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::list_daemonsets(client, &namespace, label_selector.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_daemonsets_page(
+    namespace: String,
+    label_selector: Option<String>,
+    limit: Option<u32>,
+    continue_token: Option<String>,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<ListPage<DaemonSetInfo>, String> {
+    let client = client_manager.get_client().await.map_err(|e| e.to_string())?;
+    crate::kube::list_daemonsets_page(
+        client,
+        &namespace,
+        label_selector.as_deref(),
+        limit,
+        continue_token.as_deref(),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_jobs(
+    namespace: String,
+    label_selector: Option<String>,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<JobInfo>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::list_jobs(client, &namespace, label_selector.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_jobs_page(
+    namespace: String,
+    label_selector: Option<String>,
+    limit: Option<u32>,
+    continue_token: Option<String>,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<ListPage<JobInfo>, String> {
+    let client = client_manager.get_client().await.map_err(|e| e.to_string())?;
+    crate::kube::list_jobs_page(
+        client,
+        &namespace,
+        label_selector.as_deref(),
+        limit,
+        continue_token.as_deref(),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_cronjobs(
+    namespace: String,
+    label_selector: Option<String>,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<CronJobInfo>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::list_cronjobs(client, &namespace, label_selector.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_nodes(
+    query: Option<ListQuery>,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<NodeInfo>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let items = crate::kube::list_nodes(client)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    match query {
+        Some(query) => crate::kube::query_nodes(items, &query).map_err(|e| e.to_string()),
+        None => Ok(items),
+    }
+}
+
+#[tauri::command]
+pub async fn get_node_groups_summary(
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<NodeGroupSummary>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::get_node_groups_summary(client)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Karpenter/cluster-autoscaler state, why pending pods aren't getting nodes, and recent scaling events
+#[tauri::command]
+pub async fn get_autoscaler_insight(
+    since: Option<String>,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<AutoscalerInsight, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::get_autoscaler_insight(client, since.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_node_alerts(
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<NodeAlert>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::get_node_alerts(client)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Start pushing `node-alert-{id}` events for node pressure and spot interruptions; returns the monitor id
+#[tauri::command]
+pub async fn start_node_alert_monitor(
+    app: AppHandle,
+    interval_secs: Option<u64>,
+    client_manager: State<'_, KubeClientManager>,
+    alert_monitor: State<'_, crate::kube::NodeAlertMonitor>,
+) -> Result<String, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    alert_monitor
+        .start(app, client, interval_secs)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn stop_node_alert_monitor(
+    monitor_id: String,
+    alert_monitor: State<'_, crate::kube::NodeAlertMonitor>,
+) -> Result<(), String> {
+    alert_monitor
+        .stop(&monitor_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_events(
+    namespace: String,
+    field_selector: Option<String>,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<EventInfo>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::list_events(client, &namespace, field_selector.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Resolve a name from a temporary pod in `namespace` and check CoreDNS around it
+#[tauri::command]
+pub async fn debug_dns(
+    name: String,
+    namespace: String,
+    image: Option<String>,
+    client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<DnsDebugReport, String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::debug_dns(client, &name, &namespace, image.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Events for a single object, matched by kind, namespace, name and uid
+#[tauri::command]
+pub async fn get_events_for_object(
+    kind: String,
+    namespace: Option<String>,
+    name: String,
+    uid: Option<String>,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<EventInfo>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::get_events_for_object(client, &kind, namespace.as_deref(), &name, uid.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Events since a time (RFC3339 or relative like `2h`) as CSV or JSON, with owners resolved
+#[tauri::command]
+pub async fn export_events(
+    namespace: Option<String>,
+    since: Option<String>,
+    format: String,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<EventExport, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::export_events(client, namespace.as_deref(), since.as_deref(), &format)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Any kind as a table of kubectl-style custom columns (`HEADER:.json.path`), with a CSV rendering
+#[tauri::command]
+pub async fn render_resource_table(
+    kind: String,
+    namespace: Option<String>,
+    columns: Vec<String>,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<ResourceTable, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::render_resource_table(client, &kind, namespace.as_deref(), &columns)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Run a jq expression over all objects of a kind, fed in as a kubectl-style List
+#[tauri::command]
+pub async fn query_resources(
+    kind: String,
+    namespace: Option<String>,
+    expression: String,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<QueryResult, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::query_resources(client, &kind, namespace.as_deref(), &expression)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// What in the namespace refers to a PVC, Secret, ConfigMap, ServiceAccount, Service or workload
+#[tauri::command]
+pub async fn find_references(
+    kind: String,
+    namespace: String,
+    name: String,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<ResourceReference>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::find_references(client, &kind, &namespace, &name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Events, rollouts, condition changes and restarts for one workload in time order
+#[tauri::command]
+pub async fn get_workload_timeline(
+    kind: String,
+    namespace: String,
+    name: String,
+    since: Option<String>,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<TimelineEntry>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::get_workload_timeline(client, &kind, &namespace, &name, since.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_persistent_volumes(
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<PersistentVolumeInfo>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::list_persistent_volumes(client)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_persistent_volume_claims(
+    namespace: String,
+    label_selector: Option<String>,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<PersistentVolumeClaimInfo>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::list_persistent_volume_claims(client, &namespace, label_selector.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// RBAC Commands
+#[tauri::command]
+pub async fn get_roles(
+    namespace: String,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<RoleInfo>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::list_roles(client, &namespace)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_role_bindings(
+    namespace: String,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<RoleBindingInfo>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::list_role_bindings(client, &namespace)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_cluster_roles(
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<ClusterRoleInfo>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::list_cluster_roles(client)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_cluster_role_bindings(
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<ClusterRoleBindingInfo>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::list_cluster_role_bindings(client)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_service_accounts(
+    namespace: String,
+    label_selector: Option<String>,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<ServiceAccountInfo>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::list_service_accounts(client, &namespace, label_selector.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Apply an edited manifest; `mode` is check (default), force, latest or recreate
+#[tauri::command]
+pub async fn apply_resource_yaml(
+    resource_type: String,
+    namespace: Option<String>,
+    yaml_content: String,
+    client_manager: State<'_, KubeClientManager>,
+    mode: Option<String>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+    crate::policy::enforce(&app, &yaml_content)
+        .await
+        .map_err(write_error)?;
+
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::apply_resource_yaml(
+        client,
+        &resource_type,
+        namespace.as_deref().unwrap_or(""),
+        &yaml_content,
+        mode.as_deref(),
+    )
+    .await
+    .map_err(write_error)
+}
+
+/// Compare an edited manifest with the live object before applying it
+///
+/// A report with `requires_recreate` can be applied with mode `recreate`.
+#[tauri::command]
+pub async fn check_immutable_fields(
+    resource_type: String,
+    namespace: Option<String>,
+    yaml_content: String,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<ImmutableFieldReport, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::check_immutable_fields(client, &resource_type, namespace.as_deref().unwrap_or(""), &yaml_content)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Delete and re-apply an object in one step; returns the operation id for its progress events
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn recreate_resource(
+    resource_type: String,
+    namespace: Option<String>,
+    name: String,
+    yaml_content: Option<String>,
+    preserve_pvcs: Option<bool>,
+    client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<String, String> {
+    ensure_mutation_allowed(&app, confirm)?;
+    if let Some(yaml) = &yaml_content {
+        crate::policy::enforce(&app, yaml).await.map_err(write_error)?;
+    }
+
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::recreate_resource(
+        app,
+        client,
+        &resource_type,
+        namespace.as_deref().unwrap_or(""),
+        &name,
+        yaml_content.as_deref(),
+        preserve_pvcs.unwrap_or(true),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Conflicts, policy violations and recreate-required errors are serialized as JSON so the UI can act on them
+fn write_error(e: anyhow::Error) -> String {
+    if let Some(violations) = e.downcast_ref::<PolicyViolations>() {
+        return serde_json::to_string(violations).unwrap_or_else(|_| violations.to_string());
+    }
+    if let Some(report) = e.downcast_ref::<ImmutableFieldReport>() {
+        return serde_json::to_string(report).unwrap_or_else(|_| report.to_string());
+    }
+    match e.downcast_ref::<ResourceConflict>() {
+        Some(conflict) => serde_json::to_string(conflict).unwrap_or_else(|_| conflict.to_string()),
+        None => e.to_string(),
+    }
+}
+
+/// Schema-check a manifest against the cluster's OpenAPI definitions before applying it
+#[tauri::command]
+pub async fn lint_manifest(
+    yaml_content: String,
+    k8s_version: Option<String>,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<ManifestLintResult>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::lint_manifest(client, &yaml_content, k8s_version.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Built-in boilerplate for the new resource editor
+#[tauri::command]
+pub async fn list_resource_templates() -> Result<Vec<ResourceTemplate>, String> {
+    Ok(crate::templates::list_templates())
+}
+
+#[tauri::command]
+pub async fn render_template(
+    name: String,
+    params: std::collections::HashMap<String, String>,
+) -> Result<String, String> {
+    crate::templates::render_template(&name, &params).map_err(|e| e.to_string())
+}
+
+/// Run a manifest through the policy gate's policies, whether or not the gate is enabled
+#[tauri::command]
+pub async fn check_manifest_policies(app: AppHandle, manifest: String) -> Result<PolicyReport, String> {
+    let gate = crate::preferences::get_policy_gate(&app).map_err(|e| e.to_string())?;
+    crate::policy::check_manifest(&app, &gate, &manifest)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_policy_gate(app: AppHandle) -> Result<PolicyGate, String> {
+    crate::preferences::get_policy_gate(&app).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_policy_gate(app: AppHandle, gate: PolicyGate) -> Result<(), String> {
+    crate::preferences::set_policy_gate(&app, &gate).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn scale_statefulset(
+    namespace: String,
+    statefulset_name: String,
+    replicas: i32,
+    client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::scale_statefulset(client, &namespace, &statefulset_name, replicas)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn restart_statefulset(
+    namespace: String,
+    statefulset_name: String,
+    client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::restart_statefulset(client, &namespace, &statefulset_name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_statefulset_pvcs(
+    namespace: String,
+    name: String,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<StatefulSetPvcInfo>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::get_statefulset_pvcs(client, &namespace, &name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Delete PVCs orphaned by a StatefulSet scale-down; returns the affected PVC names
+#[tauri::command]
+pub async fn delete_orphaned_pvcs(
+    namespace: String,
+    name: String,
+    dry_run: Option<bool>,
+    client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<Vec<String>, String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::delete_orphaned_pvcs(client, &namespace, &name, dry_run.unwrap_or(false))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_csi_drivers(
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<CsiDriverInfo>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::list_csi_drivers(client)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_csi_nodes(
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<CsiNodeInfo>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::list_csi_nodes(client)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// VolumeAttachments with attach/detach errors; stuck ones sort first
+#[tauri::command]
+pub async fn get_volume_attachments(
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<VolumeAttachmentInfo>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::list_volume_attachments(client)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Pods -> volumes -> PVCs -> PVs -> StorageClasses, with kubelet-reported usage where available
+#[tauri::command]
+pub async fn get_storage_topology(
+    namespace: Option<String>,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<StorageTopology, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::get_storage_topology(client, namespace.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// PVC/emptyDir filesystem usage and container ephemeral storage from kubelet stats, flagged above a threshold
+#[tauri::command]
+pub async fn get_volume_usage(
+    namespace: Option<String>,
+    threshold_percent: Option<f64>,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<VolumeUsageReport, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::get_volume_usage(client, namespace.as_deref(), threshold_percent)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_statefulset(
+    namespace: String,
+    statefulset_name: String,
+    client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::delete_statefulset(client, &namespace, &statefulset_name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn restart_daemonset(
+    namespace: String,
+    daemonset_name: String,
+    client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::restart_daemonset(client, &namespace, &daemonset_name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_daemonset(
+    namespace: String,
+    daemonset_name: String,
+    client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::delete_daemonset(client, &namespace, &daemonset_name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_job(
+    namespace: String,
+    job_name: String,
+    client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::delete_job(client, &namespace, &job_name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn suspend_cronjob(
+    namespace: String,
+    cronjob_name: String,
+    client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
+    let client = client_manager.get_client().await.map_err(|e| e.to_string())?;
+    crate::kube::suspend_cronjob(client, &namespace, &cronjob_name)
+        .await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn resume_cronjob(
+    namespace: String,
+    cronjob_name: String,
+    client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
+    let client = client_manager.get_client().await.map_err(|e| e.to_string())?;
+    crate::kube::resume_cronjob(client, &namespace, &cronjob_name)
+        .await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_cronjob(
+    namespace: String,
+    cronjob_name: String,
+    client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
+    let client = client_manager.get_client().await.map_err(|e| e.to_string())?;
+    crate::kube::delete_cronjob(client, &namespace, &cronjob_name)
+        .await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_pods_for_resource(
+    resource_type: String,
+    resource_name: String,
+    namespace: String,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<PodInfo>, String> {
+    let client = client_manager.get_client().await.map_err(|e| e.to_string())?;
+    crate::kube::get_pods_for_resource(client, &resource_type, &resource_name, &namespace)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// Port Forward Commands
+#[tauri::command]
+pub async fn start_port_forward(
+    resource_type: String,
+    resource_name: String,
+    namespace: String,
+    local_port: u16,
+    remote_port: u16,
+    portforward_manager: State<'_, crate::portforward::PortForwardManager>,
+    app: AppHandle,
+) -> Result<crate::types::PortForwardInfo, String> {
+    let info = portforward_manager
+        .start_port_forward(&resource_type, &resource_name, &namespace, local_port, remote_port)
+        .await
+        .map_err(|e| e.to_string())?;
+    let _ = crate::tray::refresh(&app).await;
+    Ok(info)
+}
+
+#[tauri::command]
+pub async fn stop_port_forward(
+    id: String,
+    portforward_manager: State<'_, crate::portforward::PortForwardManager>,
+    app: AppHandle,
+) -> Result<(), String> {
+    portforward_manager
+        .stop_port_forward(&id)
+        .await
+        .map_err(|e| e.to_string())?;
+    let _ = crate::tray::refresh(&app).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn stop_all_port_forwards(
+    portforward_manager: State<'_, crate::portforward::PortForwardManager>,
+    app: AppHandle,
+) -> Result<(), String> {
+    portforward_manager
+        .stop_all()
+        .await
+        .map_err(|e| e.to_string())?;
+    let _ = crate::tray::refresh(&app).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_port_forwards(
+    portforward_manager: State<'_, crate::portforward::PortForwardManager>,
+) -> Result<Vec<crate::types::PortForwardInfo>, String> {
+    Ok(portforward_manager.list_port_forwards().await)
+}
+
+// Tray Commands
+
+/// Current context, remembered namespace and running forwards, as the tray shows them
+#[tauri::command]
+pub async fn get_tray_status(app: AppHandle) -> Result<TrayStatus, String> {
+    Ok(crate::tray::status(&app).await)
+}
+
+/// Remember the namespace the user is in, for the tray's "open last namespace"
+#[tauri::command]
+pub async fn set_last_namespace(app: AppHandle, namespace: Option<String>) -> Result<(), String> {
+    app.state::<crate::tray::TrayState>().set_last_namespace(namespace);
+    crate::tray::refresh(&app).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn refresh_tray(app: AppHandle) -> Result<(), String> {
+    crate::tray::refresh(&app).await.map_err(|e| e.to_string())
+}
+
+/// The deep link the app was opened with, once; the frontend calls this on startup
+#[tauri::command]
+pub async fn take_pending_deep_link(app: AppHandle) -> Result<Option<DeepLink>, String> {
+    let pending = app.state::<crate::deeplink::PendingDeepLink>();
+    let mut pending = pending.0.lock().map_err(|e| e.to_string())?;
+    Ok(pending.take())
+}
+
+// App Log Commands
+
+/// The app's own log, filtered to `level` and above and to entries after `since`
+#[tauri::command]
+pub async fn get_app_logs(
+    level: Option<String>,
+    since: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<AppLogEntry>, String> {
+    crate::logging::read_logs(level.as_deref(), since.as_deref(), limit).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_log_level(level: String) -> Result<(), String> {
+    crate::logging::set_level(&level).map_err(|e| e.to_string())
+}
+
+// Usage Statistics Commands
+
+/// Locally collected command counts and API latencies; also written to disk so it survives a crash
+#[tauri::command]
+pub async fn get_usage_report(app: AppHandle) -> Result<UsageReport, String> {
+    crate::usage::save().map_err(|e| e.to_string())?;
+    Ok(crate::usage::report(&app))
+}
+
+/// Opt in to or out of usage statistics; opting out deletes what was collected
+#[tauri::command]
+pub async fn set_usage_stats_enabled(app: AppHandle, enabled: bool) -> Result<(), String> {
+    crate::usage::set_enabled(&app, enabled).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn reset_usage_stats() -> Result<(), String> {
+    crate::usage::reset().map_err(|e| e.to_string())
+}
+
+/// Capture a pprof or actuator profile through an existing port-forward or a temporary one to a pod
+///
+/// Pass `forward_id` to reuse a running port-forward, or `namespace` and
+/// `pod_name` (and optionally `port`) to tunnel just for this capture.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn capture_profile(
+    app: AppHandle,
+    forward_id: Option<String>,
+    namespace: Option<String>,
+    pod_name: Option<String>,
+    port: Option<u16>,
+    endpoint_type: String,
+    duration_secs: Option<u64>,
+    client_manager: State<'_, KubeClientManager>,
+    portforward_manager: State<'_, crate::portforward::PortForwardManager>,
+) -> Result<ProfileCaptureResult, String> {
+    let target = match (&forward_id, &namespace, &pod_name) {
+        (Some(id), _, _) => {
+            let local_port = portforward_manager
+                .local_port(id)
+                .await
+                .ok_or_else(|| format!("Port forward {} is not running", id))?;
+            crate::kube::ProfileTarget::LocalPort(local_port)
+        }
+        (None, Some(namespace), Some(pod_name)) => crate::kube::ProfileTarget::Pod {
+            namespace,
+            pod_name,
+            port,
+        },
+        _ => return Err("Either a port forward or a pod is required".to_string()),
+    };
+
+    let output_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("profiles");
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::capture_profile(client, target, &endpoint_type, duration_secs, &output_dir)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// Node Operations
+#[tauri::command]
+pub async fn cordon_node(
+    node_name: String,
+    client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::cordon_node(client, &node_name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn uncordon_node(
+    node_name: String,
+    client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::uncordon_node(client, &node_name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn drain_node(
+    node_name: String,
+    client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::drain_node(client, &node_name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Cordon and drain every node matching `selector`, `max_parallel` at a time; returns the drain id for its events
+#[tauri::command]
+pub async fn drain_node_group(
+    selector: String,
+    max_parallel: Option<usize>,
+    pause_between_secs: Option<u64>,
+    client_manager: State<'_, KubeClientManager>,
+    drain_manager: State<'_, crate::kube::NodeDrainManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<String, String> {
+    if selector.trim().is_empty() {
+        return Err("A node selector is required; an empty one would drain every node".to_string());
+    }
+    ensure_mutation_allowed(&app, confirm)?;
+
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    drain_manager
+        .start_drain(app, client, &selector, max_parallel, pause_between_secs)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn stop_node_group_drain(
+    drain_id: String,
+    drain_manager: State<'_, crate::kube::NodeDrainManager>,
+) -> Result<(), String> {
+    drain_manager
+        .stop_drain(&drain_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_node(
+    node_name: String,
+    client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::delete_node(client, &node_name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn describe_node(
+    node_name: String,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<String, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::describe_node(client, &node_name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn describe_resource(
+    resource_type: String,
+    namespace: Option<String>,
+    name: String,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<String, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::describe_resource(
+        client,
+        &resource_type,
+        namespace.as_deref(),
+        &name,
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+// ==================== Monitoring Integration Commands ====================
+
+/// Prometheus/Alertmanager endpoints for a context (the current one by default)
+#[tauri::command]
+pub async fn get_cluster_integrations(
+    app: AppHandle,
+    context: Option<String>,
+) -> Result<ClusterIntegrations, String> {
+    crate::preferences::get_cluster_integrations(&app, context.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_cluster_integrations(
+    app: AppHandle,
+    context: Option<String>,
+    integrations: ClusterIntegrations,
+) -> Result<(), String> {
+    let context = match context {
+        Some(ctx) => ctx,
+        None => load_kubeconfig().map_err(|e| e.to_string())?.current_context,
+    };
+    crate::preferences::set_cluster_integrations(&app, &context, integrations).map_err(|e| e.to_string())
+}
+
+fn prometheus_endpoint(app: &AppHandle) -> Result<crate::preferences::ServiceEndpoint, String> {
+    crate::preferences::get_cluster_integrations(app, None)
+        .map_err(|e| e.to_string())?
+        .prometheus
+        .ok_or_else(|| "Prometheus is not configured for this cluster".to_string())
+}
+
+#[tauri::command]
+pub async fn get_prometheus_alerts(
+    app: AppHandle,
+    namespace: Option<String>,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<PrometheusAlert>, String> {
+    let endpoint = prometheus_endpoint(&app)?;
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::list_prometheus_alerts(client, &endpoint, namespace.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_prometheus_rules(
+    app: AppHandle,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<AlertingRule>, String> {
+    let endpoint = prometheus_endpoint(&app)?;
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::list_alerting_rules(client, &endpoint)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_alertmanager_silences(
+    app: AppHandle,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<AlertSilence>, String> {
+    let endpoint = crate::preferences::get_cluster_integrations(&app, None)
+        .map_err(|e| e.to_string())?
+        .alertmanager
+        .ok_or_else(|| "Alertmanager is not configured for this cluster".to_string())?;
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::list_alertmanager_silences(client, &endpoint)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Grafana dashboard URLs for a namespace or workload on the current cluster
+#[tauri::command]
+pub async fn get_grafana_links(
+    app: AppHandle,
+    namespace: String,
+    kind: Option<String>,
+    name: Option<String>,
+    time_range: Option<String>,
+) -> Result<Vec<GrafanaLink>, String> {
+    let context = load_kubeconfig().map_err(|e| e.to_string())?.current_context;
+    let grafana = crate::preferences::get_cluster_integrations(&app, Some(&context))
+        .map_err(|e| e.to_string())?
+        .grafana
+        .ok_or_else(|| "Grafana is not configured for this cluster".to_string())?;
+
+    crate::grafana::grafana_links(
+        &grafana,
+        &context,
+        &namespace,
+        kind.as_deref(),
+        name.as_deref(),
+        time_range.as_deref(),
+    )
+    .map_err(|e| e.to_string())
+}
+
+// Shell commands
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn start_shell_session(
+    app: AppHandle,
+    pod_name: String,
+    namespace: String,
+    container: Option<String>,
+    shell: Option<String>,
+    record: Option<bool>,
+    client_manager: State<'_, KubeClientManager>,
+    shell_manager: State<'_, ShellManager>,
+) -> Result<String, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    shell_manager
+        .start_session(app, client, pod_name, namespace, container, shell, record.unwrap_or(false))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn send_shell_input(
+    app: AppHandle,
+    session_id: String,
+    data: String,
+    shell_manager: State<'_, ShellManager>,
+) -> Result<(), String> {
+    shell_manager
+        .send_input(&app, &session_id, data)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn close_shell_session(
+    session_id: String,
+    shell_manager: State<'_, ShellManager>,
+) -> Result<(), String> {
+    shell_manager
+        .close_session(&session_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_shell_recordings(app: AppHandle) -> Result<Vec<ShellRecordingInfo>, String> {
+    crate::recording::list_recordings(&app).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_shell_recording(app: AppHandle, file_name: String) -> Result<(), String> {
+    crate::recording::delete_recording(&app, &file_name).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_recording_retention_days(app: AppHandle) -> Result<u32, String> {
+    crate::preferences::get_recording_retention_days(&app).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_recording_retention_days(app: AppHandle, days: u32) -> Result<(), String> {
+    crate::preferences::set_recording_retention_days(&app, days).map_err(|e| e.to_string())?;
+
+    // Apply the new retention right away rather than at the next recording
+    let dir = crate::recording::recordings_dir(&app).map_err(|e| e.to_string())?;
+    crate::recording::prune_recordings(&dir, days).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_shell_policy(app: AppHandle) -> Result<ShellPolicy, String> {
+    crate::preferences::get_shell_policy(&app).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_shell_policy(app: AppHandle, policy: ShellPolicy) -> Result<(), String> {
+    crate::preferences::set_shell_policy(&app, &policy).map_err(|e| e.to_string())
+}
+
+/// Per-context defaults; the current context when none is given
+#[tauri::command]
+pub async fn get_context_settings(
+    app: AppHandle,
+    context: Option<String>,
+) -> Result<crate::settings::ContextSettings, String> {
+    crate::settings::get_context_settings(&app, context.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_context_settings(
+    app: AppHandle,
+    context: String,
+    settings: crate::settings::ContextSettings,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<(), String> {
+    let (qps, burst) = (settings.qps, settings.burst);
+    crate::settings::set_context_settings(&app, &context, settings).map_err(|e| e.to_string())?;
+    client_manager.set_rate_limit(&context, qps, burst);
+    Ok(())
+}
+
+/// Context settings, tags, integrations and policies as JSON to share with a team
+#[tauri::command]
+pub async fn export_settings(app: AppHandle) -> Result<String, String> {
+    crate::settings::export_settings(&app).map_err(|e| e.to_string())
+}
+
+/// Load a settings export; `replace` drops local entries the export doesn't have
+#[tauri::command]
+pub async fn import_settings(
+    app: AppHandle,
+    content: String,
+    replace: Option<bool>,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<crate::settings::SettingsImportResult, String> {
+    crate::settings::import_settings(&app, &client_manager, &content, replace.unwrap_or(false))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_audit_log(app: AppHandle, limit: Option<usize>) -> Result<Vec<AuditEntry>, String> {
+    crate::audit::read_entries(&app, limit.unwrap_or(500)).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_pod_containers(
+    pod_name: String,
+    namespace: String,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<String>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::get_pod_containers(client, &namespace, &pod_name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// CRD Commands
+#[tauri::command]
+pub async fn get_crds(
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<CRDInfo>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::list_crds(client)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_custom_resources(
+    client_manager: State<'_, KubeClientManager>,
+    group: String,
+    version: String,
+    plural: String,
+    namespace: Option<String>,
+) -> Result<Vec<CustomResourceInfo>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::list_custom_resources(
+        client,
+        &cache_context(None),
+        &group,
+        &version,
+        &plural,
+        namespace.as_deref(),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn delete_custom_resource(
+    client_manager: State<'_, KubeClientManager>,
+    group: String,
+    version: String,
+    plural: String,
+    name: String,
+    namespace: Option<String>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::delete_custom_resource(
+        client,
+        &cache_context(None),
+        &group,
+        &version,
+        &plural,
+        &name,
+        namespace.as_deref(),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_custom_resource_yaml(
+    client_manager: State<'_, KubeClientManager>,
+    group: String,
+    version: String,
+    plural: String,
+    name: String,
+    namespace: Option<String>,
+) -> Result<String, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::get_custom_resource_yaml(
+        client,
+        &cache_context(None),
+        &group,
+        &version,
+        &plural,
+        &name,
+        namespace.as_deref(),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Write an edited custom resource; `mode` is check (default), force or latest
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn update_custom_resource_yaml(
+    client_manager: State<'_, KubeClientManager>,
+    group: String,
+    version: String,
+    plural: String,
+    name: String,
+    namespace: Option<String>,
+    yaml: String,
+    mode: Option<String>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::update_custom_resource_yaml(
+        client,
+        &cache_context(None),
+        &group,
+        &version,
+        &plural,
+        &name,
+        namespace.as_deref(),
+        &yaml,
+        mode.as_deref(),
+    )
+    .await
+    .map_err(write_error)
+}
+
+#[tauri::command]
+pub async fn describe_custom_resource(
+    client_manager: State<'_, KubeClientManager>,
+    group: String,
+    version: String,
+    plural: String,
+    name: String,
+    namespace: Option<String>,
+) -> Result<String, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::describe_custom_resource(
+        client,
+        &cache_context(None),
+        &group,
+        &version,
+        &plural,
+        &name,
+        namespace.as_deref(),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn sync_argocd_app(
+    client_manager: State<'_, KubeClientManager>,
+    name: String,
+    namespace: String,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::sync_argocd_app(client, &name, &namespace)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ==================== Helm Commands ====================
+
+#[tauri::command]
+pub async fn helm_check_installed() -> Result<bool, String> {
+    crate::helm::check_helm_installed()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn helm_list_releases(
+    namespace: Option<String>,
+    all_namespaces: bool,
+) -> Result<Vec<crate::helm::HelmRelease>, String> {
+    crate::helm::list_releases(namespace.as_deref(), all_namespaces)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn helm_get_release(
+    name: String,
+    namespace: String,
+) -> Result<crate::helm::HelmReleaseDetail, String> {
+    crate::helm::get_release(&name, &namespace)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn helm_get_manifest(name: String, namespace: String) -> Result<String, String> {
+    crate::helm::get_manifest(&name, &namespace)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn helm_get_values(name: String, namespace: String) -> Result<String, String> {
+    crate::helm::get_values(&name, &namespace)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn helm_uninstall_release(
+    name: String,
+    namespace: String,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<String, String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
+    crate::helm::uninstall_release(&name, &namespace)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn helm_rollback_release(
+    name: String,
+    namespace: String,
+    revision: u32,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<String, String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
+    crate::helm::rollback_release(&name, &namespace, revision)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn helm_get_history(
+    name: String,
+    namespace: String,
+) -> Result<Vec<serde_json::Value>, String> {
+    crate::helm::get_history(&name, &namespace)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn helm_get_chart_values(chart: String) -> Result<String, String> {
+    crate::helm::get_chart_values(&chart)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Schema, defaults and merged values for building a values form
+#[tauri::command]
+pub async fn helm_get_values_schema(
+    chart: String,
+    version: Option<String>,
+    release: Option<String>,
+    namespace: Option<String>,
+    values: Option<String>,
+) -> Result<crate::helm::HelmValuesSchema, String> {
+    crate::helm::get_values_schema(
+        &chart,
+        version.as_deref(),
+        release.as_deref(),
+        namespace.as_deref(),
+        values.as_deref(),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Install or upgrade a release (`helm upgrade --install`)
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn helm_upgrade_release(
+    name: String,
+    chart: String,
+    namespace: String,
+    values: Option<String>,
+    create_namespace: bool,
+    version: Option<String>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<String, String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
+    // Only render the chart when there is a gate to check it against
+    if crate::preferences::get_policy_gate(&app).map(|g| g.enabled).unwrap_or(false) {
+        let manifest = crate::helm::template_release(&name, &chart, &namespace, values.as_deref(), version.as_deref())
+            .await
+            .map_err(|e| e.to_string())?;
+        crate::policy::enforce(&app, &manifest)
+            .await
+            .map_err(write_error)?;
+    }
+
+    crate::helm::upgrade_release(&name, &chart, &namespace, values.as_deref(), create_namespace, version.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ==================== Metrics Commands ====================
+
+#[tauri::command]
+pub async fn detect_metrics_capabilities(
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<crate::metrics::MetricsCapabilities, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::metrics::detect_metrics_capabilities(client)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_cluster_metrics_data(
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<crate::metrics::ClusterMetricsData, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::metrics::get_cluster_metrics(client)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_namespace_pod_metrics(
+    namespace: Option<String>,
+) -> Result<Vec<crate::metrics::PodMetrics>, String> {
+    crate::metrics::get_pod_metrics(namespace.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Usage samples for one container; keeps the pod sampled while the UI polls this
+#[tauri::command]
+pub async fn get_container_usage_history(
+    namespace: String,
+    pod: String,
+    container: String,
+    sampler: State<'_, MetricsSampler>,
+) -> Result<crate::metrics::ContainerUsageHistory, String> {
+    sampler
+        .get_container_usage_history(&namespace, &pod, &container)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Start a live top view; snapshots arrive as `top-stream-{stream_id}` events
+#[tauri::command]
+pub async fn start_top_stream(
+    app: AppHandle,
+    scope: String,
+    namespace: Option<String>,
+    sort_by: Option<String>,
+    limit: Option<usize>,
+    interval_secs: Option<u64>,
+    top_manager: State<'_, TopStreamManager>,
+) -> Result<String, String> {
+    top_manager
+        .start_stream(app, &scope, namespace, sort_by.as_deref(), limit, interval_secs)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn stop_top_stream(
+    stream_id: String,
+    top_manager: State<'_, TopStreamManager>,
+) -> Result<(), String> {
+    top_manager
+        .stop_stream(&stream_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Convert a cpu or memory quantity string to millicores or bytes
+#[tauri::command]
+pub async fn normalize_quantity(
+    quantity: String,
+    kind: String,
+) -> Result<crate::quantity::NormalizedQuantity, String> {
+    crate::quantity::normalize_quantity(&quantity, &kind).map_err(|e| e.to_string())
+}
+
+// ==================== Tekton Commands ====================
+
+#[tauri::command]
+pub async fn get_pipeline_runs(
+    namespace: String,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<PipelineRunInfo>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::list_pipeline_runs(client, &namespace)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_task_runs(
+    namespace: String,
+    pipeline_run: Option<String>,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<TaskRunInfo>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::list_task_runs(client, &namespace, pipeline_run.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Cancel a PipelineRun or TaskRun (`kind` is the Tekton kind)
+#[tauri::command]
+pub async fn cancel_tekton_run(
+    namespace: String,
+    kind: String,
+    name: String,
+    client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::cancel_tekton_run(client, &namespace, &kind, &name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Start a copy of a PipelineRun or TaskRun; returns the new run's name
+#[tauri::command]
+pub async fn rerun_tekton_run(
+    namespace: String,
+    kind: String,
+    name: String,
+    client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<String, String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::rerun_tekton_run(client, &namespace, &kind, &name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Follow a TaskRun's step logs; lines arrive as `tekton-log-{stream_id}` events
+#[tauri::command]
+pub async fn start_task_run_logs(
+    app: AppHandle,
+    namespace: String,
+    task_run: String,
+    client_manager: State<'_, KubeClientManager>,
+    log_manager: State<'_, crate::kube::TektonLogManager>,
+) -> Result<String, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    log_manager
+        .start_stream(app, client, &namespace, &task_run)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn stop_task_run_logs(
+    stream_id: String,
+    log_manager: State<'_, crate::kube::TektonLogManager>,
+) -> Result<(), String> {
+    log_manager
+        .stop_stream(&stream_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ==================== Knative Commands ====================
+
+#[tauri::command]
+pub async fn get_knative_services(
+    namespace: String,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<KnativeServiceInfo>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::list_knative_services(client, &namespace)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_knative_revisions(
+    namespace: String,
+    service: Option<String>,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<KnativeRevisionInfo>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::list_knative_revisions(client, &namespace, service.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Replace a Knative Service's traffic split; percentages must total 100
+#[tauri::command]
+pub async fn set_knative_traffic(
+    namespace: String,
+    service: String,
+    targets: Vec<KnativeTrafficTarget>,
+    client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<(), String> {
+    ensure_mutation_allowed(&app, confirm)?;
+
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::set_knative_traffic(client, &namespace, &service, targets)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ==================== CloudNativePG Commands ====================
+
+/// CNPG connection details, with the password masked unless it is explicitly asked for
+#[tauri::command]
+pub async fn get_cnpg_connection_details(
+    client_manager: State<'_, KubeClientManager>,
+    cluster_name: String,
+    namespace: String,
+    reveal_password: Option<bool>,
+) -> Result<crate::types::CNPGConnectionDetails, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let details = crate::kube::get_cnpg_cluster_connection(client, &cluster_name, &namespace)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if reveal_password.unwrap_or(false) {
+        Ok(details)
+    } else {
+        Ok(crate::kube::redact_cnpg_connection(details))
+    }
+}
+
+/// Copy a CNPG connection URI to the system clipboard without sending it to the UI
+#[tauri::command]
+pub async fn copy_cnpg_connection_uri(
+    app: AppHandle,
+    client_manager: State<'_, KubeClientManager>,
+    cluster_name: String,
+    namespace: String,
+    uri_kind: Option<String>,
+) -> Result<(), String> {
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let details = crate::kube::get_cnpg_cluster_connection(client, &cluster_name, &namespace)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let text = match uri_kind.as_deref().unwrap_or("uri") {
+        "uri" => details.uri,
+        "fqdn_uri" => details.fqdn_uri,
+        "jdbc_uri" => details.jdbc_uri,
+        "fqdn_jdbc_uri" => details.fqdn_jdbc_uri,
+        "pgpass" => details.pgpass,
+        "password" => details.password,
+        other => return Err(format!("Unknown connection string kind: {}", other)),
+    };
+
+    app.clipboard()
+        .write_text(text)
+        .map_err(|e| e.to_string())
+}
+
+// ==================== kubectl Plugin Commands ====================
+
+#[tauri::command]
+pub async fn list_kubectl_plugins() -> Result<Vec<crate::plugins::KubectlPlugin>, String> {
+    Ok(crate::plugins::list_plugins())
+}
+
+#[tauri::command]
+pub async fn run_kubectl_plugin(
+    name: String,
+    args: Vec<String>,
+    namespace: Option<String>,
+    context: Option<String>,
+) -> Result<CommandOutput, String> {
+    crate::plugins::run_plugin(&name, &args, namespace.as_deref(), context.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Start a long-running plugin; output arrives as `plugin-output-{run_id}` events
+#[tauri::command]
+pub async fn start_kubectl_plugin(
+    app: AppHandle,
+    name: String,
+    args: Vec<String>,
+    namespace: Option<String>,
+    context: Option<String>,
+    plugin_manager: State<'_, PluginManager>,
+) -> Result<String, String> {
+    plugin_manager
+        .start_plugin(app, &name, args, namespace.as_deref(), context.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn stop_kubectl_plugin(
+    run_id: String,
+    plugin_manager: State<'_, PluginManager>,
+) -> Result<(), String> {
+    plugin_manager
+        .stop_plugin(&run_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ==================== Extension Commands ====================
+
+#[tauri::command]
+pub async fn list_extensions(
+    registry: State<'_, crate::extensions::ExtensionRegistry>,
+) -> Result<Vec<crate::extensions::ExtensionInfo>, String> {
+    Ok(registry.list().await)
+}
+
+/// Run a command contributed by an extension; the result's shape depends on the command's `view`
+#[tauri::command]
+pub async fn invoke_extension(
+    app: AppHandle,
+    extension_id: String,
+    command: String,
+    args: Option<serde_json::Value>,
+    context: Option<String>,
+    client_manager: State<'_, KubeClientManager>,
+    registry: State<'_, crate::extensions::ExtensionRegistry>,
+) -> Result<serde_json::Value, String> {
+    // Extensions that only run a process don't need a cluster, so an unreachable one isn't an error here
+    let client = client_manager.get_client_in(context.as_deref()).await.ok();
+    let ctx = crate::extensions::ExtensionContext { app, client, context };
+    registry
+        .invoke(&extension_id, &command, args.unwrap_or(serde_json::Value::Null), ctx)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Re-read extensions.json; returns the ids of the external extensions now loaded
+#[tauri::command]
+pub async fn reload_extensions(
+    app: AppHandle,
+    registry: State<'_, crate::extensions::ExtensionRegistry>,
+) -> Result<Vec<String>, String> {
+    registry.load_external(&app).await.map_err(|e| e.to_string())
+}
+
+// ==================== Automation Commands ====================
+
+/// Run a YAML automation script; steps report as `automation-step-{run_id}`
+/// events and the outcome as `automation-complete-{run_id}`
+#[tauri::command]
+pub async fn start_automation(
+    app: AppHandle,
+    script: String,
+    confirm: Option<bool>,
+    client_manager: State<'_, KubeClientManager>,
+    automation_manager: State<'_, crate::automation::AutomationManager>,
+) -> Result<String, String> {
+    let script = crate::automation::parse_script(&script).map_err(|e| e.to_string())?;
+    // Checked once up front, so a protected context can't stop a run halfway through
+    if script.is_mutating() {
+        ensure_mutation_allowed(&app, confirm)?;
+    }
+
+    let client = client_manager.get_client().await.map_err(|e| e.to_string())?;
+    automation_manager
+        .start_run(app, client, script)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Cancel a running script; the current step is abandoned and the rest are skipped
+#[tauri::command]
+pub async fn stop_automation(
+    run_id: String,
+    automation_manager: State<'_, crate::automation::AutomationManager>,
+) -> Result<(), String> {
+    automation_manager.stop_run(&run_id).await.map_err(|e| e.to_string())
+}
+
+// ==================== kubectl Passthrough Commands ====================
+
+/// Run raw kubectl arguments against an explicitly pinned context
+#[tauri::command]
+pub async fn run_kubectl(
+    args: Vec<String>,
+    context: Option<String>,
+    kubeconfig: Option<String>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<crate::kubectl::KubectlRunResult, String> {
+    // Tags are keyed by context name, so a context from a custom kubeconfig is checked too
+    if crate::kubectl::is_mutating(&args) {
+        let effective = crate::kubectl::effective_context(context.as_deref(), kubeconfig.as_deref())
+            .map_err(|e| e.to_string())?;
+        crate::preferences::ensure_mutation_allowed(&app, Some(&effective), confirm.unwrap_or(false))
+            .map_err(|e| e.to_string())?;
+    }
+
+    crate::kubectl::run_kubectl(args, context.as_deref(), kubeconfig.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ==================== Cluster Version Commands ====================
+
+#[tauri::command]
+pub async fn get_cluster_version_info(
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<ClusterVersionInfo, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::get_cluster_version_info(client)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Find objects authored with deprecated APIs, optionally only those removed by `target_version`
+#[tauri::command]
+pub async fn scan_deprecated_apis(
+    target_version: Option<String>,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<DeprecationReport, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::scan_deprecated_apis(client, target_version.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_cluster_overview(
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<ClusterOverview, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::get_cluster_overview(client)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Ranked list of recent Warning events grouped by kind and reason
+#[tauri::command]
+pub async fn get_cluster_problems(
+    since_minutes: Option<i64>,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<Vec<ClusterProblem>, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::get_cluster_problems(client, since_minutes)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Resolve which Ingress rule and backend a hypothetical request would hit
+#[tauri::command]
+pub async fn test_ingress_route(
+    namespace: String,
+    ingress: String,
+    host: String,
+    path: String,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<IngressRouteTestResult, String> {
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::test_ingress_route(client, &namespace, &ingress, &host, &path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ==================== Namespace Clone Commands ====================
+
+/// Copy selected resources from `source` into `target`, creating it if needed
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn clone_namespace(
+    source: String,
+    target: String,
+    kinds: Option<Vec<String>>,
+    transform_rules: Option<NamespaceCloneRules>,
+    dry_run: Option<bool>,
+    client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<NamespaceCloneResult, String> {
+    if !dry_run.unwrap_or(false) {
+        ensure_mutation_allowed(&app, confirm)?;
+    }
+
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::clone_namespace(
+        client,
+        &source,
+        &target,
+        kinds,
+        transform_rules.unwrap_or_default(),
+        dry_run.unwrap_or(false),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Copy one object (optionally with its ConfigMap/Secret dependencies) to another namespace or context
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn copy_resource(
+    src_ref: ResourceRef,
+    dst_context: Option<String>,
+    dst_namespace: String,
+    rename: Option<String>,
+    include_dependencies: Option<bool>,
+    app: AppHandle,
+    confirm: Option<bool>,
+    client_manager: State<'_, KubeClientManager>,
+) -> Result<CopyResourceResult, String> {
+    crate::preferences::ensure_mutation_allowed(&app, dst_context.as_deref(), confirm.unwrap_or(false))
+        .map_err(|e| e.to_string())?;
+
+    let source_client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let target_client = match dst_context.as_deref() {
+        Some(context) => client_manager
+            .get_client_for(context)
+            .await
+            .map_err(|e| e.to_string())?,
+        None => source_client.clone(),
+    };
+
+    crate::kube::copy_resource(
+        source_client,
+        target_client,
+        &src_ref,
+        &dst_namespace,
+        rename.as_deref(),
+        include_dependencies.unwrap_or(false),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+// ==================== Creation Wizard Commands ====================
+
+/// Create a Deployment from a small structured spec; returns the resulting manifest
+#[tauri::command]
+pub async fn create_deployment_simple(
+    spec: SimpleDeploymentSpec,
+    dry_run: Option<bool>,
+    client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<String, String> {
+    if !dry_run.unwrap_or(false) {
+        ensure_mutation_allowed(&app, confirm)?;
+    }
+
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::create_deployment_simple(client, spec, dry_run.unwrap_or(false))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn create_service_for_workload(
+    spec: SimpleServiceSpec,
+    dry_run: Option<bool>,
+    client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<String, String> {
+    if !dry_run.unwrap_or(false) {
+        ensure_mutation_allowed(&app, confirm)?;
+    }
+
+    let client = client_manager
+        .get_client()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::kube::create_service_for_workload(client, spec, dry_run.unwrap_or(false))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn create_ingress_simple(
+    spec: SimpleIngressSpec,
+    dry_run: Option<bool>,
+    client_manager: State<'_, KubeClientManager>,
+    app: AppHandle,
+    confirm: Option<bool>,
+) -> Result<String, String> {
+    if !dry_run.unwrap_or(false) {
+        ensure_mutation_allowed(&app, confirm)?;
+    }
+
     let client = client_manager
         .get_client()
         .await
         .map_err(|e| e.to_string())?;
 
-    crate::kube::get_cnpg_cluster_connection(client, &cluster_name, &namespace)
+    crate::kube::create_ingress_simple(client, spec, dry_run.unwrap_or(false))
         .await
         .map_err(|e| e.to_string())
 }
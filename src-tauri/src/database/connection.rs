@@ -6,11 +6,15 @@ use tokio::sync::RwLock;
 use tokio_postgres::NoTls;
 
 /// Database connection with connection pooling
-#[derive(Debug)]
+///
+/// Clones share the pool, so one can be worked on (e.g. reconnected) without
+/// holding the connection manager lock.
+#[derive(Debug, Clone)]
 pub struct DatabaseConnection {
     pub info: DbConnectionInfo,
     pub pool: Pool,
     port_forward: DatabasePortForward,
+    pool_config: Config,
 }
 
 impl DatabaseConnection {
@@ -71,12 +75,43 @@ impl DatabaseConnection {
         });
 
         // Create the pool
-        let pool = cfg
-            .create_pool(Some(Runtime::Tokio1), NoTls)
-            .map_err(|e| DatabaseError::ConfigError(format!("Failed to create pool: {}", e)))?;
+        let pool = Self::build_pool(&cfg)?;
 
         tracing::info!("Connection pool created, testing connection to 127.0.0.1:{}...", port_forward.local_port);
 
+        Self::verify_pool(&pool).await?;
+
+        tracing::info!("Database connection established successfully");
+
+        // The port-forward runs against the current context; later cluster calls must too
+        let context = crate::kube::load_kubeconfig()
+            .map(|c| c.current_context)
+            .unwrap_or_default();
+        let info = DbConnectionInfo {
+            connection_id: connection_id.clone(),
+            context,
+            cluster_name: cluster_name.to_string(),
+            namespace: namespace.to_string(),
+            database: database.to_string(),
+            local_port: port_forward.local_port,
+        };
+
+        Ok(Self {
+            info,
+            pool,
+            port_forward,
+            pool_config: cfg,
+        })
+    }
+
+    /// Create a connection pool from the given configuration
+    fn build_pool(cfg: &Config) -> DatabaseResult<Pool> {
+        cfg.create_pool(Some(Runtime::Tokio1), NoTls)
+            .map_err(|e| DatabaseError::ConfigError(format!("Failed to create pool: {}", e)))
+    }
+
+    /// Verify that the pool can hand out a working connection
+    async fn verify_pool(pool: &Pool) -> DatabaseResult<()> {
         // Test the connection with detailed error logging
         let client = pool.get().await.map_err(|e| {
             tracing::error!("Failed to get connection from pool: {}", e);
@@ -94,21 +129,51 @@ impl DatabaseConnection {
                 DatabaseError::PostgresError(e)
             })?;
 
-        tracing::info!("Database connection established successfully");
+        Ok(())
+    }
 
-        let info = DbConnectionInfo {
-            connection_id: connection_id.clone(),
-            cluster_name: cluster_name.to_string(),
-            namespace: namespace.to_string(),
-            database: database.to_string(),
-            local_port: port_forward.local_port,
+    /// Check whether the kubectl port-forward backing this connection is alive
+    pub async fn port_forward_alive(&self, pf_manager: &PortForwardManager) -> bool {
+        pf_manager.is_running(&self.port_forward.port_forward_id).await
+    }
+
+    /// Re-establish the port-forward and rebuild the connection pool
+    ///
+    /// Used by the connection supervisor after the kubectl process dies, so
+    /// callers get a fresh pool instead of timing out on dead sockets. The old
+    /// pool is shared with other clones and is left open; whoever swaps this
+    /// connection in closes it. On failure the new port-forward is stopped.
+    pub async fn reconnect(&mut self, pf_manager: &PortForwardManager) -> DatabaseResult<()> {
+        tracing::info!("Reconnecting database connection: {}", self.info.connection_id);
+
+        self.port_forward.reestablish(pf_manager).await?;
+
+        // Give kubectl a moment to bind the new local port
+        tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+
+        self.pool_config.port = Some(self.port_forward.local_port);
+        let pool = match Self::build_pool(&self.pool_config) {
+            Ok(pool) => pool,
+            Err(e) => {
+                let _ = DatabasePortForward::stop(pf_manager, &self.port_forward.port_forward_id).await;
+                return Err(e);
+            }
         };
+        if let Err(e) = Self::verify_pool(&pool).await {
+            pool.close();
+            let _ = DatabasePortForward::stop(pf_manager, &self.port_forward.port_forward_id).await;
+            return Err(e);
+        }
 
-        Ok(Self {
-            info,
-            pool,
-            port_forward,
-        })
+        self.pool = pool;
+        self.info.local_port = self.port_forward.local_port;
+
+        tracing::info!(
+            "Database connection {} restored on localhost:{}",
+            self.info.connection_id,
+            self.info.local_port
+        );
+        Ok(())
     }
 
     /// Get a client from the connection pool
@@ -147,6 +212,11 @@ impl DatabaseConnection {
         &self.info
     }
 
+    /// Get the pool configuration (host, port and credentials)
+    pub fn pool_config(&self) -> &Config {
+        &self.pool_config
+    }
+
     /// Check if the connection is healthy
     pub async fn health_check(&self) -> DatabaseResult<bool> {
         match self.pool.get().await {
@@ -191,6 +261,7 @@ mod tests {
     fn test_connection_info() {
         let info = DbConnectionInfo {
             connection_id: "test-123".to_string(),
+            context: "test-context".to_string(),
             cluster_name: "test-cluster".to_string(),
             namespace: "test-ns".to_string(),
             database: "testdb".to_string(),
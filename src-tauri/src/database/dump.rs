@@ -0,0 +1,221 @@
+use crate::database::{DatabaseConnection, DatabaseError, DatabaseResult, DbDumpResult};
+use k8s_openapi::api::core::v1::Pod;
+use kube::api::{Api, AttachParams, ListParams};
+use kube::Client;
+use std::time::Instant;
+use tokio::io::AsyncReadExt;
+use tokio::process::Command;
+
+/// Dump a database to a local file
+///
+/// Runs the local `pg_dump` through the connection's port-forward when it is
+/// installed, otherwise executes `pg_dump` inside the cluster's primary pod and
+/// streams its output back into the target file.
+pub async fn dump_database(
+    client: Client,
+    conn: &DatabaseConnection,
+    path: &str,
+    format: &str,
+) -> DatabaseResult<DbDumpResult> {
+    let format_flag = pg_dump_format_flag(format)?;
+    let start = Instant::now();
+
+    let method = match dump_with_local_pg_dump(conn, path, format_flag).await {
+        Ok(()) => "local",
+        Err(DumpAttempt::NotInstalled) => {
+            tracing::info!("pg_dump not found locally, running it inside the cluster instead");
+            dump_with_exec(client, conn, path, format_flag).await?;
+            "exec"
+        }
+        Err(DumpAttempt::Failed(e)) => return Err(e),
+    };
+
+    let size_bytes = tokio::fs::metadata(path)
+        .await
+        .map(|m| m.len())
+        .unwrap_or(0);
+
+    tracing::info!(
+        "Dumped database {} to {} ({} bytes, via {})",
+        conn.info().database,
+        path,
+        size_bytes,
+        method
+    );
+
+    Ok(DbDumpResult {
+        path: path.to_string(),
+        format: format.to_string(),
+        method: method.to_string(),
+        size_bytes,
+        duration_ms: start.elapsed().as_millis() as u64,
+    })
+}
+
+enum DumpAttempt {
+    NotInstalled,
+    Failed(DatabaseError),
+}
+
+/// Map a user-facing format name to pg_dump's --format value
+fn pg_dump_format_flag(format: &str) -> DatabaseResult<&'static str> {
+    match format.to_lowercase().as_str() {
+        "custom" | "c" => Ok("c"),
+        "plain" | "sql" | "p" => Ok("p"),
+        "tar" | "t" => Ok("t"),
+        _ => Err(DatabaseError::ConfigError(format!(
+            "Unsupported dump format: {} (expected custom, plain or tar)",
+            format
+        ))),
+    }
+}
+
+async fn dump_with_local_pg_dump(
+    conn: &DatabaseConnection,
+    path: &str,
+    format_flag: &str,
+) -> Result<(), DumpAttempt> {
+    let cfg = conn.pool_config();
+
+    let mut cmd = Command::new("pg_dump");
+    cmd.arg("--host").arg("127.0.0.1");
+    cmd.arg("--port").arg(conn.info().local_port.to_string());
+    cmd.arg("--dbname").arg(&conn.info().database);
+    cmd.arg("--format").arg(format_flag);
+    cmd.arg("--file").arg(path);
+    cmd.arg("--no-password");
+
+    if let Some(user) = &cfg.user {
+        cmd.arg("--username").arg(user);
+    }
+    if let Some(password) = &cfg.password {
+        cmd.env("PGPASSWORD", password);
+    }
+
+    let output = match cmd.output().await {
+        Ok(output) => output,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Err(DumpAttempt::NotInstalled),
+        Err(e) => {
+            return Err(DumpAttempt::Failed(DatabaseError::QueryError(format!(
+                "Failed to run pg_dump: {}",
+                e
+            ))))
+        }
+    };
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(DumpAttempt::Failed(DatabaseError::QueryError(format!(
+            "pg_dump failed: {}",
+            stderr
+        ))));
+    }
+
+    Ok(())
+}
+
+async fn dump_with_exec(
+    client: Client,
+    conn: &DatabaseConnection,
+    path: &str,
+    format_flag: &str,
+) -> DatabaseResult<()> {
+    let info = conn.info();
+    let pods: Api<Pod> = Api::namespaced(client, &info.namespace);
+    let pod_name = find_primary_pod(&pods, &info.cluster_name).await?;
+
+    tracing::info!("Running pg_dump in pod {}/{}", info.namespace, pod_name);
+
+    let command = vec![
+        "pg_dump".to_string(),
+        "--dbname".to_string(),
+        info.database.clone(),
+        "--format".to_string(),
+        format_flag.to_string(),
+    ];
+
+    let attach_params = AttachParams::default()
+        .container("postgres")
+        .stdin(false)
+        .stdout(true)
+        .stderr(true);
+
+    let mut attached = pods
+        .exec(&pod_name, command, &attach_params)
+        .await
+        .map_err(|e| DatabaseError::Other(e.into()))?;
+
+    let mut stdout = attached
+        .stdout()
+        .ok_or_else(|| DatabaseError::QueryError("pg_dump produced no stdout stream".to_string()))?;
+    let mut stderr = attached
+        .stderr()
+        .ok_or_else(|| DatabaseError::QueryError("pg_dump produced no stderr stream".to_string()))?;
+    let status = attached.take_status();
+
+    let mut file = tokio::fs::File::create(path)
+        .await
+        .map_err(|e| DatabaseError::Other(e.into()))?;
+
+    let mut stderr_output = String::new();
+    let (copy_result, _) = tokio::join!(
+        tokio::io::copy(&mut stdout, &mut file),
+        stderr.read_to_string(&mut stderr_output)
+    );
+    copy_result.map_err(|e| DatabaseError::Other(e.into()))?;
+
+    let status = match status {
+        Some(status) => status.await,
+        None => None,
+    };
+
+    let succeeded = status
+        .as_ref()
+        .and_then(|s| s.status.as_deref())
+        .map(|s| s == "Success")
+        .unwrap_or(false);
+
+    if !succeeded {
+        let _ = tokio::fs::remove_file(path).await;
+        return Err(DatabaseError::QueryError(format!(
+            "pg_dump failed in pod {}: {}",
+            pod_name,
+            stderr_output.trim()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Find the running primary instance pod of a CloudNativePG cluster
+async fn find_primary_pod(pods: &Api<Pod>, cluster_name: &str) -> DatabaseResult<String> {
+    // Newer CNPG versions use instanceRole, older ones use role
+    let selectors = [
+        format!("cnpg.io/cluster={},cnpg.io/instanceRole=primary", cluster_name),
+        format!("cnpg.io/cluster={},role=primary", cluster_name),
+    ];
+
+    for selector in selectors.iter() {
+        let list = pods
+            .list(&ListParams::default().labels(selector))
+            .await
+            .map_err(|e| DatabaseError::Other(e.into()))?;
+
+        let running = list.items.into_iter().find(|pod| {
+            pod.status
+                .as_ref()
+                .and_then(|s| s.phase.as_deref())
+                .map(|p| p == "Running")
+                .unwrap_or(false)
+        });
+
+        if let Some(pod) = running {
+            return Ok(pod.metadata.name.unwrap_or_default());
+        }
+    }
+
+    Err(DatabaseError::ConfigError(format!(
+        "No running primary pod found for cluster {}",
+        cluster_name
+    )))
+}
@@ -4,8 +4,10 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 
 pub mod connection;
+pub mod dump;
 pub mod portforward;
 pub mod queries;
+pub mod supervisor;
 
 pub use connection::DatabaseConnection;
 pub use portforward::DatabasePortForward;
@@ -17,12 +19,22 @@ pub type ConnectionManager = Arc<RwLock<HashMap<String, DatabaseConnection>>>;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DbConnectionInfo {
     pub connection_id: String,
+    pub context: String,  // Kubeconfig context the cluster was reached through
     pub cluster_name: String,
     pub namespace: String,
     pub database: String,
     pub local_port: u16,
 }
 
+/// Connection state change emitted by the connection supervisor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbConnectionStatus {
+    pub connection_id: String,
+    pub status: String, // connected, reconnecting or failed
+    pub local_port: Option<u16>,
+    pub message: Option<String>,
+}
+
 /// Database table information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DbTable {
@@ -91,6 +103,48 @@ pub struct DbSchema {
     pub owner: Option<String>,
 }
 
+/// Backend session from pg_stat_activity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbActivity {
+    pub pid: i32,
+    pub database: Option<String>,
+    pub username: Option<String>,
+    pub application_name: Option<String>,
+    pub client_addr: Option<String>,
+    pub backend_type: Option<String>,
+    pub state: Option<String>,
+    pub wait_event_type: Option<String>,
+    pub wait_event: Option<String>,
+    pub query: Option<String>,
+    pub query_start: Option<String>,
+    pub query_duration_secs: Option<f64>,
+    pub blocking_pids: Vec<i32>,
+}
+
+/// Lock held or awaited by a backend, from pg_locks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbLock {
+    pub pid: Option<i32>,
+    pub lock_type: String,
+    pub mode: String,
+    pub granted: bool,
+    pub relation: Option<String>,
+    pub username: Option<String>,
+    pub query: Option<String>,
+    pub query_duration_secs: Option<f64>,
+    pub blocking_pids: Vec<i32>,
+}
+
+/// Result of a pg_dump run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbDumpResult {
+    pub path: String,
+    pub format: String,
+    pub method: String, // local (pg_dump over port-forward) or exec (inside the pod)
+    pub size_bytes: u64,
+    pub duration_ms: u64,
+}
+
 /// Error type for database operations
 #[derive(Debug, thiserror::Error)]
 pub enum DatabaseError {
@@ -78,6 +78,47 @@ impl DatabasePortForward {
         })
     }
 
+    /// Re-establish a port forward whose kubectl process has died
+    ///
+    /// The old forward is dropped and a new one is started on a fresh local
+    /// port, since the previous port may still be held by a lingering socket.
+    pub async fn reestablish(&mut self, pf_manager: &PortForwardManager) -> DatabaseResult<()> {
+        tracing::info!(
+            "Re-establishing port-forward for {}/{}",
+            self.namespace,
+            self.service_name
+        );
+
+        // The old entry may already be gone if the dead process was cleaned up
+        let _ = pf_manager.stop_port_forward(&self.port_forward_id).await;
+
+        let local_port = Self::find_free_port().await?;
+
+        let pf_info = pf_manager
+            .start_port_forward(
+                "service",
+                &self.service_name,
+                &self.namespace,
+                local_port,
+                self.remote_port,
+            )
+            .await
+            .map_err(|e| {
+                DatabaseError::PortForwardError(format!("Failed to re-create port-forward: {}", e))
+            })?;
+
+        self.port_forward_id = pf_info.id;
+        self.local_port = local_port;
+
+        tracing::info!(
+            "Port-forward re-established: {} -> localhost:{}",
+            self.service_name,
+            local_port
+        );
+
+        Ok(())
+    }
+
     /// Find an available port for port-forwarding
     async fn find_free_port() -> DatabaseResult<u16> {
         use std::net::{TcpListener, SocketAddr};
@@ -1,6 +1,6 @@
 use crate::database::{
-    DatabaseConnection, DatabaseError, DatabaseResult, DbColumn, DbDatabase, DbSchema, DbTable,
-    QueryResult, TableDataRequest,
+    DatabaseConnection, DatabaseError, DatabaseResult, DbActivity, DbColumn, DbDatabase, DbLock,
+    DbSchema, DbTable, QueryResult, TableDataRequest,
 };
 use std::collections::HashMap;
 use std::time::Instant;
@@ -222,6 +222,14 @@ pub async fn execute_custom_query(
     query: &str,
 ) -> DatabaseResult<QueryResult> {
     let client = conn.get_client().await?;
+    run_custom_query(&client, query).await
+}
+
+/// Execute a custom SQL query on a client already taken from a connection's pool
+pub async fn run_custom_query(
+    client: &deadpool_postgres::Client,
+    query: &str,
+) -> DatabaseResult<QueryResult> {
     let start = Instant::now();
 
     // Prepare the query
@@ -306,6 +314,116 @@ async fn execute_query(
     })
 }
 
+/// List backend sessions from pg_stat_activity, excluding our own
+pub async fn list_activity(conn: &DatabaseConnection) -> DatabaseResult<Vec<DbActivity>> {
+    let client = conn.get_client().await?;
+
+    let rows = client
+        .query(
+            r#"
+            SELECT
+                pid,
+                datname::text as database,
+                usename::text as username,
+                application_name,
+                client_addr::text as client_addr,
+                backend_type,
+                state,
+                wait_event_type,
+                wait_event,
+                query,
+                query_start::text as query_start,
+                EXTRACT(EPOCH FROM (now() - query_start))::float8 as query_duration_secs,
+                pg_blocking_pids(pid) as blocking_pids
+            FROM pg_stat_activity
+            WHERE pid <> pg_backend_pid()
+            ORDER BY query_start NULLS LAST
+            "#,
+            &[],
+        )
+        .await?;
+
+    let activity = rows
+        .into_iter()
+        .map(|row| DbActivity {
+            pid: row.get("pid"),
+            database: row.get("database"),
+            username: row.get("username"),
+            application_name: row.get("application_name"),
+            client_addr: row.get("client_addr"),
+            backend_type: row.get("backend_type"),
+            state: row.get("state"),
+            wait_event_type: row.get("wait_event_type"),
+            wait_event: row.get("wait_event"),
+            query: row.get("query"),
+            query_start: row.get("query_start"),
+            query_duration_secs: row.get("query_duration_secs"),
+            blocking_pids: row
+                .get::<_, Option<Vec<i32>>>("blocking_pids")
+                .unwrap_or_default(),
+        })
+        .collect();
+
+    Ok(activity)
+}
+
+/// List locks from pg_locks joined with the owning session, waiting locks first
+pub async fn list_locks(conn: &DatabaseConnection) -> DatabaseResult<Vec<DbLock>> {
+    let client = conn.get_client().await?;
+
+    let rows = client
+        .query(
+            r#"
+            SELECT
+                l.pid,
+                l.locktype as lock_type,
+                l.mode,
+                l.granted,
+                l.relation::regclass::text as relation,
+                a.usename::text as username,
+                a.query,
+                EXTRACT(EPOCH FROM (now() - a.query_start))::float8 as query_duration_secs,
+                pg_blocking_pids(l.pid) as blocking_pids
+            FROM pg_locks l
+            LEFT JOIN pg_stat_activity a ON a.pid = l.pid
+            WHERE l.pid IS DISTINCT FROM pg_backend_pid()
+            ORDER BY l.granted, l.pid
+            "#,
+            &[],
+        )
+        .await?;
+
+    let locks = rows
+        .into_iter()
+        .map(|row| DbLock {
+            pid: row.get("pid"),
+            lock_type: row.get("lock_type"),
+            mode: row.get("mode"),
+            granted: row.get("granted"),
+            relation: row.get("relation"),
+            username: row.get("username"),
+            query: row.get("query"),
+            query_duration_secs: row.get("query_duration_secs"),
+            blocking_pids: row
+                .get::<_, Option<Vec<i32>>>("blocking_pids")
+                .unwrap_or_default(),
+        })
+        .collect();
+
+    Ok(locks)
+}
+
+/// Terminate a backend session with pg_terminate_backend
+pub async fn terminate_backend(conn: &DatabaseConnection, pid: i32) -> DatabaseResult<bool> {
+    let client = conn.get_client().await?;
+
+    let row = client
+        .query_one("SELECT pg_terminate_backend($1)", &[&pid])
+        .await?;
+
+    Ok(row.get(0))
+}
+
 /// Convert a row value to JSON
 fn row_value_to_json(row: &tokio_postgres::Row, idx: usize) -> serde_json::Value {
     use tokio_postgres::types::Type;
@@ -0,0 +1,127 @@
+use crate::database::{ConnectionManager, DbConnectionStatus};
+use crate::portforward::PortForwardManager;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// How often the port-forward behind a connection is checked
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Upper bound for the delay between failed reconnect attempts
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Watch a database connection and re-establish it when its port-forward dies
+///
+/// Emits `db-connection-status-{connection_id}` events whenever the connection
+/// state changes. The task exits once the connection is removed from the
+/// manager (i.e. after `db_disconnect`).
+pub fn spawn_supervisor(app: AppHandle, connection_manager: ConnectionManager, connection_id: String) {
+    tokio::spawn(async move {
+        let event_name = format!("db-connection-status-{}", connection_id);
+        let mut failures: u32 = 0;
+
+        loop {
+            tokio::time::sleep(backoff_delay(failures)).await;
+
+            let pf_manager = app.state::<PortForwardManager>();
+
+            let alive = {
+                let manager = connection_manager.read().await;
+                match manager.get(&connection_id) {
+                    Some(conn) => conn.port_forward_alive(pf_manager.inner()).await,
+                    None => break,
+                }
+            };
+
+            if alive && failures == 0 {
+                continue;
+            }
+
+            tracing::warn!(
+                "Port-forward for database connection {} is down, reconnecting",
+                connection_id
+            );
+            let _ = app.emit(
+                &event_name,
+                DbConnectionStatus {
+                    connection_id: connection_id.clone(),
+                    status: "reconnecting".to_string(),
+                    local_port: None,
+                    message: None,
+                },
+            );
+
+            // Reconnecting takes seconds (new port-forward, test query), so it works on a
+            // copy and the write lock is only held to swap the result in
+            let Some(mut replacement) = connection_manager.read().await.get(&connection_id).cloned() else {
+                break;
+            };
+            let result = match replacement.reconnect(pf_manager.inner()).await {
+                Ok(()) => {
+                    let local_port = replacement.info().local_port;
+                    let mut manager = connection_manager.write().await;
+                    match manager.get_mut(&connection_id) {
+                        Some(conn) => {
+                            // Its port-forward is already gone; only the dead pool is left to close
+                            let old = std::mem::replace(conn, replacement);
+                            drop(manager);
+                            old.pool.close();
+                            Ok(local_port)
+                        }
+                        // Disconnected while reconnecting; don't leave the new port-forward behind
+                        None => {
+                            drop(manager);
+                            let _ = replacement.close(pf_manager.inner()).await;
+                            break;
+                        }
+                    }
+                }
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok(local_port) => {
+                    failures = 0;
+                    let _ = app.emit(
+                        &event_name,
+                        DbConnectionStatus {
+                            connection_id: connection_id.clone(),
+                            status: "connected".to_string(),
+                            local_port: Some(local_port),
+                            message: None,
+                        },
+                    );
+                }
+                Err(e) => {
+                    failures += 1;
+                    tracing::error!(
+                        "Reconnect attempt {} for database connection {} failed: {}",
+                        failures,
+                        connection_id,
+                        e
+                    );
+                    let _ = app.emit(
+                        &event_name,
+                        DbConnectionStatus {
+                            connection_id: connection_id.clone(),
+                            status: "failed".to_string(),
+                            local_port: None,
+                            message: Some(e.to_string()),
+                        },
+                    );
+                }
+            }
+        }
+
+        tracing::info!("Supervisor for database connection {} stopped", connection_id);
+    });
+}
+
+/// Delay before the next check, backing off exponentially after failures
+fn backoff_delay(failures: u32) -> Duration {
+    if failures == 0 {
+        return CHECK_INTERVAL;
+    }
+
+    let factor = 2u32.saturating_pow(failures.min(6));
+    (CHECK_INTERVAL * factor).min(MAX_BACKOFF)
+}
@@ -0,0 +1,189 @@
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::kube::KubeClientManager;
+use crate::tray;
+use crate::types::DeepLink;
+
+const DEEP_LINK_SCHEME: &str = "kubesail://";
+
+/// Views a deep link can open on a resource
+const DEEP_LINK_VIEWS: &[&str] = &["logs", "shell", "yaml", "describe", "events", "metrics"];
+
+/// The last deep link, kept until the frontend takes it, since a link that
+/// launched the app arrives before any listener is registered
+#[derive(Default)]
+pub(crate) struct PendingDeepLink(pub(crate) std::sync::Mutex<Option<DeepLink>>);
+
+fn percent_decode(segment: &str) -> String {
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = (bytes[i] == b'%')
+            .then(|| segment.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match hex {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// kubectl-style short names and plurals to the singular kind the frontend routes on
+fn normalize_link_kind(kind: &str) -> String {
+    let kind = kind.to_ascii_lowercase();
+    match kind.as_str() {
+        "po" | "pods" => "pod",
+        "deploy" | "deployments" => "deployment",
+        "sts" | "statefulsets" => "statefulset",
+        "ds" | "daemonsets" => "daemonset",
+        "svc" | "services" => "service",
+        "cm" | "configmaps" => "configmap",
+        "ing" | "ingresses" => "ingress",
+        "jobs" => "job",
+        "cj" | "cronjobs" => "cronjob",
+        "no" | "nodes" => "node",
+        _ => return kind,
+    }
+    .to_string()
+}
+
+/// Parse `kubesail://<context>[/<namespace>[/<kind>[/<name>[/<view>]]]][?container=<name>]`
+///
+/// `kubesail://<context>/<namespace>/<pod>/<view>` is accepted as a shorthand
+/// for pods, so `kubesail://prod/web/api-0/logs` opens that pod's logs.
+/// Segments are percent-decoded, so context names with `/` or `:` (EKS ARNs) work.
+fn parse(url: &str) -> Result<DeepLink, String> {
+    let rest = url
+        .strip_prefix(DEEP_LINK_SCHEME)
+        .ok_or_else(|| format!("Not a {} link: {}", DEEP_LINK_SCHEME, url))?;
+    let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let segments: Vec<String> = path
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(percent_decode)
+        .collect();
+
+    let Some((context, rest)) = segments.split_first() else {
+        return Err(format!("Link has no context: {}", url));
+    };
+    let (kind, name, view) = match rest {
+        [] | [_] => (None, None, None),
+        [_, kind] => (Some(normalize_link_kind(kind)), None, None),
+        [_, name, view] if DEEP_LINK_VIEWS.contains(&view.as_str()) => {
+            (Some("pod".to_string()), Some(name.clone()), Some(view.clone()))
+        }
+        [_, kind, name] => (Some(normalize_link_kind(kind)), Some(name.clone()), None),
+        [_, kind, name, view] => (Some(normalize_link_kind(kind)), Some(name.clone()), Some(view.clone())),
+        _ => return Err(format!("Too many path segments in {}", url)),
+    };
+    if let Some(view) = &view {
+        if !DEEP_LINK_VIEWS.contains(&view.as_str()) {
+            return Err(format!("Unknown view '{}'; expected one of {}", view, DEEP_LINK_VIEWS.join(", ")));
+        }
+    }
+
+    let container = query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == "container")
+        .map(|(_, value)| percent_decode(value));
+
+    Ok(DeepLink {
+        url: url.to_string(),
+        context: context.clone(),
+        namespace: rest.first().cloned(),
+        kind,
+        name,
+        view,
+        container,
+    })
+}
+
+/// Bring the window forward and hand the link to the frontend
+///
+/// Nothing is switched here: a link comes from outside the app, so the
+/// frontend gets a `deep-link` event and asks before opening anything. The
+/// link's context is only checked for reachability through the client pool,
+/// which leaves the kubeconfig's current-context alone. A link that can't be
+/// followed gets `deep-link-error` with a message instead.
+fn dispatch(app: &AppHandle, url: &str) {
+    let link = match parse(url) {
+        Ok(link) => link,
+        Err(e) => {
+            tracing::warn!("Ignoring deep link: {}", e);
+            let _ = app.emit("deep-link-error", e);
+            return;
+        }
+    };
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tray::show_window(&app);
+
+        if let Err(e) = app.state::<KubeClientManager>().get_client_for(&link.context).await {
+            let _ = app.emit("deep-link-error", format!("Cannot open {}: {}", link.url, e));
+            return;
+        }
+
+        if let Ok(mut pending) = app.state::<PendingDeepLink>().0.lock() {
+            *pending = Some(link.clone());
+        }
+        let _ = app.emit("deep-link", &link);
+    });
+}
+
+pub(crate) fn setup(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    use tauri_plugin_deep_link::DeepLinkExt;
+
+    // Installers register the scheme on macOS and Windows; Linux and dev builds need it at runtime
+    #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+    app.deep_link().register_all()?;
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            dispatch(&handle, url.as_str());
+        }
+    });
+
+    // The link that launched the app, if any
+    for url in app.deep_link().get_current()?.unwrap_or_default() {
+        dispatch(app, url.as_str());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let link = parse("kubesail://prod/web/api-0/logs?container=app").unwrap();
+        assert_eq!(link.context, "prod");
+        assert_eq!(link.namespace.as_deref(), Some("web"));
+        assert_eq!(link.kind.as_deref(), Some("pod"));
+        assert_eq!(link.name.as_deref(), Some("api-0"));
+        assert_eq!(link.view.as_deref(), Some("logs"));
+        assert_eq!(link.container.as_deref(), Some("app"));
+
+        let link = parse("kubesail://arn%3Aaws%3Aeks%3Aus-east-1%3A1%3Acluster%2Fprod/web/deploy/api").unwrap();
+        assert_eq!(link.context, "arn:aws:eks:us-east-1:1:cluster/prod");
+        assert_eq!(link.kind.as_deref(), Some("deployment"));
+        assert_eq!(link.name.as_deref(), Some("api"));
+        assert_eq!(link.view, None);
+
+        assert!(parse("kubesail://").is_err());
+        assert!(parse("https://prod/web").is_err());
+        assert!(parse("kubesail://prod/web/deploy/api/bogus").is_err());
+    }
+}
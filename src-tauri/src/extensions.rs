@@ -0,0 +1,293 @@
+use anyhow::{Context, Result};
+use futures::future::BoxFuture;
+use kube::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::RwLock;
+
+const EXTENSIONS_FILE: &str = "extensions.json";
+
+/// How long an external extension may take to answer one call
+const PROCESS_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A command an extension contributes, with a hint for how the frontend shows its result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtensionCommand {
+    pub name: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default = "default_view")]
+    pub view: String,  // table (array of objects), json, yaml or text
+    #[serde(default)]
+    pub kind: Option<String>,  // Resource kind whose page shows this command; None for the Extensions page
+}
+
+fn default_view() -> String {
+    "json".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtensionInfo {
+    pub id: String,
+    pub name: String,
+    pub source: String,  // builtin (compiled in) or external (configured process)
+    pub commands: Vec<ExtensionCommand>,
+}
+
+/// What an extension gets to work with for one call
+pub struct ExtensionContext {
+    pub app: AppHandle,
+    pub client: Option<Client>,  // None when no cluster is reachable
+    pub context: Option<String>,  // Kubeconfig context the call is for; None for the current one
+}
+
+/// Additional commands and resource views contributed from outside the built-in command list
+///
+/// Crates embedding the app implement this and pass their extensions to
+/// [`crate::run_with_extensions`]; everything is reached through the
+/// `list_extensions` and `invoke_extension` commands, so nothing has to be
+/// added to the handler list in `lib.rs`.
+pub trait Extension: Send + Sync {
+    /// Stable identifier, unique among extensions, e.g. `acme-billing`
+    fn id(&self) -> &str;
+
+    fn name(&self) -> &str {
+        self.id()
+    }
+
+    fn commands(&self) -> Vec<ExtensionCommand>;
+
+    /// Run one of [`commands`](Self::commands) with the arguments the frontend sent
+    fn invoke<'a>(&'a self, ctx: ExtensionContext, command: &'a str, args: Value) -> BoxFuture<'a, Result<Value>>;
+}
+
+/// An external program configured in `extensions.json`
+///
+/// Each call starts `command args... <command name>` with a JSON request
+/// (`{"command", "args", "context"}`) on stdin, and reads the result from
+/// stdout: JSON if it parses, otherwise the text as is. A non-zero exit fails
+/// the call with stderr as the message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessExtension {
+    pub id: String,
+    pub name: Option<String>,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    pub commands: Vec<ExtensionCommand>,
+}
+
+#[derive(Serialize)]
+struct ProcessRequest<'a> {
+    command: &'a str,
+    args: &'a Value,
+    context: Option<&'a str>,
+}
+
+impl Extension for ProcessExtension {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.id)
+    }
+
+    fn commands(&self) -> Vec<ExtensionCommand> {
+        self.commands.clone()
+    }
+
+    fn invoke<'a>(&'a self, ctx: ExtensionContext, command: &'a str, args: Value) -> BoxFuture<'a, Result<Value>> {
+        Box::pin(async move {
+            let request = serde_json::to_vec(&ProcessRequest {
+                command,
+                args: &args,
+                context: ctx.context.as_deref(),
+            })?;
+
+            let mut cmd = Command::new(&self.command);
+            cmd.args(&self.args)
+                .arg(command)
+                .envs(&self.env)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .kill_on_drop(true);
+            if let Some(context) = &ctx.context {
+                cmd.env("KUBESAIL_CONTEXT", context);
+            }
+
+            let mut child = cmd
+                .spawn()
+                .with_context(|| format!("Failed to start extension '{}' ({})", self.id, self.command))?;
+            // Written alongside reading the output, so a program that never reads its
+            // input (or fills stdout first) runs into the timeout instead of hanging
+            let stdin = child.stdin.take();
+            let writer = tokio::spawn(async move {
+                if let Some(mut stdin) = stdin {
+                    // A program that ignores its input may exit before reading it
+                    let _ = stdin.write_all(&request).await;
+                }
+            });
+
+            let output = tokio::time::timeout(PROCESS_TIMEOUT, child.wait_with_output()).await;
+            writer.abort();
+            let output = output
+                .map_err(|_| anyhow::anyhow!("Extension '{}' did not answer within {:?}", self.id, PROCESS_TIMEOUT))??;
+            if !output.status.success() {
+                anyhow::bail!(
+                    "Extension '{}' failed ({}): {}",
+                    self.id,
+                    output.status,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+            }
+            Ok(parse_output(&output.stdout))
+        })
+    }
+}
+
+fn parse_output(stdout: &[u8]) -> Value {
+    serde_json::from_slice(stdout).unwrap_or_else(|_| Value::String(String::from_utf8_lossy(stdout).to_string()))
+}
+
+struct Registered {
+    extension: Arc<dyn Extension>,
+    external: bool,
+}
+
+/// Every extension known to the app: compiled-in ones from startup plus the configured processes
+pub struct ExtensionRegistry {
+    extensions: RwLock<Vec<Registered>>,
+}
+
+impl ExtensionRegistry {
+    pub fn new(builtin: Vec<Arc<dyn Extension>>) -> Self {
+        let extensions = builtin
+            .into_iter()
+            .map(|extension| Registered {
+                extension,
+                external: false,
+            })
+            .collect();
+        Self {
+            extensions: RwLock::new(extensions),
+        }
+    }
+
+    /// Replace the external extensions with what `extensions.json` lists now
+    ///
+    /// An id already taken by a compiled-in extension or an earlier entry is skipped.
+    pub async fn load_external<R: Runtime>(&self, app: &AppHandle<R>) -> Result<Vec<String>> {
+        let configured = read_config(&config_path(app)?)?;
+        let mut extensions = self.extensions.write().await;
+        extensions.retain(|r| !r.external);
+
+        let mut loaded = Vec::new();
+        for extension in configured {
+            if extensions.iter().any(|r| r.extension.id() == extension.id) {
+                tracing::warn!("Skipping extension '{}': the id is already registered", extension.id);
+                continue;
+            }
+            loaded.push(extension.id.clone());
+            extensions.push(Registered {
+                extension: Arc::new(extension),
+                external: true,
+            });
+        }
+        Ok(loaded)
+    }
+
+    pub async fn list(&self) -> Vec<ExtensionInfo> {
+        self.extensions
+            .read()
+            .await
+            .iter()
+            .map(|r| ExtensionInfo {
+                id: r.extension.id().to_string(),
+                name: r.extension.name().to_string(),
+                source: if r.external { "external" } else { "builtin" }.to_string(),
+                commands: r.extension.commands(),
+            })
+            .collect()
+    }
+
+    pub async fn invoke(&self, id: &str, command: &str, args: Value, ctx: ExtensionContext) -> Result<Value> {
+        // Cloned out so a slow call doesn't hold the lock against reloads
+        let extension = self
+            .extensions
+            .read()
+            .await
+            .iter()
+            .find(|r| r.extension.id() == id)
+            .map(|r| r.extension.clone())
+            .ok_or_else(|| anyhow::anyhow!("Extension '{}' not found", id))?;
+        if !extension.commands().iter().any(|c| c.name == command) {
+            anyhow::bail!("Extension '{}' has no command '{}'", id, command);
+        }
+        extension.invoke(ctx, command, args).await
+    }
+}
+
+impl Default for ExtensionRegistry {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+fn config_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf> {
+    Ok(app.path().app_config_dir()?.join(EXTENSIONS_FILE))
+}
+
+/// External extensions from the config file; none when it doesn't exist
+fn read_config(path: &std::path::Path) -> Result<Vec<ProcessExtension>> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => parse_config(&content).with_context(|| format!("Invalid {}", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn parse_config(content: &str) -> Result<Vec<ProcessExtension>> {
+    let extensions: Vec<ProcessExtension> = serde_json::from_str(content)?;
+    for extension in &extensions {
+        if extension.id.is_empty() || extension.command.is_empty() {
+            anyhow::bail!("Every extension needs an id and a command");
+        }
+    }
+    Ok(extensions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let extensions = parse_config(
+            r#"[{"id": "billing", "command": "/opt/acme/kubesail-billing",
+                 "commands": [{"name": "costs", "title": "Namespace costs", "view": "table", "kind": "Namespace"},
+                              {"name": "report", "title": "Report"}]}]"#,
+        )
+        .unwrap();
+        assert_eq!(extensions.len(), 1);
+        assert_eq!(extensions[0].name(), "billing");
+        assert_eq!(extensions[0].commands[0].kind.as_deref(), Some("Namespace"));
+        assert_eq!(extensions[0].commands[1].view, "json");
+
+        assert!(parse_config(r#"[{"id": "", "command": "x", "commands": []}]"#).is_err());
+        assert_eq!(parse_output(br#"{"rows": []}"#), serde_json::json!({"rows": []}));
+        assert_eq!(parse_output(b"plain text"), Value::String("plain text".to_string()));
+    }
+}
@@ -0,0 +1,123 @@
+use anyhow::{anyhow, Result};
+
+use crate::preferences::GrafanaConfig;
+use crate::types::GrafanaLink;
+
+/// Dashboard URLs for a namespace or workload, with template variables filled in
+///
+/// Workload-level dashboards (those listing `kinds`) are only returned when
+/// `kind` and `name` are given and match; namespace-level dashboards always are.
+pub fn grafana_links(
+    config: &GrafanaConfig,
+    cluster: &str,
+    namespace: &str,
+    kind: Option<&str>,
+    name: Option<&str>,
+    time_range: Option<&str>,
+) -> Result<Vec<GrafanaLink>> {
+    let base_url = config.base_url.trim_end_matches('/');
+    if !base_url.starts_with("http://") && !base_url.starts_with("https://") {
+        return Err(anyhow!("Grafana base URL must start with http:// or https://"));
+    }
+
+    let fill = |template: &str| {
+        template
+            .replace("{cluster}", cluster)
+            .replace("{namespace}", namespace)
+            .replace("{kind}", kind.unwrap_or_default())
+            .replace("{name}", name.unwrap_or_default())
+    };
+
+    let links = config
+        .dashboards
+        .iter()
+        .filter(|dashboard| match (kind, name) {
+            _ if dashboard.kinds.is_empty() => true,
+            (Some(kind), Some(_)) => dashboard.kinds.iter().any(|k| k.eq_ignore_ascii_case(kind)),
+            _ => false,
+        })
+        .map(|dashboard| {
+            let mut params = vec![format!("orgId={}", config.org_id.unwrap_or(1))];
+
+            let mut variables: Vec<_> = dashboard.variables.iter().collect();
+            variables.sort();
+            for (variable, template) in variables {
+                params.push(format!(
+                    "var-{}={}",
+                    percent_encode(variable),
+                    percent_encode(&fill(template))
+                ));
+            }
+
+            params.push(format!("from={}", percent_encode(&format!("now-{}", time_range.unwrap_or("1h")))));
+            params.push("to=now".to_string());
+
+            GrafanaLink {
+                title: dashboard.title.clone(),
+                url: format!("{}/d/{}?{}", base_url, percent_encode(&dashboard.uid), params.join("&")),
+            }
+        })
+        .collect();
+
+    Ok(links)
+}
+
+/// Percent-encode everything outside the URL unreserved set
+pub(crate) fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::preferences::GrafanaDashboard;
+
+    #[test]
+    fn test_grafana_links_fill_variables() {
+        let config = GrafanaConfig {
+            base_url: "https://grafana.example.com/".to_string(),
+            org_id: None,
+            dashboards: vec![
+                GrafanaDashboard {
+                    title: "Workload".to_string(),
+                    uid: "abc123".to_string(),
+                    kinds: vec!["Deployment".to_string()],
+                    variables: [
+                        ("namespace".to_string(), "{namespace}".to_string()),
+                        ("workload".to_string(), "{name}".to_string()),
+                    ]
+                    .into_iter()
+                    .collect(),
+                },
+                GrafanaDashboard {
+                    title: "Namespace".to_string(),
+                    uid: "ns".to_string(),
+                    kinds: Vec::new(),
+                    variables: [("cluster".to_string(), "{cluster}".to_string())].into_iter().collect(),
+                },
+            ],
+        };
+
+        let links = grafana_links(&config, "prod eu", "shop", Some("Deployment"), Some("web"), None).unwrap();
+        assert_eq!(links.len(), 2);
+        assert_eq!(
+            links[0].url,
+            "https://grafana.example.com/d/abc123?orgId=1&var-namespace=shop&var-workload=web&from=now-1h&to=now"
+        );
+        assert_eq!(
+            links[1].url,
+            "https://grafana.example.com/d/ns?orgId=1&var-cluster=prod%20eu&from=now-1h&to=now"
+        );
+
+        // Workload dashboards need a workload
+        let links = grafana_links(&config, "prod", "shop", None, None, Some("6h")).unwrap();
+        assert_eq!(links.len(), 1);
+        assert!(links[0].url.ends_with("from=now-6h&to=now"));
+    }
+}
@@ -210,6 +210,44 @@ pub async fn get_chart_values(chart: &str) -> Result<String> {
     Ok(stdout.to_string())
 }
 
+/// Render a chart locally with the same arguments an upgrade would use
+pub async fn template_release(
+    name: &str,
+    chart: &str,
+    namespace: &str,
+    values: Option<&str>,
+    version: Option<&str>,
+) -> Result<String> {
+    let mut cmd = Command::new("helm");
+    cmd.arg("template");
+    cmd.arg(name);
+    cmd.arg(chart);
+    cmd.arg("--namespace").arg(namespace);
+
+    if let Some(ver) = version {
+        cmd.arg("--version").arg(ver);
+    }
+
+    let temp_file = std::env::temp_dir().join(format!("helm-values-{}.yaml", uuid::Uuid::new_v4()));
+    if let Some(vals) = values {
+        tokio::fs::write(&temp_file, vals).await?;
+        cmd.arg("--values").arg(&temp_file);
+    }
+
+    let output = cmd.output().await;
+    if values.is_some() {
+        let _ = tokio::fs::remove_file(&temp_file).await;
+    }
+    let output = output?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("Failed to render Helm chart: {}", stderr));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
 /// Upgrade a Helm release with new values
 pub async fn upgrade_release(
     name: &str,
@@ -251,3 +289,146 @@ pub async fn upgrade_release(
     let stdout = String::from_utf8_lossy(&output.stdout);
     Ok(stdout.to_string())
 }
+
+/// A chart's values schema alongside its defaults and the values a user set on top
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HelmValuesSchema {
+    pub chart: String,
+    pub version: Option<String>,
+    pub schema: Option<serde_json::Value>,  // values.schema.json, when the chart ships one
+    pub default_values: serde_json::Value,
+    pub user_values: serde_json::Value,
+    pub merged_values: serde_json::Value,  // Defaults with user values applied, as Helm merges them
+}
+
+/// Merge `overrides` into `base` the way Helm coalesces values: maps merge
+/// recursively, anything else replaces, and a null removes the key
+fn merge_values(base: &mut serde_json::Value, overrides: &serde_json::Value) {
+    match (base, overrides) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                if value.is_null() {
+                    base.remove(key);
+                } else if let Some(existing) = base.get_mut(key) {
+                    merge_values(existing, value);
+                } else {
+                    base.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        (base, overrides) => *base = overrides.clone(),
+    }
+}
+
+fn yaml_to_json(yaml: &str) -> Result<serde_json::Value> {
+    let value: serde_json::Value = serde_yaml::from_str(yaml)?;
+    // An empty values file parses as null
+    Ok(if value.is_null() { serde_json::json!({}) } else { value })
+}
+
+/// Read values.yaml, values.schema.json and the version from an unpacked chart directory
+async fn read_chart_dir(dir: &std::path::Path) -> Result<(Option<String>, Option<serde_json::Value>, serde_json::Value)> {
+    let version = match tokio::fs::read_to_string(dir.join("Chart.yaml")).await {
+        Ok(chart) => yaml_to_json(&chart)?
+            .get("version")
+            .and_then(|v| v.as_str().map(|s| s.to_string()).or_else(|| Some(v.to_string()))),
+        Err(_) => None,
+    };
+    let schema = match tokio::fs::read_to_string(dir.join("values.schema.json")).await {
+        Ok(schema) => Some(serde_json::from_str(&schema).map_err(|e| anyhow!("Invalid values.schema.json: {}", e))?),
+        Err(_) => None,
+    };
+    let defaults = match tokio::fs::read_to_string(dir.join("values.yaml")).await {
+        Ok(values) => yaml_to_json(&values)?,
+        Err(_) => serde_json::json!({}),
+    };
+    Ok((version, schema, defaults))
+}
+
+/// Get a chart's values schema, default values, and those merged with user values
+///
+/// `chart` is a local chart directory or anything `helm pull` accepts
+/// (`repo/name`, a URL, `oci://...`). User values come from `values` if given,
+/// otherwise from the installed `release` in `namespace`, so the result can seed
+/// a form-based editor for either a new install or an upgrade.
+pub async fn get_values_schema(
+    chart: &str,
+    version: Option<&str>,
+    release: Option<&str>,
+    namespace: Option<&str>,
+    values: Option<&str>,
+) -> Result<HelmValuesSchema> {
+    let local = std::path::Path::new(chart);
+    let (chart_version, schema, default_values) = if local.is_dir() {
+        read_chart_dir(local).await?
+    } else {
+        let temp_dir = std::env::temp_dir().join(format!("helm-chart-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&temp_dir).await?;
+
+        let mut cmd = Command::new("helm");
+        cmd.arg("pull").arg(chart);
+        cmd.arg("--untar").arg("--untardir").arg(&temp_dir);
+        if let Some(ver) = version {
+            cmd.arg("--version").arg(ver);
+        }
+
+        let read = async {
+            let output = cmd.output().await?;
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                return Err(anyhow!("Failed to pull Helm chart: {}", stderr));
+            }
+            // The chart unpacks into a directory named after it
+            let mut entries = tokio::fs::read_dir(&temp_dir).await?;
+            let chart_dir = entries
+                .next_entry()
+                .await?
+                .ok_or_else(|| anyhow!("Pulled chart {} is empty", chart))?;
+            read_chart_dir(&chart_dir.path()).await
+        };
+        let result = read.await;
+        let _ = tokio::fs::remove_dir_all(&temp_dir).await;
+        result?
+    };
+
+    let user_values = match (values, release) {
+        (Some(values), _) => yaml_to_json(values)?,
+        (None, Some(release)) => yaml_to_json(&get_values(release, namespace.unwrap_or("default")).await?)?,
+        (None, None) => serde_json::json!({}),
+    };
+
+    let mut merged_values = default_values.clone();
+    merge_values(&mut merged_values, &user_values);
+
+    Ok(HelmValuesSchema {
+        chart: chart.to_string(),
+        version: chart_version,
+        schema,
+        default_values,
+        user_values,
+        merged_values,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_merge_values() {
+        let mut values = json!({
+            "image": { "repository": "nginx", "tag": "1.25" },
+            "replicas": 1,
+            "ingress": { "enabled": false }
+        });
+        merge_values(
+            &mut values,
+            &json!({ "image": { "tag": "1.27" }, "replicas": 3, "ingress": null, "extra": [1] }),
+        );
+        assert_eq!(
+            values,
+            json!({ "image": { "repository": "nginx", "tag": "1.27" }, "replicas": 3, "extra": [1] })
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use anyhow::Result;
+use futures::future::join_all;
+use k8s_openapi::api::authorization::v1::{
+    ResourceAttributes, SelfSubjectAccessReview, SelfSubjectAccessReviewSpec,
+};
+use kube::api::{Api, PostParams};
+use kube::Client;
+use std::collections::HashMap;
+
+use crate::kube::columns::resolve_kind;
+use crate::types::AllowedActions;
+
+/// One access review: (verb, (group, resource) when it isn't the reviewed kind itself, subresource)
+type Check = (&'static str, Option<(&'static str, &'static str)>, Option<&'static str>);
+
+/// Actions checked for every kind, each as a single review on the kind
+const COMMON_ACTIONS: &[(&str, &[Check])] = &[
+    ("get", &[("get", None, None)]),
+    ("list", &[("list", None, None)]),
+    ("watch", &[("watch", None, None)]),
+    ("create", &[("create", None, None)]),
+    ("update", &[("update", None, None)]),
+    ("patch", &[("patch", None, None)]),
+    ("delete", &[("delete", None, None)]),
+];
+
+/// Kind-specific actions the UI exposes as buttons; an action is allowed only when all its reviews are
+fn extra_actions(kind: &str) -> &'static [(&'static str, &'static [Check])] {
+    match kind {
+        "Pod" => &[
+            ("logs", &[("get", None, Some("log"))]),
+            ("exec", &[("create", None, Some("exec"))]),
+            ("port-forward", &[("create", None, Some("portforward"))]),
+        ],
+        // Scaling patches the scale subresource
+        "Deployment" | "StatefulSet" | "ReplicaSet" => &[("scale", &[("patch", None, Some("scale"))])],
+        // Draining cordons the node, then evicts its pods through the pods/eviction subresource
+        "Node" => &[("drain", &[("patch", None, None), ("create", Some(("", "pods")), Some("eviction"))])],
+        _ => &[],
+    }
+}
+
+/// Group and plural for the kinds the UI works with, avoiding a discovery round-trip
+fn known_resource(kind: &str) -> Option<(&'static str, &'static str)> {
+    Some(match kind {
+        "Pod" => ("", "pods"),
+        "Service" => ("", "services"),
+        "ConfigMap" => ("", "configmaps"),
+        "Secret" => ("", "secrets"),
+        "Namespace" => ("", "namespaces"),
+        "Node" => ("", "nodes"),
+        "Event" => ("", "events"),
+        "ServiceAccount" => ("", "serviceaccounts"),
+        "PersistentVolume" => ("", "persistentvolumes"),
+        "PersistentVolumeClaim" => ("", "persistentvolumeclaims"),
+        "Deployment" => ("apps", "deployments"),
+        "StatefulSet" => ("apps", "statefulsets"),
+        "DaemonSet" => ("apps", "daemonsets"),
+        "ReplicaSet" => ("apps", "replicasets"),
+        "Job" => ("batch", "jobs"),
+        "CronJob" => ("batch", "cronjobs"),
+        "Ingress" => ("networking.k8s.io", "ingresses"),
+        "Role" => ("rbac.authorization.k8s.io", "roles"),
+        "RoleBinding" => ("rbac.authorization.k8s.io", "rolebindings"),
+        "ClusterRole" => ("rbac.authorization.k8s.io", "clusterroles"),
+        "ClusterRoleBinding" => ("rbac.authorization.k8s.io", "clusterrolebindings"),
+        _ => return None,
+    })
+}
+
+/// Check which actions the current user may perform on a kind in a namespace
+///
+/// One SelfSubjectAccessReview is sent per check, all in parallel. Kinds
+/// outside the built-in table are resolved through discovery. A review that
+/// fails counts as not allowed, so the UI errs on the side of disabling.
+pub async fn get_allowed_actions(
+    client: Client,
+    kind: &str,
+    namespace: Option<&str>,
+) -> Result<AllowedActions> {
+    let (group, resource) = match known_resource(kind) {
+        Some((group, resource)) => (group.to_string(), resource.to_string()),
+        None => discover_resource(client.clone(), kind).await?,
+    };
+
+    let reviews: Api<SelfSubjectAccessReview> = Api::all(client);
+    let actions: Vec<_> = COMMON_ACTIONS.iter().chain(extra_actions(kind)).collect();
+    let namespace_attr = namespace.filter(|ns| !ns.is_empty()).map(|ns| ns.to_string());
+
+    let checks = actions.iter().flat_map(|(_, checks)| checks.iter()).map(|(verb, other, subresource)| {
+        let (group, resource) = match other {
+            Some((group, resource)) => (group.to_string(), resource.to_string()),
+            None => (group.clone(), resource.clone()),
+        };
+        let review = SelfSubjectAccessReview {
+            spec: SelfSubjectAccessReviewSpec {
+                resource_attributes: Some(ResourceAttributes {
+                    group: Some(group),
+                    resource: Some(resource),
+                    subresource: subresource.map(|s| s.to_string()),
+                    verb: Some(verb.to_string()),
+                    namespace: namespace_attr.clone(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let reviews = reviews.clone();
+        async move { reviews.create(&PostParams::default(), &review).await }
+    });
+
+    let mut results = join_all(checks).await.into_iter();
+
+    let mut allowed = HashMap::new();
+    for (action, checks) in &actions {
+        // Every review is consumed, even after one fails, to keep the results lined up
+        let mut permitted = true;
+        for result in results.by_ref().take(checks.len()) {
+            permitted &= match result {
+                Ok(review) => review.status.map(|s| s.allowed).unwrap_or(false),
+                Err(e) => {
+                    tracing::debug!("Access review for {} {} failed: {}", action, resource, e);
+                    false
+                }
+            };
+        }
+        allowed.insert(action.to_string(), permitted);
+    }
+
+    Ok(AllowedActions {
+        kind: kind.to_string(),
+        namespace: namespace.map(|ns| ns.to_string()),
+        allowed,
+    })
+}
+
+async fn discover_resource(client: Client, kind: &str) -> Result<(String, String)> {
+    let (resource, _) = resolve_kind(client, kind).await?;
+    Ok((resource.group, resource.plural))
+}
@@ -0,0 +1,252 @@
+use anyhow::Result;
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment};
+use k8s_openapi::api::core::v1::PodTemplateSpec;
+use kube::api::{Api, ListParams};
+use kube::{Client, ResourceExt};
+use std::cmp::Ordering;
+
+use crate::kube::images::parse_image_ref;
+use crate::kube::registry::version_key;
+use crate::types::AddonVersion;
+
+/// A well-known addon: how to recognise its image and the newest release we know of
+struct KnownAddon {
+    name: &'static str,
+    category: &'static str,
+    repositories: &'static [&'static str],  // Repository path suffixes, registry ignored
+    latest: &'static str,
+}
+
+/// Latest releases as of the last table update; bump alongside app releases
+const KNOWN_ADDONS: &[KnownAddon] = &[
+    KnownAddon {
+        name: "Calico",
+        category: "cni",
+        repositories: &["calico/node"],
+        latest: "v3.30.2",
+    },
+    KnownAddon {
+        name: "Cilium",
+        category: "cni",
+        repositories: &["cilium/cilium"],
+        latest: "v1.18.0",
+    },
+    KnownAddon {
+        name: "Flannel",
+        category: "cni",
+        repositories: &["flannel/flannel"],
+        latest: "v0.27.1",
+    },
+    KnownAddon {
+        name: "Amazon VPC CNI",
+        category: "cni",
+        repositories: &["amazon-k8s-cni"],
+        latest: "v1.20.0",
+    },
+    KnownAddon {
+        name: "CoreDNS",
+        category: "dns",
+        repositories: &["coredns/coredns", "eks/coredns"],
+        latest: "v1.12.2",
+    },
+    KnownAddon {
+        name: "AWS EBS CSI driver",
+        category: "csi",
+        repositories: &["ebs-csi-driver/aws-ebs-csi-driver"],
+        latest: "v1.46.0",
+    },
+    KnownAddon {
+        name: "AWS EFS CSI driver",
+        category: "csi",
+        repositories: &["efs-csi-driver/aws-efs-csi-driver"],
+        latest: "v2.1.9",
+    },
+    KnownAddon {
+        name: "GCE PD CSI driver",
+        category: "csi",
+        repositories: &["gcp-compute-persistent-disk-csi-driver"],
+        latest: "v1.20.0",
+    },
+    KnownAddon {
+        name: "Azure Disk CSI driver",
+        category: "csi",
+        repositories: &["azuredisk-csi"],
+        latest: "v1.33.0",
+    },
+    KnownAddon {
+        name: "Longhorn",
+        category: "csi",
+        repositories: &["longhornio/longhorn-manager"],
+        latest: "v1.9.1",
+    },
+    KnownAddon {
+        name: "ingress-nginx",
+        category: "ingress",
+        repositories: &["ingress-nginx/controller"],
+        latest: "v1.13.0",
+    },
+    KnownAddon {
+        name: "Traefik",
+        category: "ingress",
+        repositories: &["library/traefik", "traefik/traefik"],
+        latest: "v3.5.0",
+    },
+    KnownAddon {
+        name: "AWS Load Balancer Controller",
+        category: "ingress",
+        repositories: &["eks/aws-load-balancer-controller"],
+        latest: "v2.13.3",
+    },
+    KnownAddon {
+        name: "cert-manager",
+        category: "certificates",
+        repositories: &["jetstack/cert-manager-controller"],
+        latest: "v1.18.2",
+    },
+    KnownAddon {
+        name: "istiod",
+        category: "service-mesh",
+        repositories: &["istio/pilot"],
+        latest: "1.26.3",
+    },
+    KnownAddon {
+        name: "Linkerd",
+        category: "service-mesh",
+        repositories: &["linkerd/controller"],
+        latest: "stable-2.14.10",
+    },
+    KnownAddon {
+        name: "metrics-server",
+        category: "monitoring",
+        repositories: &["metrics-server/metrics-server"],
+        latest: "v0.8.0",
+    },
+    KnownAddon {
+        name: "Karpenter",
+        category: "autoscaling",
+        repositories: &["karpenter/controller"],
+        latest: "1.6.1",
+    },
+    KnownAddon {
+        name: "ExternalDNS",
+        category: "dns",
+        repositories: &["external-dns/external-dns"],
+        latest: "v0.18.0",
+    },
+];
+
+/// Drop a channel prefix like `stable-`, which would otherwise stop version_key parsing anything
+fn strip_channel(tag: &str) -> &str {
+    match tag.split_once('-') {
+        Some((channel, version)) if channel.chars().all(|c| c.is_ascii_alphabetic()) => version,
+        _ => tag,
+    }
+}
+
+/// Compare two version tags by their numeric parts, padding the shorter with zeros
+fn compare_versions(deployed: &str, latest: &str) -> Option<Ordering> {
+    let (mut a, _) = version_key(strip_channel(deployed))?;
+    let (mut b, _) = version_key(strip_channel(latest))?;
+    let len = a.len().max(b.len());
+    a.resize(len, 0);
+    b.resize(len, 0);
+    Some(a.cmp(&b))
+}
+
+fn known_addon(repository: &str) -> Option<&'static KnownAddon> {
+    KNOWN_ADDONS
+        .iter()
+        .find(|addon| {
+            addon
+                .repositories
+                .iter()
+                .any(|r| repository == *r || repository.ends_with(&format!("/{}", r)))
+        })
+}
+
+fn addon_versions_in(
+    kind: &str,
+    namespace: &str,
+    name: &str,
+    template: &PodTemplateSpec,
+    found: &mut Vec<AddonVersion>,
+) {
+    let containers = template.spec.as_ref().map(|s| s.containers.as_slice()).unwrap_or_default();
+    for container in containers {
+        let Some(image) = &container.image else { continue };
+        let reference = parse_image_ref(image);
+        let Some(addon) = known_addon(&reference.repository) else { continue };
+
+        let status = match reference.tag.as_deref().and_then(|tag| compare_versions(tag, addon.latest)) {
+            Some(Ordering::Less) => "outdated",
+            Some(Ordering::Equal) => "current",
+            Some(Ordering::Greater) => "newer",
+            None => "unknown",
+        };
+        found.push(AddonVersion {
+            name: addon.name.to_string(),
+            category: addon.category.to_string(),
+            namespace: namespace.to_string(),
+            workload: format!("{}/{}", kind, name),
+            container: container.name.clone(),
+            image: image.clone(),
+            version: reference.tag.clone(),
+            latest_known: addon.latest.to_string(),
+            status: status.to_string(),
+        });
+    }
+}
+
+/// Versions of common cluster addons (CNI, DNS, CSI drivers, ingress controllers,
+/// cert-manager, service meshes, autoscalers) found in Deployments and DaemonSets
+///
+/// `latest_known` comes from a built-in table, so `outdated` means older than
+/// what that table lists; digest-pinned images come back as `unknown`.
+pub async fn get_addon_versions(client: Client) -> Result<Vec<AddonVersion>> {
+    let (deployments, daemonsets) = tokio::try_join!(
+        Api::<Deployment>::all(client.clone()).list(&ListParams::default()),
+        Api::<DaemonSet>::all(client).list(&ListParams::default()),
+    )?;
+
+    let mut found = Vec::new();
+    for deployment in &deployments.items {
+        if let Some(spec) = &deployment.spec {
+            let namespace = deployment.namespace().unwrap_or_default();
+            addon_versions_in("Deployment", &namespace, &deployment.name_any(), &spec.template, &mut found);
+        }
+    }
+    for daemonset in &daemonsets.items {
+        if let Some(spec) = &daemonset.spec {
+            let namespace = daemonset.namespace().unwrap_or_default();
+            addon_versions_in("DaemonSet", &namespace, &daemonset.name_any(), &spec.template, &mut found);
+        }
+    }
+
+    found.sort_by(|a, b| {
+        (a.category.as_str(), a.name.as_str(), a.namespace.as_str(), a.workload.as_str())
+            .cmp(&(b.category.as_str(), b.name.as_str(), b.namespace.as_str(), b.workload.as_str()))
+    });
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("v1.11.1", "v1.12.2"), Some(Ordering::Less));
+        assert_eq!(compare_versions("v1.12", "v1.12.0"), Some(Ordering::Equal));
+        assert_eq!(compare_versions("1.27.0", "1.26.3"), Some(Ordering::Greater));
+        assert_eq!(compare_versions("v1.9.0-eksbuild.1", "v1.12.2"), Some(Ordering::Less));
+        assert_eq!(compare_versions("stable-2.14.1", "stable-2.14.10"), Some(Ordering::Less));
+        assert_eq!(compare_versions("latest", "v1.12.2"), None);
+    }
+
+    #[test]
+    fn test_known_addon() {
+        assert_eq!(known_addon("coredns/coredns").map(|a| a.name), Some("CoreDNS"));
+        assert_eq!(known_addon("eks/coredns").map(|a| a.name), Some("CoreDNS"));
+        assert_eq!(known_addon("myorg/coredns-proxy").map(|a| a.name), None);
+    }
+}
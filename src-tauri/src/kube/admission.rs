@@ -0,0 +1,250 @@
+use anyhow::Result;
+use futures::future::join_all;
+use k8s_openapi::api::admissionregistration::v1::{
+    MutatingWebhookConfiguration, ValidatingWebhookConfiguration,
+};
+use k8s_openapi::api::core::v1::{ConfigMap, Container, Endpoints, Namespace, Pod, PodSpec};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::api::{Api, ListParams, PostParams};
+use kube::{Client, ResourceExt};
+use std::collections::HashMap;
+use std::time::Instant;
+
+use crate::types::{AdmissionProbeResult, AdmissionReport, WebhookInfo};
+
+/// A dry-run slower than this is reported as slow
+const SLOW_ADMISSION_MS: u64 = 1000;
+
+/// Image for the probe pod; dry-run never pulls it
+const PROBE_IMAGE: &str = "registry.k8s.io/pause:3.9";
+
+/// Measure admission latency per namespace and point at the webhooks behind failures
+///
+/// Dry-run creates a ConfigMap and a Pod in each namespace, which runs the
+/// full admission chain (including webhooks) without persisting anything.
+/// Webhooks named in admission errors, or whose backing Service has no ready
+/// endpoints, are flagged.
+pub async fn detect_admission_problems(
+    client: Client,
+    namespaces: Option<Vec<String>>,
+) -> Result<AdmissionReport> {
+    let namespaces = match namespaces.filter(|n| !n.is_empty()) {
+        Some(namespaces) => namespaces,
+        None => Api::<Namespace>::all(client.clone())
+            .list(&ListParams::default())
+            .await?
+            .items
+            .iter()
+            .filter(|ns| ns.metadata.deletion_timestamp.is_none())
+            .map(|ns| ns.name_any())
+            .collect(),
+    };
+
+    let probes = namespaces.iter().map(|ns| probe_namespace(client.clone(), ns.clone()));
+    let mut probes: Vec<AdmissionProbeResult> = join_all(probes).await.into_iter().flatten().collect();
+    probes.sort_by_key(|p| (p.ok, std::cmp::Reverse(p.latency_ms)));
+
+    let mut webhooks = list_webhooks(client).await?;
+    for webhook in &mut webhooks {
+        webhook.failing_probes = probes
+            .iter()
+            .filter(|p| {
+                p.error
+                    .as_deref()
+                    .map(|e| e.contains(&format!("\"{}\"", webhook.name)))
+                    .unwrap_or(false)
+            })
+            .count();
+        webhook.implicated = webhook.failing_probes > 0 || webhook.service_has_endpoints == Some(false);
+    }
+    webhooks.sort_by(|a, b| (!a.implicated, &a.name).cmp(&(!b.implicated, &b.name)));
+
+    Ok(AdmissionReport { probes, webhooks })
+}
+
+async fn probe_namespace(client: Client, namespace: String) -> Vec<AdmissionProbeResult> {
+    let params = PostParams {
+        dry_run: true,
+        ..PostParams::default()
+    };
+    let metadata = ObjectMeta {
+        generate_name: Some("kubesail-admission-probe-".to_string()),
+        namespace: Some(namespace.clone()),
+        ..ObjectMeta::default()
+    };
+
+    let config_map = ConfigMap {
+        metadata: metadata.clone(),
+        ..ConfigMap::default()
+    };
+    let pod = Pod {
+        metadata,
+        spec: Some(PodSpec {
+            containers: vec![Container {
+                name: "probe".to_string(),
+                image: Some(PROBE_IMAGE.to_string()),
+                ..Container::default()
+            }],
+            ..PodSpec::default()
+        }),
+        ..Pod::default()
+    };
+
+    let config_maps: Api<ConfigMap> = Api::namespaced(client.clone(), &namespace);
+    let pods: Api<Pod> = Api::namespaced(client, &namespace);
+
+    let started = Instant::now();
+    let config_map_result = config_maps.create(&params, &config_map).await.map(|_| ());
+    let config_map_ms = started.elapsed().as_millis() as u64;
+
+    let started = Instant::now();
+    let pod_result = pods.create(&params, &pod).await.map(|_| ());
+    let pod_ms = started.elapsed().as_millis() as u64;
+
+    vec![
+        probe_result(&namespace, "ConfigMap", config_map_ms, config_map_result),
+        probe_result(&namespace, "Pod", pod_ms, pod_result),
+    ]
+}
+
+fn probe_result(
+    namespace: &str,
+    kind: &str,
+    latency_ms: u64,
+    result: std::result::Result<(), kube::Error>,
+) -> AdmissionProbeResult {
+    let error = result.err().map(|e| match e {
+        kube::Error::Api(resp) => resp.message,
+        other => other.to_string(),
+    });
+
+    AdmissionProbeResult {
+        namespace: namespace.to_string(),
+        kind: kind.to_string(),
+        latency_ms,
+        ok: error.is_none(),
+        slow: latency_ms > SLOW_ADMISSION_MS,
+        webhook: error.as_deref().and_then(webhook_in_error),
+        error,
+    }
+}
+
+/// The webhook named in an admission error, e.g. `admission webhook "x.example.com" denied the request`
+fn webhook_in_error(error: &str) -> Option<String> {
+    let start = error.find("webhook \"")? + "webhook \"".len();
+    let end = error[start..].find('"')?;
+    Some(error[start..start + end].to_string())
+}
+
+async fn list_webhooks(client: Client) -> Result<Vec<WebhookInfo>> {
+    let lp = ListParams::default();
+    let (mutating, validating) = tokio::try_join!(
+        Api::<MutatingWebhookConfiguration>::all(client.clone()).list(&lp),
+        Api::<ValidatingWebhookConfiguration>::all(client.clone()).list(&lp),
+    )?;
+
+    let mut webhooks = Vec::new();
+    for config in &mutating.items {
+        for hook in config.webhooks.iter().flatten() {
+            let service = hook.client_config.service.as_ref().map(|s| (s.namespace.clone(), s.name.clone()));
+            webhooks.push(webhook_info(
+                &hook.name,
+                &config.name_any(),
+                "Mutating",
+                hook.failure_policy.clone(),
+                hook.timeout_seconds,
+                service,
+            ));
+        }
+    }
+    for config in &validating.items {
+        for hook in config.webhooks.iter().flatten() {
+            let service = hook.client_config.service.as_ref().map(|s| (s.namespace.clone(), s.name.clone()));
+            webhooks.push(webhook_info(
+                &hook.name,
+                &config.name_any(),
+                "Validating",
+                hook.failure_policy.clone(),
+                hook.timeout_seconds,
+                service,
+            ));
+        }
+    }
+
+    // One lookup per distinct backing Service
+    let mut endpoints: HashMap<(String, String), Option<bool>> = HashMap::new();
+    for webhook in &webhooks {
+        if let Some((ns, name)) = webhook.service.as_deref().and_then(|s| s.split_once('/')) {
+            let key = (ns.to_string(), name.to_string());
+            if endpoints.contains_key(&key) {
+                continue;
+            }
+            let ready = Api::<Endpoints>::namespaced(client.clone(), ns)
+                .get_opt(name)
+                .await
+                .ok()
+                .map(|ep| {
+                    ep.and_then(|ep| ep.subsets)
+                        .map(|subsets| {
+                            subsets
+                                .iter()
+                                .any(|s| s.addresses.as_ref().map(|a| !a.is_empty()).unwrap_or(false))
+                        })
+                        .unwrap_or(false)
+                });
+            endpoints.insert(key, ready);
+        }
+    }
+    for webhook in &mut webhooks {
+        let key = webhook
+            .service
+            .as_deref()
+            .and_then(|s| s.split_once('/'))
+            .map(|(ns, name)| (ns.to_string(), name.to_string()));
+        if let Some(key) = key {
+            webhook.service_has_endpoints = endpoints.get(&key).copied().flatten();
+        }
+    }
+
+    Ok(webhooks)
+}
+
+fn webhook_info(
+    name: &str,
+    configuration: &str,
+    webhook_type: &str,
+    failure_policy: Option<String>,
+    timeout_seconds: Option<i32>,
+    service: Option<(String, String)>,
+) -> WebhookInfo {
+    WebhookInfo {
+        name: name.to_string(),
+        configuration: configuration.to_string(),
+        webhook_type: webhook_type.to_string(),
+        // Both default to the API server's values when unset
+        failure_policy: failure_policy.unwrap_or_else(|| "Fail".to_string()),
+        timeout_seconds: timeout_seconds.unwrap_or(10),
+        service: service.map(|(ns, name)| format!("{}/{}", ns, name)),
+        service_has_endpoints: None,
+        failing_probes: 0,
+        implicated: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_webhook_in_error() {
+        assert_eq!(
+            webhook_in_error(r#"admission webhook "validate.kyverno.svc" denied the request: policy x"#),
+            Some("validate.kyverno.svc".to_string())
+        );
+        assert_eq!(
+            webhook_in_error(r#"Internal error occurred: failed calling webhook "mutate.example.com": context deadline exceeded"#),
+            Some("mutate.example.com".to_string())
+        );
+        assert_eq!(webhook_in_error("pods \"x\" is forbidden: exceeded quota"), None);
+    }
+}
@@ -0,0 +1,122 @@
+use anyhow::{Context, Result};
+use http_body_util::BodyExt;
+use k8s_openapi::http::{header, Request};
+use kube::client::Body;
+use kube::Client;
+use std::time::{Duration, Instant};
+
+use crate::types::ProxyResponse;
+
+const REQUEST_TIMEOUT_SECS: u64 = 30;
+
+/// Bodies beyond this are cut off; proxied checks are for health endpoints, not downloads
+const MAX_BODY_BYTES: usize = 256 * 1024;
+
+/// GET `url` through the API server's proxy subresource and return whatever came back
+///
+/// Unlike `request_text`, error statuses from the target are returned as a
+/// response rather than an error, since a 503 from /healthz is the answer.
+/// The timeout covers reading the body too, and reading stops at `max_body_bytes`.
+pub(crate) async fn proxy_get(client: &Client, url: String, max_body_bytes: usize) -> Result<ProxyResponse> {
+    let started = Instant::now();
+
+    let exchange = async {
+        let request = Request::get(&url).body(Body::from(Vec::new()))?;
+        let response = client
+            .send(request)
+            .await
+            .with_context(|| format!("Proxy request to {} failed", url))?;
+
+        let (parts, body) = response.into_parts();
+        let mut body = std::pin::pin!(body);
+        let mut bytes = Vec::new();
+        let mut truncated = false;
+        while let Some(frame) = body.frame().await {
+            let Ok(data) = frame?.into_data() else {
+                continue;
+            };
+            let room = max_body_bytes - bytes.len();
+            if data.len() > room {
+                bytes.extend_from_slice(&data[..room]);
+                truncated = true;
+                break;
+            }
+            bytes.extend_from_slice(&data);
+        }
+        Ok::<_, anyhow::Error>((parts, bytes, truncated))
+    };
+
+    let (parts, bytes, truncated) = tokio::time::timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS), exchange)
+        .await
+        .with_context(|| format!("No complete response within {}s", REQUEST_TIMEOUT_SECS))??;
+
+    Ok(ProxyResponse {
+        url,
+        status: parts.status.as_u16(),
+        content_type: parts
+            .headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string()),
+        body: String::from_utf8_lossy(&bytes).into_owned(),
+        truncated,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+/// `port` may be a number or a port name; `https` proxies to a TLS port
+pub(crate) fn proxy_url(namespace: &str, resource: &str, name: &str, port: &str, https: bool, path: &str) -> String {
+    let target = match (https, port.is_empty()) {
+        (true, true) => format!("https:{}:", name),
+        (true, false) => format!("https:{}:{}", name, port),
+        (false, true) => name.to_string(),
+        (false, false) => format!("{}:{}", name, port),
+    };
+    let path = if path.starts_with('/') { path.to_string() } else { format!("/{}", path) };
+    format!("/api/v1/namespaces/{}/{}/{}/proxy{}", namespace, resource, target, path)
+}
+
+/// Quick HTTP check of a Service without port-forwarding, e.g. its /healthz
+pub async fn proxy_service_request(
+    client: Client,
+    namespace: &str,
+    service: &str,
+    port: &str,
+    path: &str,
+    https: bool,
+) -> Result<ProxyResponse> {
+    proxy_get(&client, proxy_url(namespace, "services", service, port, https, path), MAX_BODY_BYTES).await
+}
+
+/// Same as [`proxy_service_request`] but straight to one pod, bypassing the Service
+pub async fn proxy_pod_request(
+    client: Client,
+    namespace: &str,
+    pod: &str,
+    port: &str,
+    path: &str,
+    https: bool,
+) -> Result<ProxyResponse> {
+    proxy_get(&client, proxy_url(namespace, "pods", pod, port, https, path), MAX_BODY_BYTES).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proxy_url() {
+        assert_eq!(
+            proxy_url("default", "services", "api", "8080", false, "/healthz"),
+            "/api/v1/namespaces/default/services/api:8080/proxy/healthz"
+        );
+        assert_eq!(
+            proxy_url("web", "services", "api", "metrics", true, "ready?verbose"),
+            "/api/v1/namespaces/web/services/https:api:metrics/proxy/ready?verbose"
+        );
+        assert_eq!(
+            proxy_url("web", "pods", "api-0", "", false, "/"),
+            "/api/v1/namespaces/web/pods/api-0/proxy/"
+        );
+    }
+}
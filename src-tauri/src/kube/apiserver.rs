@@ -0,0 +1,181 @@
+use anyhow::Result;
+use kube::Client;
+use std::collections::HashMap;
+
+use crate::kube::operations::parse_metric_labels;
+use crate::types::{ApiRequestStats, ApiServerMetricsSummary, StorageObjectCount};
+
+/// How many rows each ranking keeps
+const TOP_N: usize = 15;
+
+/// Parse one Prometheus text-format sample into (name, labels, value)
+fn parse_sample(line: &str) -> Option<(&str, HashMap<String, String>, f64)> {
+    if line.starts_with('#') || line.is_empty() {
+        return None;
+    }
+
+    let (name, labels, rest) = match line.find('{') {
+        Some(open) => {
+            let close = line.rfind('}')?;
+            (&line[..open], parse_metric_labels(&line[open + 1..close]), &line[close + 1..])
+        }
+        None => {
+            let (name, rest) = line.split_once(' ')?;
+            (name, HashMap::new(), rest)
+        }
+    };
+
+    let value = rest.split_whitespace().next()?.parse().ok()?;
+    Some((name, labels, value))
+}
+
+#[derive(Default)]
+struct RequestAccumulator {
+    count: f64,
+    sum: f64,
+    buckets: HashMap<String, f64>, // le -> cumulative count, summed over other labels
+}
+
+/// Upper bound of the bucket holding quantile `q`, from cumulative histogram buckets
+fn histogram_quantile(buckets: &HashMap<String, f64>, q: f64) -> Option<f64> {
+    let mut sorted: Vec<(f64, f64)> = buckets
+        .iter()
+        .filter_map(|(le, count)| {
+            let le = if le == "+Inf" { f64::INFINITY } else { le.parse().ok()? };
+            Some((le, *count))
+        })
+        .collect();
+    sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let total = sorted.last()?.1;
+    if total <= 0.0 {
+        return None;
+    }
+
+    let bound = sorted.iter().find(|(_, count)| *count >= total * q)?.0;
+    // Past the last finite bucket all we know is "slower than the largest bound"
+    if bound.is_infinite() {
+        sorted.iter().rev().find(|(le, _)| le.is_finite()).map(|(le, _)| *le)
+    } else {
+        Some(bound)
+    }
+}
+
+/// Request latency, inflight requests and stored object counts from the API server's /metrics
+///
+/// Counters are cumulative since the API server started, so rankings show
+/// which resources have been the most expensive over its lifetime, which is
+/// usually enough to spot a controller hammering the API. Needs RBAC access to
+/// the `/metrics` non-resource URL, which managed clusters often restrict.
+pub async fn get_apiserver_metrics(client: Client) -> Result<ApiServerMetricsSummary> {
+    let request = k8s_openapi::http::Request::get("/metrics").body(Vec::new())?;
+    let body = client.request_text(request).await?;
+
+    let mut requests: HashMap<(String, String), RequestAccumulator> = HashMap::new();
+    let mut inflight_read_only = 0.0;
+    let mut inflight_mutating = 0.0;
+    let mut throttled = 0.0;
+    let mut storage: HashMap<String, f64> = HashMap::new();
+    let mut legacy_storage: HashMap<String, f64> = HashMap::new();
+
+    for line in body.lines() {
+        let Some((name, labels, value)) = parse_sample(line) else {
+            continue;
+        };
+        let label = |key: &str| labels.get(key).cloned().unwrap_or_default();
+        let request_key = || (label("verb"), label("resource"));
+
+        match name {
+            "apiserver_request_duration_seconds_bucket" => {
+                // WATCH and CONNECT are long-running; their durations aren't latency
+                if !matches!(label("verb").as_str(), "WATCH" | "CONNECT") {
+                    let acc = requests.entry(request_key()).or_default();
+                    *acc.buckets.entry(label("le")).or_default() += value;
+                }
+            }
+            "apiserver_request_duration_seconds_sum" => {
+                requests.entry(request_key()).or_default().sum += value;
+            }
+            "apiserver_request_duration_seconds_count" => {
+                requests.entry(request_key()).or_default().count += value;
+            }
+            "apiserver_request_total" if label("code") == "429" => throttled += value,
+            "apiserver_current_inflight_requests" => match label("request_kind").as_str() {
+                "readOnly" => inflight_read_only += value,
+                "mutating" => inflight_mutating += value,
+                _ => {}
+            },
+            "apiserver_storage_objects" => *storage.entry(label("resource")).or_default() += value,
+            "etcd_object_counts" => *legacy_storage.entry(label("resource")).or_default() += value,
+            _ => {}
+        }
+    }
+
+    if requests.is_empty() && storage.is_empty() && legacy_storage.is_empty() {
+        return Err(anyhow::anyhow!("The API server's /metrics did not include request or storage metrics"));
+    }
+
+    let stats: Vec<ApiRequestStats> = requests
+        .into_iter()
+        .filter(|(_, acc)| acc.count > 0.0 && !acc.buckets.is_empty())
+        .map(|((verb, resource), acc)| ApiRequestStats {
+            verb,
+            resource,
+            count: acc.count as u64,
+            avg_ms: acc.sum / acc.count * 1000.0,
+            p99_ms: histogram_quantile(&acc.buckets, 0.99).map(|s| s * 1000.0),
+        })
+        .collect();
+
+    let mut slowest = stats.clone();
+    slowest.sort_by(|a, b| b.p99_ms.unwrap_or(b.avg_ms).total_cmp(&a.p99_ms.unwrap_or(a.avg_ms)));
+    slowest.truncate(TOP_N);
+
+    let mut busiest = stats;
+    busiest.sort_by(|a, b| b.count.cmp(&a.count));
+    busiest.truncate(TOP_N);
+
+    // Older API servers only expose etcd_object_counts
+    let storage = if storage.is_empty() { legacy_storage } else { storage };
+    let mut storage_objects: Vec<StorageObjectCount> = storage
+        .into_iter()
+        .filter(|(resource, _)| !resource.is_empty())
+        .map(|(resource, count)| StorageObjectCount {
+            resource,
+            count: count.max(0.0) as u64,
+        })
+        .collect();
+    storage_objects.sort_by(|a, b| b.count.cmp(&a.count));
+
+    Ok(ApiServerMetricsSummary {
+        inflight_read_only: inflight_read_only as u64,
+        inflight_mutating: inflight_mutating as u64,
+        throttled_requests: throttled as u64,
+        slowest,
+        busiest,
+        storage_objects,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sample_and_quantile() {
+        let (name, labels, value) =
+            parse_sample(r#"apiserver_request_duration_seconds_bucket{resource="pods",verb="LIST",le="0.5"} 42"#)
+                .unwrap();
+        assert_eq!(name, "apiserver_request_duration_seconds_bucket");
+        assert_eq!(labels.get("le").map(|s| s.as_str()), Some("0.5"));
+        assert_eq!(value, 42.0);
+        assert!(parse_sample("# HELP apiserver_request_total Counter").is_none());
+
+        let buckets: HashMap<String, f64> = [("0.1", 90.0), ("0.5", 99.0), ("1", 100.0), ("+Inf", 100.0)]
+            .into_iter()
+            .map(|(le, count)| (le.to_string(), count))
+            .collect();
+        assert_eq!(histogram_quantile(&buckets, 0.99), Some(0.5));
+        assert_eq!(histogram_quantile(&buckets, 0.5), Some(0.1));
+    }
+}
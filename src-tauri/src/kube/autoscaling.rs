@@ -0,0 +1,261 @@
+use anyhow::Result;
+use k8s_openapi::api::core::v1::{ConfigMap, Event, Pod};
+use kube::api::{Api, DynamicObject, ListParams};
+use kube::core::GroupVersionKind;
+use kube::discovery::ApiResource;
+use kube::{Client, ResourceExt};
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::kube::events::{event_time, parse_since};
+use crate::types::{
+    AutoscalerInsight, ClusterAutoscalerStatus, KarpenterNodeClaimInfo, KarpenterNodePoolInfo, PendingPodInsight,
+    ScalingEvent,
+};
+
+/// Karpenter API versions to try, newest first
+const KARPENTER_VERSIONS: &[&str] = &["v1", "v1beta1"];
+
+/// Event sources whose events explain scaling decisions
+const AUTOSCALER_COMPONENTS: &[&str] = &["cluster-autoscaler", "karpenter"];
+
+const CA_STATUS_CONFIGMAP: &str = "cluster-autoscaler-status";
+const CA_LAST_UPDATED_ANNOTATION: &str = "cluster-autoscaler.kubernetes.io/last-updated";
+
+/// Most recent scaling events returned
+const MAX_SCALING_EVENTS: usize = 100;
+
+fn str_at(value: &Value, pointer: &str) -> Option<String> {
+    value.pointer(pointer).and_then(|v| v.as_str()).map(|s| s.to_string())
+}
+
+/// `key=value` pairs of a JSON object, e.g. NodePool limits or resources
+fn quantities_at(value: &Value, pointer: &str) -> Vec<String> {
+    value
+        .pointer(pointer)
+        .and_then(|v| v.as_object())
+        .map(|map| {
+            map.iter()
+                .map(|(k, v)| format!("{}={}", k, v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Whether the Ready condition is True, and the reason/message of the first condition that isn't
+fn conditions(data: &Value) -> (bool, Option<String>) {
+    let conditions = data
+        .pointer("/status/conditions")
+        .and_then(|c| c.as_array())
+        .cloned()
+        .unwrap_or_default();
+    let is_true = |c: &Value| c.get("status").and_then(|s| s.as_str()) == Some("True");
+    let ready = conditions
+        .iter()
+        .any(|c| c.get("type").and_then(|t| t.as_str()) == Some("Ready") && is_true(c));
+    let problem = conditions
+        .iter()
+        .find(|c| !is_true(c))
+        .map(|c| {
+            let kind = str_at(c, "/type").unwrap_or_default();
+            match (str_at(c, "/reason"), str_at(c, "/message").filter(|m| !m.is_empty())) {
+                (_, Some(message)) => format!("{}: {}", kind, message),
+                (Some(reason), None) => format!("{}: {}", kind, reason),
+                (None, None) => format!("{} is not True", kind),
+            }
+        });
+    (ready, problem)
+}
+
+/// List a Karpenter kind from the first API version the cluster serves; None when Karpenter isn't installed
+async fn list_karpenter(client: &Client, kind: &str, plural: &str) -> Result<Option<Vec<DynamicObject>>> {
+    for version in KARPENTER_VERSIONS {
+        let resource = ApiResource::from_gvk_with_plural(&GroupVersionKind::gvk("karpenter.sh", version, kind), plural);
+        match Api::<DynamicObject>::all_with(client.clone(), &resource)
+            .list(&ListParams::default())
+            .await
+        {
+            Ok(list) => return Ok(Some(list.items)),
+            Err(kube::Error::Api(e)) if e.code == 404 => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(None)
+}
+
+/// What drives node scaling in this cluster and why pending pods are (not) getting nodes
+///
+/// Detects Karpenter (NodePools and NodeClaims) and the cluster-autoscaler
+/// status ConfigMap, lists unschedulable pods with the autoscaler's latest word
+/// on each, and collects scaling events from both since `since` (default 1h).
+pub async fn get_autoscaler_insight(client: Client, since: Option<&str>) -> Result<AutoscalerInsight> {
+    let cutoff = parse_since(since.unwrap_or("1h"))?;
+
+    let (node_pools, node_claims) = tokio::try_join!(
+        list_karpenter(&client, "NodePool", "nodepools"),
+        list_karpenter(&client, "NodeClaim", "nodeclaims"),
+    )?;
+    let status_map = Api::<ConfigMap>::namespaced(client.clone(), "kube-system")
+        .get_opt(CA_STATUS_CONFIGMAP)
+        .await?;
+
+    let node_claims: Vec<KarpenterNodeClaimInfo> = node_claims
+        .unwrap_or_default()
+        .iter()
+        .map(|claim| {
+            let labels = claim.labels();
+            let (ready, problem) = conditions(&claim.data);
+            KarpenterNodeClaimInfo {
+                name: claim.name_any(),
+                node_pool: labels.get("karpenter.sh/nodepool").cloned(),
+                node_name: str_at(&claim.data, "/status/nodeName"),
+                instance_type: labels.get("node.kubernetes.io/instance-type").cloned(),
+                capacity_type: labels.get("karpenter.sh/capacity-type").cloned(),
+                zone: labels.get("topology.kubernetes.io/zone").cloned(),
+                ready,
+                problem,
+                created_at: claim.metadata.creation_timestamp.as_ref().map(|ts| ts.0.to_rfc3339()),
+            }
+        })
+        .collect();
+
+    let karpenter_installed = node_pools.is_some();
+    let node_pools: Vec<KarpenterNodePoolInfo> = node_pools
+        .unwrap_or_default()
+        .iter()
+        .map(|pool| {
+            let name = pool.name_any();
+            let (ready, problem) = conditions(&pool.data);
+            KarpenterNodePoolInfo {
+                node_claims: node_claims.iter().filter(|c| c.node_pool.as_deref() == Some(name.as_str())).count(),
+                name,
+                ready,
+                problem,
+                limits: quantities_at(&pool.data, "/spec/limits"),
+                resources: quantities_at(&pool.data, "/status/resources"),
+                consolidation_policy: str_at(&pool.data, "/spec/disruption/consolidationPolicy"),
+                weight: pool.data.pointer("/spec/weight").and_then(|w| w.as_i64()),
+            }
+        })
+        .collect();
+
+    let cluster_autoscaler = status_map.map(|cm| ClusterAutoscalerStatus {
+        last_updated: cm.annotations().get(CA_LAST_UPDATED_ANNOTATION).cloned(),
+        status: cm.data.as_ref().and_then(|d| d.get("status").cloned()).unwrap_or_default(),
+    });
+
+    let provider = match (karpenter_installed, cluster_autoscaler.is_some()) {
+        (true, true) => "karpenter+cluster-autoscaler",
+        (true, false) => "karpenter",
+        (false, true) => "cluster-autoscaler",
+        (false, false) => "none",
+    };
+
+    let (pods, events) = tokio::try_join!(
+        Api::<Pod>::all(client.clone()).list(&ListParams::default().fields("status.phase=Pending")),
+        Api::<Event>::all(client).list(&ListParams::default()),
+    )?;
+
+    let mut scaling: Vec<&Event> = events
+        .items
+        .iter()
+        .filter(|e| {
+            let component = e
+                .source
+                .as_ref()
+                .and_then(|s| s.component.as_deref())
+                .or(e.reporting_component.as_deref())
+                .unwrap_or_default();
+            AUTOSCALER_COMPONENTS.iter().any(|c| component.contains(c))
+        })
+        .filter(|e| event_time(e).map(|t| t >= cutoff).unwrap_or(false))
+        .collect();
+    scaling.sort_by_key(|e| std::cmp::Reverse(event_time(e)));
+
+    // The newest autoscaler event about each pod
+    let mut latest_for_pod: HashMap<(String, String), &Event> = HashMap::new();
+    for event in &scaling {
+        if event.involved_object.kind.as_deref() == Some("Pod") {
+            let key = (
+                event.involved_object.namespace.clone().unwrap_or_default(),
+                event.involved_object.name.clone().unwrap_or_default(),
+            );
+            latest_for_pod.entry(key).or_insert(event);
+        }
+    }
+
+    let pending_pods = pods
+        .items
+        .iter()
+        .filter_map(|pod| {
+            let condition = pod
+                .status
+                .as_ref()?
+                .conditions
+                .as_ref()?
+                .iter()
+                .find(|c| c.type_ == "PodScheduled" && c.status == "False")?;
+            let namespace = pod.namespace().unwrap_or_default();
+            let name = pod.name_any();
+            let autoscaler = latest_for_pod.get(&(namespace.clone(), name.clone()));
+            Some(PendingPodInsight {
+                namespace,
+                name,
+                scheduler_reason: condition.reason.clone(),
+                scheduler_message: condition.message.clone(),
+                autoscaler_reason: autoscaler.and_then(|e| e.reason.clone()),
+                autoscaler_message: autoscaler.and_then(|e| e.message.clone()),
+                created_at: pod.metadata.creation_timestamp.as_ref().map(|ts| ts.0.to_rfc3339()),
+            })
+        })
+        .collect();
+
+    let scaling_events = scaling
+        .into_iter()
+        .take(MAX_SCALING_EVENTS)
+        .map(|e| ScalingEvent {
+            time: event_time(e).map(|t| t.to_rfc3339()),
+            source: e
+                .source
+                .as_ref()
+                .and_then(|s| s.component.clone())
+                .or_else(|| e.reporting_component.clone())
+                .unwrap_or_default(),
+            reason: e.reason.clone().unwrap_or_default(),
+            kind: e.involved_object.kind.clone().unwrap_or_default(),
+            namespace: e.involved_object.namespace.clone(),
+            name: e.involved_object.name.clone().unwrap_or_default(),
+            message: e.message.clone().unwrap_or_default(),
+            count: e.count.unwrap_or(1),
+        })
+        .collect();
+
+    Ok(AutoscalerInsight {
+        provider: provider.to_string(),
+        karpenter_node_pools: node_pools,
+        karpenter_node_claims: node_claims,
+        cluster_autoscaler,
+        pending_pods,
+        scaling_events,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_conditions() {
+        let claim = json!({ "status": { "conditions": [
+            { "type": "Launched", "status": "True" },
+            { "type": "Registered", "status": "False", "reason": "NodeNotFound" },
+            { "type": "Ready", "status": "Unknown", "message": "waiting on registration" },
+        ]}});
+        assert_eq!(conditions(&claim), (false, Some("Registered: NodeNotFound".to_string())));
+
+        let ready = json!({ "status": { "conditions": [{ "type": "Ready", "status": "True" }] } });
+        assert_eq!(conditions(&ready), (true, None));
+        assert_eq!(conditions(&json!({})), (false, None));
+    }
+}
@@ -0,0 +1,182 @@
+use futures::StreamExt;
+use kube::api::Api;
+use kube::runtime::reflector::{self, Store};
+use kube::runtime::{watcher, WatchStreamExt};
+use kube::{Client, Resource, ResourceExt};
+use serde::de::DeserializeOwned;
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+
+/// Reflectors nobody has listed from for this long are stopped
+const IDLE_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// (context, kind, namespace); an empty namespace is a cluster-wide reflector
+type CacheKey = (String, String, String);
+
+struct CacheEntry {
+    store: Box<dyn Any + Send + Sync>,  // Store<K> for the entry's kind
+    ready: Arc<AtomicBool>,  // Initial list done and the watch not currently failing
+    failed: Arc<AtomicBool>,  // The watch errored; the entry is restarted rather than waited on
+    task: JoinHandle<()>,
+    last_used: Instant,
+}
+
+impl Drop for CacheEntry {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// In-memory stores for frequently listed kinds, kept current by watches
+///
+/// List commands read from here instead of re-listing from the API server on
+/// every refresh. A reflector starts on the first list of a kind in a
+/// namespace; until its initial list completes, or while its watch is failing,
+/// callers get `None` and list from the API server as before. A reflector
+/// whose watch fails is dropped and started again on the next list.
+///
+/// Reflectors keep the client they were started with, token included, so a
+/// context's reflectors are dropped when the client manager's generation for
+/// that context moves on.
+pub struct ResourceCache {
+    entries: Arc<Mutex<HashMap<CacheKey, CacheEntry>>>,
+    generations: std::sync::Mutex<HashMap<String, u64>>,  // Per context, the client generation its reflectors were started with
+}
+
+impl ResourceCache {
+    pub fn new() -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(HashMap::new())),
+            generations: std::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Cached objects of kind `K` in `namespace` (all namespaces when empty), or
+    /// None when the cache can't answer yet
+    ///
+    /// `generation` is [`KubeClientManager::generation`](crate::kube::KubeClientManager::generation)
+    /// for `context` as of when `client` was taken; a client older than the
+    /// running reflectors' gets `None` rather than starting one.
+    pub async fn list<K>(&self, client: &Client, generation: u64, context: &str, namespace: &str) -> Option<Vec<K>>
+    where
+        K: Resource<DynamicType = ()> + Clone + DeserializeOwned + Debug + Send + Sync + 'static,
+    {
+        let kind = K::kind(&()).to_string();
+        let now = Instant::now();
+        let mut entries = self.entries.lock().await;
+        {
+            let mut generations = self.generations.lock().unwrap_or_else(|e| e.into_inner());
+            let current = generations.entry(context.to_string()).or_insert(0);
+            if generation > *current {
+                *current = generation;
+                entries.retain(|(ctx, _, _), _| ctx != context);
+            } else if generation < *current {
+                return None;
+            }
+        }
+        entries.retain(|_, entry| {
+            now.duration_since(entry.last_used) < IDLE_TIMEOUT && !entry.failed.load(Ordering::SeqCst)
+        });
+
+        // A ready cluster-wide store answers for any namespace
+        if !namespace.is_empty() {
+            let wide = (context.to_string(), kind.clone(), String::new());
+            if let Some(objects) = entries.get_mut(&wide).and_then(|entry| read::<K>(entry, now, Some(namespace))) {
+                return Some(objects);
+            }
+        }
+
+        let key = (context.to_string(), kind, namespace.to_string());
+        if let Some(entry) = entries.get_mut(&key) {
+            return read::<K>(entry, now, None);
+        }
+
+        let api: Api<K> = if namespace.is_empty() {
+            Api::all(client.clone())
+        } else {
+            Api::namespaced(client.clone(), namespace)
+        };
+        entries.insert(key, start_reflector(api, now));
+        None
+    }
+
+    /// Stop every reflector for a context, or all of them when `None`
+    pub async fn evict(&self, context: Option<&str>) {
+        let mut entries = self.entries.lock().await;
+        match context {
+            Some(context) => entries.retain(|(ctx, _, _), _| ctx != context),
+            None => entries.clear(),
+        }
+    }
+}
+
+impl Default for ResourceCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn start_reflector<K>(api: Api<K>, now: Instant) -> CacheEntry
+where
+    K: Resource<DynamicType = ()> + Clone + DeserializeOwned + Debug + Send + Sync + 'static,
+{
+    let (store, writer) = reflector::store::<K>();
+    let ready = Arc::new(AtomicBool::new(false));
+    let failed = Arc::new(AtomicBool::new(false));
+
+    let flag = ready.clone();
+    let failure = failed.clone();
+    let task = tokio::spawn(async move {
+        let stream = watcher(api, watcher::Config::default())
+            .default_backoff()
+            .reflect(writer);
+        futures::pin_mut!(stream);
+        while let Some(event) = stream.next().await {
+            match event {
+                // The store may be missing changes while the watch is down; don't serve it
+                Err(e) => {
+                    tracing::debug!("Cache watch for {} failed: {}", K::kind(&()), e);
+                    flag.store(false, Ordering::SeqCst);
+                    failure.store(true, Ordering::SeqCst);
+                }
+                Ok(watcher::Event::Init) | Ok(watcher::Event::InitApply(_)) => {}
+                Ok(_) => flag.store(true, Ordering::SeqCst),
+            }
+        }
+    });
+
+    CacheEntry {
+        store: Box::new(store),
+        ready,
+        failed,
+        task,
+        last_used: now,
+    }
+}
+
+fn read<K>(entry: &mut CacheEntry, now: Instant, namespace: Option<&str>) -> Option<Vec<K>>
+where
+    K: Resource<DynamicType = ()> + Clone + Send + Sync + 'static,
+{
+    // Only a store that answers counts as used, so one stuck syncing still idles out
+    if !entry.ready.load(Ordering::SeqCst) {
+        return None;
+    }
+    entry.last_used = now;
+    let store = entry.store.downcast_ref::<Store<K>>()?;
+    let mut objects: Vec<K> = store
+        .state()
+        .into_iter()
+        .filter(|object| namespace.is_none_or(|ns| object.namespace().as_deref() == Some(ns)))
+        .map(|object| (*object).clone())
+        .collect();
+    // Same order a LIST returns, so rows don't jump between cached and live reads
+    objects.sort_by_cached_key(|object| (object.namespace(), object.name_any()));
+    Some(objects)
+}
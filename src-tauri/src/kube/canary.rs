@@ -0,0 +1,201 @@
+use anyhow::Result;
+use k8s_openapi::api::apps::v1::Deployment;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::api::{Api, DeleteParams, Patch, PatchParams, PostParams};
+use kube::{Client, ResourceExt};
+use serde_json::json;
+
+use crate::types::CanaryStatus;
+
+/// Label telling stable and canary pods apart; the Service selects neither value
+const TRACK_LABEL: &str = "kubesail.io/track";
+
+/// Canary annotations recording the stable Deployment and how to restore it
+const CANARY_OF_ANNOTATION: &str = "kubesail.io/canary-of";
+const STABLE_REPLICAS_ANNOTATION: &str = "kubesail.io/stable-replicas";
+
+fn canary_name(deployment: &str) -> String {
+    format!("{}-canary", deployment)
+}
+
+/// Split `total` replicas so roughly `percent` of pods run the canary, at least one each
+fn split_replicas(total: i32, percent: u8) -> (i32, i32) {
+    let canary = ((total as f64 * percent as f64 / 100.0).round() as i32).max(1);
+    let stable = (total - canary).max(1);
+    (stable, canary)
+}
+
+/// Start a canary of a plain Deployment running `new_image`
+///
+/// A `<name>-canary` copy of the Deployment is created with the new image and
+/// its pods labelled as the canary track, and the original is scaled down so
+/// about `percent` of the pods behind the shared Service run the new version.
+/// Traffic splits by pod count, so small Deployments get coarse splits.
+pub async fn canary_deploy(
+    client: Client,
+    namespace: &str,
+    deployment: &str,
+    new_image: &str,
+    percent: u8,
+    container: Option<&str>,
+) -> Result<CanaryStatus> {
+    if percent == 0 || percent >= 100 {
+        return Err(anyhow::anyhow!("Canary percent must be between 1 and 99"));
+    }
+
+    let api: Api<Deployment> = Api::namespaced(client, namespace);
+    let stable = api.get(deployment).await?;
+    let name = canary_name(deployment);
+    if api.get_opt(&name).await?.is_some() {
+        return Err(anyhow::anyhow!("{} already exists; promote or roll it back first", name));
+    }
+
+    let total = stable.spec.as_ref().and_then(|s| s.replicas).unwrap_or(1);
+    let (stable_replicas, canary_replicas) = split_replicas(total, percent);
+
+    let mut spec = stable
+        .spec
+        .clone()
+        .ok_or_else(|| anyhow::anyhow!("Deployment {} has no spec", deployment))?;
+    spec.replicas = Some(canary_replicas);
+    spec.selector
+        .match_labels
+        .get_or_insert_with(Default::default)
+        .insert(TRACK_LABEL.to_string(), "canary".to_string());
+
+    let template_meta = spec.template.metadata.get_or_insert_with(Default::default);
+    template_meta
+        .labels
+        .get_or_insert_with(Default::default)
+        .insert(TRACK_LABEL.to_string(), "canary".to_string());
+
+    let pod_spec = spec
+        .template
+        .spec
+        .as_mut()
+        .ok_or_else(|| anyhow::anyhow!("Deployment {} has no pod spec", deployment))?;
+    let target = match container {
+        Some(container) => pod_spec.containers.iter_mut().find(|c| c.name == container),
+        None => pod_spec.containers.first_mut(),
+    }
+    .ok_or_else(|| anyhow::anyhow!("Container {} not found", container.unwrap_or_default()))?;
+    let previous_image = target.image.replace(new_image.to_string()).unwrap_or_default();
+    let container_name = target.name.clone();
+
+    let canary = Deployment {
+        metadata: ObjectMeta {
+            name: Some(name.clone()),
+            namespace: Some(namespace.to_string()),
+            labels: stable.metadata.labels.clone(),
+            annotations: Some(
+                [
+                    (CANARY_OF_ANNOTATION.to_string(), deployment.to_string()),
+                    (STABLE_REPLICAS_ANNOTATION.to_string(), total.to_string()),
+                ]
+                .into(),
+            ),
+            ..ObjectMeta::default()
+        },
+        spec: Some(spec),
+        ..Deployment::default()
+    };
+    api.create(&PostParams::default(), &canary).await?;
+
+    api.patch(
+        deployment,
+        &PatchParams::default(),
+        &Patch::Merge(json!({ "spec": { "replicas": stable_replicas } })),
+    )
+    .await?;
+
+    Ok(CanaryStatus {
+        deployment: deployment.to_string(),
+        canary: name,
+        container: container_name,
+        stable_image: previous_image,
+        canary_image: new_image.to_string(),
+        stable_replicas,
+        canary_replicas,
+    })
+}
+
+/// The canary's Deployment and the replica count the stable one had before it
+async fn load_canary(api: &Api<Deployment>, deployment: &str) -> Result<(Deployment, i32)> {
+    let name = canary_name(deployment);
+    let canary = api
+        .get_opt(&name)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("No canary found for {}", deployment))?;
+
+    let annotations = canary.annotations();
+    if annotations.get(CANARY_OF_ANNOTATION).map(|s| s.as_str()) != Some(deployment) {
+        return Err(anyhow::anyhow!("{} was not created as a canary of {}", name, deployment));
+    }
+    let replicas = annotations
+        .get(STABLE_REPLICAS_ANNOTATION)
+        .and_then(|r| r.parse().ok())
+        .unwrap_or(1);
+
+    Ok((canary, replicas))
+}
+
+/// Roll the canary's images out to the stable Deployment and remove the canary
+///
+/// The stable Deployment goes back to its original replica count and does a
+/// normal rolling update; the canary is deleted once that has been applied.
+pub async fn promote_canary(client: Client, namespace: &str, deployment: &str) -> Result<()> {
+    let api: Api<Deployment> = Api::namespaced(client, namespace);
+    let (canary, replicas) = load_canary(&api, deployment).await?;
+
+    let containers: Vec<_> = canary
+        .spec
+        .as_ref()
+        .and_then(|s| s.template.spec.as_ref())
+        .map(|s| {
+            s.containers
+                .iter()
+                .map(|c| json!({ "name": c.name, "image": c.image }))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let patch = json!({
+        "spec": {
+            "replicas": replicas,
+            "template": { "spec": { "containers": containers } }
+        }
+    });
+    api.patch(deployment, &PatchParams::default(), &Patch::Strategic(patch))
+        .await?;
+    api.delete(&canary.name_any(), &DeleteParams::default()).await?;
+    Ok(())
+}
+
+/// Delete the canary and give the stable Deployment its replicas back
+pub async fn rollback_canary(client: Client, namespace: &str, deployment: &str) -> Result<()> {
+    let api: Api<Deployment> = Api::namespaced(client, namespace);
+    let (canary, replicas) = load_canary(&api, deployment).await?;
+
+    // Scale up first so capacity never drops below the canary's share
+    api.patch(
+        deployment,
+        &PatchParams::default(),
+        &Patch::Merge(json!({ "spec": { "replicas": replicas } })),
+    )
+    .await?;
+    api.delete(&canary.name_any(), &DeleteParams::default()).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_replicas() {
+        assert_eq!(split_replicas(10, 10), (9, 1));
+        assert_eq!(split_replicas(10, 25), (7, 3));
+        assert_eq!(split_replicas(4, 5), (3, 1));
+        assert_eq!(split_replicas(1, 50), (1, 1));
+    }
+}
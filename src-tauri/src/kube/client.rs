@@ -1,30 +1,117 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
+use k8s_openapi::http::Request;
+use kube::client::{Body, ClientBuilder};
+use kube::config::{KubeConfigOptions, Kubeconfig};
 use kube::{Client, Config};
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio::sync::RwLock;
+use tower::buffer::BufferLayer;
+
+use super::credentials::{apply_cached_exec_credential, user_for_context, EXPIRY_MARGIN_SECS};
+use super::retry::RetryLayer;
+use super::throttle::{RateLimiter, ThrottleLayer};
+use crate::usage::UsageLayer;
+
+/// Requests queued for the throttled service; the retry layer needs a cloneable service under it
+const RETRY_BUFFER: usize = 1024;
 
 pub struct KubeClientManager {
     client: Arc<RwLock<Option<Client>>>,
+    /// When the cached exec credential baked into the client runs out
+    credential_expiry: Arc<RwLock<Option<DateTime<Utc>>>>,
+    /// Clients for named contexts, independent of the current-context client
+    pool: Arc<RwLock<HashMap<String, PooledClient>>>,
+    /// Per-context throttles, kept across client rebuilds so a rebuild doesn't reset the budget
+    limiters: Arc<Mutex<HashMap<String, Arc<RateLimiter>>>>,
+    /// Source of generation numbers, so a context's generation only ever grows
+    generation: Arc<AtomicU64>,
+    /// Per context, the generation of its latest client rebuild or eviction, so
+    /// holders of old clients know to let go
+    generations: Arc<Mutex<HashMap<String, u64>>>,
+}
+
+struct PooledClient {
+    client: Client,
+    credential_expiry: Option<DateTime<Utc>>,
+}
+
+fn near_expiry(expiry: Option<DateTime<Utc>>) -> bool {
+    expiry
+        .map(|expiry| expiry - chrono::Duration::seconds(EXPIRY_MARGIN_SECS) <= Utc::now())
+        .unwrap_or(false)
 }
 
 impl KubeClientManager {
     pub fn new() -> Self {
         Self {
             client: Arc::new(RwLock::new(None)),
+            credential_expiry: Arc::new(RwLock::new(None)),
+            pool: Arc::new(RwLock::new(HashMap::new())),
+            limiters: Arc::new(Mutex::new(HashMap::new())),
+            generation: Arc::new(AtomicU64::new(0)),
+            generations: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Changes every time `context`'s client is rebuilt (e.g. for a fresh exec token) or evicted
+    pub fn generation(&self, context: &str) -> u64 {
+        let generations = self.generations.lock().unwrap_or_else(|e| e.into_inner());
+        generations.get(context).copied().unwrap_or(0)
+    }
+
+    /// Move `context` to a new generation, or every known context when `None`
+    fn bump_generation(&self, context: Option<&str>) {
+        let next = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        let mut generations = self.generations.lock().unwrap_or_else(|e| e.into_inner());
+        match context {
+            Some(context) => {
+                generations.insert(context.to_string(), next);
+            }
+            None => generations.values_mut().for_each(|g| *g = next),
         }
     }
 
+    /// The throttle for `context`, created with default limits on first use
+    fn limiter_for(&self, context: &str) -> Arc<RateLimiter> {
+        let mut limiters = self.limiters.lock().unwrap_or_else(|e| e.into_inner());
+        limiters
+            .entry(context.to_string())
+            .or_insert_with(|| Arc::new(RateLimiter::new(None, None)))
+            .clone()
+    }
+
+    /// Set a context's client-side QPS and burst; `None` restores the default
+    ///
+    /// Takes effect immediately for clients already built for the context.
+    pub fn set_rate_limit(&self, context: &str, qps: Option<f64>, burst: Option<u32>) {
+        self.limiter_for(context).configure(qps, burst);
+    }
+
     pub async fn init_client(&self) -> Result<()> {
-        let config = Config::infer().await?;
-        let client = Client::try_from(config)?;
+        let mut config = Config::infer().await?;
+        let expiry = with_cached_credentials(&mut config, None).await;
+        let context = super::load_kubeconfig().map(|c| c.current_context).unwrap_or_default();
+        let client = throttled_client(config, self.limiter_for(&context))?;
 
         let mut client_lock = self.client.write().await;
         *client_lock = Some(client);
+        *self.credential_expiry.write().await = expiry;
+        self.bump_generation(Some(&context));
 
         Ok(())
     }
 
     pub async fn get_client(&self) -> Result<Client> {
+        // A client holding a cached token can't refresh it itself, so rebuild it near expiry
+        let expired = near_expiry(*self.credential_expiry.read().await);
+        if expired {
+            self.init_client().await?;
+        }
+
         let client_lock = self.client.read().await;
 
         match client_lock.as_ref() {
@@ -42,6 +129,121 @@ impl KubeClientManager {
     pub async fn reinit_client(&self) -> Result<()> {
         self.init_client().await
     }
+
+    /// A client for a named kubeconfig context, built once and reused
+    ///
+    /// Unlike [`get_client`](Self::get_client) this doesn't follow the current
+    /// context, so several windows or tabs can each talk to their own cluster
+    /// without switching it globally.
+    pub async fn get_client_for(&self, context: &str) -> Result<Client> {
+        if let Some(pooled) = self.pool.read().await.get(context) {
+            if !near_expiry(pooled.credential_expiry) {
+                return Ok(pooled.client.clone());
+            }
+        }
+
+        let (client, credential_expiry) =
+            build_context_client(context, None, Some(self.limiter_for(context))).await?;
+        self.pool.write().await.insert(
+            context.to_string(),
+            PooledClient {
+                client: client.clone(),
+                credential_expiry,
+            },
+        );
+        self.bump_generation(Some(context));
+        Ok(client)
+    }
+
+    /// The pooled client for `context`, or the current-context client when `None`
+    pub async fn get_client_in(&self, context: Option<&str>) -> Result<Client> {
+        match context {
+            Some(context) => self.get_client_for(context).await,
+            None => self.get_client().await,
+        }
+    }
+
+    /// Drop pooled clients, e.g. after the kubeconfig or a context's credentials changed
+    ///
+    /// `None` drops every context's client.
+    pub async fn evict(&self, context: Option<&str>) {
+        let mut pool = self.pool.write().await;
+        match context {
+            Some(context) => {
+                pool.remove(context);
+            }
+            None => pool.clear(),
+        }
+        self.bump_generation(context);
+    }
+
+    /// Contexts that currently have a pooled client
+    pub async fn pooled_contexts(&self) -> Vec<String> {
+        let mut contexts: Vec<String> = self.pool.read().await.keys().cloned().collect();
+        contexts.sort();
+        contexts
+    }
+}
+
+/// Build a standalone client for a named kubeconfig context
+///
+/// `timeout` bounds connecting and reading, for probes that must not hang on
+/// unreachable clusters.
+pub async fn client_for_context(context: &str, timeout: Option<Duration>) -> Result<Client> {
+    Ok(build_context_client(context, timeout, None).await?.0)
+}
+
+/// A client for `context` and when its cached credential expires, if it has one
+async fn build_context_client(
+    context: &str,
+    timeout: Option<Duration>,
+    limiter: Option<Arc<RateLimiter>>,
+) -> Result<(Client, Option<DateTime<Utc>>)> {
+    let kubeconfig = Kubeconfig::read()?;
+    let options = KubeConfigOptions {
+        context: Some(context.to_string()),
+        ..Default::default()
+    };
+
+    let mut config = Config::from_custom_kubeconfig(kubeconfig, &options).await?;
+    let expiry = with_cached_credentials(&mut config, Some(context)).await;
+    if let Some(timeout) = timeout {
+        config.connect_timeout = Some(timeout);
+        config.read_timeout = Some(timeout);
+    }
+
+    let client = match limiter {
+        Some(limiter) => throttled_client(config, limiter)?,
+        None => Client::try_from(config)?,
+    };
+    Ok((client, expiry))
+}
+
+/// A client that waits on `limiter` before each request and retries transient failures
+///
+/// Each retry goes back through the throttle, so retries can't burst past the limit.
+fn throttled_client(config: Config, limiter: Arc<RateLimiter>) -> Result<Client> {
+    Ok(ClientBuilder::try_from(config)?
+        .with_layer(&ThrottleLayer::new(limiter))
+        .with_layer(&BufferLayer::<Request<Body>>::new(RETRY_BUFFER))
+        .with_layer(&RetryLayer)
+        .with_layer(&UsageLayer)
+        .build())
+}
+
+/// Use a cached exec credential when possible; on failure kube runs the plugin itself
+async fn with_cached_credentials(
+    config: &mut Config,
+    context: Option<&str>,
+) -> Option<DateTime<Utc>> {
+    let user = user_for_context(context)?;
+    match apply_cached_exec_credential(config, &user).await {
+        Ok(expiry) => expiry,
+        Err(e) => {
+            tracing::warn!("Credential cache unavailable for user {}: {}", user, e);
+            None
+        }
+    }
 }
 
 impl Default for KubeClientManager {
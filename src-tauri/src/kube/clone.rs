@@ -0,0 +1,465 @@
+use anyhow::Result;
+use k8s_openapi::api::core::v1::{ConfigMap, Namespace, Secret};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::api::{Api, DynamicObject, ListParams, PostParams};
+use kube::discovery::{ApiResource, Discovery, Scope};
+use kube::{Client, ResourceExt};
+use std::collections::HashMap;
+
+use crate::kube::columns::find_kind;
+use crate::types::{
+    CopyResourceResult, NamespaceCloneFailure, NamespaceCloneResult, NamespaceCloneRules, ResourceRef,
+};
+
+/// Kinds copied when the caller doesn't pick any
+const DEFAULT_CLONE_KINDS: &[&str] = &[
+    "ConfigMap",
+    "Secret",
+    "ServiceAccount",
+    "Service",
+    "Deployment",
+    "StatefulSet",
+    "DaemonSet",
+    "CronJob",
+    "Ingress",
+    "PersistentVolumeClaim",
+    "Role",
+    "RoleBinding",
+];
+
+/// Annotations that belong to the source object's lifecycle, not its definition
+const SERVER_ANNOTATIONS: &[&str] = &[
+    "kubectl.kubernetes.io/last-applied-configuration",
+    "deployment.kubernetes.io/revision",
+    "pv.kubernetes.io/bind-completed",
+    "pv.kubernetes.io/bound-by-controller",
+    "volume.beta.kubernetes.io/storage-provisioner",
+    "volume.kubernetes.io/storage-provisioner",
+    "volume.kubernetes.io/selected-node",
+];
+
+/// Copy resources from one namespace into another to spin up a test environment
+///
+/// Objects owned by controllers (ReplicaSets, Jobs spawned by CronJobs, ...)
+/// and cluster-generated objects like service account tokens are skipped.
+/// Names can be prefixed/suffixed and extra labels added; note that renaming
+/// does not rewrite references between objects, use `replacements` for that.
+/// With `dry_run` the objects are validated by the API server but not stored;
+/// when the target namespace doesn't exist yet they can't be validated and are
+/// only listed.
+pub async fn clone_namespace(
+    client: Client,
+    source: &str,
+    target: &str,
+    kinds: Option<Vec<String>>,
+    rules: NamespaceCloneRules,
+    dry_run: bool,
+) -> Result<NamespaceCloneResult> {
+    if source == target {
+        return Err(anyhow::anyhow!("Source and target namespace must differ"));
+    }
+
+    let kinds: Vec<String> = kinds
+        .filter(|k| !k.is_empty())
+        .unwrap_or_else(|| DEFAULT_CLONE_KINDS.iter().map(|k| k.to_string()).collect());
+
+    let resources = resolve_namespaced_kinds(client.clone(), &kinds).await?;
+
+    let mut result = NamespaceCloneResult {
+        created: Vec::new(),
+        skipped: Vec::new(),
+        failed: Vec::new(),
+    };
+
+    for kind in &kinds {
+        if !resources.contains_key(kind) {
+            result.skipped.push(format!("{} (unknown or cluster-scoped kind)", kind));
+        }
+    }
+
+    // A dry-run namespace isn't there for the dry-run creates that follow, so
+    // those would all fail with NotFound; report them as would-be creates instead
+    let namespace_existed = ensure_namespace(client.clone(), target, dry_run).await?;
+    let validate = !dry_run || namespace_existed;
+    if !namespace_existed {
+        result.created.push(format!("Namespace/{}", target));
+    }
+
+    let post_params = PostParams {
+        dry_run,
+        ..Default::default()
+    };
+
+    for kind in &kinds {
+        let Some(resource) = resources.get(kind) else {
+            continue;
+        };
+
+        let source_api: Api<DynamicObject> = Api::namespaced_with(client.clone(), source, resource);
+        let target_api: Api<DynamicObject> = Api::namespaced_with(client.clone(), target, resource);
+
+        let objects = source_api.list(&ListParams::default()).await?;
+
+        for mut obj in objects.items {
+            let original = format!("{}/{}", kind, obj.name_any());
+
+            if let Some(reason) = skip_reason(kind, &obj) {
+                result.skipped.push(format!("{} ({})", original, reason));
+                continue;
+            }
+
+            prepare_copy(kind, &mut obj);
+
+            let obj = match apply_clone_rules(obj, target, &rules) {
+                Ok(obj) => obj,
+                Err(e) => {
+                    result.failed.push(NamespaceCloneFailure {
+                        resource: original,
+                        error: e.to_string(),
+                    });
+                    continue;
+                }
+            };
+
+            let created_name = format!("{}/{}", kind, obj.name_any());
+            if !validate {
+                result.created.push(created_name);
+                continue;
+            }
+            match target_api.create(&post_params, &obj).await {
+                Ok(_) => result.created.push(created_name),
+                Err(e) => result.failed.push(NamespaceCloneFailure {
+                    resource: original,
+                    error: e.to_string(),
+                }),
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Strip everything tying `obj` to its source so a copy can be created elsewhere
+fn prepare_copy(kind: &str, obj: &mut DynamicObject) {
+    strip_server_fields(obj);
+    match kind {
+        "Service" => strip_service_allocations(obj),
+        // A copy bound to the source's volume would stay Pending forever
+        "PersistentVolumeClaim" => {
+            if let Some(spec) = obj.data.get_mut("spec").and_then(|s| s.as_object_mut()) {
+                spec.remove("volumeName");
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Remove fields the API server owns so the object can be created elsewhere
+pub(crate) fn strip_server_fields(obj: &mut DynamicObject) {
+    let meta = &mut obj.metadata;
+    meta.uid = None;
+    meta.resource_version = None;
+    meta.creation_timestamp = None;
+    meta.deletion_timestamp = None;
+    meta.deletion_grace_period_seconds = None;
+    meta.generation = None;
+    meta.managed_fields = None;
+    meta.owner_references = None;
+    meta.self_link = None;
+    meta.finalizers = None;
+
+    if let Some(annotations) = meta.annotations.as_mut() {
+        for key in SERVER_ANNOTATIONS {
+            annotations.remove(*key);
+        }
+        if annotations.is_empty() {
+            meta.annotations = None;
+        }
+    }
+
+    if let Some(data) = obj.data.as_object_mut() {
+        data.remove("status");
+    }
+}
+
+/// Drop cluster-allocated IPs and ports from a Service spec
+pub(crate) fn strip_service_allocations(obj: &mut DynamicObject) {
+    let Some(spec) = obj.data.get_mut("spec").and_then(|s| s.as_object_mut()) else {
+        return;
+    };
+
+    // Headless services keep clusterIP: None, which is part of their definition
+    let headless = spec.get("clusterIP").and_then(|ip| ip.as_str()) == Some("None");
+    if !headless {
+        spec.remove("clusterIP");
+        spec.remove("clusterIPs");
+    }
+    spec.remove("healthCheckNodePort");
+
+    if let Some(ports) = spec.get_mut("ports").and_then(|p| p.as_array_mut()) {
+        for port in ports {
+            if let Some(port) = port.as_object_mut() {
+                port.remove("nodePort");
+            }
+        }
+    }
+}
+
+fn skip_reason(kind: &str, obj: &DynamicObject) -> Option<&'static str> {
+    if obj
+        .metadata
+        .owner_references
+        .as_ref()
+        .map(|refs| !refs.is_empty())
+        .unwrap_or(false)
+    {
+        return Some("managed by an owner");
+    }
+
+    match kind {
+        "Secret" => {
+            let secret_type = obj.data.get("type").and_then(|t| t.as_str());
+            (secret_type == Some("kubernetes.io/service-account-token")).then_some("service account token")
+        }
+        "ConfigMap" => (obj.name_any() == "kube-root-ca.crt").then_some("created by the cluster"),
+        "ServiceAccount" => (obj.name_any() == "default").then_some("created by the cluster"),
+        _ => None,
+    }
+}
+
+fn apply_clone_rules(
+    mut obj: DynamicObject,
+    target: &str,
+    rules: &NamespaceCloneRules,
+) -> Result<DynamicObject> {
+    let name = obj.name_any();
+    obj.metadata.name = Some(format!(
+        "{}{}{}",
+        rules.name_prefix.as_deref().unwrap_or_default(),
+        name,
+        rules.name_suffix.as_deref().unwrap_or_default()
+    ));
+
+    if !rules.labels.is_empty() {
+        let labels = obj.metadata.labels.get_or_insert_with(Default::default);
+        for (key, value) in &rules.labels {
+            labels.insert(key.clone(), value.clone());
+        }
+    }
+
+    if !rules.replacements.is_empty() {
+        // Replacements operate on the serialized object so they reach every field
+        let mut text = serde_json::to_string(&obj)?;
+        for replacement in &rules.replacements {
+            if !replacement.from.is_empty() {
+                text = text.replace(&replacement.from, &replacement.to);
+            }
+        }
+        obj = serde_json::from_str(&text)?;
+    }
+
+    // Set on the parsed object, after replacements, so a rule can't move it out of the target
+    obj.metadata.namespace = Some(target.to_string());
+    Ok(obj)
+}
+
+/// Map kind names to the preferred namespaced API resource for each; cluster-scoped kinds are left out
+async fn resolve_namespaced_kinds(client: Client, kinds: &[String]) -> Result<HashMap<String, ApiResource>> {
+    let discovery = Discovery::new(client).run().await?;
+    Ok(kinds
+        .iter()
+        .filter_map(|kind| match find_kind(&discovery, kind) {
+            Some((resource, Scope::Namespaced)) => Some((kind.clone(), resource)),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Create the namespace unless it exists, returning whether it already did;
+/// with `dry_run` the API server only validates the create
+async fn ensure_namespace(client: Client, name: &str, dry_run: bool) -> Result<bool> {
+    let namespaces: Api<Namespace> = Api::all(client);
+    if namespaces.get_opt(name).await?.is_some() {
+        return Ok(true);
+    }
+
+    let namespace: Namespace = serde_json::from_value(serde_json::json!({
+        "apiVersion": "v1",
+        "kind": "Namespace",
+        "metadata": { "name": name }
+    }))?;
+    let params = PostParams {
+        dry_run,
+        ..Default::default()
+    };
+    namespaces.create(&params, &namespace).await?;
+
+    Ok(false)
+}
+
+/// Copy a single object into another namespace and/or cluster
+///
+/// `target_client` talks to the destination cluster (the source client when
+/// copying within one cluster). With `include_dependencies`, ConfigMaps and
+/// Secrets referenced by a workload's pod template are copied first; ones that
+/// already exist at the destination are left alone.
+pub async fn copy_resource(
+    source_client: Client,
+    target_client: Client,
+    source: &ResourceRef,
+    target_namespace: &str,
+    rename: Option<&str>,
+    include_dependencies: bool,
+) -> Result<CopyResourceResult> {
+    let resources = resolve_namespaced_kinds(source_client.clone(), std::slice::from_ref(&source.kind)).await?;
+    let resource = resources
+        .get(&source.kind)
+        .ok_or_else(|| anyhow::anyhow!("Unknown or cluster-scoped kind: {}", source.kind))?;
+
+    let source_api: Api<DynamicObject> = Api::namespaced_with(source_client.clone(), &source.namespace, resource);
+    let obj = source_api.get(&source.name).await?;
+
+    let mut result = CopyResourceResult {
+        created: Vec::new(),
+        skipped: Vec::new(),
+    };
+
+    if include_dependencies {
+        for (kind, name) in pod_template_dependencies(&obj) {
+            let dependency = ResourceRef {
+                kind: kind.to_string(),
+                namespace: source.namespace.clone(),
+                name,
+            };
+            copy_dependency(source_client.clone(), target_client.clone(), &dependency, target_namespace, &mut result)
+                .await?;
+        }
+    }
+
+    let mut obj = obj;
+    prepare_copy(&source.kind, &mut obj);
+    if let Some(name) = rename.filter(|n| !n.is_empty()) {
+        obj.metadata.name = Some(name.to_string());
+    }
+    obj.metadata.namespace = Some(target_namespace.to_string());
+
+    let target_api: Api<DynamicObject> = Api::namespaced_with(target_client, target_namespace, resource);
+    target_api.create(&PostParams::default(), &obj).await?;
+    result.created.push(format!("{}/{}", source.kind, obj.name_any()));
+
+    Ok(result)
+}
+
+async fn copy_dependency(
+    source_client: Client,
+    target_client: Client,
+    dependency: &ResourceRef,
+    target_namespace: &str,
+    result: &mut CopyResourceResult,
+) -> Result<()> {
+    let label = format!("{}/{}", dependency.kind, dependency.name);
+    match dependency.kind.as_str() {
+        "ConfigMap" => {
+            let target: Api<ConfigMap> = Api::namespaced(target_client, target_namespace);
+            if target.get_opt(&dependency.name).await?.is_some() {
+                result.skipped.push(format!("{} (already exists)", label));
+                return Ok(());
+            }
+            let source: Api<ConfigMap> = Api::namespaced(source_client, &dependency.namespace);
+            let Some(mut cm) = source.get_opt(&dependency.name).await? else {
+                result.skipped.push(format!("{} (not found in source)", label));
+                return Ok(());
+            };
+            cm.metadata = clean_metadata(cm.metadata, target_namespace);
+            target.create(&PostParams::default(), &cm).await?;
+        }
+        _ => {
+            let target: Api<Secret> = Api::namespaced(target_client, target_namespace);
+            if target.get_opt(&dependency.name).await?.is_some() {
+                result.skipped.push(format!("{} (already exists)", label));
+                return Ok(());
+            }
+            let source: Api<Secret> = Api::namespaced(source_client, &dependency.namespace);
+            let Some(mut secret) = source.get_opt(&dependency.name).await? else {
+                result.skipped.push(format!("{} (not found in source)", label));
+                return Ok(());
+            };
+            if secret.type_.as_deref() == Some("kubernetes.io/service-account-token") {
+                result.skipped.push(format!("{} (service account token)", label));
+                return Ok(());
+            }
+            secret.metadata = clean_metadata(secret.metadata, target_namespace);
+            target.create(&PostParams::default(), &secret).await?;
+        }
+    }
+
+    result.created.push(label);
+    Ok(())
+}
+
+fn clean_metadata(meta: ObjectMeta, namespace: &str) -> ObjectMeta {
+    let mut annotations = meta.annotations.unwrap_or_default();
+    for key in SERVER_ANNOTATIONS {
+        annotations.remove(*key);
+    }
+
+    ObjectMeta {
+        name: meta.name,
+        namespace: Some(namespace.to_string()),
+        labels: meta.labels,
+        annotations: (!annotations.is_empty()).then_some(annotations),
+        ..Default::default()
+    }
+}
+
+/// ConfigMaps and Secrets a workload's pod template refers to, as (kind, name)
+fn pod_template_dependencies(obj: &DynamicObject) -> Vec<(&'static str, String)> {
+    let pod_spec = ["/spec/template/spec", "/spec/jobTemplate/spec/template/spec", "/spec"]
+        .iter()
+        .filter_map(|path| obj.data.pointer(path))
+        .find(|spec| spec.get("containers").is_some());
+    let Some(pod_spec) = pod_spec else {
+        return Vec::new();
+    };
+
+    let mut deps: Vec<(&'static str, String)> = Vec::new();
+    let mut add = |kind: &'static str, name: Option<&serde_json::Value>| {
+        if let Some(name) = name.and_then(|n| n.as_str()) {
+            let dep = (kind, name.to_string());
+            if !deps.contains(&dep) {
+                deps.push(dep);
+            }
+        }
+    };
+
+    let containers = ["containers", "initContainers"]
+        .iter()
+        .filter_map(|key| pod_spec.get(*key).and_then(|c| c.as_array()))
+        .flatten();
+    for container in containers {
+        for env in container.get("env").and_then(|e| e.as_array()).into_iter().flatten() {
+            add("ConfigMap", env.pointer("/valueFrom/configMapKeyRef/name"));
+            add("Secret", env.pointer("/valueFrom/secretKeyRef/name"));
+        }
+        for env_from in container.get("envFrom").and_then(|e| e.as_array()).into_iter().flatten() {
+            add("ConfigMap", env_from.pointer("/configMapRef/name"));
+            add("Secret", env_from.pointer("/secretRef/name"));
+        }
+    }
+
+    for volume in pod_spec.get("volumes").and_then(|v| v.as_array()).into_iter().flatten() {
+        add("ConfigMap", volume.pointer("/configMap/name"));
+        add("Secret", volume.pointer("/secret/secretName"));
+        let sources = volume.pointer("/projected/sources").and_then(|s| s.as_array());
+        for source in sources.into_iter().flatten() {
+            add("ConfigMap", source.pointer("/configMap/name"));
+            add("Secret", source.pointer("/secret/name"));
+        }
+    }
+
+    for pull_secret in pod_spec.get("imagePullSecrets").and_then(|p| p.as_array()).into_iter().flatten() {
+        add("Secret", pull_secret.get("name"));
+    }
+
+    deps
+}
@@ -0,0 +1,395 @@
+use anyhow::Result;
+use kube::api::{Api, DynamicObject, ListParams};
+use kube::discovery::{ApiGroup, ApiResource, Discovery, Scope};
+use kube::Client;
+use serde_json::Value;
+
+use crate::kube::events::csv_field;
+use crate::types::ResourceTable;
+
+/// Printed for a column whose expression matches nothing, as kubectl does
+const NONE: &str = "<none>";
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Field(String),
+    Index(i64),
+    Wildcard,
+    /// `[?(@.path op "value")]`; no operator means the path must exist
+    Filter { path: Vec<Segment>, op: Option<(String, String)> },
+}
+
+/// Parse a kubectl-style JSONPath such as `{.status.conditions[?(@.type=="Ready")].status}`
+///
+/// Supports fields, quoted keys (`['app.kubernetes.io/name']`), indices
+/// (negative counts from the end), `*`/`[*]` and `==`/`!=` filters; enough for
+/// custom columns without pulling in a JSONPath engine.
+fn parse_path(expression: &str) -> Result<Vec<Segment>> {
+    let expression = expression.trim();
+    let expression = expression
+        .strip_prefix('{')
+        .and_then(|e| e.strip_suffix('}'))
+        .unwrap_or(expression);
+    let expression = expression.strip_prefix('@').or_else(|| expression.strip_prefix('$')).unwrap_or(expression);
+
+    let chars: Vec<char> = expression.chars().collect();
+    let mut segments = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '.' => {
+                i += 1;
+                if chars.get(i) == Some(&'*') {
+                    segments.push(Segment::Wildcard);
+                    i += 1;
+                    continue;
+                }
+                let start = i;
+                while i < chars.len() && chars[i] != '.' && chars[i] != '[' {
+                    i += 1;
+                }
+                if i > start {
+                    segments.push(Segment::Field(chars[start..i].iter().collect()));
+                }
+            }
+            '[' => {
+                let close = find_close(&chars, i)
+                    .ok_or_else(|| anyhow::anyhow!("Unclosed '[' in {}", expression))?;
+                let inner: String = chars[i + 1..close].iter().collect();
+                let inner = inner.trim();
+                segments.push(if inner == "*" {
+                    Segment::Wildcard
+                } else if let Some(filter) = inner.strip_prefix("?(").and_then(|f| f.strip_suffix(')')) {
+                    parse_filter(filter)?
+                } else if let Some(key) = unquote(inner) {
+                    Segment::Field(key)
+                } else {
+                    Segment::Index(
+                        inner
+                            .parse()
+                            .map_err(|_| anyhow::anyhow!("Invalid index [{}] in {}", inner, expression))?,
+                    )
+                });
+                i = close + 1;
+            }
+            c => return Err(anyhow::anyhow!("Unexpected '{}' in {}; paths start with '.'", c, expression)),
+        }
+    }
+    Ok(segments)
+}
+
+/// Index of the `]` closing the `[` at `open`, skipping brackets inside quotes and filters
+fn find_close(chars: &[char], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    for (i, &c) in chars.iter().enumerate().skip(open) {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '[') => depth += 1,
+            (None, ']') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn unquote(value: &str) -> Option<String> {
+    let value = value.trim();
+    ['\'', '"'].iter().find_map(|q| {
+        value
+            .strip_prefix(*q)
+            .and_then(|v| v.strip_suffix(*q))
+            .map(|v| v.to_string())
+    })
+}
+
+fn parse_filter(filter: &str) -> Result<Segment> {
+    for op in ["==", "!="] {
+        if let Some((path, value)) = filter.split_once(op) {
+            let value = value.trim();
+            let value = unquote(value).unwrap_or_else(|| value.to_string());
+            return Ok(Segment::Filter {
+                path: parse_path(path)?,
+                op: Some((op.to_string(), value)),
+            });
+        }
+    }
+    Ok(Segment::Filter { path: parse_path(filter)?, op: None })
+}
+
+fn evaluate<'a>(root: &'a Value, path: &[Segment]) -> Vec<&'a Value> {
+    let mut current = vec![root];
+    for segment in path {
+        current = current
+            .into_iter()
+            .flat_map(|value| -> Vec<&'a Value> {
+                match (segment, value) {
+                    (Segment::Field(name), Value::Object(map)) => map.get(name).into_iter().collect(),
+                    (Segment::Index(index), Value::Array(items)) => {
+                        let index = if *index < 0 { items.len() as i64 + index } else { *index };
+                        usize::try_from(index).ok().and_then(|i| items.get(i)).into_iter().collect()
+                    }
+                    (Segment::Wildcard, Value::Array(items)) => items.iter().collect(),
+                    (Segment::Wildcard, Value::Object(map)) => map.values().collect(),
+                    (Segment::Filter { path, op }, Value::Array(items)) => items
+                        .iter()
+                        .filter(|item| {
+                            let found = evaluate(item, path);
+                            match op {
+                                None => !found.is_empty(),
+                                Some((op, expected)) => {
+                                    let matches = found.iter().any(|v| display(v) == *expected);
+                                    if op == "==" { matches } else { !matches }
+                                }
+                            }
+                        })
+                        .collect(),
+                    _ => Vec::new(),
+                }
+            })
+            .collect();
+    }
+    current
+}
+
+fn display(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// A `HEADER:expression` column, as in `kubectl get -o custom-columns`
+fn parse_column(column: &str) -> Result<(String, Vec<Segment>)> {
+    let (header, expression) = column
+        .split_once(':')
+        .ok_or_else(|| anyhow::anyhow!("Column '{}' must be HEADER:.json.path", column))?;
+    Ok((header.trim().to_string(), parse_path(expression)?))
+}
+
+/// Short names kubectl accepts for built-in kinds; discovery doesn't report them
+const SHORT_NAMES: &[(&str, &str)] = &[
+    ("cj", "cronjobs"),
+    ("cm", "configmaps"),
+    ("crd", "customresourcedefinitions"),
+    ("deploy", "deployments"),
+    ("ds", "daemonsets"),
+    ("ep", "endpoints"),
+    ("hpa", "horizontalpodautoscalers"),
+    ("ing", "ingresses"),
+    ("netpol", "networkpolicies"),
+    ("no", "nodes"),
+    ("ns", "namespaces"),
+    ("pdb", "poddisruptionbudgets"),
+    ("po", "pods"),
+    ("pv", "persistentvolumes"),
+    ("pvc", "persistentvolumeclaims"),
+    ("rs", "replicasets"),
+    ("sa", "serviceaccounts"),
+    ("sc", "storageclasses"),
+    ("sts", "statefulsets"),
+    ("svc", "services"),
+];
+
+/// Split a kind as given into (name, group, version)
+///
+/// Accepts `Kind` or `plural`, kubectl's group-qualified `plural.group`
+/// (`certificates.cert-manager.io`), and exact `group/version/Kind`
+/// (`v1/Kind` for the core group).
+fn parse_kind(kind: &str) -> (&str, Option<&str>, Option<&str>) {
+    let kind = kind.trim();
+    match kind.split('/').collect::<Vec<_>>().as_slice() {
+        [version, name] => (*name, Some(""), Some(*version)),
+        [group, version, name] => (*name, Some(*group), Some(*version)),
+        _ => match kind.split_once('.') {
+            Some((name, group)) => (name, Some(group), None),
+            None => (
+                SHORT_NAMES
+                    .iter()
+                    .find(|(short, _)| short.eq_ignore_ascii_case(kind))
+                    .map_or(kind, |(_, plural)| *plural),
+                None,
+                None,
+            ),
+        },
+    }
+}
+
+/// API groups in the order a kind is looked up in: core, then apps, then the rest by name
+///
+/// Discovery returns groups in no particular order, so without this a kind
+/// served by two groups could resolve differently from one call to the next.
+fn groups_by_preference(discovery: &Discovery) -> Vec<&ApiGroup> {
+    let mut groups = discovery.groups_alphabetical();
+    groups.sort_by_key(|group| match group.name() {
+        ApiGroup::CORE_GROUP => 0,
+        "apps" => 1,
+        _ => 2,
+    });
+    groups
+}
+
+/// Find the API resource for a kind, matching kind or plural case-insensitively
+///
+/// Without a version the group's preferred one is used; see [`parse_kind`]
+/// for the accepted forms.
+pub(crate) async fn resolve_kind(client: Client, kind: &str) -> Result<(ApiResource, Scope)> {
+    let discovery = Discovery::new(client).run().await?;
+    find_kind(&discovery, kind).ok_or_else(|| anyhow::anyhow!("Unknown resource kind: {}", kind))
+}
+
+/// [`resolve_kind`] against a discovery that has already run, for callers resolving several kinds
+pub(crate) fn find_kind(discovery: &Discovery, kind: &str) -> Option<(ApiResource, Scope)> {
+    let (name, group, version) = parse_kind(kind);
+    for api_group in groups_by_preference(discovery) {
+        if group.is_some_and(|group| api_group.name() != group) {
+            continue;
+        }
+        let resources = match version {
+            Some(version) => api_group.versioned_resources(version),
+            None => api_group.recommended_resources(),
+        };
+        let found = resources.into_iter().find(|(r, _)| {
+            r.kind.eq_ignore_ascii_case(name) || r.plural.eq_ignore_ascii_case(name)
+        });
+        if let Some((resource, caps)) = found {
+            return Some((resource, caps.scope));
+        }
+    }
+    None
+}
+
+/// Every object of a kind as JSON, in `namespace` when the kind is namespaced and one is given
+pub(crate) async fn list_objects(client: Client, kind: &str, namespace: Option<&str>) -> Result<(ApiResource, Vec<Value>)> {
+    let (resource, scope) = resolve_kind(client.clone(), kind).await?;
+    let api: Api<DynamicObject> = match namespace.filter(|ns| !ns.is_empty()) {
+        Some(ns) if scope == Scope::Namespaced => Api::namespaced_with(client, ns, &resource),
+        _ => Api::all_with(client, &resource),
+    };
+    let list = api.list(&ListParams::default()).await?;
+
+    let mut items = Vec::with_capacity(list.items.len());
+    for item in &list.items {
+        let mut value = serde_json::to_value(item)?;
+        // DynamicObject lists don't repeat apiVersion/kind per item
+        value["apiVersion"] = Value::String(resource.api_version.clone());
+        value["kind"] = Value::String(resource.kind.clone());
+        items.push(value);
+    }
+    Ok((resource, items))
+}
+
+/// List any kind and render it as a table of custom columns, plus the same table as CSV
+///
+/// Columns use kubectl's custom-columns syntax, e.g.
+/// `NAME:.metadata.name` or `READY:.status.conditions[?(@.type=="Ready")].status`.
+/// A path matching several values joins them with commas; one matching
+/// nothing shows `<none>`.
+pub async fn render_resource_table(
+    client: Client,
+    kind: &str,
+    namespace: Option<&str>,
+    columns: &[String],
+) -> Result<ResourceTable> {
+    if columns.is_empty() {
+        return Err(anyhow::anyhow!("At least one column is required"));
+    }
+    let columns: Vec<(String, Vec<Segment>)> = columns.iter().map(|c| parse_column(c)).collect::<Result<_>>()?;
+
+    let (resource, items) = list_objects(client, kind, namespace).await?;
+
+    let mut rows = Vec::new();
+    for value in &items {
+        rows.push(
+            columns
+                .iter()
+                .map(|(_, path)| {
+                    let values: Vec<String> = evaluate(value, path).into_iter().map(display).collect();
+                    if values.is_empty() { NONE.to_string() } else { values.join(",") }
+                })
+                .collect::<Vec<String>>(),
+        );
+    }
+
+    let headers: Vec<String> = columns.into_iter().map(|(header, _)| header).collect();
+    let mut csv = headers.iter().map(|h| csv_field(h)).collect::<Vec<_>>().join(",");
+    csv.push('\n');
+    for row in &rows {
+        csv.push_str(&row.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(","));
+        csv.push('\n');
+    }
+
+    Ok(ResourceTable {
+        kind: resource.kind,
+        columns: headers,
+        rows,
+        csv,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_kind() {
+        assert_eq!(parse_kind("Deployment"), ("Deployment", None, None));
+        assert_eq!(parse_kind("pvc"), ("persistentvolumeclaims", None, None));
+        assert_eq!(parse_kind("certificates.cert-manager.io"), ("certificates", Some("cert-manager.io"), None));
+        assert_eq!(
+            parse_kind("networking.k8s.io/v1/NetworkPolicy"),
+            ("NetworkPolicy", Some("networking.k8s.io"), Some("v1"))
+        );
+        assert_eq!(parse_kind("v1/ConfigMap"), ("ConfigMap", Some(""), Some("v1")));
+    }
+
+    fn column(value: &Value, expression: &str) -> Vec<String> {
+        evaluate(value, &parse_path(expression).unwrap()).into_iter().map(display).collect()
+    }
+
+    #[test]
+    fn test_evaluate_paths() {
+        let pod = json!({
+            "metadata": { "name": "web-0", "labels": { "app.kubernetes.io/name": "web" } },
+            "spec": { "containers": [{ "name": "app", "image": "nginx:1.27" }, { "name": "proxy", "image": "envoy" }] },
+            "status": { "conditions": [
+                { "type": "PodScheduled", "status": "True" },
+                { "type": "Ready", "status": "False" }
+            ]}
+        });
+
+        assert_eq!(column(&pod, "{.metadata.name}"), vec!["web-0"]);
+        assert_eq!(column(&pod, ".metadata.labels['app.kubernetes.io/name']"), vec!["web"]);
+        assert_eq!(column(&pod, ".spec.containers[*].image"), vec!["nginx:1.27", "envoy"]);
+        assert_eq!(column(&pod, ".spec.containers[-1].name"), vec!["proxy"]);
+        assert_eq!(column(&pod, ".status.conditions[?(@.type==\"Ready\")].status"), vec!["False"]);
+        assert_eq!(column(&pod, ".status.conditions[?(@.status!='True')].type"), vec!["Ready"]);
+        assert!(column(&pod, ".spec.nodeName").is_empty());
+    }
+
+    #[test]
+    fn test_parse_column() {
+        let (header, path) = parse_column("IMAGE:.spec.containers[0].image").unwrap();
+        assert_eq!(header, "IMAGE");
+        assert_eq!(
+            path,
+            vec![
+                Segment::Field("spec".into()),
+                Segment::Field("containers".into()),
+                Segment::Index(0),
+                Segment::Field("image".into())
+            ]
+        );
+        assert!(parse_column("no-path").is_err());
+        assert!(parse_column("BAD:spec").is_err());
+    }
+}
@@ -0,0 +1,191 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use kube::config::ExecConfig;
+use kube::Config;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use tokio::process::Command;
+
+use super::config::{get_kubeconfig_paths, load_kubeconfig};
+
+/// Tokens are refreshed this long before they expire
+pub(crate) const EXPIRY_MARGIN_SECS: i64 = 60;
+
+/// How long to reuse a credential whose plugin didn't say when it expires
+const NO_EXPIRY_TTL_SECS: i64 = 600;
+
+#[derive(Clone)]
+struct CachedCredential {
+    token: String,
+    expires_at: DateTime<Utc>,
+}
+
+/// What a cached token was minted for: the same user name in another
+/// kubeconfig, or with a different plugin invocation, is a different identity
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct CredentialKey {
+    kubeconfig: Vec<String>,
+    user: String,
+    command: Option<String>,
+    args: Vec<String>,
+    env: Vec<(String, String)>,
+}
+
+impl CredentialKey {
+    fn new(user: &str, exec: &ExecConfig) -> Self {
+        let kubeconfig = get_kubeconfig_paths()
+            .unwrap_or_default()
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        let mut env: Vec<(String, String)> = exec
+            .env
+            .iter()
+            .flatten()
+            .filter_map(|var| Some((var.get("name")?.clone(), var.get("value")?.clone())))
+            .collect();
+        env.sort();
+        Self {
+            kubeconfig,
+            user: user.to_string(),
+            command: exec.command.clone(),
+            args: exec.args.clone().unwrap_or_default(),
+            env,
+        }
+    }
+}
+
+fn cache() -> &'static Mutex<HashMap<CredentialKey, CachedCredential>> {
+    static CACHE: OnceLock<Mutex<HashMap<CredentialKey, CachedCredential>>> = OnceLock::new();
+    CACHE.get_or_init(Default::default)
+}
+
+/// Drop every cached exec credential so the next client runs the plugins again
+///
+/// Also called whenever the kubeconfig or the current context changes.
+pub fn clear_credential_cache() -> usize {
+    let mut cache = cache().lock().unwrap_or_else(|e| e.into_inner());
+    let cleared = cache.len();
+    cache.clear();
+    cleared
+}
+
+/// The kubeconfig user entry a context (or the current context) authenticates as
+pub fn user_for_context(context: Option<&str>) -> Option<String> {
+    let config = load_kubeconfig().ok()?;
+    let context = context.unwrap_or(&config.current_context);
+
+    config
+        .contexts
+        .iter()
+        .find(|c| c.name == context)
+        .map(|c| c.context.user.clone())
+}
+
+/// Swap an exec-plugin user for a cached bearer token
+///
+/// Runs the plugin only when there's no unexpired token for `user` with the
+/// same kubeconfig and plugin invocation, so rebuilding clients doesn't pay
+/// for `aws eks get-token` style plugins every time. Plugins that return client
+/// certificates are left for kube to run itself. Returns when the token
+/// expires, so the caller knows when to rebuild its client.
+pub async fn apply_cached_exec_credential(
+    config: &mut Config,
+    user: &str,
+) -> Result<Option<DateTime<Utc>>> {
+    let Some(exec) = config.auth_info.exec.clone() else {
+        return Ok(None);
+    };
+
+    let key = CredentialKey::new(user, &exec);
+    let cached = cache()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&key)
+        .cloned()
+        .filter(|c| c.expires_at - Duration::seconds(EXPIRY_MARGIN_SECS) > Utc::now());
+
+    let credential = match cached {
+        Some(credential) => credential,
+        None => {
+            let Some(credential) = run_exec_plugin(&exec).await? else {
+                return Ok(None);
+            };
+            cache()
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(key, credential.clone());
+            credential
+        }
+    };
+
+    // AuthInfo keeps its secrets in secrecy types; going through serde avoids depending on them
+    let mut auth = serde_json::to_value(&config.auth_info)?;
+    if let Some(auth) = auth.as_object_mut() {
+        auth.remove("exec");
+        auth.insert("token".to_string(), serde_json::json!(credential.token));
+    }
+    config.auth_info = serde_json::from_value(auth)?;
+
+    Ok(Some(credential.expires_at))
+}
+
+async fn run_exec_plugin(exec: &ExecConfig) -> Result<Option<CachedCredential>> {
+    let command = exec
+        .command
+        .as_ref()
+        .ok_or_else(|| anyhow!("Exec credential plugin has no command"))?;
+
+    let mut cmd = Command::new(command);
+    cmd.args(exec.args.iter().flatten());
+    for env in exec.env.iter().flatten() {
+        if let (Some(name), Some(value)) = (env.get("name"), env.get("value")) {
+            cmd.env(name, value);
+        }
+    }
+
+    let api_version = exec
+        .api_version
+        .clone()
+        .unwrap_or_else(|| "client.authentication.k8s.io/v1beta1".to_string());
+    cmd.env(
+        "KUBERNETES_EXEC_INFO",
+        serde_json::json!({
+            "apiVersion": api_version,
+            "kind": "ExecCredential",
+            "spec": { "interactive": false }
+        })
+        .to_string(),
+    );
+
+    let output = cmd
+        .output()
+        .await
+        .with_context(|| format!("Failed to run credential plugin '{}'", command))?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "Credential plugin '{}' failed: {}",
+            command,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let credential: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .with_context(|| format!("Credential plugin '{}' returned invalid JSON", command))?;
+
+    let Some(token) = credential.pointer("/status/token").and_then(|t| t.as_str()) else {
+        return Ok(None);
+    };
+
+    let expires_at = credential
+        .pointer("/status/expirationTimestamp")
+        .and_then(|t| t.as_str())
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc::now() + Duration::seconds(NO_EXPIRY_TTL_SECS));
+
+    Ok(Some(CachedCredential {
+        token: token.to_string(),
+        expires_at,
+    }))
+}
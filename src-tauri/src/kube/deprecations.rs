@@ -0,0 +1,215 @@
+use anyhow::Result;
+use kube::api::{Api, DynamicObject, ListParams};
+use kube::core::GroupVersionKind;
+use kube::discovery::ApiResource;
+use kube::{Client, ResourceExt};
+
+use crate::types::{DeprecatedObject, DeprecationReport};
+
+/// A deprecated API version and where its objects live today
+struct DeprecatedApi {
+    group: &'static str,
+    version: &'static str,
+    kind: &'static str,
+    removed_in: &'static str,
+    /// Served group/version the objects can be read from, None if the kind was removed outright
+    replacement: Option<(&'static str, &'static str)>,
+    plural: &'static str,
+}
+
+const fn api(
+    group: &'static str,
+    version: &'static str,
+    kind: &'static str,
+    plural: &'static str,
+    removed_in: &'static str,
+    replacement: Option<(&'static str, &'static str)>,
+) -> DeprecatedApi {
+    DeprecatedApi { group, version, kind, removed_in, replacement, plural }
+}
+
+/// Deprecated and removed API versions, following the upstream deprecation guide
+const DEPRECATED_APIS: &[DeprecatedApi] = &[
+    // 1.16
+    api("extensions", "v1beta1", "Deployment", "deployments", "1.16", Some(("apps", "v1"))),
+    api("apps", "v1beta1", "Deployment", "deployments", "1.16", Some(("apps", "v1"))),
+    api("apps", "v1beta2", "Deployment", "deployments", "1.16", Some(("apps", "v1"))),
+    api("extensions", "v1beta1", "DaemonSet", "daemonsets", "1.16", Some(("apps", "v1"))),
+    api("apps", "v1beta2", "DaemonSet", "daemonsets", "1.16", Some(("apps", "v1"))),
+    api("apps", "v1beta1", "StatefulSet", "statefulsets", "1.16", Some(("apps", "v1"))),
+    api("apps", "v1beta2", "StatefulSet", "statefulsets", "1.16", Some(("apps", "v1"))),
+    api("extensions", "v1beta1", "ReplicaSet", "replicasets", "1.16", Some(("apps", "v1"))),
+    api("apps", "v1beta2", "ReplicaSet", "replicasets", "1.16", Some(("apps", "v1"))),
+    api("extensions", "v1beta1", "NetworkPolicy", "networkpolicies", "1.16", Some(("networking.k8s.io", "v1"))),
+    // 1.22
+    api("extensions", "v1beta1", "Ingress", "ingresses", "1.22", Some(("networking.k8s.io", "v1"))),
+    api("networking.k8s.io", "v1beta1", "Ingress", "ingresses", "1.22", Some(("networking.k8s.io", "v1"))),
+    api("networking.k8s.io", "v1beta1", "IngressClass", "ingressclasses", "1.22", Some(("networking.k8s.io", "v1"))),
+    api("apiextensions.k8s.io", "v1beta1", "CustomResourceDefinition", "customresourcedefinitions", "1.22", Some(("apiextensions.k8s.io", "v1"))),
+    api("apiregistration.k8s.io", "v1beta1", "APIService", "apiservices", "1.22", Some(("apiregistration.k8s.io", "v1"))),
+    api("admissionregistration.k8s.io", "v1beta1", "MutatingWebhookConfiguration", "mutatingwebhookconfigurations", "1.22", Some(("admissionregistration.k8s.io", "v1"))),
+    api("admissionregistration.k8s.io", "v1beta1", "ValidatingWebhookConfiguration", "validatingwebhookconfigurations", "1.22", Some(("admissionregistration.k8s.io", "v1"))),
+    api("rbac.authorization.k8s.io", "v1beta1", "Role", "roles", "1.22", Some(("rbac.authorization.k8s.io", "v1"))),
+    api("rbac.authorization.k8s.io", "v1beta1", "RoleBinding", "rolebindings", "1.22", Some(("rbac.authorization.k8s.io", "v1"))),
+    api("rbac.authorization.k8s.io", "v1beta1", "ClusterRole", "clusterroles", "1.22", Some(("rbac.authorization.k8s.io", "v1"))),
+    api("rbac.authorization.k8s.io", "v1beta1", "ClusterRoleBinding", "clusterrolebindings", "1.22", Some(("rbac.authorization.k8s.io", "v1"))),
+    api("scheduling.k8s.io", "v1beta1", "PriorityClass", "priorityclasses", "1.22", Some(("scheduling.k8s.io", "v1"))),
+    api("storage.k8s.io", "v1beta1", "StorageClass", "storageclasses", "1.22", Some(("storage.k8s.io", "v1"))),
+    api("storage.k8s.io", "v1beta1", "CSIDriver", "csidrivers", "1.22", Some(("storage.k8s.io", "v1"))),
+    api("storage.k8s.io", "v1beta1", "VolumeAttachment", "volumeattachments", "1.22", Some(("storage.k8s.io", "v1"))),
+    api("certificates.k8s.io", "v1beta1", "CertificateSigningRequest", "certificatesigningrequests", "1.22", Some(("certificates.k8s.io", "v1"))),
+    api("coordination.k8s.io", "v1beta1", "Lease", "leases", "1.22", Some(("coordination.k8s.io", "v1"))),
+    // 1.25
+    api("batch", "v1beta1", "CronJob", "cronjobs", "1.25", Some(("batch", "v1"))),
+    api("discovery.k8s.io", "v1beta1", "EndpointSlice", "endpointslices", "1.25", Some(("discovery.k8s.io", "v1"))),
+    api("autoscaling", "v2beta1", "HorizontalPodAutoscaler", "horizontalpodautoscalers", "1.25", Some(("autoscaling", "v2"))),
+    api("policy", "v1beta1", "PodDisruptionBudget", "poddisruptionbudgets", "1.25", Some(("policy", "v1"))),
+    api("policy", "v1beta1", "PodSecurityPolicy", "podsecuritypolicies", "1.25", None),
+    api("node.k8s.io", "v1beta1", "RuntimeClass", "runtimeclasses", "1.25", Some(("node.k8s.io", "v1"))),
+    // 1.26
+    api("autoscaling", "v2beta2", "HorizontalPodAutoscaler", "horizontalpodautoscalers", "1.26", Some(("autoscaling", "v2"))),
+    api("flowcontrol.apiserver.k8s.io", "v1beta1", "FlowSchema", "flowschemas", "1.26", Some(("flowcontrol.apiserver.k8s.io", "v1"))),
+    api("flowcontrol.apiserver.k8s.io", "v1beta1", "PriorityLevelConfiguration", "prioritylevelconfigurations", "1.26", Some(("flowcontrol.apiserver.k8s.io", "v1"))),
+    // 1.27
+    api("storage.k8s.io", "v1beta1", "CSIStorageCapacity", "csistoragecapacities", "1.27", Some(("storage.k8s.io", "v1"))),
+    // 1.29
+    api("flowcontrol.apiserver.k8s.io", "v1beta2", "FlowSchema", "flowschemas", "1.29", Some(("flowcontrol.apiserver.k8s.io", "v1"))),
+    api("flowcontrol.apiserver.k8s.io", "v1beta2", "PriorityLevelConfiguration", "prioritylevelconfigurations", "1.29", Some(("flowcontrol.apiserver.k8s.io", "v1"))),
+    // 1.32
+    api("flowcontrol.apiserver.k8s.io", "v1beta3", "FlowSchema", "flowschemas", "1.32", Some(("flowcontrol.apiserver.k8s.io", "v1"))),
+    api("flowcontrol.apiserver.k8s.io", "v1beta3", "PriorityLevelConfiguration", "prioritylevelconfigurations", "1.32", Some(("flowcontrol.apiserver.k8s.io", "v1"))),
+];
+
+/// Scan the cluster for objects written with deprecated or removed API versions
+///
+/// The API server converts objects to whatever version they are read with, so
+/// the version an object was authored in is recovered from its managedFields
+/// and its `kubectl.kubernetes.io/last-applied-configuration` annotation, the
+/// same signals kubent relies on. With a `target_version` (e.g. "1.29") only
+/// APIs removed in or before that release are reported.
+pub async fn scan_deprecated_apis(
+    client: Client,
+    target_version: Option<&str>,
+) -> Result<DeprecationReport> {
+    let target = match target_version {
+        Some(v) => Some(
+            parse_release(v).ok_or_else(|| anyhow::anyhow!("Invalid Kubernetes version: {}", v))?,
+        ),
+        None => None,
+    };
+
+    let candidates: Vec<&DeprecatedApi> = DEPRECATED_APIS
+        .iter()
+        .filter(|d| match (target, parse_release(d.removed_in)) {
+            (Some(target), Some(removed)) => removed <= target,
+            _ => true,
+        })
+        .collect();
+
+    // Read each kind once through its served version and check it against every matching entry
+    let mut sources: Vec<(&str, &str, &str, &str)> = Vec::new();
+    for d in &candidates {
+        let (group, version) = d.replacement.unwrap_or((d.group, d.version));
+        let source = (group, version, d.kind, d.plural);
+        if !sources.contains(&source) {
+            sources.push(source);
+        }
+    }
+
+    let mut findings = Vec::new();
+    let mut skipped = Vec::new();
+    let mut scanned_resources = 0;
+
+    for (group, version, kind, plural) in sources {
+        let gvk = GroupVersionKind::gvk(group, version, kind);
+        let resource = ApiResource::from_gvk_with_plural(&gvk, plural);
+        let api: Api<DynamicObject> = Api::all_with(client.clone(), &resource);
+
+        let objects = match api.list(&ListParams::default()).await {
+            Ok(list) => list.items,
+            Err(e) => {
+                tracing::debug!("Skipping {}/{} {}: {}", group, version, plural, e);
+                skipped.push(format!("{} ({})", kind, resource.api_version));
+                continue;
+            }
+        };
+        scanned_resources += 1;
+
+        let entries: Vec<&&DeprecatedApi> = candidates
+            .iter()
+            .filter(|d| d.kind == kind && d.replacement.unwrap_or((d.group, d.version)) == (group, version))
+            .collect();
+
+        for obj in &objects {
+            for (api_version, source) in authored_api_versions(obj) {
+                if let Some(entry) = entries.iter().find(|d| group_version(d.group, d.version) == api_version) {
+                    findings.push(DeprecatedObject {
+                        kind: kind.to_string(),
+                        name: obj.name_any(),
+                        namespace: obj.namespace(),
+                        api_version: api_version.clone(),
+                        replacement: entry.replacement.map(|(g, v)| group_version(g, v)),
+                        removed_in: entry.removed_in.to_string(),
+                        source: source.to_string(),
+                    });
+                    break;
+                }
+            }
+        }
+    }
+
+    findings.sort_by(|a, b| {
+        (parse_release(&a.removed_in), &a.kind, &a.namespace, &a.name)
+            .cmp(&(parse_release(&b.removed_in), &b.kind, &b.namespace, &b.name))
+    });
+    findings.dedup_by(|a, b| a.kind == b.kind && a.namespace == b.namespace && a.name == b.name);
+
+    Ok(DeprecationReport {
+        target_version: target_version.map(|v| v.to_string()),
+        findings,
+        scanned_resources,
+        skipped,
+    })
+}
+
+/// API versions an object was written with, with where each was found
+fn authored_api_versions(obj: &DynamicObject) -> Vec<(String, &'static str)> {
+    let mut versions = Vec::new();
+
+    if let Some(managed) = &obj.metadata.managed_fields {
+        for entry in managed {
+            if let Some(api_version) = &entry.api_version {
+                versions.push((api_version.clone(), "managedFields"));
+            }
+        }
+    }
+
+    if let Some(last_applied) = obj
+        .annotations()
+        .get("kubectl.kubernetes.io/last-applied-configuration")
+    {
+        if let Ok(value) = serde_json::from_str::<serde_json::Value>(last_applied) {
+            if let Some(api_version) = value.get("apiVersion").and_then(|v| v.as_str()) {
+                versions.push((api_version.to_string(), "last-applied-configuration"));
+            }
+        }
+    }
+
+    versions
+}
+
+fn group_version(group: &str, version: &str) -> String {
+    if group.is_empty() {
+        version.to_string()
+    } else {
+        format!("{}/{}", group, version)
+    }
+}
+
+/// Parse "1.25", "v1.25" or "1.25.3" into (major, minor)
+fn parse_release(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.trim().trim_start_matches('v').split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.trim_end_matches('+').parse().ok()?;
+    Some((major, minor))
+}
@@ -0,0 +1,159 @@
+use anyhow::Result;
+use chrono::Utc;
+use k8s_openapi::api::core::v1::Pod;
+use kube::api::{Api, LogParams};
+use kube::Client;
+use std::path::Path;
+
+use crate::kube::operations::{container_restart_count, exec_capture};
+use crate::types::RuntimeDiagnosticResult;
+
+/// How long to wait for a Go process to dump its stacks and restart
+const GO_RESTART_WAIT_SECS: u64 = 60;
+
+/// Capture a runtime diagnostic from a container and save it under `output_dir`
+///
+/// Supported kinds:
+/// - `java-threads`: thread dump via `jcmd Thread.print`, falling back to `jstack`
+/// - `java-heap-histogram`: class histogram via `jcmd GC.class_histogram`, falling back to `jmap -histo`
+/// - `java-heap-info`: heap summary via `jcmd GC.heap_info`
+/// - `go-stacks`: goroutine dump via `kill -QUIT 1`. The Go runtime exits after
+///   dumping, so the container restarts and the dump is read from its previous logs.
+pub async fn capture_runtime_diagnostic(
+    client: Client,
+    namespace: &str,
+    pod_name: &str,
+    container: &str,
+    kind: &str,
+    output_dir: &Path,
+) -> Result<RuntimeDiagnosticResult> {
+    let pods: Api<Pod> = Api::namespaced(client, namespace);
+
+    let (output, command) = match kind {
+        "java-threads" => java_diagnostic(&pods, pod_name, container, "Thread.print", &["jstack"]).await?,
+        "java-heap-histogram" => {
+            java_diagnostic(&pods, pod_name, container, "GC.class_histogram", &["jmap", "-histo"]).await?
+        }
+        "java-heap-info" => java_diagnostic(&pods, pod_name, container, "GC.heap_info", &[]).await?,
+        "go-stacks" => (go_stack_dump(&pods, pod_name, container).await?, "kill -QUIT 1".to_string()),
+        other => return Err(anyhow::anyhow!("Unknown diagnostic kind: {}", other)),
+    };
+
+    std::fs::create_dir_all(output_dir)?;
+    let file_name = format!(
+        "{}-{}-{}-{}.txt",
+        Utc::now().format("%Y%m%dT%H%M%S"),
+        pod_name,
+        container,
+        kind
+    );
+    let path = output_dir.join(file_name);
+    std::fs::write(&path, &output)?;
+
+    Ok(RuntimeDiagnosticResult {
+        kind: kind.to_string(),
+        pod_name: pod_name.to_string(),
+        container: container.to_string(),
+        command,
+        path: path.to_string_lossy().to_string(),
+        size_bytes: output.len() as u64,
+    })
+}
+
+/// Run a jcmd command against the container's JVM, or a JDK fallback tool if jcmd is missing
+async fn java_diagnostic(
+    pods: &Api<Pod>,
+    pod_name: &str,
+    container: &str,
+    jcmd_command: &str,
+    fallback: &[&str],
+) -> Result<(String, String)> {
+    let pid = java_pid(pods, pod_name, container).await;
+
+    let command = vec!["jcmd".to_string(), pid.clone(), jcmd_command.to_string()];
+    let output = exec_capture(pods, pod_name, Some(container), command).await?;
+    if output.exit_code == Some(0) {
+        return Ok((output.stdout, format!("jcmd {} {}", pid, jcmd_command)));
+    }
+
+    if fallback.is_empty() {
+        return Err(anyhow::anyhow!(
+            "jcmd failed in container {}: {}",
+            container,
+            output.stderr.trim()
+        ));
+    }
+
+    let mut command: Vec<String> = fallback.iter().map(|s| s.to_string()).collect();
+    command.push(pid);
+    let display = command.join(" ");
+    let fallback_output = exec_capture(pods, pod_name, Some(container), command).await?;
+    if fallback_output.exit_code != Some(0) {
+        return Err(anyhow::anyhow!(
+            "Neither jcmd nor {} worked in container {} (is it a JDK image?): {}",
+            fallback[0],
+            container,
+            fallback_output.stderr.trim()
+        ));
+    }
+
+    Ok((fallback_output.stdout, display))
+}
+
+/// The JVM's pid from `jcmd -l`, or 1 when it can't be listed
+async fn java_pid(pods: &Api<Pod>, pod_name: &str, container: &str) -> String {
+    let command = vec!["jcmd".to_string(), "-l".to_string()];
+    let Ok(output) = exec_capture(pods, pod_name, Some(container), command).await else {
+        return "1".to_string();
+    };
+
+    output
+        .stdout
+        .lines()
+        .filter(|line| !line.contains("sun.tools.jcmd.JCmd") && !line.contains("jdk.jcmd"))
+        .filter_map(|line| line.split_whitespace().next())
+        .find(|pid| pid.chars().all(|c| c.is_ascii_digit()))
+        .map(|pid| pid.to_string())
+        .unwrap_or_else(|| "1".to_string())
+}
+
+/// Send SIGQUIT to the Go process and read the dump from the restarted container's previous logs
+async fn go_stack_dump(pods: &Api<Pod>, pod_name: &str, container: &str) -> Result<String> {
+    let restarts_before = container_restart_count(&pods.get(pod_name).await?, container)
+        .ok_or_else(|| anyhow::anyhow!("Container {} not found in pod {}", container, pod_name))?;
+
+    let command = vec!["kill".to_string(), "-QUIT".to_string(), "1".to_string()];
+    // The exec stream usually dies with the process, so its result doesn't tell us much
+    let _ = exec_capture(pods, pod_name, Some(container), command).await;
+
+    let mut restarted = false;
+    for _ in 0..GO_RESTART_WAIT_SECS {
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        let pod = pods.get(pod_name).await?;
+        if container_restart_count(&pod, container).map(|r| r > restarts_before).unwrap_or(false) {
+            restarted = true;
+            break;
+        }
+    }
+    if !restarted {
+        return Err(anyhow::anyhow!(
+            "Container {} did not exit after SIGQUIT; it may not be a Go binary running as PID 1",
+            container
+        ));
+    }
+
+    let params = LogParams {
+        container: Some(container.to_string()),
+        previous: true,
+        ..LogParams::default()
+    };
+    let logs = pods.logs(pod_name, &params).await?;
+
+    // Keep everything from the runtime's dump header on
+    let start = logs
+        .find("SIGQUIT: quit")
+        .or_else(|| logs.find("goroutine "))
+        .ok_or_else(|| anyhow::anyhow!("No goroutine dump found in the previous container logs"))?;
+
+    Ok(logs[start..].to_string())
+}
@@ -0,0 +1,281 @@
+use anyhow::Result;
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment};
+use k8s_openapi::api::core::v1::{ConfigMap, Pod, Service};
+use kube::api::{Api, DeleteParams, PostParams};
+use kube::{Client, ResourceExt};
+
+use crate::kube::operations::exec_capture;
+use crate::types::{DnsDebugReport, DnsDebugStep};
+
+const DEFAULT_DEBUG_IMAGE: &str = "busybox:1.36";
+
+/// Address NodeLocal DNSCache listens on by default
+const NODE_LOCAL_DNS_IP: &str = "169.254.20.10";
+
+const POD_READY_TIMEOUT_SECS: u64 = 60;
+
+/// Resolve a name from inside the cluster and check the DNS stack around it
+///
+/// Starts a short-lived utility pod in `namespace` (so search domains match
+/// what workloads there see), runs each resolution step in it, and
+/// cross-checks the CoreDNS deployment, its Corefile, the kube-dns Service and
+/// NodeLocal DNSCache. The pod is always deleted afterwards.
+pub async fn debug_dns(
+    client: Client,
+    name: &str,
+    namespace: &str,
+    image: Option<&str>,
+) -> Result<DnsDebugReport> {
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return Err(anyhow::anyhow!("Invalid DNS name: {:?}", name));
+    }
+
+    let mut steps = cluster_dns_checks(client.clone()).await;
+
+    let pods: Api<Pod> = Api::namespaced(client, namespace);
+    let pod_name = format!("kubesail-dns-debug-{}", &uuid::Uuid::new_v4().to_string()[..8]);
+    let pod: Pod = serde_json::from_value(serde_json::json!({
+        "apiVersion": "v1",
+        "kind": "Pod",
+        "metadata": {
+            "name": pod_name,
+            "labels": { "app.kubernetes.io/managed-by": "kubesail" }
+        },
+        "spec": {
+            "restartPolicy": "Never",
+            "terminationGracePeriodSeconds": 0,
+            "containers": [{
+                "name": "debug",
+                "image": image.unwrap_or(DEFAULT_DEBUG_IMAGE),
+                "command": ["sleep", "300"]
+            }]
+        }
+    }))?;
+    pods.create(&PostParams::default(), &pod).await?;
+
+    let result = resolve_in_pod(&pods, &pod_name, name, &mut steps).await;
+    let _ = pods.delete(&pod_name, &DeleteParams::default()).await;
+
+    let addresses = result?;
+    Ok(DnsDebugReport {
+        name: name.to_string(),
+        namespace: namespace.to_string(),
+        resolved: !addresses.is_empty(),
+        addresses,
+        steps,
+    })
+}
+
+fn step(name: &str, ok: bool, detail: impl Into<String>) -> DnsDebugStep {
+    DnsDebugStep {
+        name: name.to_string(),
+        ok,
+        detail: detail.into(),
+    }
+}
+
+/// CoreDNS, kube-dns Service and NodeLocal DNSCache health from the API
+async fn cluster_dns_checks(client: Client) -> Vec<DnsDebugStep> {
+    let mut steps = Vec::new();
+
+    let deployments: Api<Deployment> = Api::namespaced(client.clone(), "kube-system");
+    match deployments.get_opt("coredns").await {
+        Ok(Some(coredns)) => {
+            let desired = coredns.spec.as_ref().and_then(|s| s.replicas).unwrap_or(1);
+            let ready = coredns.status.as_ref().and_then(|s| s.ready_replicas).unwrap_or(0);
+            steps.push(step(
+                "CoreDNS deployment",
+                ready > 0,
+                format!("{}/{} replicas ready", ready, desired),
+            ));
+        }
+        Ok(None) => steps.push(step("CoreDNS deployment", false, "No coredns deployment in kube-system")),
+        Err(e) => steps.push(step("CoreDNS deployment", false, format!("Could not read: {}", e))),
+    }
+
+    let config_maps: Api<ConfigMap> = Api::namespaced(client.clone(), "kube-system");
+    if let Ok(Some(cm)) = config_maps.get_opt("coredns").await {
+        let corefile = cm.data.as_ref().and_then(|d| d.get("Corefile")).cloned().unwrap_or_default();
+        let plugins: Vec<&str> = corefile
+            .lines()
+            .map(|l| l.trim())
+            .filter(|l| ["forward", "rewrite", "stub", "hosts", "cache"].iter().any(|p| l.starts_with(p)))
+            .collect();
+        steps.push(step(
+            "Corefile",
+            corefile.contains("kubernetes"),
+            if plugins.is_empty() {
+                "No forward/rewrite/hosts/cache directives".to_string()
+            } else {
+                plugins.join("; ")
+            },
+        ));
+    }
+
+    let services: Api<Service> = Api::namespaced(client.clone(), "kube-system");
+    match services.get_opt("kube-dns").await {
+        Ok(Some(svc)) => {
+            let ip = svc.spec.as_ref().and_then(|s| s.cluster_ip.clone()).unwrap_or_default();
+            steps.push(step("kube-dns Service", !ip.is_empty(), format!("ClusterIP {}", ip)));
+        }
+        Ok(None) => steps.push(step("kube-dns Service", false, "No kube-dns Service in kube-system")),
+        Err(e) => steps.push(step("kube-dns Service", false, format!("Could not read: {}", e))),
+    }
+
+    let daemon_sets: Api<DaemonSet> = Api::namespaced(client, "kube-system");
+    if let Ok(Some(ds)) = daemon_sets.get_opt("node-local-dns").await {
+        let status = ds.status.as_ref();
+        let desired = status.map(|s| s.desired_number_scheduled).unwrap_or(0);
+        let ready = status.map(|s| s.number_ready).unwrap_or(0);
+        steps.push(step(
+            "NodeLocal DNSCache",
+            ready == desired,
+            format!("{}/{} nodes ready ({})", ready, desired, ds.name_any()),
+        ));
+    }
+
+    steps
+}
+
+/// Run the in-pod steps, returning the addresses the name resolved to
+async fn resolve_in_pod(
+    pods: &Api<Pod>,
+    pod_name: &str,
+    name: &str,
+    steps: &mut Vec<DnsDebugStep>,
+) -> Result<Vec<String>> {
+    wait_for_running(pods, pod_name).await?;
+
+    let run = |command: &[&str]| {
+        let command: Vec<String> = command.iter().map(|s| s.to_string()).collect();
+        exec_capture(pods, pod_name, Some("debug"), command)
+    };
+
+    // resolv.conf decides which server is asked and which suffixes are tried
+    let resolv = run(&["cat", "/etc/resolv.conf"]).await?.stdout;
+    let nameservers: Vec<String> = resolv
+        .lines()
+        .filter_map(|l| l.strip_prefix("nameserver"))
+        .map(|s| s.trim().to_string())
+        .collect();
+    let search = resolv
+        .lines()
+        .find_map(|l| l.strip_prefix("search"))
+        .map(|s| s.trim().to_string())
+        .unwrap_or_default();
+    let options = resolv
+        .lines()
+        .find_map(|l| l.strip_prefix("options"))
+        .map(|s| s.trim().to_string())
+        .unwrap_or_default();
+    let via_node_local = nameservers.iter().any(|ns| ns == NODE_LOCAL_DNS_IP);
+    steps.push(step(
+        "Pod resolv.conf",
+        !nameservers.is_empty(),
+        format!(
+            "nameserver {}; search {}; options {}{}",
+            nameservers.join(", "),
+            search,
+            options,
+            if via_node_local { " (NodeLocal DNSCache)" } else { "" }
+        ),
+    ));
+
+    let lookup = run(&["nslookup", name]).await?;
+    let addresses = nslookup_addresses(&lookup.stdout);
+    steps.push(step(
+        &format!("nslookup {}", name),
+        !addresses.is_empty(),
+        if addresses.is_empty() {
+            first_error_line(&lookup.stdout, &lookup.stderr)
+        } else {
+            addresses.join(", ")
+        },
+    ));
+
+    // Ask each nameserver directly to tell a broken cache apart from a broken upstream
+    for server in &nameservers {
+        let direct = run(&["nslookup", name, server]).await?;
+        let direct_addresses = nslookup_addresses(&direct.stdout);
+        steps.push(step(
+            &format!("nslookup {} via {}", name, server),
+            !direct_addresses.is_empty(),
+            if direct_addresses.is_empty() {
+                first_error_line(&direct.stdout, &direct.stderr)
+            } else {
+                direct_addresses.join(", ")
+            },
+        ));
+    }
+
+    // On failure, an external name shows whether CoreDNS can reach its upstream at all
+    if !addresses.is_empty() {
+        return Ok(addresses);
+    }
+    let external = run(&["nslookup", "kubernetes.io"]).await?;
+    steps.push(step(
+        "Upstream resolution (kubernetes.io)",
+        !nslookup_addresses(&external.stdout).is_empty(),
+        first_error_line(&external.stdout, &external.stderr),
+    ));
+
+    Ok(addresses)
+}
+
+async fn wait_for_running(pods: &Api<Pod>, pod_name: &str) -> Result<()> {
+    for _ in 0..POD_READY_TIMEOUT_SECS {
+        let pod = pods.get(pod_name).await?;
+        let phase = pod.status.as_ref().and_then(|s| s.phase.clone()).unwrap_or_default();
+        match phase.as_str() {
+            "Running" => return Ok(()),
+            "Failed" | "Succeeded" => {
+                return Err(anyhow::anyhow!("DNS debug pod exited early ({})", phase));
+            }
+            _ => tokio::time::sleep(std::time::Duration::from_secs(1)).await,
+        }
+    }
+
+    Err(anyhow::anyhow!(
+        "DNS debug pod did not start within {}s (image pull or scheduling problem?)",
+        POD_READY_TIMEOUT_SECS
+    ))
+}
+
+/// Addresses from the answer section of busybox/bind nslookup output
+fn nslookup_addresses(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .skip_while(|l| !l.trim_start().starts_with("Name:"))
+        .filter_map(|l| l.trim().strip_prefix("Address"))
+        .filter_map(|rest| rest.split_once(':').map(|(_, addr)| addr.trim()))
+        .map(|addr| addr.split_whitespace().next().unwrap_or(addr).to_string())
+        .filter(|addr| !addr.is_empty())
+        .collect()
+}
+
+fn first_error_line(stdout: &str, stderr: &str) -> String {
+    stderr
+        .lines()
+        .chain(stdout.lines())
+        .map(|l| l.trim())
+        .find(|l| !l.is_empty() && (l.contains("can't") || l.contains("NXDOMAIN") || l.contains("timed out")))
+        .or_else(|| stdout.lines().map(|l| l.trim()).find(|l| !l.is_empty()))
+        .unwrap_or("No answer")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nslookup_addresses() {
+        let busybox = "Server:\t\t10.96.0.10\nAddress:\t10.96.0.10:53\n\nName:\tweb.shop.svc.cluster.local\nAddress: 10.100.4.7\n";
+        assert_eq!(nslookup_addresses(busybox), vec!["10.100.4.7"]);
+
+        let bind = "Server:  10.96.0.10\nAddress 1: 10.96.0.10 kube-dns.kube-system.svc.cluster.local\n\nName:      web\nAddress 1: 10.100.4.7 web.shop.svc.cluster.local\n";
+        assert_eq!(nslookup_addresses(bind), vec!["10.100.4.7"]);
+
+        assert!(nslookup_addresses("** server can't find nope: NXDOMAIN\n").is_empty());
+    }
+}
@@ -0,0 +1,258 @@
+use anyhow::Result;
+use futures::future::join_all;
+use k8s_openapi::api::core::v1::{Node, Pod};
+use kube::api::{Api, EvictParams, ListParams};
+use kube::{Client, ResourceExt};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::{oneshot, Mutex};
+use uuid::Uuid;
+
+use crate::kube::operations::cordon_node;
+use crate::types::{NodeDrainProgress, NodeGroupDrainResult};
+
+/// How long one node may take to empty before it is reported as failed
+const NODE_DRAIN_TIMEOUT_SECS: u64 = 600;
+
+/// Wait between eviction retries and pod checks; a PodDisruptionBudget answers 429 until it allows more
+const EVICTION_RETRY_SECS: u64 = 5;
+
+/// Cordons and drains every node matching a label selector, a few at a time
+pub struct NodeDrainManager {
+    drains: Arc<Mutex<HashMap<String, oneshot::Sender<()>>>>,
+}
+
+impl NodeDrainManager {
+    pub fn new() -> Self {
+        Self {
+            drains: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Drain the nodes selected by `selector` in batches of `max_parallel`,
+    /// pausing `pause_between_secs` between batches
+    ///
+    /// Emits `node-drain-progress-{drain_id}` as each node is cordoned, evicted
+    /// and emptied, and one `node-drain-complete-{drain_id}`. A failed node stops
+    /// the run before the next batch so a pool is never drained past a problem.
+    /// Stopping leaves nodes that were already cordoned cordoned.
+    pub async fn start_drain(
+        &self,
+        app: AppHandle,
+        client: Client,
+        selector: &str,
+        max_parallel: Option<usize>,
+        pause_between_secs: Option<u64>,
+    ) -> Result<String> {
+        // An empty selector matches every node in the cluster
+        if selector.trim().is_empty() {
+            return Err(anyhow::anyhow!("A node selector is required"));
+        }
+
+        let nodes: Vec<String> = Api::<Node>::all(client.clone())
+            .list(&ListParams::default().labels(selector))
+            .await?
+            .items
+            .iter()
+            .map(|n| n.name_any())
+            .collect();
+        if nodes.is_empty() {
+            return Err(anyhow::anyhow!("No nodes match {}", selector));
+        }
+
+        let max_parallel = max_parallel.unwrap_or(1).max(1);
+        let pause = Duration::from_secs(pause_between_secs.unwrap_or(0));
+
+        let drain_id = Uuid::new_v4().to_string();
+        let (cancel_tx, mut cancel_rx) = oneshot::channel::<()>();
+        self.drains.lock().await.insert(drain_id.clone(), cancel_tx);
+
+        let drains = self.drains.clone();
+        let id = drain_id.clone();
+        tokio::spawn(async move {
+            let progress_event = format!("node-drain-progress-{}", id);
+            let total = nodes.len();
+            let mut drained = Vec::new();
+            let mut failed = Vec::new();
+
+            let outcome = 'run: {
+                for (index, batch) in nodes.chunks(max_parallel).enumerate() {
+                    if index > 0 && !pause.is_zero() {
+                        tokio::select! {
+                            _ = tokio::time::sleep(pause) => {}
+                            _ = &mut cancel_rx => break 'run "cancelled",
+                        }
+                    }
+
+                    let done_before = drained.len();
+                    let batch_drains = join_all(batch.iter().map(|node| {
+                        drain_and_wait(&app, &progress_event, &client, node, done_before, total)
+                    }));
+                    let results = tokio::select! {
+                        results = batch_drains => results,
+                        _ = &mut cancel_rx => break 'run "cancelled",
+                    };
+
+                    for (node, result) in batch.iter().zip(results) {
+                        match result {
+                            Ok(()) => drained.push(node.clone()),
+                            Err(e) => failed.push(format!("{}: {}", node, e)),
+                        }
+                    }
+                    if !failed.is_empty() {
+                        break 'run "failed";
+                    }
+                }
+                "success"
+            };
+
+            let message = match outcome {
+                "success" => format!("Drained {} nodes", drained.len()),
+                "cancelled" => format!("Stopped after draining {} of {} nodes", drained.len(), total),
+                _ => format!("Stopped after {} of {} nodes: {}", drained.len(), total, failed.join("; ")),
+            };
+            let result = NodeGroupDrainResult {
+                outcome: outcome.to_string(),
+                message,
+                drained,
+                failed,
+            };
+            let _ = app.emit(&format!("node-drain-complete-{}", id), &result);
+            drains.lock().await.remove(&id);
+        });
+
+        Ok(drain_id)
+    }
+
+    pub async fn stop_drain(&self, drain_id: &str) -> Result<()> {
+        if let Some(cancel) = self.drains.lock().await.remove(drain_id) {
+            let _ = cancel.send(());
+        }
+        Ok(())
+    }
+}
+
+impl Default for NodeDrainManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Static pods are mirrored into the API but can only be removed on the node itself
+fn is_mirror(pod: &Pod) -> bool {
+    pod.annotations().contains_key("kubernetes.io/config.mirror")
+}
+
+fn is_finished(pod: &Pod) -> bool {
+    let phase = pod.status.as_ref().and_then(|s| s.phase.as_deref());
+    matches!(phase, Some("Succeeded") | Some("Failed"))
+}
+
+/// DaemonSet and static (mirror) pods are left in place, as `kubectl drain` does
+fn is_evictable(pod: &Pod) -> bool {
+    let owners = pod.owner_references();
+    !is_mirror(pod) && !owners.is_empty() && !owners.iter().any(|o| o.kind == "DaemonSet")
+}
+
+/// Running pods no controller would recreate elsewhere; `kubectl drain` refuses
+/// these without `--force`, since evicting them loses them for good
+fn is_unmanaged(pod: &Pod) -> bool {
+    !is_mirror(pod) && pod.owner_references().is_empty() && !is_finished(pod)
+}
+
+/// Cordon a node, evict its pods (retrying while a PodDisruptionBudget refuses)
+/// and wait until they are gone
+async fn drain_and_wait(
+    app: &AppHandle,
+    event: &str,
+    client: &Client,
+    node: &str,
+    nodes_done: usize,
+    nodes_total: usize,
+) -> Result<()> {
+    let emit = |phase: &str, pods_remaining: usize, message: String| {
+        let _ = app.emit(
+            event,
+            &NodeDrainProgress {
+                node: node.to_string(),
+                phase: phase.to_string(),
+                pods_remaining,
+                nodes_done,
+                nodes_total,
+                message,
+            },
+        );
+    };
+
+    let pods: Api<Pod> = Api::all(client.clone());
+    let on_node = ListParams::default().fields(&format!("spec.nodeName={}", node));
+
+    // Checked before cordoning so a refused node is left as it was
+    let unmanaged: Vec<String> = pods
+        .list(&on_node)
+        .await?
+        .items
+        .iter()
+        .filter(|p| is_unmanaged(p))
+        .map(|p| format!("{}/{}", p.namespace().unwrap_or_default(), p.name_any()))
+        .collect();
+    if !unmanaged.is_empty() {
+        emit("failed", unmanaged.len(), format!("Pods without a controller: {}", unmanaged.join(", ")));
+        return Err(anyhow::anyhow!(
+            "{} pods are not managed by a controller and would not be recreated: {}",
+            unmanaged.len(),
+            unmanaged.join(", ")
+        ));
+    }
+
+    cordon_node(client.clone(), node).await?;
+    emit("cordoned", 0, format!("Cordoned {}", node));
+
+    let started = Instant::now();
+
+    loop {
+        let remaining: Vec<Pod> = pods
+            .list(&on_node)
+            .await?
+            .items
+            .into_iter()
+            .filter(|p| is_evictable(p) && !is_finished(p))
+            .collect();
+
+        if remaining.is_empty() {
+            emit("drained", 0, format!("Drained {}", node));
+            return Ok(());
+        }
+        if started.elapsed() >= Duration::from_secs(NODE_DRAIN_TIMEOUT_SECS) {
+            let names: Vec<String> = remaining.iter().map(|p| p.name_any()).collect();
+            emit("failed", remaining.len(), format!("Timed out with pods left: {}", names.join(", ")));
+            return Err(anyhow::anyhow!(
+                "{} pods still running after {}s: {}",
+                remaining.len(),
+                NODE_DRAIN_TIMEOUT_SECS,
+                names.join(", ")
+            ));
+        }
+
+        let mut blocked = Vec::new();
+        for pod in remaining.iter().filter(|p| p.metadata.deletion_timestamp.is_none()) {
+            let api: Api<Pod> = Api::namespaced(client.clone(), &pod.namespace().unwrap_or_default());
+            match api.evict(&pod.name_any(), &EvictParams::default()).await {
+                Ok(_) => {}
+                Err(kube::Error::Api(e)) if e.code == 429 => blocked.push(pod.name_any()),
+                Err(kube::Error::Api(e)) if e.code == 404 => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        let message = if blocked.is_empty() {
+            format!("Waiting for {} pods to terminate", remaining.len())
+        } else {
+            format!("Disruption budget is blocking eviction of {}", blocked.join(", "))
+        };
+        emit("evicting", remaining.len(), message);
+        tokio::time::sleep(Duration::from_secs(EVICTION_RETRY_SECS)).await;
+    }
+}
@@ -0,0 +1,275 @@
+use anyhow::Result;
+use k8s_openapi::api::core::v1::Pod;
+use kube::api::Api;
+use kube::Client;
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::kube::operations::exec_capture;
+use crate::types::{ProxyCertificate, ProxyCluster, ProxyDiagnostics, ProxyListener};
+
+const PROXY_CONTAINER: &str = "istio-proxy";
+
+/// Stat prefixes included in the diagnostics summary
+const SUMMARY_STAT_PREFIXES: &[&str] = &["server.", "cluster_manager.", "listener_manager."];
+
+/// Collect clusters, listeners, certificates and key stats from a pod's istio-proxy
+///
+/// Everything is read through `pilot-agent request` inside the sidecar, so no
+/// port-forward or istioctl is needed. Each section is fetched independently;
+/// failures are reported in `errors` rather than failing the whole call.
+pub async fn get_proxy_diagnostics(
+    client: Client,
+    namespace: &str,
+    pod_name: &str,
+) -> Result<ProxyDiagnostics> {
+    let pods: Api<Pod> = Api::namespaced(client, namespace);
+
+    let (server_info, clusters, listeners, certs, stats) = tokio::join!(
+        envoy_admin_get(&pods, pod_name, "server_info"),
+        envoy_admin_get(&pods, pod_name, "clusters?format=json"),
+        envoy_admin_get(&pods, pod_name, "listeners?format=json"),
+        envoy_admin_get(&pods, pod_name, "certs"),
+        envoy_admin_get(&pods, pod_name, "stats"),
+    );
+
+    let mut diagnostics = ProxyDiagnostics {
+        pod: pod_name.to_string(),
+        namespace: namespace.to_string(),
+        proxy_version: None,
+        state: None,
+        clusters: Vec::new(),
+        listeners: Vec::new(),
+        certificates: Vec::new(),
+        stats: HashMap::new(),
+        errors: Vec::new(),
+    };
+
+    match server_info.and_then(|body| serde_json::from_str::<Value>(&body).map_err(Into::into)) {
+        Ok(info) => {
+            diagnostics.proxy_version = info.get("version").and_then(|v| v.as_str()).map(String::from);
+            diagnostics.state = info.get("state").and_then(|v| v.as_str()).map(String::from);
+        }
+        Err(e) => diagnostics.errors.push(format!("server_info: {}", e)),
+    }
+
+    match clusters.and_then(|body| parse_clusters(&body)) {
+        Ok(clusters) => diagnostics.clusters = clusters,
+        Err(e) => diagnostics.errors.push(format!("clusters: {}", e)),
+    }
+
+    match listeners.and_then(|body| parse_listeners(&body)) {
+        Ok(listeners) => diagnostics.listeners = listeners,
+        Err(e) => diagnostics.errors.push(format!("listeners: {}", e)),
+    }
+
+    match certs.and_then(|body| parse_certificates(&body)) {
+        Ok(certificates) => diagnostics.certificates = certificates,
+        Err(e) => diagnostics.errors.push(format!("certs: {}", e)),
+    }
+
+    match stats {
+        Ok(body) => diagnostics.stats = parse_stats(&body, |key| {
+            SUMMARY_STAT_PREFIXES.iter().any(|p| key.starts_with(p))
+        }),
+        Err(e) => diagnostics.errors.push(format!("stats: {}", e)),
+    }
+
+    Ok(diagnostics)
+}
+
+/// Envoy stats whose name contains `filter` (all stats when empty)
+pub async fn get_proxy_stats(
+    client: Client,
+    namespace: &str,
+    pod_name: &str,
+    filter: Option<&str>,
+) -> Result<HashMap<String, String>> {
+    let pods: Api<Pod> = Api::namespaced(client, namespace);
+    let body = envoy_admin_get(&pods, pod_name, "stats").await?;
+    let filter = filter.unwrap_or_default();
+
+    Ok(parse_stats(&body, |key| key.contains(filter)))
+}
+
+/// Raw Envoy config dump, optionally narrowed to one resource type
+/// (e.g. `dynamic_active_clusters`)
+pub async fn get_proxy_config_dump(
+    client: Client,
+    namespace: &str,
+    pod_name: &str,
+    resource: Option<&str>,
+) -> Result<Value> {
+    let pods: Api<Pod> = Api::namespaced(client, namespace);
+    let path = match resource {
+        Some(resource) => format!("config_dump?resource={}", resource),
+        None => "config_dump".to_string(),
+    };
+
+    let body = envoy_admin_get(&pods, pod_name, &path).await?;
+    Ok(serde_json::from_str(&body)?)
+}
+
+async fn envoy_admin_get(pods: &Api<Pod>, pod_name: &str, path: &str) -> Result<String> {
+    let command = vec![
+        "pilot-agent".to_string(),
+        "request".to_string(),
+        "GET".to_string(),
+        path.to_string(),
+    ];
+
+    let output = exec_capture(pods, pod_name, Some(PROXY_CONTAINER), command).await?;
+
+    if output.exit_code.unwrap_or(0) != 0 {
+        return Err(anyhow::anyhow!(
+            "pilot-agent request {} failed: {}",
+            path,
+            output.stderr.trim()
+        ));
+    }
+
+    Ok(output.stdout)
+}
+
+fn parse_clusters(body: &str) -> Result<Vec<ProxyCluster>> {
+    let json: Value = serde_json::from_str(body)?;
+
+    let clusters = json
+        .get("cluster_statuses")
+        .and_then(|c| c.as_array())
+        .map(|statuses| {
+            statuses
+                .iter()
+                .map(|cluster| {
+                    let hosts = cluster
+                        .get("host_statuses")
+                        .and_then(|h| h.as_array())
+                        .cloned()
+                        .unwrap_or_default();
+
+                    // Hosts without an EDS health status are static and treated as healthy
+                    let healthy_hosts = hosts
+                        .iter()
+                        .filter(|h| {
+                            h.pointer("/health_status/eds_health_status")
+                                .and_then(|s| s.as_str())
+                                .map(|s| s == "HEALTHY")
+                                .unwrap_or(true)
+                        })
+                        .count();
+
+                    ProxyCluster {
+                        name: cluster
+                            .get("name")
+                            .and_then(|n| n.as_str())
+                            .unwrap_or_default()
+                            .to_string(),
+                        hosts: hosts.len(),
+                        healthy_hosts,
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(clusters)
+}
+
+fn parse_listeners(body: &str) -> Result<Vec<ProxyListener>> {
+    let json: Value = serde_json::from_str(body)?;
+
+    let listeners = json
+        .get("listener_statuses")
+        .and_then(|l| l.as_array())
+        .map(|statuses| {
+            statuses
+                .iter()
+                .map(|listener| {
+                    let socket = listener.pointer("/local_address/socket_address");
+                    let address = match socket {
+                        Some(socket) => format!(
+                            "{}:{}",
+                            socket.get("address").and_then(|a| a.as_str()).unwrap_or_default(),
+                            socket.get("port_value").and_then(|p| p.as_u64()).unwrap_or_default()
+                        ),
+                        None => String::new(),
+                    };
+
+                    ProxyListener {
+                        name: listener
+                            .get("name")
+                            .and_then(|n| n.as_str())
+                            .unwrap_or_default()
+                            .to_string(),
+                        address,
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(listeners)
+}
+
+fn parse_certificates(body: &str) -> Result<Vec<ProxyCertificate>> {
+    let json: Value = serde_json::from_str(body)?;
+    let mut result = Vec::new();
+
+    for cert in json
+        .get("certificates")
+        .and_then(|c| c.as_array())
+        .into_iter()
+        .flatten()
+    {
+        for (field, is_ca) in [("cert_chain", false), ("ca_cert", true)] {
+            for details in cert.get(field).and_then(|c| c.as_array()).into_iter().flatten() {
+                let subject_alt_names = details
+                    .get("subject_alt_names")
+                    .and_then(|s| s.as_array())
+                    .map(|sans| {
+                        sans.iter()
+                            .filter_map(|san| {
+                                san.get("uri")
+                                    .or_else(|| san.get("dns"))
+                                    .and_then(|v| v.as_str())
+                                    .map(String::from)
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+
+                // Envoy reports this as a string in its JSON output
+                let days_until_expiration = details.get("days_until_expiration").and_then(|d| {
+                    d.as_i64().or_else(|| d.as_str().and_then(|s| s.parse().ok()))
+                });
+
+                result.push(ProxyCertificate {
+                    serial_number: details
+                        .get("serial_number")
+                        .and_then(|s| s.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                    subject_alt_names,
+                    valid_from: details.get("valid_from").and_then(|v| v.as_str()).map(String::from),
+                    expiration_time: details
+                        .get("expiration_time")
+                        .and_then(|v| v.as_str())
+                        .map(String::from),
+                    days_until_expiration,
+                    is_ca,
+                });
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Parse Envoy's `name: value` stats output, keeping keys accepted by `keep`
+fn parse_stats(body: &str, keep: impl Fn(&str) -> bool) -> HashMap<String, String> {
+    body.lines()
+        .filter_map(|line| line.split_once(": "))
+        .filter(|(key, _)| keep(key))
+        .map(|(key, value)| (key.to_string(), value.trim().to_string()))
+        .collect()
+}
@@ -0,0 +1,435 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use k8s_openapi::api::apps::v1::{ControllerRevision, ReplicaSet};
+use k8s_openapi::api::batch::v1::Job;
+use k8s_openapi::api::core::v1::{Event, Pod};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::OwnerReference;
+use kube::api::{Api, DynamicObject, ListParams};
+use kube::core::GroupVersionKind;
+use kube::discovery::ApiResource;
+use kube::{Client, ResourceExt};
+use std::collections::HashMap;
+
+use crate::types::{EventExport, ExportedEvent, TimelineEntry};
+
+/// Parse `since` as an RFC3339 time or a relative duration like `30m`, `2h` or `1d`
+pub(crate) fn parse_since(since: &str) -> Result<DateTime<Utc>> {
+    if let Ok(ts) = DateTime::parse_from_rfc3339(since) {
+        return Ok(ts.with_timezone(&Utc));
+    }
+
+    let duration = parse_duration(since)
+        .map_err(|_| anyhow::anyhow!("Invalid time '{}': use RFC3339 or e.g. 30m, 2h, 1d", since.trim()))?;
+    Ok(Utc::now() - duration)
+}
+
+/// Parse a short duration like `90s`, `30m`, `2h` or `1d`
+pub(crate) fn parse_duration(duration: &str) -> Result<Duration> {
+    let duration = duration.trim();
+    let unit_len = duration.chars().last().map(|c| c.len_utf8()).unwrap_or(0);
+    let (value, unit) = duration.split_at(duration.len() - unit_len);
+    let value: i64 = value
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid duration '{}': use e.g. 30m, 2h, 1d", duration))?;
+
+    Ok(match unit {
+        "s" => Duration::seconds(value),
+        "m" => Duration::minutes(value),
+        "h" => Duration::hours(value),
+        "d" => Duration::days(value),
+        _ => return Err(anyhow::anyhow!("Invalid duration '{}': use e.g. 30m, 2h, 1d", duration)),
+    })
+}
+
+/// When an event last happened, falling back through the fields different emitters set
+pub(crate) fn event_time(event: &Event) -> Option<DateTime<Utc>> {
+    event
+        .last_timestamp
+        .as_ref()
+        .map(|t| t.0)
+        .or_else(|| event.event_time.as_ref().map(|t| t.0))
+        .or_else(|| event.first_timestamp.as_ref().map(|t| t.0))
+        .or_else(|| event.metadata.creation_timestamp.as_ref().map(|t| t.0))
+}
+
+/// Resolves pods and ReplicaSets/Jobs to the workload that ultimately owns them
+pub(crate) struct OwnerResolver {
+    owners: HashMap<(String, String, String), OwnerReference>, // (kind, namespace, name) -> controller
+}
+
+impl OwnerResolver {
+    pub(crate) async fn load(client: Client, namespace: Option<&str>) -> Result<Self> {
+        let lp = ListParams::default();
+        let (pods, replica_sets, jobs) = match namespace {
+            Some(ns) => tokio::try_join!(
+                Api::<Pod>::namespaced(client.clone(), ns).list(&lp),
+                Api::<ReplicaSet>::namespaced(client.clone(), ns).list(&lp),
+                Api::<Job>::namespaced(client, ns).list(&lp),
+            )?,
+            None => tokio::try_join!(
+                Api::<Pod>::all(client.clone()).list(&lp),
+                Api::<ReplicaSet>::all(client.clone()).list(&lp),
+                Api::<Job>::all(client).list(&lp),
+            )?,
+        };
+
+        let mut owners = HashMap::new();
+        let mut add = |kind: &str, meta: &k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta| {
+            let controller = meta
+                .owner_references
+                .iter()
+                .flatten()
+                .find(|o| o.controller.unwrap_or(false));
+            if let Some(controller) = controller {
+                owners.insert(
+                    (
+                        kind.to_string(),
+                        meta.namespace.clone().unwrap_or_default(),
+                        meta.name.clone().unwrap_or_default(),
+                    ),
+                    controller.clone(),
+                );
+            }
+        };
+        pods.items.iter().for_each(|p| add("Pod", &p.metadata));
+        replica_sets.items.iter().for_each(|r| add("ReplicaSet", &r.metadata));
+        jobs.items.iter().for_each(|j| add("Job", &j.metadata));
+
+        Ok(Self { owners })
+    }
+
+    /// The top-level owner as `Kind/name`, following Pod -> ReplicaSet -> Deployment style chains
+    pub(crate) fn top_owner(&self, kind: &str, namespace: &str, name: &str) -> Option<String> {
+        let mut current: Option<(String, String)> = None;
+        let (mut kind, mut name) = (kind.to_string(), name.to_string());
+
+        // Owner chains are short; the bound only guards against cycles
+        for _ in 0..5 {
+            match self.owners.get(&(kind.clone(), namespace.to_string(), name.clone())) {
+                Some(owner) => {
+                    kind = owner.kind.clone();
+                    name = owner.name.clone();
+                    current = Some((kind.clone(), name.clone()));
+                }
+                None => break,
+            }
+        }
+
+        current.map(|(kind, name)| format!("{}/{}", kind, name))
+    }
+}
+
+/// Events in a time window as CSV or JSON, for incident timelines and postmortems
+///
+/// Rows are oldest first. Each event's object is resolved to the workload
+/// that owns it, so pod churn shows up under its Deployment, StatefulSet or CronJob.
+pub async fn export_events(
+    client: Client,
+    namespace: Option<&str>,
+    since: Option<&str>,
+    format: &str,
+) -> Result<EventExport> {
+    if format != "csv" && format != "json" {
+        return Err(anyhow::anyhow!("Unsupported export format: {}", format));
+    }
+
+    let namespace = namespace.filter(|ns| !ns.is_empty());
+    let since = since.map(parse_since).transpose()?;
+
+    let events: Api<Event> = match namespace {
+        Some(ns) => Api::namespaced(client.clone(), ns),
+        None => Api::all(client.clone()),
+    };
+    let (events, resolver) = tokio::try_join!(
+        async { Ok::<_, anyhow::Error>(events.list(&ListParams::default()).await?) },
+        OwnerResolver::load(client, namespace),
+    )?;
+
+    let mut rows: Vec<(DateTime<Utc>, ExportedEvent)> = events
+        .items
+        .iter()
+        .filter_map(|event| {
+            let time = event_time(event)?;
+            if since.map(|since| time < since).unwrap_or(false) {
+                return None;
+            }
+
+            let object = &event.involved_object;
+            let kind = object.kind.clone().unwrap_or_default();
+            let object_namespace = object.namespace.clone().or_else(|| event.namespace()).unwrap_or_default();
+            let name = object.name.clone().unwrap_or_default();
+
+            Some((
+                time,
+                ExportedEvent {
+                    timestamp: time.to_rfc3339(),
+                    first_timestamp: event.first_timestamp.as_ref().map(|t| t.0.to_rfc3339()),
+                    event_type: event.type_.clone().unwrap_or_else(|| "Normal".to_string()),
+                    reason: event.reason.clone().unwrap_or_default(),
+                    owner: resolver.top_owner(&kind, &object_namespace, &name),
+                    namespace: object_namespace,
+                    kind,
+                    name,
+                    uid: object.uid.clone(),
+                    message: event.message.clone().unwrap_or_default(),
+                    source: event
+                        .source
+                        .as_ref()
+                        .and_then(|s| s.component.clone())
+                        .or_else(|| event.reporting_component.clone())
+                        .unwrap_or_default(),
+                    count: event.count.unwrap_or(1),
+                },
+            ))
+        })
+        .collect();
+
+    rows.sort_by(|a, b| a.0.cmp(&b.0));
+    let rows: Vec<ExportedEvent> = rows.into_iter().map(|(_, row)| row).collect();
+
+    let content = match format {
+        "json" => serde_json::to_string_pretty(&rows)?,
+        _ => events_csv(&rows),
+    };
+
+    Ok(EventExport {
+        format: format.to_string(),
+        count: rows.len(),
+        content,
+    })
+}
+
+/// Everything that happened to a Deployment, StatefulSet or DaemonSet, oldest first
+///
+/// Merges events on the workload, its ReplicaSets/ControllerRevisions and
+/// pods, rollout revisions, condition transitions and container restarts
+/// into one feed. `since` defaults to the last 24 hours. Events expire after
+/// an hour on most clusters, so older windows rely on the other sources.
+pub async fn get_workload_timeline(
+    client: Client,
+    kind: &str,
+    namespace: &str,
+    name: &str,
+    since: Option<&str>,
+) -> Result<Vec<TimelineEntry>> {
+    let plural = match kind {
+        "Deployment" => "deployments",
+        "StatefulSet" => "statefulsets",
+        "DaemonSet" => "daemonsets",
+        other => return Err(anyhow::anyhow!("Timelines are not supported for {}", other)),
+    };
+    let since = parse_since(since.unwrap_or("24h"))?;
+
+    let resource = ApiResource::from_gvk_with_plural(&GroupVersionKind::gvk("apps", "v1", kind), plural);
+    let workloads: Api<DynamicObject> = Api::namespaced_with(client.clone(), namespace, &resource);
+    let workload = workloads.get(name).await?;
+    let workload_ref = format!("{}/{}", kind, name);
+
+    let lp = ListParams::default();
+    let (events, pods, replica_sets, revisions, resolver) = tokio::try_join!(
+        async { Ok::<_, anyhow::Error>(Api::<Event>::namespaced(client.clone(), namespace).list(&lp).await?) },
+        async { Ok::<_, anyhow::Error>(Api::<Pod>::namespaced(client.clone(), namespace).list(&lp).await?) },
+        async { Ok::<_, anyhow::Error>(Api::<ReplicaSet>::namespaced(client.clone(), namespace).list(&lp).await?) },
+        async {
+            Ok::<_, anyhow::Error>(Api::<ControllerRevision>::namespaced(client.clone(), namespace).list(&lp).await?)
+        },
+        OwnerResolver::load(client, Some(namespace)),
+    )?;
+
+    let belongs = |kind: &str, name: &str| {
+        resolver.top_owner(kind, namespace, name).as_deref() == Some(workload_ref.as_str())
+    };
+    let uid = workload.uid();
+    let owned_by_workload = |owners: &Option<Vec<OwnerReference>>| {
+        owners.iter().flatten().any(|o| Some(&o.uid) == uid.as_ref())
+    };
+
+    let mut entries: Vec<(DateTime<Utc>, TimelineEntry)> = Vec::new();
+    let mut push = |time: DateTime<Utc>,
+                    category: &str,
+                    severity: &str,
+                    object: String,
+                    summary: String,
+                    detail: Option<String>| {
+        if time >= since {
+            entries.push((
+                time,
+                TimelineEntry {
+                    timestamp: time.to_rfc3339(),
+                    category: category.to_string(),
+                    severity: severity.to_string(),
+                    object,
+                    summary,
+                    detail,
+                },
+            ));
+        }
+    };
+
+    for event in &events.items {
+        let object = &event.involved_object;
+        let (Some(obj_kind), Some(obj_name)) = (object.kind.as_deref(), object.name.as_deref()) else {
+            continue;
+        };
+        let is_workload = obj_kind == kind && obj_name == name;
+        if !is_workload && !belongs(obj_kind, obj_name) {
+            continue;
+        }
+        let Some(time) = event_time(event) else { continue };
+
+        let count = event.count.unwrap_or(1);
+        push(
+            time,
+            "event",
+            event.type_.as_deref().unwrap_or("Normal"),
+            format!("{}/{}", obj_kind, obj_name),
+            event.reason.clone().unwrap_or_default(),
+            event.message.clone().map(|m| if count > 1 { format!("{} (x{})", m, count) } else { m }),
+        );
+    }
+
+    // Rollouts: a new ReplicaSet (Deployments) or ControllerRevision (StatefulSets/DaemonSets) per revision
+    for rs in replica_sets.items.iter().filter(|rs| owned_by_workload(&rs.metadata.owner_references)) {
+        let Some(created) = rs.metadata.creation_timestamp.as_ref() else { continue };
+        let revision = rs
+            .annotations()
+            .get("deployment.kubernetes.io/revision")
+            .cloned()
+            .unwrap_or_else(|| "?".to_string());
+        let images: Vec<String> = rs
+            .spec
+            .as_ref()
+            .and_then(|s| s.template.as_ref())
+            .and_then(|t| t.spec.as_ref())
+            .map(|s| s.containers.iter().filter_map(|c| c.image.clone()).collect())
+            .unwrap_or_default();
+        push(
+            created.0,
+            "rollout",
+            "Normal",
+            format!("ReplicaSet/{}", rs.name_any()),
+            format!("Revision {} rolled out", revision),
+            (!images.is_empty()).then(|| images.join(", ")),
+        );
+    }
+    for rev in revisions.items.iter().filter(|r| owned_by_workload(&r.metadata.owner_references)) {
+        let Some(created) = rev.metadata.creation_timestamp.as_ref() else { continue };
+        push(
+            created.0,
+            "rollout",
+            "Normal",
+            format!("ControllerRevision/{}", rev.name_any()),
+            format!("Revision {} rolled out", rev.revision),
+            None,
+        );
+    }
+
+    for condition in workload
+        .data
+        .pointer("/status/conditions")
+        .and_then(|c| c.as_array())
+        .into_iter()
+        .flatten()
+    {
+        let time = condition
+            .get("lastTransitionTime")
+            .and_then(|t| t.as_str())
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.with_timezone(&Utc));
+        let Some(time) = time else { continue };
+        let field = |key: &str| condition.get(key).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+
+        let status = field("status");
+        push(
+            time,
+            "condition",
+            if status == "True" { "Normal" } else { "Warning" },
+            workload_ref.clone(),
+            format!("{}={} ({})", field("type"), status, field("reason")),
+            Some(field("message")).filter(|m| !m.is_empty()),
+        );
+    }
+
+    // Restarts: the last termination of each container is the most we can see
+    for pod in pods.items.iter().filter(|p| belongs("Pod", &p.name_any())) {
+        for status in pod
+            .status
+            .as_ref()
+            .and_then(|s| s.container_statuses.as_ref())
+            .into_iter()
+            .flatten()
+        {
+            let Some(terminated) = status.last_state.as_ref().and_then(|s| s.terminated.as_ref()) else {
+                continue;
+            };
+            let Some(finished) = terminated.finished_at.as_ref() else { continue };
+
+            push(
+                finished.0,
+                "restart",
+                "Warning",
+                format!("Pod/{}", pod.name_any()),
+                format!(
+                    "Container {} restarted ({}, exit code {})",
+                    status.name,
+                    terminated.reason.as_deref().unwrap_or("Unknown"),
+                    terminated.exit_code
+                ),
+                Some(format!("{} restarts in total", status.restart_count)),
+            );
+        }
+    }
+
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(entries.into_iter().map(|(_, entry)| entry).collect())
+}
+
+fn events_csv(rows: &[ExportedEvent]) -> String {
+    let mut csv = String::from(
+        "timestamp,first_timestamp,type,reason,namespace,kind,name,owner,uid,source,count,message\n",
+    );
+
+    for row in rows {
+        let fields = [
+            row.timestamp.clone(),
+            row.first_timestamp.clone().unwrap_or_default(),
+            row.event_type.clone(),
+            row.reason.clone(),
+            row.namespace.clone(),
+            row.kind.clone(),
+            row.name.clone(),
+            row.owner.clone().unwrap_or_default(),
+            row.uid.clone().unwrap_or_default(),
+            row.source.clone(),
+            row.count.to_string(),
+            row.message.clone(),
+        ];
+        let line: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        csv.push_str(&line.join(","));
+        csv.push('\n');
+    }
+
+    csv
+}
+
+/// Quote a CSV field when it contains a delimiter, quote or newline
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a, b"), "\"a, b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+}
@@ -0,0 +1,230 @@
+use anyhow::Result;
+use futures::future::join_all;
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment};
+use k8s_openapi::api::core::v1::{ComponentStatus, Node, Pod};
+use kube::api::{Api, ListParams};
+use kube::{Client, ResourceExt};
+use std::time::{Duration, Instant};
+
+use super::client::client_for_context;
+use super::config::load_kubeconfig;
+use crate::types::{
+    ComponentHealth, ComponentStatusInfo, ContextHealth, ControlPlaneHealth, RestartOffender,
+};
+
+/// Per-request timeout while probing a context
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Probe every kubeconfig context concurrently
+///
+/// Each context gets its own client with short timeouts, so one unreachable
+/// cluster (VPN down, expired credentials) doesn't hold up the others.
+pub async fn get_all_contexts_health() -> Result<Vec<ContextHealth>> {
+    let config = load_kubeconfig()?;
+
+    let probes = config.contexts.iter().map(|ctx| {
+        let current = ctx.name == config.current_context;
+        probe_context(ctx.name.clone(), ctx.context.cluster.clone(), current)
+    });
+
+    Ok(join_all(probes).await)
+}
+
+async fn probe_context(context: String, cluster: String, current: bool) -> ContextHealth {
+    let mut health = ContextHealth {
+        context,
+        cluster,
+        current,
+        reachable: false,
+        version: None,
+        nodes_ready: None,
+        nodes_total: None,
+        latency_ms: None,
+        error: None,
+    };
+
+    // Exec credential plugins can block on their own, so bound the whole probe too
+    let probe = tokio::time::timeout(PROBE_TIMEOUT * 2, async {
+        let client = client_for_context(&health.context, Some(PROBE_TIMEOUT)).await?;
+
+        let started = Instant::now();
+        let version = client.apiserver_version().await?;
+        let latency = started.elapsed();
+
+        let nodes: Api<Node> = Api::all(client);
+        let nodes = nodes.list(&ListParams::default()).await;
+
+        anyhow::Ok((version, latency, nodes))
+    })
+    .await;
+
+    match probe {
+        Ok(Ok((version, latency, nodes))) => {
+            health.reachable = true;
+            health.version = Some(version.git_version);
+            health.latency_ms = Some(latency.as_millis() as u64);
+
+            // Listing nodes may be forbidden even when the cluster is up
+            match nodes {
+                Ok(nodes) => {
+                    health.nodes_total = Some(nodes.items.len());
+                    health.nodes_ready = Some(nodes.items.iter().filter(|n| node_ready(n)).count());
+                }
+                Err(e) => health.error = Some(format!("Cannot list nodes: {}", e)),
+            }
+        }
+        Ok(Err(e)) => health.error = Some(e.to_string()),
+        Err(_) => health.error = Some(format!("Timed out after {}s", (PROBE_TIMEOUT * 2).as_secs())),
+    }
+
+    health
+}
+
+fn node_ready(node: &Node) -> bool {
+    node.status
+        .as_ref()
+        .and_then(|s| s.conditions.as_ref())
+        .map(|conditions| {
+            conditions
+                .iter()
+                .any(|c| c.type_ == "Ready" && c.status == "True")
+        })
+        .unwrap_or(false)
+}
+
+/// kube-system workloads worth calling out by name; anything else there is still reported
+const CRITICAL_COMPONENTS: &[(&str, &str)] = &[
+    ("coredns", "DNS"),
+    ("kube-dns", "DNS"),
+    ("node-local-dns", "DNS"),
+    ("kube-proxy", "Networking"),
+    ("calico-node", "CNI"),
+    ("calico-kube-controllers", "CNI"),
+    ("cilium", "CNI"),
+    ("cilium-operator", "CNI"),
+    ("aws-node", "CNI"),
+    ("kube-flannel-ds", "CNI"),
+    ("weave-net", "CNI"),
+    ("canal", "CNI"),
+    ("antrea-agent", "CNI"),
+    ("metrics-server", "Metrics"),
+    ("konnectivity-agent", "Control plane tunnel"),
+];
+
+/// Restarts within this window count as recent
+const RECENT_RESTART_WINDOW_SECS: i64 = 3600;
+
+/// Health of kube-system components, to rule control-plane problems in or out quickly
+///
+/// Covers every Deployment and DaemonSet in kube-system (known components like
+/// CoreDNS, kube-proxy, the CNI and metrics-server are labelled and sorted
+/// first), componentstatuses on clusters that still serve them, and
+/// kube-system containers that restarted in the last hour.
+pub async fn get_control_plane_health(client: Client) -> Result<ControlPlaneHealth> {
+    let lp = ListParams::default();
+    let (deployments, daemon_sets, pods) = tokio::try_join!(
+        Api::<Deployment>::namespaced(client.clone(), "kube-system").list(&lp),
+        Api::<DaemonSet>::namespaced(client.clone(), "kube-system").list(&lp),
+        Api::<Pod>::namespaced(client.clone(), "kube-system").list(&lp),
+    )?;
+
+    let mut components: Vec<ComponentHealth> = Vec::new();
+    for deployment in &deployments.items {
+        let desired = deployment.spec.as_ref().and_then(|s| s.replicas).unwrap_or(1);
+        let ready = deployment.status.as_ref().and_then(|s| s.ready_replicas).unwrap_or(0);
+        components.push(component_health("Deployment", &deployment.name_any(), desired, ready));
+    }
+    for daemon_set in &daemon_sets.items {
+        let status = daemon_set.status.as_ref();
+        let desired = status.map(|s| s.desired_number_scheduled).unwrap_or(0);
+        let ready = status.map(|s| s.number_ready).unwrap_or(0);
+        components.push(component_health("DaemonSet", &daemon_set.name_any(), desired, ready));
+    }
+    components.sort_by(|a, b| {
+        (a.category.is_none(), a.healthy, &a.name).cmp(&(b.category.is_none(), b.healthy, &b.name))
+    });
+
+    // Deprecated since 1.19 and gone on many managed clusters; absence isn't an error
+    let component_statuses: Vec<ComponentStatusInfo> = Api::<ComponentStatus>::all(client)
+        .list(&lp)
+        .await
+        .map(|list| {
+            list.items
+                .into_iter()
+                .map(|cs| {
+                    let healthy = cs
+                        .conditions
+                        .iter()
+                        .flatten()
+                        .any(|c| c.type_ == "Healthy" && c.status == "True");
+                    let message = cs
+                        .conditions
+                        .iter()
+                        .flatten()
+                        .find_map(|c| c.error.clone().or_else(|| c.message.clone()));
+                    ComponentStatusInfo {
+                        name: cs.name_any(),
+                        healthy,
+                        message,
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let now = chrono::Utc::now();
+    let mut recent_restarts: Vec<RestartOffender> = pods
+        .items
+        .iter()
+        .flat_map(|pod| {
+            let pod_name = pod.name_any();
+            pod.status
+                .as_ref()
+                .and_then(|s| s.container_statuses.clone())
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(move |status| {
+                    let terminated = status.last_state.as_ref()?.terminated.as_ref()?;
+                    let finished = terminated.finished_at.as_ref()?;
+                    if (now - finished.0).num_seconds() > RECENT_RESTART_WINDOW_SECS {
+                        return None;
+                    }
+                    Some(RestartOffender {
+                        pod: pod_name.clone(),
+                        namespace: "kube-system".to_string(),
+                        container: status.name.clone(),
+                        restarts: status.restart_count,
+                        last_termination_reason: terminated.reason.clone(),
+                    })
+                })
+        })
+        .collect();
+    recent_restarts.sort_by(|a, b| b.restarts.cmp(&a.restarts));
+
+    let healthy = components.iter().all(|c| c.healthy)
+        && component_statuses.iter().all(|c| c.healthy);
+
+    Ok(ControlPlaneHealth {
+        healthy,
+        components,
+        component_statuses,
+        recent_restarts,
+    })
+}
+
+fn component_health(kind: &str, name: &str, desired: i32, ready: i32) -> ComponentHealth {
+    // DaemonSet pods are suffixed per node pool on some distributions, e.g. aws-node-windows
+    let category = CRITICAL_COMPONENTS
+        .iter()
+        .find(|(component, _)| name == *component || name.starts_with(&format!("{}-", component)))
+        .map(|(_, category)| category.to_string());
+
+    ComponentHealth {
+        kind: kind.to_string(),
+        name: name.to_string(),
+        category,
+        desired,
+        ready,
+        healthy: ready >= desired,
+    }
+}
@@ -0,0 +1,331 @@
+use anyhow::Result;
+use futures::future::join_all;
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, StatefulSet};
+use k8s_openapi::api::core::v1::PodTemplateSpec;
+use kube::api::{Api, ListParams};
+use kube::{Client, ResourceExt};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::{oneshot, Mutex};
+use uuid::Uuid;
+
+use crate::kube::images::parse_image_ref;
+use crate::kube::operations::{restart_daemonset, restart_deployment, restart_statefulset};
+use crate::kube::rollout::check_rollout;
+use crate::types::{ImageRestartProgress, ImageRestartResult, ImageRestartTarget};
+
+const POLL_INTERVAL_SECS: u64 = 3;
+
+/// Used when the workload has no progressDeadlineSeconds of its own
+const ROLLOUT_TIMEOUT_SECS: u64 = 600;
+
+/// `*` matches any run of characters, everything else literally
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == text;
+    }
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if !text.starts_with(first) || !text[first.len()..].ends_with(last) {
+        return false;
+    }
+    let mut rest = &text[first.len()..text.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+/// Whether a container image matches a pattern
+///
+/// Patterns with `*` are globs over the image as written or fully qualified
+/// (`docker.io/library/nginx:1.25`). Plain patterns compare parsed references,
+/// so `nginx` matches any tag of Docker Hub's nginx and `nginx:1.25` only that tag.
+fn image_matches(pattern: &str, image: &str) -> bool {
+    let parsed = parse_image_ref(image);
+    if pattern.contains('*') {
+        let mut qualified = format!("{}/{}", parsed.registry, parsed.repository);
+        if let Some(tag) = &parsed.tag {
+            qualified = format!("{}:{}", qualified, tag);
+        }
+        if let Some(digest) = &parsed.digest {
+            qualified = format!("{}@{}", qualified, digest);
+        }
+        return glob_match(pattern, image) || glob_match(pattern, &qualified);
+    }
+
+    let wanted = parse_image_ref(pattern);
+    wanted.registry == parsed.registry
+        && wanted.repository == parsed.repository
+        && (wanted.tag.is_none() || wanted.tag == parsed.tag)
+        && (wanted.digest.is_none() || wanted.digest == parsed.digest)
+}
+
+fn matching_images(pattern: &str, template: &PodTemplateSpec) -> Vec<String> {
+    let Some(spec) = &template.spec else {
+        return Vec::new();
+    };
+    let mut images: Vec<String> = spec
+        .init_containers
+        .iter()
+        .flatten()
+        .chain(spec.containers.iter())
+        .filter_map(|c| c.image.clone())
+        .filter(|image| image_matches(pattern, image))
+        .collect();
+    images.sort();
+    images.dedup();
+    images
+}
+
+/// Deployments, StatefulSets and DaemonSets whose pod template runs an image matching `image_pattern`
+///
+/// This is the preview step of [`ImageRestartManager::start_restart`]; it
+/// changes nothing. An empty `namespaces` searches every namespace.
+pub async fn preview_image_restart(
+    client: Client,
+    image_pattern: &str,
+    namespaces: &[String],
+) -> Result<Vec<ImageRestartTarget>> {
+    if image_pattern.trim().is_empty() {
+        return Err(anyhow::anyhow!("An image pattern is required"));
+    }
+    let scopes: Vec<Option<&str>> = if namespaces.is_empty() {
+        vec![None]
+    } else {
+        namespaces.iter().map(|ns| Some(ns.as_str())).collect()
+    };
+
+    let mut targets = Vec::new();
+    for scope in scopes {
+        let (deployments, statefulsets, daemonsets) = match scope {
+            Some(ns) => (
+                Api::<Deployment>::namespaced(client.clone(), ns),
+                Api::<StatefulSet>::namespaced(client.clone(), ns),
+                Api::<DaemonSet>::namespaced(client.clone(), ns),
+            ),
+            None => (
+                Api::<Deployment>::all(client.clone()),
+                Api::<StatefulSet>::all(client.clone()),
+                Api::<DaemonSet>::all(client.clone()),
+            ),
+        };
+        let lp = ListParams::default();
+        let (deployments, statefulsets, daemonsets) =
+            tokio::try_join!(deployments.list(&lp), statefulsets.list(&lp), daemonsets.list(&lp))?;
+
+        let templates = deployments
+            .items
+            .iter()
+            .map(|d| ("Deployment", d.namespace(), d.name_any(), d.spec.as_ref().map(|s| &s.template)))
+            .chain(
+                statefulsets
+                    .items
+                    .iter()
+                    .map(|s| ("StatefulSet", s.namespace(), s.name_any(), s.spec.as_ref().map(|s| &s.template))),
+            )
+            .chain(
+                daemonsets
+                    .items
+                    .iter()
+                    .map(|d| ("DaemonSet", d.namespace(), d.name_any(), d.spec.as_ref().map(|s| &s.template))),
+            );
+        for (kind, namespace, name, template) in templates {
+            let images = template.map(|t| matching_images(image_pattern, t)).unwrap_or_default();
+            if !images.is_empty() {
+                targets.push(ImageRestartTarget {
+                    kind: kind.to_string(),
+                    namespace: namespace.unwrap_or_default(),
+                    name,
+                    images,
+                });
+            }
+        }
+    }
+
+    targets.sort_by(|a, b| (&a.namespace, &a.kind, &a.name).cmp(&(&b.namespace, &b.kind, &b.name)));
+    Ok(targets)
+}
+
+/// Rolling-restarts every workload running an image, a few at a time, for base-image rotations
+pub struct ImageRestartManager {
+    restarts: Arc<Mutex<HashMap<String, oneshot::Sender<()>>>>,
+}
+
+impl ImageRestartManager {
+    pub fn new() -> Self {
+        Self {
+            restarts: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Restart the workloads [`preview_image_restart`] finds, `max_parallel` at a
+    /// time, waiting for each batch's rollouts to finish before starting the next
+    ///
+    /// Emits `image-restart-progress-{restart_id}` as each workload is restarted
+    /// and settles, and one `image-restart-complete-{restart_id}`. A failed
+    /// rollout stops the run before the next batch, as a bad image would
+    /// otherwise be rolled across the whole cluster. Returns the restart id.
+    pub async fn start_restart(
+        &self,
+        app: AppHandle,
+        client: Client,
+        image_pattern: &str,
+        namespaces: &[String],
+        max_parallel: Option<usize>,
+    ) -> Result<String> {
+        let targets = preview_image_restart(client.clone(), image_pattern, namespaces).await?;
+        if targets.is_empty() {
+            return Err(anyhow::anyhow!("No workloads run an image matching {}", image_pattern));
+        }
+        let max_parallel = max_parallel.unwrap_or(1).max(1);
+
+        let restart_id = Uuid::new_v4().to_string();
+        let (cancel_tx, mut cancel_rx) = oneshot::channel::<()>();
+        self.restarts.lock().await.insert(restart_id.clone(), cancel_tx);
+
+        let restarts = self.restarts.clone();
+        let id = restart_id.clone();
+        tokio::spawn(async move {
+            let progress_event = format!("image-restart-progress-{}", id);
+            let total = targets.len();
+            let mut restarted = Vec::new();
+            let mut failed = Vec::new();
+
+            let outcome = 'run: {
+                for batch in targets.chunks(max_parallel) {
+                    let done_before = restarted.len();
+                    let batch_restarts = join_all(
+                        batch
+                            .iter()
+                            .map(|target| restart_and_wait(&app, &progress_event, &client, target, done_before, total)),
+                    );
+                    let results = tokio::select! {
+                        results = batch_restarts => results,
+                        _ = &mut cancel_rx => break 'run "cancelled",
+                    };
+
+                    for (target, result) in batch.iter().zip(results) {
+                        let label = format!("{}/{}/{}", target.namespace, target.kind, target.name);
+                        match result {
+                            Ok(()) => restarted.push(label),
+                            Err(e) => failed.push(format!("{}: {}", label, e)),
+                        }
+                    }
+                    if !failed.is_empty() {
+                        break 'run "failed";
+                    }
+                }
+                "success"
+            };
+
+            let message = match outcome {
+                "success" => format!("Restarted {} workloads", restarted.len()),
+                "cancelled" => format!("Stopped after restarting {} of {} workloads", restarted.len(), total),
+                _ => format!("Stopped after {} of {} workloads: {}", restarted.len(), total, failed.join("; ")),
+            };
+            let result = ImageRestartResult {
+                outcome: outcome.to_string(),
+                message,
+                restarted,
+                failed,
+            };
+            let _ = app.emit(&format!("image-restart-complete-{}", id), &result);
+            restarts.lock().await.remove(&id);
+        });
+
+        Ok(restart_id)
+    }
+
+    pub async fn stop_restart(&self, restart_id: &str) -> Result<()> {
+        if let Some(cancel) = self.restarts.lock().await.remove(restart_id) {
+            let _ = cancel.send(());
+        }
+        Ok(())
+    }
+}
+
+impl Default for ImageRestartManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Restart one workload and wait until its rollout succeeds, fails or times out
+async fn restart_and_wait(
+    app: &AppHandle,
+    event: &str,
+    client: &Client,
+    target: &ImageRestartTarget,
+    workloads_done: usize,
+    workloads_total: usize,
+) -> Result<()> {
+    let emit = |phase: &str, message: String| {
+        let _ = app.emit(
+            event,
+            &ImageRestartProgress {
+                kind: target.kind.clone(),
+                namespace: target.namespace.clone(),
+                name: target.name.clone(),
+                phase: phase.to_string(),
+                workloads_done,
+                workloads_total,
+                message,
+            },
+        );
+    };
+
+    let (namespace, name) = (target.namespace.as_str(), target.name.as_str());
+    let restart = match target.kind.as_str() {
+        "Deployment" => restart_deployment(client.clone(), namespace, name).await,
+        "StatefulSet" => restart_statefulset(client.clone(), namespace, name).await,
+        _ => restart_daemonset(client.clone(), namespace, name).await,
+    };
+    if let Err(e) = restart {
+        emit("failed", e.to_string());
+        return Err(e);
+    }
+    emit("restarting", format!("Restarted {} {}", target.kind, name));
+
+    let started = Instant::now();
+    loop {
+        tokio::time::sleep(Duration::from_secs(POLL_INTERVAL_SECS)).await;
+        let status = check_rollout(client, &target.kind, namespace, name).await?;
+        let progress = status.progress;
+        if progress.done {
+            emit("restarted", progress.message);
+            return Ok(());
+        }
+        if let Some(failure) = progress.failure {
+            emit("failed", failure.clone());
+            return Err(anyhow::anyhow!(failure));
+        }
+        let timeout = status.deadline_secs.unwrap_or(ROLLOUT_TIMEOUT_SECS);
+        if started.elapsed() >= Duration::from_secs(timeout) {
+            emit("failed", format!("Rollout did not finish within {}s", timeout));
+            return Err(anyhow::anyhow!("Rollout did not finish within {}s: {}", timeout, progress.message));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_matches() {
+        assert!(image_matches("nginx", "nginx:1.25"));
+        assert!(image_matches("nginx", "docker.io/library/nginx@sha256:abc"));
+        assert!(image_matches("nginx:1.25", "nginx:1.25"));
+        assert!(!image_matches("nginx:1.25", "nginx:1.27"));
+        assert!(!image_matches("nginx", "ghcr.io/acme/nginx:1.25"));
+        assert!(image_matches("*/acme/*", "ghcr.io/acme/api:2.0"));
+        assert!(image_matches("docker.io/library/python:3.11*", "python:3.11-slim"));
+        assert!(!image_matches("*alpine", "python:3.11-slim"));
+    }
+}
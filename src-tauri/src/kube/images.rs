@@ -0,0 +1,228 @@
+use anyhow::Result;
+use k8s_openapi::api::core::v1::Pod;
+use kube::api::{Api, ListParams};
+use kube::{Client, ResourceExt};
+use std::collections::BTreeMap;
+
+use crate::types::{ImageInventory, ImageUsage, RegistrySummary};
+
+/// Waiting reasons that mean the image couldn't be pulled
+const PULL_FAILURE_REASONS: &[&str] = &["ErrImagePull", "ImagePullBackOff", "InvalidImageName"];
+
+/// An image reference split into its parts
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ImageRef {
+    pub registry: String,
+    pub repository: String,
+    pub tag: Option<String>,
+    pub digest: Option<String>,
+}
+
+/// Parse an image reference the way the container runtime does: the first
+/// path component is a registry only if it looks like a host, otherwise the
+/// image lives on Docker Hub
+pub(crate) fn parse_image_ref(image: &str) -> ImageRef {
+    let (name, digest) = match image.split_once('@') {
+        Some((name, digest)) => (name, Some(digest.to_string())),
+        None => (image, None),
+    };
+
+    // A colon after the last slash separates the tag; earlier ones are registry ports
+    let (name, tag) = match name.rfind(':') {
+        Some(i) if !name[i..].contains('/') => (&name[..i], Some(name[i + 1..].to_string())),
+        _ => (name, None),
+    };
+
+    let (registry, repository) = match name.split_once('/') {
+        Some((host, rest)) if host.contains('.') || host.contains(':') || host == "localhost" => {
+            (host.to_string(), rest.to_string())
+        }
+        _ if name.contains('/') => ("docker.io".to_string(), name.to_string()),
+        _ => ("docker.io".to_string(), format!("library/{}", name)),
+    };
+
+    ImageRef { registry, repository, tag, digest }
+}
+
+/// Aggregate every container image running in pods, grouped by image
+///
+/// Images pinned to `:latest` (or with no tag or digest, which means the
+/// same) are flagged, and registries are marked unreachable when pods are
+/// stuck pulling from them.
+pub async fn get_image_inventory(client: Client, namespace: Option<&str>) -> Result<ImageInventory> {
+    let pods: Api<Pod> = match namespace.filter(|ns| !ns.is_empty()) {
+        Some(ns) => Api::namespaced(client, ns),
+        None => Api::all(client),
+    };
+    let pod_list = pods.list(&ListParams::default()).await?;
+
+    let mut images: BTreeMap<String, ImageUsage> = BTreeMap::new();
+
+    for pod in &pod_list.items {
+        let workload = format!("{}/{}", pod.namespace().unwrap_or_default(), owning_workload(pod));
+        let Some(spec) = &pod.spec else {
+            continue;
+        };
+
+        let pull_secrets: Vec<String> = spec
+            .image_pull_secrets
+            .iter()
+            .flatten()
+            .map(|s| s.name.clone())
+            .collect();
+
+        let containers = spec
+            .init_containers
+            .iter()
+            .flatten()
+            .chain(spec.containers.iter());
+
+        for container in containers {
+            let Some(image) = container.image.clone() else {
+                continue;
+            };
+
+            let usage = images.entry(image.clone()).or_insert_with(|| {
+                let parsed = parse_image_ref(&image);
+                ImageUsage {
+                    latest: parsed.digest.is_none()
+                        && parsed.tag.as_deref().map(|t| t == "latest").unwrap_or(true),
+                    image: image.clone(),
+                    registry: parsed.registry,
+                    repository: parsed.repository,
+                    tag: parsed.tag,
+                    digest: parsed.digest,
+                    container_count: 0,
+                    pull_policies: Vec::new(),
+                    pull_secrets: Vec::new(),
+                    workloads: Vec::new(),
+                    pull_errors: Vec::new(),
+                }
+            });
+
+            usage.container_count += 1;
+            let policy = container
+                .image_pull_policy
+                .clone()
+                .unwrap_or_else(|| "IfNotPresent".to_string());
+            push_unique(&mut usage.pull_policies, policy);
+            for secret in &pull_secrets {
+                push_unique(&mut usage.pull_secrets, secret.clone());
+            }
+            push_unique(&mut usage.workloads, workload.clone());
+        }
+
+        // Pull failures are reported on the status, keyed by the spec's image
+        let statuses = pod.status.iter().flat_map(|s| {
+            s.init_container_statuses
+                .iter()
+                .flatten()
+                .chain(s.container_statuses.iter().flatten())
+        });
+        for status in statuses {
+            let Some(waiting) = status.state.as_ref().and_then(|s| s.waiting.as_ref()) else {
+                continue;
+            };
+            let reason = waiting.reason.as_deref().unwrap_or_default();
+            if !PULL_FAILURE_REASONS.contains(&reason) {
+                continue;
+            }
+
+            let spec_image = spec
+                .init_containers
+                .iter()
+                .flatten()
+                .chain(spec.containers.iter())
+                .find(|c| c.name == status.name)
+                .and_then(|c| c.image.clone())
+                .unwrap_or_else(|| status.image.clone());
+
+            if let Some(usage) = images.get_mut(&spec_image) {
+                let message = waiting.message.clone().unwrap_or_else(|| reason.to_string());
+                push_unique(&mut usage.pull_errors, message);
+            }
+        }
+    }
+
+    let mut registries: BTreeMap<String, RegistrySummary> = BTreeMap::new();
+    for usage in images.values() {
+        let summary = registries
+            .entry(usage.registry.clone())
+            .or_insert_with(|| RegistrySummary {
+                registry: usage.registry.clone(),
+                image_count: 0,
+                pull_secrets: Vec::new(),
+                unreachable: false,
+                failing_images: Vec::new(),
+            });
+
+        summary.image_count += 1;
+        for secret in &usage.pull_secrets {
+            push_unique(&mut summary.pull_secrets, secret.clone());
+        }
+        if !usage.pull_errors.is_empty() {
+            summary.failing_images.push(usage.image.clone());
+        }
+    }
+
+    // A registry is unreachable when none of its images could be pulled
+    for summary in registries.values_mut() {
+        summary.unreachable =
+            !summary.failing_images.is_empty() && summary.failing_images.len() == summary.image_count;
+    }
+
+    let images: Vec<ImageUsage> = images.into_values().collect();
+    let latest_count = images.iter().filter(|i| i.latest).count();
+
+    Ok(ImageInventory {
+        images,
+        registries: registries.into_values().collect(),
+        latest_count,
+    })
+}
+
+/// Best-effort owning workload as "Kind/name", folding ReplicaSets into their Deployment
+fn owning_workload(pod: &Pod) -> String {
+    let Some(owner) = pod.owner_references().iter().find(|o| o.controller == Some(true)) else {
+        return format!("Pod/{}", pod.name_any());
+    };
+
+    if owner.kind == "ReplicaSet" {
+        if let Some(hash) = pod.labels().get("pod-template-hash") {
+            if let Some(deployment) = owner.name.strip_suffix(&format!("-{}", hash)) {
+                return format!("Deployment/{}", deployment);
+            }
+        }
+    }
+
+    format!("{}/{}", owner.kind, owner.name)
+}
+
+fn push_unique(values: &mut Vec<String>, value: String) {
+    if !values.contains(&value) {
+        values.push(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_image_ref() {
+        let image = parse_image_ref("nginx");
+        assert_eq!(image.registry, "docker.io");
+        assert_eq!(image.repository, "library/nginx");
+        assert_eq!(image.tag, None);
+
+        let image = parse_image_ref("localhost:5000/team/app:1.2");
+        assert_eq!(image.registry, "localhost:5000");
+        assert_eq!(image.repository, "team/app");
+        assert_eq!(image.tag.as_deref(), Some("1.2"));
+
+        let image = parse_image_ref("ghcr.io/org/tool@sha256:abc");
+        assert_eq!(image.registry, "ghcr.io");
+        assert_eq!(image.repository, "org/tool");
+        assert_eq!(image.digest.as_deref(), Some("sha256:abc"));
+    }
+}
@@ -0,0 +1,342 @@
+use anyhow::Result;
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, ReplicaSet, StatefulSet};
+use k8s_openapi::api::batch::v1::{CronJob, Job};
+use k8s_openapi::api::core::v1::{
+    ConfigMap, PersistentVolume, PersistentVolumeClaim, Pod, Secret, Service, ServiceAccount,
+};
+use k8s_openapi::api::networking::v1::Ingress;
+use k8s_openapi::api::rbac::v1::{ClusterRole, ClusterRoleBinding, Role, RoleBinding};
+use kube::api::{Api, DeleteParams, DynamicObject, PropagationPolicy};
+use kube::discovery::ApiResource;
+use kube::Client;
+use serde_json::Value;
+use std::time::{Duration, Instant};
+
+use crate::types::{ImmutableFieldChange, ImmutableFieldReport};
+
+/// How long to wait for a deleted object (and its finalizers) to go before recreating it
+const DELETE_TIMEOUT_SECS: u64 = 120;
+
+/// A field the API server refuses to change after creation
+struct ImmutableRule {
+    kind: &'static str,
+    path: &'static str,
+    /// Require equality rather than the manifest being a subset of the live value.
+    /// Subset matching tolerates server-side defaults; exact matching catches removals.
+    exact: bool,
+    reason: &'static str,
+}
+
+const RULES: &[ImmutableRule] = &[
+    ImmutableRule { kind: "deployment", path: "/spec/selector", exact: true, reason: "Deployment selectors are immutable" },
+    ImmutableRule { kind: "replicaset", path: "/spec/selector", exact: true, reason: "ReplicaSet selectors are immutable" },
+    ImmutableRule { kind: "daemonset", path: "/spec/selector", exact: true, reason: "DaemonSet selectors are immutable" },
+    ImmutableRule { kind: "statefulset", path: "/spec/selector", exact: true, reason: "StatefulSet selectors are immutable" },
+    ImmutableRule {
+        kind: "statefulset",
+        path: "/spec/volumeClaimTemplates",
+        exact: false,
+        reason: "volumeClaimTemplates cannot be changed; existing PVCs keep their size and class",
+    },
+    ImmutableRule { kind: "statefulset", path: "/spec/serviceName", exact: true, reason: "serviceName is immutable" },
+    ImmutableRule {
+        kind: "statefulset",
+        path: "/spec/podManagementPolicy",
+        exact: false,
+        reason: "podManagementPolicy is immutable",
+    },
+    ImmutableRule { kind: "service", path: "/spec/clusterIP", exact: false, reason: "clusterIP cannot be changed once allocated" },
+    ImmutableRule { kind: "service", path: "/spec/clusterIPs", exact: false, reason: "clusterIPs cannot be changed once allocated" },
+    ImmutableRule { kind: "job", path: "/spec/selector", exact: false, reason: "Job selectors are immutable" },
+    ImmutableRule { kind: "job", path: "/spec/completionMode", exact: false, reason: "completionMode is immutable" },
+    ImmutableRule {
+        kind: "job",
+        path: "/spec/template/spec/containers",
+        exact: false,
+        reason: "A Job's pod template is immutable",
+    },
+    ImmutableRule {
+        kind: "persistentvolumeclaim",
+        path: "/spec/storageClassName",
+        exact: false,
+        reason: "A claim's storage class is immutable",
+    },
+    ImmutableRule {
+        kind: "persistentvolumeclaim",
+        path: "/spec/accessModes",
+        exact: false,
+        reason: "A claim's access modes are immutable",
+    },
+    ImmutableRule {
+        kind: "persistentvolumeclaim",
+        path: "/spec/volumeName",
+        exact: false,
+        reason: "A bound claim cannot move to another volume",
+    },
+    ImmutableRule {
+        kind: "persistentvolumeclaim",
+        path: "/spec/volumeMode",
+        exact: false,
+        reason: "volumeMode is immutable",
+    },
+    ImmutableRule {
+        kind: "persistentvolumeclaim",
+        path: "/spec/dataSource",
+        exact: false,
+        reason: "A claim's data source is immutable",
+    },
+    ImmutableRule { kind: "pod", path: "/spec/volumes", exact: false, reason: "A pod's volumes are immutable" },
+    ImmutableRule { kind: "pod", path: "/spec/nodeName", exact: false, reason: "A scheduled pod cannot move node" },
+    ImmutableRule {
+        kind: "pod",
+        path: "/spec/serviceAccountName",
+        exact: false,
+        reason: "A pod's service account is immutable",
+    },
+    ImmutableRule { kind: "rolebinding", path: "/roleRef", exact: true, reason: "roleRef cannot be changed" },
+    ImmutableRule { kind: "clusterrolebinding", path: "/roleRef", exact: true, reason: "roleRef cannot be changed" },
+];
+
+/// Canonical lowercase type name, accepting the same aliases as apply
+pub(crate) fn normalize_type(resource_type: &str) -> String {
+    match resource_type.to_lowercase().as_str() {
+        "pvc" => "persistentvolumeclaim".to_string(),
+        "pv" => "persistentvolume".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// The API resource and whether it is namespaced, for the types apply supports
+pub(crate) fn api_resource_for(resource_type: &str) -> Option<(ApiResource, bool)> {
+    Some(match normalize_type(resource_type).as_str() {
+        "pod" => (ApiResource::erase::<Pod>(&()), true),
+        "deployment" => (ApiResource::erase::<Deployment>(&()), true),
+        "replicaset" => (ApiResource::erase::<ReplicaSet>(&()), true),
+        "service" => (ApiResource::erase::<Service>(&()), true),
+        "configmap" => (ApiResource::erase::<ConfigMap>(&()), true),
+        "secret" => (ApiResource::erase::<Secret>(&()), true),
+        "statefulset" => (ApiResource::erase::<StatefulSet>(&()), true),
+        "daemonset" => (ApiResource::erase::<DaemonSet>(&()), true),
+        "job" => (ApiResource::erase::<Job>(&()), true),
+        "cronjob" => (ApiResource::erase::<CronJob>(&()), true),
+        "ingress" => (ApiResource::erase::<Ingress>(&()), true),
+        "persistentvolumeclaim" => (ApiResource::erase::<PersistentVolumeClaim>(&()), true),
+        "persistentvolume" => (ApiResource::erase::<PersistentVolume>(&()), false),
+        "role" => (ApiResource::erase::<Role>(&()), true),
+        "rolebinding" => (ApiResource::erase::<RoleBinding>(&()), true),
+        "clusterrole" => (ApiResource::erase::<ClusterRole>(&()), false),
+        "clusterrolebinding" => (ApiResource::erase::<ClusterRoleBinding>(&()), false),
+        "serviceaccount" => (ApiResource::erase::<ServiceAccount>(&()), true),
+        _ => return None,
+    })
+}
+
+pub(crate) fn dynamic_api(client: Client, resource_type: &str, namespace: &str) -> Result<Api<DynamicObject>> {
+    let (resource, namespaced) = api_resource_for(resource_type)
+        .ok_or_else(|| anyhow::anyhow!("Unsupported resource type: {}", resource_type))?;
+    Ok(if namespaced {
+        Api::namespaced_with(client, namespace, &resource)
+    } else {
+        Api::all_with(client, &resource)
+    })
+}
+
+/// Whether every field set in `desired` has the same value in `live`
+fn is_subset(desired: &Value, live: &Value) -> bool {
+    match (desired, live) {
+        (Value::Object(d), Value::Object(l)) => d
+            .iter()
+            .all(|(key, value)| value.is_null() || l.get(key).map(|lv| is_subset(value, lv)).unwrap_or(false)),
+        (Value::Array(d), Value::Array(l)) => d.len() == l.len() && d.iter().zip(l).all(|(d, l)| is_subset(d, l)),
+        // An empty string asks for the default, e.g. clusterIP: ""
+        (Value::String(d), _) if d.is_empty() => true,
+        (d, l) => d == l,
+    }
+}
+
+/// Immutable fields the manifest would change on the live object
+fn immutable_changes(resource_type: &str, desired: &Value, live: &Value) -> Vec<ImmutableFieldChange> {
+    let kind = normalize_type(resource_type);
+    let mut changes: Vec<ImmutableFieldChange> = RULES
+        .iter()
+        .filter(|rule| rule.kind == kind)
+        .filter_map(|rule| {
+            let wanted = desired.pointer(rule.path).filter(|v| !v.is_null())?;
+            let current = live.pointer(rule.path);
+            let unchanged = match current {
+                Some(current) if rule.exact => wanted == current,
+                Some(current) => is_subset(wanted, current),
+                None => false,
+            };
+            (!unchanged).then(|| ImmutableFieldChange {
+                path: rule.path.to_string(),
+                live: current.cloned(),
+                desired: wanted.clone(),
+                reason: rule.reason.to_string(),
+            })
+        })
+        .collect();
+
+    // ConfigMaps and Secrets marked immutable can't have their data touched at all
+    if (kind == "configmap" || kind == "secret") && live.get("immutable") == Some(&Value::Bool(true)) {
+        for path in ["/data", "/binaryData", "/stringData"] {
+            if let Some(wanted) = desired.pointer(path).filter(|v| !v.is_null()) {
+                if live.pointer(path) != Some(wanted) {
+                    changes.push(ImmutableFieldChange {
+                        path: path.to_string(),
+                        live: live.pointer(path).cloned(),
+                        desired: wanted.clone(),
+                        reason: format!("This {} is marked immutable", kind),
+                    });
+                }
+            }
+        }
+    }
+
+    changes
+}
+
+/// Compare a manifest with the live object and report immutable fields it would change
+///
+/// A manifest for an object that doesn't exist yet never requires a recreate.
+pub async fn check_immutable_fields(
+    client: Client,
+    resource_type: &str,
+    namespace: &str,
+    yaml_content: &str,
+) -> Result<ImmutableFieldReport> {
+    let desired: Value = serde_yaml::from_str(yaml_content)?;
+    let name = desired
+        .pointer("/metadata/name")
+        .and_then(|n| n.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Manifest has no metadata.name"))?
+        .to_string();
+    let namespace = desired
+        .pointer("/metadata/namespace")
+        .and_then(|n| n.as_str())
+        .unwrap_or(namespace);
+
+    // Kinds without known immutable fields have nothing to compare
+    let live = match api_resource_for(resource_type) {
+        Some(_) => dynamic_api(client, resource_type, namespace)?.get_opt(&name).await?,
+        None => None,
+    };
+    let changes = match live {
+        Some(live) => immutable_changes(resource_type, &desired, &serde_json::to_value(&live)?),
+        None => Vec::new(),
+    };
+
+    let message = if changes.is_empty() {
+        "No immutable fields change; the manifest can be applied in place".to_string()
+    } else {
+        let fields: Vec<&str> = changes.iter().map(|c| c.path.as_str()).collect();
+        format!(
+            "{} {} must be deleted and recreated to change {}. {}",
+            resource_type,
+            name,
+            fields.join(", "),
+            changes.first().map(|c| c.reason.as_str()).unwrap_or_default()
+        )
+    };
+
+    Ok(ImmutableFieldReport {
+        error: (!changes.is_empty()).then(|| "RecreateRequired".to_string()),
+        resource_type: resource_type.to_string(),
+        name,
+        requires_recreate: !changes.is_empty(),
+        changes,
+        message,
+    })
+}
+
+/// Delete an object and wait until it is gone, so a replacement with the same name can be created
+///
+/// `orphan` leaves dependents (a StatefulSet's pods, a Job's pods) running,
+/// as `kubectl delete --cascade=orphan` does.
+pub(crate) async fn delete_and_wait(
+    client: Client,
+    resource_type: &str,
+    namespace: &str,
+    name: &str,
+    orphan: bool,
+) -> Result<()> {
+    let api = dynamic_api(client, resource_type, namespace)?;
+    let params = DeleteParams {
+        propagation_policy: Some(if orphan { PropagationPolicy::Orphan } else { PropagationPolicy::Foreground }),
+        ..DeleteParams::default()
+    };
+    match api.delete(name, &params).await {
+        Ok(_) => {}
+        Err(kube::Error::Api(e)) if e.code == 404 => return Ok(()),
+        Err(e) => return Err(e.into()),
+    }
+
+    let started = Instant::now();
+    while api.get_opt(name).await?.is_some() {
+        if started.elapsed() >= Duration::from_secs(DELETE_TIMEOUT_SECS) {
+            return Err(anyhow::anyhow!(
+                "{} {} still exists after {}s; check its finalizers",
+                resource_type,
+                name,
+                DELETE_TIMEOUT_SECS
+            ));
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_immutable_changes() {
+        let live = json!({
+            "spec": {
+                "selector": { "matchLabels": { "app": "web" } },
+                "serviceName": "web",
+                "volumeClaimTemplates": [{
+                    "metadata": { "name": "data" },
+                    "spec": { "accessModes": ["ReadWriteOnce"], "volumeMode": "Filesystem",
+                              "resources": { "requests": { "storage": "10Gi" } } }
+                }]
+            }
+        });
+
+        // Server-side defaults like volumeMode don't count as changes
+        let same = json!({
+            "spec": {
+                "selector": { "matchLabels": { "app": "web" } },
+                "volumeClaimTemplates": [{
+                    "metadata": { "name": "data" },
+                    "spec": { "accessModes": ["ReadWriteOnce"], "resources": { "requests": { "storage": "10Gi" } } }
+                }]
+            }
+        });
+        assert!(immutable_changes("StatefulSet", &same, &live).is_empty());
+
+        let resized = json!({
+            "spec": {
+                "selector": { "matchLabels": { "app": "web", "tier": "db" } },
+                "volumeClaimTemplates": [{
+                    "metadata": { "name": "data" },
+                    "spec": { "resources": { "requests": { "storage": "20Gi" } } }
+                }]
+            }
+        });
+        let paths: Vec<String> = immutable_changes("statefulset", &resized, &live)
+            .into_iter()
+            .map(|c| c.path)
+            .collect();
+        assert_eq!(paths, vec!["/spec/selector", "/spec/volumeClaimTemplates"]);
+
+        let service = json!({ "spec": { "clusterIP": "10.0.0.10" } });
+        assert!(immutable_changes("service", &json!({ "spec": { "clusterIP": "" } }), &service).is_empty());
+        assert_eq!(
+            immutable_changes("service", &json!({ "spec": { "clusterIP": "10.0.0.11" } }), &service).len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,223 @@
+use anyhow::Result;
+use kube::api::{Api, DynamicObject, ListParams, Patch, PatchParams};
+use kube::core::GroupVersionKind;
+use kube::discovery::ApiResource;
+use kube::{Client, ResourceExt};
+use serde_json::Value;
+
+use crate::types::{KnativeRevisionInfo, KnativeServiceInfo, KnativeTrafficTarget};
+
+fn serving_resource(kind: &str, plural: &str) -> ApiResource {
+    ApiResource::from_gvk_with_plural(&GroupVersionKind::gvk("serving.knative.dev", "v1", kind), plural)
+}
+
+fn serving_api(client: Client, namespace: &str, kind: &str, plural: &str) -> Api<DynamicObject> {
+    let resource = serving_resource(kind, plural);
+    if namespace.is_empty() {
+        Api::all_with(client, &resource)
+    } else {
+        Api::namespaced_with(client, namespace, &resource)
+    }
+}
+
+/// Map a 404 on the list call to a clear "not installed" error
+fn knative_error(e: kube::Error) -> anyhow::Error {
+    match &e {
+        kube::Error::Api(resp) if resp.code == 404 => {
+            anyhow::anyhow!("Knative Serving does not appear to be installed")
+        }
+        _ => e.into(),
+    }
+}
+
+pub async fn list_knative_services(client: Client, namespace: &str) -> Result<Vec<KnativeServiceInfo>> {
+    let services = serving_api(client, namespace, "Service", "services")
+        .list(&ListParams::default())
+        .await
+        .map_err(knative_error)?;
+
+    Ok(services
+        .items
+        .iter()
+        .map(|svc| {
+            let (ready, reason) = ready_condition(&svc.data);
+            KnativeServiceInfo {
+                name: svc.name_any(),
+                namespace: svc.namespace().unwrap_or_default(),
+                url: str_at(&svc.data, "/status/url"),
+                ready,
+                reason,
+                latest_created_revision: str_at(&svc.data, "/status/latestCreatedRevisionName"),
+                latest_ready_revision: str_at(&svc.data, "/status/latestReadyRevisionName"),
+                traffic: traffic_targets(svc.data.pointer("/status/traffic")),
+                created_at: svc.metadata.creation_timestamp.as_ref().map(|ts| ts.0.to_rfc3339()),
+            }
+        })
+        .collect())
+}
+
+/// Revisions, optionally for one Knative Service, with their traffic share and scale state
+pub async fn list_knative_revisions(
+    client: Client,
+    namespace: &str,
+    service: Option<&str>,
+) -> Result<Vec<KnativeRevisionInfo>> {
+    let mut lp = ListParams::default();
+    if let Some(service) = service {
+        lp = lp.labels(&format!("serving.knative.dev/service={}", service));
+    }
+
+    let (revisions, services) = tokio::try_join!(
+        serving_api(client.clone(), namespace, "Revision", "revisions").list(&lp),
+        serving_api(client, namespace, "Service", "services").list(&ListParams::default()),
+    )
+    .map_err(knative_error)?;
+
+    // Traffic is recorded on the Service; index it by (namespace, revision)
+    let mut traffic: std::collections::HashMap<(String, String), i64> = std::collections::HashMap::new();
+    for svc in &services.items {
+        let ns = svc.namespace().unwrap_or_default();
+        for target in traffic_targets(svc.data.pointer("/status/traffic")) {
+            if let Some(revision) = target.revision_name {
+                *traffic.entry((ns.clone(), revision)).or_default() += target.percent.unwrap_or(0);
+            }
+        }
+    }
+
+    let mut result: Vec<KnativeRevisionInfo> = revisions
+        .items
+        .iter()
+        .map(|rev| {
+            let (ready, reason) = ready_condition(&rev.data);
+            let ns = rev.namespace().unwrap_or_default();
+            let actual_replicas = rev.data.pointer("/status/actualReplicas").and_then(|r| r.as_i64());
+            let annotations = rev.annotations();
+            let scale_annotation = |key: &str| {
+                annotations
+                    .get(&format!("autoscaling.knative.dev/{}", key))
+                    .and_then(|v| v.parse::<i64>().ok())
+            };
+
+            KnativeRevisionInfo {
+                name: rev.name_any(),
+                namespace: ns.clone(),
+                service: rev.labels().get("serving.knative.dev/service").cloned(),
+                ready,
+                reason,
+                image: str_at(&rev.data, "/spec/containers/0/image"),
+                actual_replicas,
+                desired_replicas: rev.data.pointer("/status/desiredReplicas").and_then(|r| r.as_i64()),
+                scaled_to_zero: actual_replicas == Some(0),
+                min_scale: scale_annotation("min-scale").or_else(|| scale_annotation("minScale")),
+                max_scale: scale_annotation("max-scale").or_else(|| scale_annotation("maxScale")),
+                traffic_percent: traffic.get(&(ns, rev.name_any())).copied().unwrap_or(0),
+                created_at: rev.metadata.creation_timestamp.as_ref().map(|ts| ts.0.to_rfc3339()),
+            }
+        })
+        .collect();
+
+    result.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(result)
+}
+
+/// Replace a Knative Service's traffic block
+///
+/// Each target names a revision or sets `latest_revision`; percentages must
+/// add up to 100. Tag-only targets with 0% are allowed for preview URLs.
+pub async fn set_knative_traffic(
+    client: Client,
+    namespace: &str,
+    service: &str,
+    targets: Vec<KnativeTrafficTarget>,
+) -> Result<()> {
+    if targets.is_empty() {
+        return Err(anyhow::anyhow!("At least one traffic target is required"));
+    }
+
+    let total: i64 = targets.iter().map(|t| t.percent.unwrap_or(0)).sum();
+    if total != 100 {
+        return Err(anyhow::anyhow!("Traffic percentages must add up to 100, got {}", total));
+    }
+
+    let mut traffic = Vec::new();
+    let mut seen = Vec::new();
+    for target in &targets {
+        let percent = target.percent.unwrap_or(0);
+        if !(0..=100).contains(&percent) {
+            return Err(anyhow::anyhow!("Invalid traffic percentage: {}", percent));
+        }
+
+        // A revision may appear again only under another tag; a plain repeat would count twice
+        let key = (target.revision_name.clone(), target.tag.clone().filter(|t| !t.is_empty()));
+        if seen.contains(&key) {
+            return Err(anyhow::anyhow!(
+                "Duplicate traffic target for {}",
+                target.revision_name.as_deref().unwrap_or("the latest revision")
+            ));
+        }
+        seen.push(key);
+
+        let mut entry = serde_json::json!({ "percent": percent });
+        match (&target.revision_name, target.latest_revision) {
+            (Some(revision), _) => {
+                entry["revisionName"] = serde_json::json!(revision);
+                entry["latestRevision"] = serde_json::json!(false);
+            }
+            (None, Some(true)) => entry["latestRevision"] = serde_json::json!(true),
+            _ => return Err(anyhow::anyhow!("Each target needs a revision name or latest_revision")),
+        }
+        if let Some(tag) = target.tag.as_ref().filter(|t| !t.is_empty()) {
+            entry["tag"] = serde_json::json!(tag);
+        }
+        traffic.push(entry);
+    }
+
+    // A merge patch replaces the traffic list wholesale
+    let patch = serde_json::json!({ "spec": { "traffic": traffic } });
+    serving_api(client, namespace, "Service", "services")
+        .patch(service, &PatchParams::default(), &Patch::Merge(&patch))
+        .await?;
+
+    Ok(())
+}
+
+fn traffic_targets(traffic: Option<&Value>) -> Vec<KnativeTrafficTarget> {
+    traffic
+        .and_then(|t| t.as_array())
+        .map(|targets| {
+            targets
+                .iter()
+                .map(|t| KnativeTrafficTarget {
+                    revision_name: str_at(t, "/revisionName"),
+                    latest_revision: t.get("latestRevision").and_then(|l| l.as_bool()),
+                    percent: t.get("percent").and_then(|p| p.as_i64()),
+                    tag: str_at(t, "/tag"),
+                    url: str_at(t, "/url"),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn ready_condition(data: &Value) -> (bool, Option<String>) {
+    let condition = data
+        .pointer("/status/conditions")
+        .and_then(|c| c.as_array())
+        .and_then(|conditions| {
+            conditions
+                .iter()
+                .find(|c| c.get("type").and_then(|t| t.as_str()) == Some("Ready"))
+        });
+
+    match condition {
+        Some(c) => (
+            c.get("status").and_then(|s| s.as_str()) == Some("True"),
+            str_at(c, "/reason"),
+        ),
+        None => (false, None),
+    }
+}
+
+fn str_at(value: &Value, pointer: &str) -> Option<String> {
+    value.pointer(pointer).and_then(|v| v.as_str()).map(|s| s.to_string())
+}
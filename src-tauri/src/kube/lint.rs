@@ -0,0 +1,309 @@
+use anyhow::{Context, Result};
+use kube::Client;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::types::{ManifestLintError, ManifestLintResult};
+
+/// Parsed /openapi/v2 documents, keyed by the server's git version
+static SCHEMA_CACHE: Mutex<Option<(String, Arc<SchemaIndex>)>> = Mutex::new(None);
+
+/// Stop reporting after this many errors in one document
+const MAX_ERRORS_PER_DOCUMENT: usize = 50;
+
+struct SchemaIndex {
+    definitions: HashMap<String, Value>,
+    by_gvk: HashMap<(String, String, String), String>, // (group, version, kind) -> definition name
+}
+
+impl SchemaIndex {
+    fn new(mut document: Value) -> Self {
+        let definitions: HashMap<String, Value> = document
+            .get_mut("definitions")
+            .map(|d| d.take())
+            .and_then(|d| serde_json::from_value(d).ok())
+            .unwrap_or_default();
+
+        let mut by_gvk = HashMap::new();
+        for (name, schema) in &definitions {
+            for gvk in schema
+                .get("x-kubernetes-group-version-kind")
+                .and_then(|g| g.as_array())
+                .into_iter()
+                .flatten()
+            {
+                let field = |key: &str| gvk.get(key).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+                by_gvk.insert((field("group"), field("version"), field("kind")), name.clone());
+            }
+        }
+
+        Self { definitions, by_gvk }
+    }
+
+    fn resolve<'a>(&'a self, schema: &'a Value) -> &'a Value {
+        match schema.get("$ref").and_then(|r| r.as_str()) {
+            Some(reference) => self
+                .definitions
+                .get(reference.trim_start_matches("#/definitions/"))
+                .unwrap_or(schema),
+            None => schema,
+        }
+    }
+}
+
+/// Validate every document in a manifest against the cluster's OpenAPI schemas
+///
+/// Catches unknown fields, wrong types and missing required fields with the
+/// path to the offending field, instead of the API server's single-line
+/// decode error. Schemas come from the connected cluster, so CRDs that
+/// publish a structural schema are checked too; `k8s_version`, when given,
+/// must match the cluster's major.minor.
+pub async fn lint_manifest(
+    client: Client,
+    yaml_content: &str,
+    k8s_version: Option<&str>,
+) -> Result<Vec<ManifestLintResult>> {
+    let index = load_schemas(&client, k8s_version).await?;
+
+    let mut results = Vec::new();
+    for (position, document) in serde_yaml::Deserializer::from_str(yaml_content).enumerate() {
+        let value = match Value::deserialize(document) {
+            Ok(Value::Null) => continue,
+            Ok(value) => value,
+            Err(e) => {
+                results.push(ManifestLintResult {
+                    document: position,
+                    api_version: String::new(),
+                    kind: String::new(),
+                    name: None,
+                    valid: false,
+                    errors: vec![lint_error("", format!("Invalid YAML: {}", e))],
+                });
+                continue;
+            }
+        };
+
+        results.push(lint_document(&index, position, &value));
+    }
+
+    Ok(results)
+}
+
+async fn load_schemas(client: &Client, k8s_version: Option<&str>) -> Result<Arc<SchemaIndex>> {
+    let server = client.apiserver_version().await?;
+    if let Some(wanted) = k8s_version {
+        let wanted = wanted.trim_start_matches('v');
+        let server_minor = format!("{}.{}", server.major, server.minor.trim_end_matches('+'));
+        if !wanted.starts_with(&server_minor) {
+            return Err(anyhow::anyhow!(
+                "Connected cluster runs v{}; schemas for v{} are only available from a cluster running it",
+                server_minor,
+                wanted
+            ));
+        }
+    }
+
+    let cached = SCHEMA_CACHE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .filter(|(version, _)| *version == server.git_version)
+        .map(|(_, index)| index.clone());
+    if let Some(index) = cached {
+        return Ok(index);
+    }
+
+    let request = k8s_openapi::http::Request::get("/openapi/v2").body(Vec::new())?;
+    let body = client.request_text(request).await.context("Failed to fetch /openapi/v2")?;
+    let index = Arc::new(SchemaIndex::new(serde_json::from_str(&body)?));
+
+    *SCHEMA_CACHE.lock().unwrap_or_else(|e| e.into_inner()) = Some((server.git_version, index.clone()));
+    Ok(index)
+}
+
+fn lint_error(path: &str, message: impl Into<String>) -> ManifestLintError {
+    ManifestLintError {
+        path: if path.is_empty() { ".".to_string() } else { path.to_string() },
+        message: message.into(),
+    }
+}
+
+fn lint_document(index: &SchemaIndex, position: usize, value: &Value) -> ManifestLintResult {
+    let api_version = value.get("apiVersion").and_then(|v| v.as_str()).unwrap_or_default();
+    let kind = value.get("kind").and_then(|v| v.as_str()).unwrap_or_default();
+    let (group, version) = match api_version.split_once('/') {
+        Some((group, version)) => (group, version),
+        None => ("", api_version),
+    };
+
+    let mut errors = Vec::new();
+    if api_version.is_empty() || kind.is_empty() {
+        errors.push(lint_error("", "Document is missing apiVersion or kind"));
+    } else {
+        let key = (group.to_string(), version.to_string(), kind.to_string());
+        match index.by_gvk.get(&key).and_then(|name| index.definitions.get(name)) {
+            Some(schema) => validate(index, schema, value, "", &mut errors),
+            None => errors.push(lint_error(
+                "",
+                format!("No schema for {} {} in this cluster (unknown kind or CRD not installed)", api_version, kind),
+            )),
+        }
+    }
+    errors.truncate(MAX_ERRORS_PER_DOCUMENT);
+
+    ManifestLintResult {
+        document: position,
+        api_version: api_version.to_string(),
+        kind: kind.to_string(),
+        name: value.pointer("/metadata/name").and_then(|n| n.as_str()).map(|n| n.to_string()),
+        valid: errors.is_empty(),
+        errors,
+    }
+}
+
+fn validate(index: &SchemaIndex, schema: &Value, value: &Value, path: &str, errors: &mut Vec<ManifestLintError>) {
+    if errors.len() >= MAX_ERRORS_PER_DOCUMENT {
+        return;
+    }
+
+    // Quantity and IntOrString are strings in the schema but accept plain numbers
+    let lenient_number = schema
+        .get("$ref")
+        .and_then(|r| r.as_str())
+        .map(|r| r.ends_with("api.resource.Quantity") || r.ends_with("util.intstr.IntOrString"))
+        .unwrap_or(false);
+    let schema = index.resolve(schema);
+
+    // Explicit nulls are dropped by the API server, same as omitting the field
+    if value.is_null() || schema.get("x-kubernetes-preserve-unknown-fields").and_then(|v| v.as_bool()) == Some(true) {
+        return;
+    }
+    let int_or_string = lenient_number || schema.get("format").and_then(|f| f.as_str()) == Some("int-or-string");
+
+    let expected = schema.get("type").and_then(|t| t.as_str());
+    let type_ok = match expected {
+        None => true,
+        Some("object") => value.is_object(),
+        Some("array") => value.is_array(),
+        Some("string") => value.is_string() || (int_or_string && value.is_number()),
+        Some("integer") => value.is_i64() || value.is_u64() || (int_or_string && value.is_string()),
+        Some("number") => value.is_number(),
+        Some("boolean") => value.is_boolean(),
+        Some(_) => true,
+    };
+    if !type_ok {
+        errors.push(lint_error(path, format!("Expected {}, got {}", expected.unwrap_or_default(), type_name(value))));
+        return;
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(|e| e.as_array()) {
+        if !allowed.contains(value) {
+            let allowed: Vec<String> = allowed.iter().map(|v| v.to_string()).collect();
+            errors.push(lint_error(path, format!("Must be one of {}", allowed.join(", "))));
+        }
+    }
+
+    match value {
+        Value::Object(map) => {
+            let properties = schema.get("properties").and_then(|p| p.as_object());
+            let additional = schema.get("additionalProperties");
+
+            for name in schema.get("required").and_then(|r| r.as_array()).into_iter().flatten() {
+                if let Some(name) = name.as_str() {
+                    if map.get(name).map(|v| v.is_null()).unwrap_or(true) {
+                        errors.push(lint_error(&format!("{}.{}", path, name), "Required field is missing"));
+                    }
+                }
+            }
+
+            for (name, child) in map {
+                let child_path = format!("{}.{}", path, name);
+                match (properties.and_then(|p| p.get(name)), additional) {
+                    (Some(child_schema), _) => validate(index, child_schema, child, &child_path, errors),
+                    (None, Some(Value::Bool(false))) => {
+                        errors.push(lint_error(&child_path, "Unknown field"));
+                    }
+                    (None, Some(additional)) if additional.is_object() => {
+                        validate(index, additional, child, &child_path, errors)
+                    }
+                    (None, Some(_)) => {}
+                    (None, None) if properties.is_some() => errors.push(lint_error(&child_path, "Unknown field")),
+                    (None, None) => {}
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    validate(index, item_schema, item, &format!("{}[{}]", path, i), errors);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lint_document() {
+        let index = SchemaIndex::new(serde_json::json!({
+            "definitions": {
+                "io.k8s.api.core.v1.ConfigMap": {
+                    "type": "object",
+                    "properties": {
+                        "apiVersion": { "type": "string" },
+                        "kind": { "type": "string" },
+                        "metadata": { "$ref": "#/definitions/io.k8s.apimachinery.pkg.apis.meta.v1.ObjectMeta" },
+                        "data": { "type": "object", "additionalProperties": { "type": "string" } },
+                        "immutable": { "type": "boolean" }
+                    },
+                    "x-kubernetes-group-version-kind": [{ "group": "", "version": "v1", "kind": "ConfigMap" }]
+                },
+                "io.k8s.apimachinery.pkg.apis.meta.v1.ObjectMeta": {
+                    "type": "object",
+                    "required": ["name"],
+                    "properties": { "name": { "type": "string" }, "namespace": { "type": "string" } }
+                }
+            }
+        }));
+
+        let valid = serde_json::json!({
+            "apiVersion": "v1", "kind": "ConfigMap",
+            "metadata": { "name": "app" }, "data": { "key": "value" }
+        });
+        assert!(lint_document(&index, 0, &valid).valid);
+
+        let invalid = serde_json::json!({
+            "apiVersion": "v1", "kind": "ConfigMap",
+            "metadata": { "namespce": "default" },
+            "data": { "port": 8080 },
+            "immutable": "yes"
+        });
+        let result = lint_document(&index, 0, &invalid);
+        let paths: Vec<&str> = result.errors.iter().map(|e| e.path.as_str()).collect();
+        assert!(paths.contains(&".metadata.name"));
+        assert!(paths.contains(&".metadata.namespce"));
+        assert!(paths.contains(&".data.port"));
+        assert!(paths.contains(&".immutable"));
+
+        let unknown = serde_json::json!({ "apiVersion": "example.com/v1", "kind": "Widget" });
+        assert!(!lint_document(&index, 0, &unknown).valid);
+    }
+}
@@ -0,0 +1,216 @@
+use anyhow::Result;
+use chrono::Utc;
+use kube::api::{Api, DynamicObject, ListParams, ObjectList};
+use kube::discovery::{ApiResource, Scope};
+use kube::Client;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Mutex;
+
+use crate::kube::columns::resolve_kind;
+use crate::types::ResourceListSnapshot;
+
+/// Lists kept; the least recently read is dropped beyond this
+const MAX_ENTRIES: usize = 64;
+
+/// (context, apiVersion/kind, namespace)
+type ListKey = (String, String, String);
+
+struct CachedList {
+    snapshot: ResourceListSnapshot,
+    last_read: Instant,
+}
+
+/// Last list of each kind and namespace, so reopening a view renders at once
+///
+/// A hit is returned immediately and then revalidated: the list is re-read
+/// with `resourceVersion=0`, which the API server answers from its watch cache
+/// without going to etcd, and a `resource-list-updated` event carries the new
+/// snapshot if its resourceVersion moved.
+pub struct ListCache {
+    entries: Arc<Mutex<HashMap<ListKey, CachedList>>>,
+    /// Discovery is slow, so what each name a view asked for resolved to is kept per context;
+    /// the YAML view resolves through here too
+    kinds: Arc<Mutex<HashMap<(String, String), (ApiResource, Scope)>>>,
+}
+
+impl ListCache {
+    pub fn new() -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(HashMap::new())),
+            kinds: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// What `resource_type` names in `context`, from discovery the first time it's asked
+    pub async fn resolve_kind(&self, client: &Client, context: &str, resource_type: &str) -> Result<(ApiResource, Scope)> {
+        let alias = (context.to_string(), resource_type.to_ascii_lowercase());
+        if let Some(resolved) = self.kinds.lock().await.get(&alias) {
+            return Ok(resolved.clone());
+        }
+        let resolved = resolve_kind(client.clone(), resource_type).await?;
+        self.kinds.lock().await.insert(alias, resolved.clone());
+        Ok(resolved)
+    }
+
+    /// Objects of `resource_type` in `namespace` (everywhere when empty or cluster-scoped)
+    pub async fn list(
+        &self,
+        app: AppHandle,
+        client: Client,
+        context: &str,
+        resource_type: &str,
+        namespace: &str,
+    ) -> Result<ResourceListSnapshot> {
+        let (resource, scope) = self.resolve_kind(&client, context, resource_type).await?;
+        let namespace = if scope == Scope::Namespaced { namespace } else { "" };
+        let key = (
+            context.to_string(),
+            format!("{}/{}", resource.api_version, resource.kind),
+            namespace.to_string(),
+        );
+
+        let cached = {
+            let mut entries = self.entries.lock().await;
+            entries.get_mut(&key).map(|entry| {
+                entry.last_read = Instant::now();
+                entry.snapshot.clone()
+            })
+        };
+
+        let api: Api<DynamicObject> = if namespace.is_empty() {
+            Api::all_with(client, &resource)
+        } else {
+            Api::namespaced_with(client, namespace, &resource)
+        };
+
+        let Some(mut cached) = cached else {
+            // Nothing to show yet, so this read must be current rather than from the watch cache
+            let list = api.list(&ListParams::default()).await?;
+            let snapshot = snapshot(&key, &resource, list);
+            self.store(key, snapshot.clone()).await;
+            return Ok(snapshot);
+        };
+
+        let entries = self.entries.clone();
+        let resource_version = cached.resource_version.clone();
+        tokio::spawn(async move {
+            let list = match api.list(&ListParams::default().match_any()).await {
+                Ok(list) => list,
+                Err(e) => {
+                    tracing::debug!("Revalidating {} failed: {}", resource.kind, e);
+                    return;
+                }
+            };
+            let fresh = snapshot(&key, &resource, list);
+            if !is_newer(&fresh.resource_version, &resource_version) {
+                return;
+            }
+            if let Some(entry) = entries.lock().await.get_mut(&key) {
+                entry.snapshot = fresh.clone();
+            }
+            let _ = app.emit("resource-list-updated", &fresh);
+        });
+
+        cached.from_cache = true;
+        Ok(cached)
+    }
+
+    async fn store(&self, key: ListKey, snapshot: ResourceListSnapshot) {
+        let mut entries = self.entries.lock().await;
+        if entries.len() >= MAX_ENTRIES && !entries.contains_key(&key) {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_read)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(
+            key,
+            CachedList {
+                snapshot,
+                last_read: Instant::now(),
+            },
+        );
+    }
+
+    /// Forget a context's lists and resolved kinds, or everything when `None`
+    pub async fn evict(&self, context: Option<&str>) {
+        let mut entries = self.entries.lock().await;
+        let mut kinds = self.kinds.lock().await;
+        match context {
+            Some(context) => {
+                entries.retain(|(ctx, _, _), _| ctx != context);
+                kinds.retain(|(ctx, _), _| ctx != context);
+            }
+            None => {
+                entries.clear();
+                kinds.clear();
+            }
+        }
+    }
+}
+
+impl Default for ListCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether `fresh` supersedes `cached`
+///
+/// resourceVersions are opaque, but every API server in use hands out
+/// increasing integers; comparing them keeps a lagging watch cache from
+/// replacing a newer list. Anything else only counts when it differs.
+fn is_newer(fresh: &str, cached: &str) -> bool {
+    match (fresh.parse::<u64>(), cached.parse::<u64>()) {
+        (Ok(fresh), Ok(cached)) => fresh > cached,
+        _ => fresh != cached,
+    }
+}
+
+fn snapshot(key: &ListKey, resource: &ApiResource, list: ObjectList<DynamicObject>) -> ResourceListSnapshot {
+    let items = list
+        .items
+        .into_iter()
+        .filter_map(|item| serde_json::to_value(item).ok())
+        .map(|mut value| {
+            // DynamicObject lists don't repeat apiVersion/kind per item
+            value["apiVersion"] = Value::String(resource.api_version.clone());
+            value["kind"] = Value::String(resource.kind.clone());
+            if let Some(metadata) = value.get_mut("metadata").and_then(|m| m.as_object_mut()) {
+                metadata.remove("managedFields");
+            }
+            value
+        })
+        .collect();
+
+    ResourceListSnapshot {
+        context: key.0.clone(),
+        api_version: resource.api_version.clone(),
+        kind: resource.kind.clone(),
+        namespace: Some(key.2.clone()).filter(|ns| !ns.is_empty()),
+        resource_version: list.metadata.resource_version.unwrap_or_default(),
+        items,
+        fetched_at: Utc::now().to_rfc3339(),
+        from_cache: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("1200", "1100"));
+        assert!(!is_newer("1100", "1200"));
+        assert!(!is_newer("1200", "1200"));
+        assert!(is_newer("b", "a"));
+    }
+}
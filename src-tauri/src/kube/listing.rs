@@ -0,0 +1,198 @@
+use anyhow::Result;
+use chrono::DateTime;
+use std::cmp::Ordering;
+
+use crate::types::{DeploymentInfo, ListQuery, NodeInfo, PodInfo, StatefulSetInfo};
+
+/// Comparable value of a sort column
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum SortValue {
+    Int(i64),
+    Text(String),
+}
+
+/// A list row that can be filtered and sorted server-side
+trait ListItem {
+    fn name(&self) -> &str;
+    fn namespace(&self) -> &str {
+        ""
+    }
+    fn created_at(&self) -> Option<&str>;
+    fn status(&self) -> String;
+    /// Whether the row needs attention (not ready, failing, cordoned, ...)
+    fn is_problem(&self) -> bool;
+    /// Type-specific sort columns beyond name, namespace, age and status
+    fn sort_value(&self, _key: &str) -> Option<SortValue> {
+        None
+    }
+}
+
+impl ListItem for PodInfo {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn namespace(&self) -> &str {
+        &self.namespace
+    }
+    fn created_at(&self) -> Option<&str> {
+        self.created_at.as_deref()
+    }
+    fn status(&self) -> String {
+        self.status.clone()
+    }
+    fn is_problem(&self) -> bool {
+        match self.status.as_str() {
+            "Succeeded" | "Completed" => false,
+            "Running" => !ready_complete(&self.ready),
+            _ => true,
+        }
+    }
+    fn sort_value(&self, key: &str) -> Option<SortValue> {
+        match key {
+            "restarts" => Some(SortValue::Int(self.restarts as i64)),
+            "node" => Some(SortValue::Text(self.node.clone().unwrap_or_default())),
+            _ => None,
+        }
+    }
+}
+
+impl ListItem for DeploymentInfo {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn namespace(&self) -> &str {
+        &self.namespace
+    }
+    fn created_at(&self) -> Option<&str> {
+        self.created_at.as_deref()
+    }
+    fn status(&self) -> String {
+        let status = if ready_complete(&self.ready) { "Ready" } else { "NotReady" };
+        status.to_string()
+    }
+    fn is_problem(&self) -> bool {
+        !ready_complete(&self.ready)
+    }
+}
+
+impl ListItem for StatefulSetInfo {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn namespace(&self) -> &str {
+        &self.namespace
+    }
+    fn created_at(&self) -> Option<&str> {
+        self.created_at.as_deref()
+    }
+    fn status(&self) -> String {
+        let status = if ready_complete(&self.ready) { "Ready" } else { "NotReady" };
+        status.to_string()
+    }
+    fn is_problem(&self) -> bool {
+        !ready_complete(&self.ready)
+    }
+    fn sort_value(&self, key: &str) -> Option<SortValue> {
+        (key == "replicas").then(|| SortValue::Int(self.replicas as i64))
+    }
+}
+
+impl ListItem for NodeInfo {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn created_at(&self) -> Option<&str> {
+        self.created_at.as_deref()
+    }
+    fn status(&self) -> String {
+        self.status.clone()
+    }
+    fn is_problem(&self) -> bool {
+        self.status != "Ready"
+    }
+    fn sort_value(&self, key: &str) -> Option<SortValue> {
+        match key {
+            "version" => Some(SortValue::Text(self.version.clone())),
+            "pool" => Some(SortValue::Text(self.node_pool.clone().unwrap_or_default())),
+            "zone" => Some(SortValue::Text(self.zone.clone().unwrap_or_default())),
+            _ => None,
+        }
+    }
+}
+
+pub fn query_pods(items: Vec<PodInfo>, query: &ListQuery) -> Result<Vec<PodInfo>> {
+    apply_query(items, query, &["restarts", "node"])
+}
+
+pub fn query_deployments(items: Vec<DeploymentInfo>, query: &ListQuery) -> Result<Vec<DeploymentInfo>> {
+    apply_query(items, query, &[])
+}
+
+pub fn query_statefulsets(items: Vec<StatefulSetInfo>, query: &ListQuery) -> Result<Vec<StatefulSetInfo>> {
+    apply_query(items, query, &["replicas"])
+}
+
+pub fn query_nodes(items: Vec<NodeInfo>, query: &ListQuery) -> Result<Vec<NodeInfo>> {
+    apply_query(items, query, &["version", "pool", "zone"])
+}
+
+/// Filter by status/problem state, then sort
+///
+/// Sorting by `age` puts the newest objects first in ascending order, the
+/// same direction as the age column reads.
+fn apply_query<T: ListItem>(items: Vec<T>, query: &ListQuery, extra_keys: &[&str]) -> Result<Vec<T>> {
+    let statuses: Vec<String> = query
+        .status
+        .iter()
+        .flatten()
+        .map(|s| s.to_lowercase())
+        .collect();
+    let problems_only = query.problems_only.unwrap_or(false);
+
+    let mut items: Vec<T> = items
+        .into_iter()
+        .filter(|item| !problems_only || item.is_problem())
+        .filter(|item| statuses.is_empty() || statuses.contains(&item.status().to_lowercase()))
+        .collect();
+
+    let Some(key) = query.sort_by.as_deref() else {
+        return Ok(items);
+    };
+    if !matches!(key, "name" | "namespace" | "age" | "status") && !extra_keys.contains(&key) {
+        return Err(anyhow::anyhow!("Cannot sort by '{}'", key));
+    }
+
+    let descending = query.descending.unwrap_or(false);
+    items.sort_by(|a, b| {
+        let ordering = compare(a, b, key);
+        // Ties fall back to namespace/name so the order is stable across refreshes
+        let ordering = if descending { ordering.reverse() } else { ordering };
+        ordering.then_with(|| (a.namespace(), a.name()).cmp(&(b.namespace(), b.name())))
+    });
+
+    Ok(items)
+}
+
+fn compare<T: ListItem>(a: &T, b: &T, key: &str) -> Ordering {
+    match key {
+        "name" => a.name().cmp(b.name()),
+        "namespace" => a.namespace().cmp(b.namespace()),
+        "status" => a.status().cmp(&b.status()),
+        "age" => created_seconds(b).cmp(&created_seconds(a)),
+        _ => a.sort_value(key).cmp(&b.sort_value(key)),
+    }
+}
+
+fn created_seconds<T: ListItem>(item: &T) -> Option<i64> {
+    item.created_at()
+        .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+        .map(|ts| ts.timestamp())
+}
+
+/// "2/3" style ready counts are complete when both sides match
+fn ready_complete(ready: &str) -> bool {
+    match ready.split_once('/') {
+        Some((ready, total)) => ready.trim() == total.trim(),
+        None => false,
+    }
+}
@@ -0,0 +1,193 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::core::v1::Pod;
+use kube::api::{Api, LogParams};
+use kube::Client;
+use serde_json::Value;
+
+use crate::grafana::percent_encode;
+use crate::kube::events::parse_since;
+use crate::kube::monitoring::service_proxy_get;
+use crate::preferences::{ClusterIntegrations, ServiceEndpoint};
+use crate::types::{LogEntry, LogSearch};
+
+const LOKI_DEFAULT_PORT: u16 = 3100;
+const DEFAULT_SEARCH_LIMIT: u32 = 1000;
+
+/// Where log searches are answered from
+pub enum LogProvider {
+    /// The kubelet, via the pod log API; only covers what the node still has on disk
+    Kubelet,
+    /// Grafana Loki, for history beyond the kubelet's retention
+    Loki(ServiceEndpoint),
+}
+
+impl LogProvider {
+    /// The provider selected for a cluster; `name` overrides the configured choice
+    pub fn for_cluster(integrations: &ClusterIntegrations, name: Option<&str>) -> Result<Self> {
+        match name.or(integrations.log_provider.as_deref()).unwrap_or("kubelet") {
+            "kubelet" => Ok(LogProvider::Kubelet),
+            "loki" => integrations
+                .loki
+                .clone()
+                .map(LogProvider::Loki)
+                .ok_or_else(|| anyhow::anyhow!("Loki is not configured for this cluster")),
+            other => Err(anyhow::anyhow!("Unknown log provider: {}", other)),
+        }
+    }
+
+    /// Matching log lines, oldest first
+    pub async fn search(&self, client: Client, search: &LogSearch) -> Result<Vec<LogEntry>> {
+        match self {
+            LogProvider::Kubelet => search_kubelet(client, search).await,
+            LogProvider::Loki(endpoint) => search_loki(client, endpoint, search).await,
+        }
+    }
+}
+
+async fn search_kubelet(client: Client, search: &LogSearch) -> Result<Vec<LogEntry>> {
+    let pod_name = search
+        .pod_name
+        .as_deref()
+        .ok_or_else(|| anyhow::anyhow!("Searching kubelet logs requires a pod"))?;
+    let pods: Api<Pod> = Api::namespaced(client, &search.namespace);
+
+    let since = search.since.as_deref().map(parse_since).transpose()?;
+    let until = search.until.as_deref().map(parse_since).transpose()?;
+    let params = LogParams {
+        container: search.container.clone(),
+        timestamps: true,
+        since_time: since,
+        ..LogParams::default()
+    };
+    let logs = pods.logs(pod_name, &params).await?;
+
+    let query = search.query.as_deref().unwrap_or_default();
+    let mut entries: Vec<LogEntry> = logs
+        .lines()
+        .filter(|line| line.contains(query))
+        .filter_map(|line| {
+            let (timestamp, message) = line.split_once(' ').unwrap_or(("", line));
+            let time = DateTime::parse_from_rfc3339(timestamp).ok().map(|t| t.with_timezone(&Utc));
+            if let (Some(until), Some(time)) = (until, time) {
+                if time > until {
+                    return None;
+                }
+            }
+            Some(LogEntry {
+                timestamp: time.map(|t| t.to_rfc3339()),
+                message: message.to_string(),
+                pod_name: pod_name.to_string(),
+            })
+        })
+        .collect();
+
+    // Keep the most recent lines when over the limit, like Loki's backward direction
+    let limit = search.limit.unwrap_or(DEFAULT_SEARCH_LIMIT) as usize;
+    if entries.len() > limit {
+        entries.drain(..entries.len() - limit);
+    }
+    Ok(entries)
+}
+
+/// Build a LogQL query from the search, or use `query` as-is when it is already LogQL
+fn logql(search: &LogSearch) -> String {
+    if let Some(query) = search.query.as_deref().filter(|q| q.trim_start().starts_with('{')) {
+        return query.to_string();
+    }
+
+    let mut selectors = vec![format!("namespace=\"{}\"", search.namespace)];
+    if let Some(pod) = &search.pod_name {
+        selectors.push(format!("pod=\"{}\"", pod));
+    }
+    if let Some(container) = &search.container {
+        selectors.push(format!("container=\"{}\"", container));
+    }
+
+    let mut query = format!("{{{}}}", selectors.join(", "));
+    if let Some(text) = search.query.as_deref().filter(|q| !q.is_empty()) {
+        query.push_str(&format!(" |= \"{}\"", text.replace('\\', "\\\\").replace('"', "\\\"")));
+    }
+    query
+}
+
+async fn search_loki(client: Client, endpoint: &ServiceEndpoint, search: &LogSearch) -> Result<Vec<LogEntry>> {
+    let end = search.until.as_deref().map(parse_since).transpose()?.unwrap_or_else(Utc::now);
+    let start = parse_since(search.since.as_deref().unwrap_or("1h"))?;
+    let nanos = |t: DateTime<Utc>| t.timestamp_nanos_opt().unwrap_or_default();
+
+    let path = format!(
+        "/loki/api/v1/query_range?query={}&start={}&end={}&limit={}&direction=backward",
+        percent_encode(&logql(search)),
+        nanos(start),
+        nanos(end),
+        search.limit.unwrap_or(DEFAULT_SEARCH_LIMIT)
+    );
+    let body = service_proxy_get(&client, endpoint, LOKI_DEFAULT_PORT, &path).await?;
+
+    let response: Value = serde_json::from_str(&body)?;
+    if response.get("status").and_then(|s| s.as_str()) != Some("success") {
+        return Err(anyhow::anyhow!("Loki query failed: {}", body.chars().take(200).collect::<String>()));
+    }
+
+    let mut entries: Vec<(i64, LogEntry)> = Vec::new();
+    for stream in response
+        .pointer("/data/result")
+        .and_then(|r| r.as_array())
+        .into_iter()
+        .flatten()
+    {
+        let pod_name = stream
+            .pointer("/stream/pod")
+            .and_then(|p| p.as_str())
+            .unwrap_or_default()
+            .to_string();
+
+        for value in stream.get("values").and_then(|v| v.as_array()).into_iter().flatten() {
+            let Some(ts) = value.get(0).and_then(|t| t.as_str()).and_then(|t| t.parse::<i64>().ok()) else {
+                continue;
+            };
+            let line = value.get(1).and_then(|l| l.as_str()).unwrap_or_default();
+
+            entries.push((
+                ts,
+                LogEntry {
+                    timestamp: Some(DateTime::<Utc>::from_timestamp_nanos(ts).to_rfc3339()),
+                    message: line.to_string(),
+                    pod_name: pod_name.clone(),
+                },
+            ));
+        }
+    }
+
+    entries.sort_by_key(|(ts, _)| *ts);
+    Ok(entries.into_iter().map(|(_, entry)| entry).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search(query: Option<&str>) -> LogSearch {
+        LogSearch {
+            namespace: "shop".to_string(),
+            pod_name: Some("web-0".to_string()),
+            container: None,
+            query: query.map(|q| q.to_string()),
+            since: None,
+            until: None,
+            limit: None,
+            provider: None,
+        }
+    }
+
+    #[test]
+    fn test_logql_from_search() {
+        assert_eq!(logql(&search(None)), r#"{namespace="shop", pod="web-0"}"#);
+        assert_eq!(
+            logql(&search(Some(r#"say "hi""#))),
+            r#"{namespace="shop", pod="web-0"} |= "say \"hi\"""#
+        );
+        assert_eq!(logql(&search(Some(r#"{app="web"} |= "x""#))), r#"{app="web"} |= "x""#);
+    }
+}
@@ -1,7 +1,93 @@
+pub mod access;
+pub mod addons;
+pub mod admission;
+pub mod apiproxy;
+pub mod apiserver;
+pub mod autoscaling;
+pub mod cache;
+pub mod canary;
 pub mod client;
+pub mod clone;
+pub mod columns;
 pub mod config;
+pub mod credentials;
+pub mod deprecations;
+pub mod diagnostics;
+pub mod dns;
+pub mod drain;
+pub mod envoy;
+pub mod events;
+pub mod health;
+pub mod image_restart;
+pub mod images;
+pub mod immutable;
+pub mod knative;
+pub mod lint;
+pub mod list_cache;
+pub mod listing;
+pub mod logs;
+pub mod monitoring;
+pub mod node_alerts;
 pub mod operations;
+pub mod profiling;
+pub mod query;
+pub mod recreate;
+pub mod references;
+pub mod registry;
+pub mod retry;
+pub mod rollout;
+pub mod scoped;
+pub mod spread;
+pub mod storage;
+pub mod tail;
+pub mod tekton;
+pub mod termination;
+pub mod throttle;
+pub mod watch;
+pub mod wizards;
 
+pub use access::get_allowed_actions;
+pub use addons::get_addon_versions;
+pub use admission::detect_admission_problems;
+pub use apiproxy::{proxy_pod_request, proxy_service_request};
+pub use apiserver::get_apiserver_metrics;
+pub use autoscaling::get_autoscaler_insight;
+pub use cache::ResourceCache;
+pub use canary::{canary_deploy, promote_canary, rollback_canary};
 pub use client::KubeClientManager;
+pub use clone::{clone_namespace, copy_resource};
+pub use columns::render_resource_table;
 pub use config::{get_current_context, load_kubeconfig, load_custom_kubeconfig, switch_context, set_kubeconfig_path};
+pub use credentials::clear_credential_cache;
+pub use deprecations::scan_deprecated_apis;
+pub use diagnostics::capture_runtime_diagnostic;
+pub use dns::debug_dns;
+pub use drain::NodeDrainManager;
+pub use envoy::{get_proxy_config_dump, get_proxy_diagnostics, get_proxy_stats};
+pub use events::{export_events, get_workload_timeline};
+pub use health::{get_all_contexts_health, get_control_plane_health};
+pub use image_restart::{preview_image_restart, ImageRestartManager};
+pub use images::get_image_inventory;
+pub use immutable::check_immutable_fields;
+pub use knative::{list_knative_revisions, list_knative_services, set_knative_traffic};
+pub use list_cache::ListCache;
+pub use lint::lint_manifest;
+pub use listing::{query_deployments, query_nodes, query_pods, query_statefulsets};
+pub use logs::LogProvider;
+pub use monitoring::{list_alerting_rules, list_alertmanager_silences, list_prometheus_alerts};
+pub use node_alerts::{get_node_alerts, NodeAlertMonitor};
 pub use operations::*;
+pub use profiling::{capture_profile, ProfileTarget};
+pub use query::query_resources;
+pub use recreate::recreate_resource;
+pub use references::find_references;
+pub use registry::list_image_tags;
+pub use rollout::RolloutWatchManager;
+pub use scoped::generate_scoped_kubeconfig;
+pub use spread::analyze_spread;
+pub use storage::{get_storage_topology, get_volume_usage, list_csi_drivers, list_csi_nodes, list_volume_attachments};
+pub use tail::FileTailManager;
+pub use tekton::{cancel_tekton_run, list_pipeline_runs, list_task_runs, rerun_tekton_run, TektonLogManager};
+pub use termination::{get_namespace_termination_status, remove_stuck_finalizer, NamespaceTerminationManager};
+pub use watch::{PodWatchManager, ResourceWatchManager};
+pub use wizards::{create_deployment_simple, create_ingress_simple, create_service_for_workload};
@@ -0,0 +1,216 @@
+use anyhow::{Context, Result};
+use kube::Client;
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::kube::apiproxy::{proxy_get, proxy_url};
+use crate::preferences::ServiceEndpoint;
+use crate::types::{AlertSilence, AlertingRule, PrometheusAlert};
+
+const PROMETHEUS_DEFAULT_PORT: u16 = 9090;
+const ALERTMANAGER_DEFAULT_PORT: u16 = 9093;
+
+/// Labels that name the workload an alert is about, in order of preference
+const WORKLOAD_LABELS: &[(&str, &str)] = &[
+    ("deployment", "Deployment"),
+    ("statefulset", "StatefulSet"),
+    ("daemonset", "DaemonSet"),
+    ("job_name", "Job"),
+    ("cronjob", "CronJob"),
+    ("pod", "Pod"),
+];
+
+/// Integration API responses (alert lists, log queries) can be large but not unbounded
+const MAX_INTEGRATION_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+/// GET a path on an in-cluster service through the API server's service proxy
+///
+/// Works wherever the kubeconfig works, without port-forwards or cluster DNS.
+/// Error statuses and cut-off bodies are errors here, since callers parse the body.
+pub(crate) async fn service_proxy_get(
+    client: &Client,
+    endpoint: &ServiceEndpoint,
+    default_port: u16,
+    path: &str,
+) -> Result<String> {
+    let url = proxy_url(
+        &endpoint.namespace,
+        "services",
+        &endpoint.name,
+        &endpoint.port.unwrap_or(default_port).to_string(),
+        endpoint.scheme.as_deref() == Some("https"),
+        path,
+    );
+
+    let response = proxy_get(client, url, MAX_INTEGRATION_BODY_BYTES)
+        .await
+        .with_context(|| format!("Request to {}/{} failed", endpoint.namespace, endpoint.name))?;
+    if !(200..300).contains(&response.status) {
+        return Err(anyhow::anyhow!(
+            "Request to {}/{} failed with {}: {}",
+            endpoint.namespace,
+            endpoint.name,
+            response.status,
+            response.body.trim()
+        ));
+    }
+    if response.truncated {
+        return Err(anyhow::anyhow!(
+            "Response from {}/{} is larger than {} bytes",
+            endpoint.namespace,
+            endpoint.name,
+            MAX_INTEGRATION_BODY_BYTES
+        ));
+    }
+    Ok(response.body)
+}
+
+/// Unwrap Prometheus' `{"status": "success", "data": ...}` envelope
+fn prometheus_data(body: &str) -> Result<Value> {
+    let mut response: Value = serde_json::from_str(body).context("Prometheus returned invalid JSON")?;
+    if response.get("status").and_then(|s| s.as_str()) != Some("success") {
+        return Err(anyhow::anyhow!(
+            "Prometheus query failed: {}",
+            response.get("error").and_then(|e| e.as_str()).unwrap_or("unknown error")
+        ));
+    }
+    Ok(response["data"].take())
+}
+
+fn string_map(value: Option<&Value>) -> HashMap<String, String> {
+    value
+        .and_then(|v| v.as_object())
+        .map(|map| {
+            map.iter()
+                .filter_map(|(k, v)| v.as_str().map(|v| (k.clone(), v.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(|v| v.as_str()).map(|s| s.to_string())
+}
+
+/// The workload an alert's labels point at, as `Kind/name`
+fn alert_workload(labels: &HashMap<String, String>) -> Option<String> {
+    WORKLOAD_LABELS
+        .iter()
+        .find_map(|(label, kind)| labels.get(*label).map(|name| format!("{}/{}", kind, name)))
+}
+
+/// Firing and pending alerts, optionally only those labelled with a namespace
+pub async fn list_prometheus_alerts(
+    client: Client,
+    endpoint: &ServiceEndpoint,
+    namespace: Option<&str>,
+) -> Result<Vec<PrometheusAlert>> {
+    let body = service_proxy_get(&client, endpoint, PROMETHEUS_DEFAULT_PORT, "/api/v1/alerts").await?;
+    let data = prometheus_data(&body)?;
+
+    let mut alerts: Vec<PrometheusAlert> = data
+        .get("alerts")
+        .and_then(|a| a.as_array())
+        .into_iter()
+        .flatten()
+        .map(|alert| {
+            let labels = string_map(alert.get("labels"));
+            PrometheusAlert {
+                name: labels.get("alertname").cloned().unwrap_or_default(),
+                state: str_field(alert, "state").unwrap_or_default(),
+                severity: labels.get("severity").cloned(),
+                namespace: labels.get("namespace").cloned(),
+                workload: alert_workload(&labels),
+                active_at: str_field(alert, "activeAt"),
+                value: str_field(alert, "value"),
+                annotations: string_map(alert.get("annotations")),
+                labels,
+            }
+        })
+        .filter(|alert| namespace.map(|ns| alert.namespace.as_deref() == Some(ns)).unwrap_or(true))
+        .collect();
+
+    // Firing before pending, then by name
+    alerts.sort_by(|a, b| (a.state != "firing", &a.name).cmp(&(b.state != "firing", &b.name)));
+    Ok(alerts)
+}
+
+/// Alerting rules with their health and how many alerts each currently has
+pub async fn list_alerting_rules(client: Client, endpoint: &ServiceEndpoint) -> Result<Vec<AlertingRule>> {
+    let body = service_proxy_get(&client, endpoint, PROMETHEUS_DEFAULT_PORT, "/api/v1/rules?type=alert").await?;
+    let data = prometheus_data(&body)?;
+
+    let mut rules = Vec::new();
+    for group in data.get("groups").and_then(|g| g.as_array()).into_iter().flatten() {
+        let group_name = str_field(group, "name").unwrap_or_default();
+        for rule in group.get("rules").and_then(|r| r.as_array()).into_iter().flatten() {
+            if rule.get("type").and_then(|t| t.as_str()) != Some("alerting") {
+                continue;
+            }
+
+            rules.push(AlertingRule {
+                name: str_field(rule, "name").unwrap_or_default(),
+                group: group_name.clone(),
+                query: str_field(rule, "query").unwrap_or_default(),
+                duration_secs: rule.get("duration").and_then(|d| d.as_f64()).unwrap_or(0.0) as u64,
+                state: str_field(rule, "state").unwrap_or_else(|| "inactive".to_string()),
+                health: str_field(rule, "health").unwrap_or_default(),
+                last_error: str_field(rule, "lastError").filter(|e| !e.is_empty()),
+                active_alerts: rule.get("alerts").and_then(|a| a.as_array()).map(|a| a.len()).unwrap_or(0),
+                labels: string_map(rule.get("labels")),
+                annotations: string_map(rule.get("annotations")),
+            });
+        }
+    }
+
+    Ok(rules)
+}
+
+/// Active and pending Alertmanager silences; expired ones are left out
+pub async fn list_alertmanager_silences(
+    client: Client,
+    endpoint: &ServiceEndpoint,
+) -> Result<Vec<AlertSilence>> {
+    let body = service_proxy_get(&client, endpoint, ALERTMANAGER_DEFAULT_PORT, "/api/v2/silences").await?;
+    let silences: Vec<Value> = serde_json::from_str(&body).context("Alertmanager returned invalid JSON")?;
+
+    Ok(silences
+        .iter()
+        .map(|silence| AlertSilence {
+            id: str_field(silence, "id").unwrap_or_default(),
+            state: silence
+                .pointer("/status/state")
+                .and_then(|s| s.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            matchers: silence
+                .get("matchers")
+                .and_then(|m| m.as_array())
+                .into_iter()
+                .flatten()
+                .map(|m| {
+                    let op = match (
+                        m.get("isEqual").and_then(|v| v.as_bool()).unwrap_or(true),
+                        m.get("isRegex").and_then(|v| v.as_bool()).unwrap_or(false),
+                    ) {
+                        (true, false) => "=",
+                        (false, false) => "!=",
+                        (true, true) => "=~",
+                        (false, true) => "!~",
+                    };
+                    format!(
+                        "{}{}\"{}\"",
+                        str_field(m, "name").unwrap_or_default(),
+                        op,
+                        str_field(m, "value").unwrap_or_default()
+                    )
+                })
+                .collect(),
+            created_by: str_field(silence, "createdBy"),
+            comment: str_field(silence, "comment"),
+            starts_at: str_field(silence, "startsAt"),
+            ends_at: str_field(silence, "endsAt"),
+        })
+        .filter(|silence| silence.state != "expired")
+        .collect())
+}
@@ -0,0 +1,241 @@
+use anyhow::Result;
+use chrono::Utc;
+use k8s_openapi::api::core::v1::{Event, Node, Pod};
+use kube::api::{Api, ListParams};
+use kube::{Client, ResourceExt};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::{oneshot, Mutex};
+use uuid::Uuid;
+
+use crate::kube::events::{event_time, OwnerResolver};
+use crate::types::NodeAlert;
+
+const DEFAULT_INTERVAL_SECS: u64 = 15;
+
+const PRESSURE_CONDITIONS: &[&str] = &["MemoryPressure", "DiskPressure", "PIDPressure"];
+
+/// Taints set by termination handlers and Karpenter when a node is about to go away
+const INTERRUPTION_TAINTS: &[(&str, &str)] = &[
+    ("aws-node-termination-handler/spot-itn", "SpotInterruption"),
+    ("aws-node-termination-handler/rebalance-recommendation", "RebalanceRecommendation"),
+    ("aws-node-termination-handler/scheduled-maintenance", "ScheduledMaintenance"),
+    ("cloud.google.com/impending-node-termination", "SpotInterruption"),
+    ("karpenter.sh/disrupted", "Disruption"),
+];
+
+/// Node event reasons that announce an interruption, from Karpenter and the termination handlers
+const INTERRUPTION_EVENT_REASONS: &[&str] = &[
+    "SpotInterrupted",
+    "SpotInterruption",
+    "RebalanceRecommendation",
+    "PreemptScheduled",
+];
+
+/// How far back interruption events count; the node is usually gone soon after
+const INTERRUPTION_EVENT_WINDOW_MINS: i64 = 15;
+
+/// (node, kind) -> (message, since)
+type ActiveAlerts = HashMap<(String, String), (String, Option<String>)>;
+
+/// Node pressure conditions and interruption signals active right now
+async fn detect(client: &Client) -> Result<ActiveAlerts> {
+    let (nodes, events) = tokio::try_join!(
+        Api::<Node>::all(client.clone()).list(&ListParams::default()),
+        Api::<Event>::all(client.clone()).list(&ListParams::default().fields("involvedObject.kind=Node")),
+    )?;
+
+    let mut alerts = ActiveAlerts::new();
+    for node in &nodes.items {
+        let name = node.name_any();
+
+        let conditions = node.status.as_ref().and_then(|s| s.conditions.as_ref());
+        for condition in conditions.into_iter().flatten() {
+            if PRESSURE_CONDITIONS.contains(&condition.type_.as_str()) && condition.status == "True" {
+                alerts.insert(
+                    (name.clone(), condition.type_.clone()),
+                    (
+                        condition.message.clone().unwrap_or_else(|| condition.type_.clone()),
+                        condition.last_transition_time.as_ref().map(|t| t.0.to_rfc3339()),
+                    ),
+                );
+            }
+        }
+
+        let taints = node.spec.as_ref().and_then(|s| s.taints.as_ref());
+        for taint in taints.into_iter().flatten() {
+            if let Some((_, kind)) = INTERRUPTION_TAINTS.iter().find(|(key, _)| *key == taint.key) {
+                alerts.insert(
+                    (name.clone(), kind.to_string()),
+                    (
+                        format!("Tainted {}:{}", taint.key, taint.effect),
+                        taint.time_added.as_ref().map(|t| t.0.to_rfc3339()),
+                    ),
+                );
+            }
+        }
+    }
+
+    let cutoff = Utc::now() - chrono::Duration::minutes(INTERRUPTION_EVENT_WINDOW_MINS);
+    let node_names: BTreeSet<String> = nodes.items.iter().map(|n| n.name_any()).collect();
+    for event in &events.items {
+        let reason = event.reason.as_deref().unwrap_or_default();
+        let node = event.involved_object.name.clone().unwrap_or_default();
+        let time = event_time(event);
+        if !INTERRUPTION_EVENT_REASONS.contains(&reason)
+            || !node_names.contains(&node)
+            || time.map(|t| t < cutoff).unwrap_or(true)
+        {
+            continue;
+        }
+        alerts.entry((node, "SpotInterruption".to_string())).or_insert((
+            event.message.clone().unwrap_or_else(|| reason.to_string()),
+            time.map(|t| t.to_rfc3339()),
+        ));
+    }
+
+    Ok(alerts)
+}
+
+/// Workloads with running pods on each of `nodes`, as `namespace/Kind/name`
+async fn affected_workloads(client: &Client, nodes: &BTreeSet<String>) -> Result<HashMap<String, Vec<String>>> {
+    let mut affected = HashMap::new();
+    if nodes.is_empty() {
+        return Ok(affected);
+    }
+
+    let resolver = OwnerResolver::load(client.clone(), None).await?;
+    let pods: Api<Pod> = Api::all(client.clone());
+    for node in nodes {
+        let on_node = pods
+            .list(&ListParams::default().fields(&format!("spec.nodeName={},status.phase=Running", node)))
+            .await?;
+        let workloads: BTreeSet<String> = on_node
+            .items
+            .iter()
+            .map(|pod| {
+                let namespace = pod.namespace().unwrap_or_default();
+                let owner = resolver
+                    .top_owner("Pod", &namespace, &pod.name_any())
+                    .unwrap_or_else(|| format!("Pod/{}", pod.name_any()));
+                format!("{}/{}", namespace, owner)
+            })
+            .collect();
+        affected.insert(node.clone(), workloads.into_iter().collect());
+    }
+    Ok(affected)
+}
+
+fn alert(
+    (node, kind): &(String, String),
+    active: bool,
+    message: String,
+    since: Option<String>,
+    affected: &HashMap<String, Vec<String>>,
+) -> NodeAlert {
+    NodeAlert {
+        node: node.clone(),
+        kind: kind.clone(),
+        active,
+        message,
+        since,
+        affected_workloads: affected.get(node).cloned().unwrap_or_default(),
+    }
+}
+
+/// Nodes under memory, disk or PID pressure or about to be interrupted, with the workloads running on them
+pub async fn get_node_alerts(client: Client) -> Result<Vec<NodeAlert>> {
+    let active = detect(&client).await?;
+    let nodes: BTreeSet<String> = active.keys().map(|(node, _)| node.clone()).collect();
+    let affected = affected_workloads(&client, &nodes).await?;
+
+    let mut alerts: Vec<NodeAlert> = active
+        .iter()
+        .map(|(key, (message, since))| alert(key, true, message.clone(), since.clone(), &affected))
+        .collect();
+    alerts.sort_by(|a, b| a.node.cmp(&b.node).then_with(|| a.kind.cmp(&b.kind)));
+    Ok(alerts)
+}
+
+/// Polls node conditions, taints and events and pushes alerts as they start and clear
+pub struct NodeAlertMonitor {
+    monitors: Arc<Mutex<HashMap<String, oneshot::Sender<()>>>>,
+}
+
+impl NodeAlertMonitor {
+    pub fn new() -> Self {
+        Self {
+            monitors: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Emit `node-alert-{monitor_id}` with `active: true` when a node comes under
+    /// pressure or gets an interruption notice, and `active: false` once it clears
+    ///
+    /// Alerts already active when monitoring starts are emitted on the first poll.
+    pub async fn start(&self, app: AppHandle, client: Client, interval_secs: Option<u64>) -> Result<String> {
+        // Surface RBAC or connection problems to the caller rather than the background task
+        detect(&client).await?;
+
+        let monitor_id = Uuid::new_v4().to_string();
+        let (cancel_tx, mut cancel_rx) = oneshot::channel::<()>();
+        self.monitors.lock().await.insert(monitor_id.clone(), cancel_tx);
+
+        let interval = Duration::from_secs(interval_secs.unwrap_or(DEFAULT_INTERVAL_SECS).max(5));
+        let monitors = self.monitors.clone();
+        let id = monitor_id.clone();
+        tokio::spawn(async move {
+            let event = format!("node-alert-{}", id);
+            let mut previous = ActiveAlerts::new();
+
+            loop {
+                // A failed poll keeps the previous state rather than clearing every alert
+                if let Ok(current) = detect(&client).await {
+                    let raised: BTreeSet<String> = current
+                        .keys()
+                        .filter(|key| !previous.contains_key(*key))
+                        .map(|(node, _)| node.clone())
+                        .collect();
+                    let affected = affected_workloads(&client, &raised).await.unwrap_or_default();
+
+                    for (key, (message, since)) in &current {
+                        if !previous.contains_key(key) {
+                            let _ = app.emit(&event, alert(key, true, message.clone(), since.clone(), &affected));
+                        }
+                    }
+                    for (key, (message, since)) in &previous {
+                        if !current.contains_key(key) {
+                            let cleared = format!("Cleared: {}", message);
+                            let _ = app.emit(&event, alert(key, false, cleared, since.clone(), &HashMap::new()));
+                        }
+                    }
+                    previous = current;
+                }
+
+                tokio::select! {
+                    _ = tokio::time::sleep(interval) => {}
+                    _ = &mut cancel_rx => break,
+                }
+            }
+
+            monitors.lock().await.remove(&id);
+        });
+
+        Ok(monitor_id)
+    }
+
+    pub async fn stop(&self, monitor_id: &str) -> Result<()> {
+        if let Some(cancel) = self.monitors.lock().await.remove(monitor_id) {
+            let _ = cancel.send(());
+        }
+        Ok(())
+    }
+}
+
+impl Default for NodeAlertMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
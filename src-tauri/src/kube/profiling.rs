@@ -0,0 +1,192 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use k8s_openapi::api::core::v1::Pod;
+use kube::api::Api;
+use kube::Client;
+use std::path::Path;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::types::ProfileCaptureResult;
+
+/// Extra time allowed on top of the profile duration for the endpoint to respond
+const RESPONSE_GRACE_SECS: u64 = 60;
+
+const DEFAULT_CPU_PROFILE_SECS: u64 = 30;
+
+/// Where to reach the profiling endpoint
+pub enum ProfileTarget<'a> {
+    /// An existing port-forward listening on localhost
+    LocalPort(u16),
+    /// A pod port, reached through a temporary port-forward
+    Pod {
+        namespace: &'a str,
+        pod_name: &'a str,
+        port: Option<u16>,
+    },
+}
+
+/// Request path, default container port and file extension for an endpoint type
+fn endpoint(endpoint_type: &str, duration_secs: u64) -> Result<(String, u16, &'static str)> {
+    Ok(match endpoint_type {
+        "pprof-cpu" => (format!("/debug/pprof/profile?seconds={}", duration_secs), 6060, "pb.gz"),
+        "pprof-heap" => ("/debug/pprof/heap".to_string(), 6060, "pb.gz"),
+        "pprof-allocs" => ("/debug/pprof/allocs".to_string(), 6060, "pb.gz"),
+        "pprof-goroutine" => ("/debug/pprof/goroutine?debug=2".to_string(), 6060, "txt"),
+        "pprof-trace" => (format!("/debug/pprof/trace?seconds={}", duration_secs), 6060, "trace"),
+        "actuator-heapdump" => ("/actuator/heapdump".to_string(), 8080, "hprof"),
+        "actuator-threaddump" => ("/actuator/threaddump".to_string(), 8080, "json"),
+        other => return Err(anyhow::anyhow!("Unknown profile endpoint type: {}", other)),
+    })
+}
+
+/// Fetch a profile from a pprof or Spring Boot actuator endpoint and save it under `output_dir`
+///
+/// The artifact is written next to a `.json` file recording what was captured,
+/// from where and when, so it still makes sense when handed to someone else.
+pub async fn capture_profile(
+    client: Client,
+    target: ProfileTarget<'_>,
+    endpoint_type: &str,
+    duration_secs: Option<u64>,
+    output_dir: &Path,
+) -> Result<ProfileCaptureResult> {
+    let duration_secs = duration_secs.unwrap_or(DEFAULT_CPU_PROFILE_SECS);
+    let (path, default_port, extension) = endpoint(endpoint_type, duration_secs)?;
+    let timeout = Duration::from_secs(duration_secs + RESPONSE_GRACE_SECS);
+    let started = Utc::now();
+
+    let (body, target_name) = match target {
+        ProfileTarget::LocalPort(port) => {
+            let stream = tokio::net::TcpStream::connect(("127.0.0.1", port))
+                .await
+                .with_context(|| format!("Failed to connect to localhost:{}", port))?;
+            let body = tokio::time::timeout(timeout, http_get(stream, &path))
+                .await
+                .map_err(|_| anyhow::anyhow!("Profile request timed out"))??;
+            (body, format!("localhost-{}", port))
+        }
+        ProfileTarget::Pod { namespace, pod_name, port } => {
+            let port = port.unwrap_or(default_port);
+            let pods: Api<Pod> = Api::namespaced(client, namespace);
+            let mut forwarder = pods.portforward(pod_name, &[port]).await?;
+            let stream = forwarder
+                .take_stream(port)
+                .ok_or_else(|| anyhow::anyhow!("Port-forward to {}:{} has no stream", pod_name, port))?;
+
+            let body = tokio::time::timeout(timeout, http_get(stream, &path)).await;
+            forwarder.abort();
+            let body = body.map_err(|_| anyhow::anyhow!("Profile request timed out"))??;
+            (body, format!("{}-{}", namespace, pod_name))
+        }
+    };
+
+    std::fs::create_dir_all(output_dir)?;
+    let base = format!("{}-{}-{}", started.format("%Y%m%dT%H%M%S"), target_name, endpoint_type);
+    let artifact_path = output_dir.join(format!("{}.{}", base, extension));
+    let metadata_path = output_dir.join(format!("{}.json", base));
+    std::fs::write(&artifact_path, &body)?;
+
+    let result = ProfileCaptureResult {
+        endpoint_type: endpoint_type.to_string(),
+        target: target_name,
+        request_path: path,
+        duration_secs,
+        captured_at: started.to_rfc3339(),
+        path: artifact_path.to_string_lossy().to_string(),
+        metadata_path: metadata_path.to_string_lossy().to_string(),
+        size_bytes: body.len() as u64,
+    };
+    std::fs::write(&metadata_path, serde_json::to_vec_pretty(&result)?)?;
+
+    Ok(result)
+}
+
+/// Minimal HTTP/1.1 GET over an already-open stream, returning the body of a 2xx response
+async fn http_get<S>(mut stream: S, path: &str) -> Result<Vec<u8>>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: localhost\r\nAccept: */*\r\nConnection: close\r\n\r\n",
+        path
+    );
+    stream.write_all(request.as_bytes()).await?;
+    stream.flush().await?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await?;
+
+    let header_end = response
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or_else(|| anyhow::anyhow!("Malformed HTTP response"))?;
+    let headers = String::from_utf8_lossy(&response[..header_end]).to_string();
+    let body = response.split_off(header_end + 4);
+
+    let status_line = headers.lines().next().unwrap_or_default();
+    let status: u16 = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| anyhow::anyhow!("Malformed HTTP status line: {}", status_line))?;
+    if !(200..300).contains(&status) {
+        return Err(anyhow::anyhow!(
+            "{} returned {}: {}",
+            path,
+            status_line,
+            String::from_utf8_lossy(&body).chars().take(200).collect::<String>()
+        ));
+    }
+
+    let chunked = headers.lines().any(|line| {
+        let line = line.to_ascii_lowercase();
+        line.starts_with("transfer-encoding:") && line.contains("chunked")
+    });
+
+    if chunked {
+        decode_chunked(&body)
+    } else {
+        Ok(body)
+    }
+}
+
+/// Reassemble a chunked transfer-encoded body
+fn decode_chunked(mut data: &[u8]) -> Result<Vec<u8>> {
+    let mut body = Vec::new();
+
+    loop {
+        let line_end = data
+            .windows(2)
+            .position(|w| w == b"\r\n")
+            .ok_or_else(|| anyhow::anyhow!("Truncated chunked response"))?;
+        let size_line = String::from_utf8_lossy(&data[..line_end]).to_string();
+        let size_hex = size_line.split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size_hex, 16)
+            .with_context(|| format!("Invalid chunk size: {}", size_line))?;
+
+        data = &data[line_end + 2..];
+        if size == 0 {
+            return Ok(body);
+        }
+        if data.len() < size {
+            return Err(anyhow::anyhow!("Truncated chunked response"));
+        }
+
+        body.extend_from_slice(&data[..size]);
+        data = data.get(size + 2..).unwrap_or_default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_chunked() {
+        let data = b"4\r\nWiki\r\n6;ext=1\r\npedia \r\nE\r\nin \r\n\r\nchunks.\r\n0\r\n\r\n";
+        assert_eq!(decode_chunked(data).unwrap(), b"Wikipedia in \r\n\r\nchunks.");
+
+        assert!(decode_chunked(b"10\r\nshort\r\n").is_err());
+    }
+}
@@ -0,0 +1,95 @@
+use anyhow::Result;
+use jaq_interpret::{Ctx, FilterT, ParseCtx, RcIter, Val};
+use kube::Client;
+use serde_json::{json, Value};
+
+use crate::kube::columns::list_objects;
+use crate::types::QueryResult;
+
+/// Results beyond this are dropped so a runaway filter can't flood the UI
+const MAX_RESULTS: usize = 5000;
+
+/// Run a jq filter over `input`, returning at most `limit` outputs and whether more were cut off
+fn run_jq(expression: &str, input: Value, limit: usize) -> Result<(Vec<Value>, bool)> {
+    let mut defs = ParseCtx::new(Vec::new());
+    defs.insert_natives(jaq_core::core());
+    defs.insert_defs(jaq_std::std());
+
+    let (filter, errors) = jaq_parse::parse(expression, jaq_parse::main());
+    if let Some(error) = errors.first() {
+        return Err(anyhow::anyhow!("Invalid jq expression: {:?}", error));
+    }
+    let filter = filter.ok_or_else(|| anyhow::anyhow!("Invalid jq expression"))?;
+    let filter = defs.compile(filter);
+    if let Some(error) = defs.errs.first() {
+        return Err(anyhow::anyhow!("Invalid jq expression: {:?}", error.0));
+    }
+
+    let inputs = RcIter::new(core::iter::empty());
+    let mut results = Vec::new();
+    for output in filter.run((Ctx::new([], &inputs), Val::from(input))) {
+        if results.len() == limit {
+            return Ok((results, true));
+        }
+        let output = output.map_err(|e| anyhow::anyhow!("jq error: {}", e))?;
+        results.push(Value::from(output));
+    }
+    Ok((results, false))
+}
+
+/// Run a jq expression over every object of a kind, as with `kubectl get -o json | jq`
+///
+/// The input is shaped like kubectl's list output (`{"kind": "List",
+/// "items": [...]}`) so existing snippets such as
+/// `.items[] | select(.status.phase != "Running") | .metadata.name` work as-is.
+pub async fn query_resources(
+    client: Client,
+    kind: &str,
+    namespace: Option<&str>,
+    expression: &str,
+) -> Result<QueryResult> {
+    let (resource, items) = list_objects(client, kind, namespace).await?;
+    let object_count = items.len();
+    let input = json!({ "apiVersion": "v1", "kind": "List", "items": items });
+
+    // jaq values are not Send, so parsing and evaluation stay on one blocking thread
+    let expression_owned = expression.to_string();
+    let (results, truncated) =
+        tokio::task::spawn_blocking(move || run_jq(&expression_owned, input, MAX_RESULTS)).await??;
+
+    Ok(QueryResult {
+        kind: resource.kind,
+        expression: expression.to_string(),
+        object_count,
+        results,
+        truncated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_jq() {
+        let input = json!({ "items": [
+            { "metadata": { "name": "a" }, "status": { "phase": "Running" } },
+            { "metadata": { "name": "b" }, "status": { "phase": "Pending" } },
+        ]});
+
+        let (results, truncated) = run_jq(
+            r#".items[] | select(.status.phase != "Running") | .metadata.name"#,
+            input.clone(),
+            10,
+        )
+        .unwrap();
+        assert_eq!(results, vec![json!("b")]);
+        assert!(!truncated);
+
+        let (results, truncated) = run_jq(".items[].metadata.name", input.clone(), 1).unwrap();
+        assert_eq!(results, vec![json!("a")]);
+        assert!(truncated);
+
+        assert!(run_jq(".items[", input, 10).is_err());
+    }
+}
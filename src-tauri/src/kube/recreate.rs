@@ -0,0 +1,244 @@
+use anyhow::Result;
+use k8s_openapi::api::core::v1::{PersistentVolume, PersistentVolumeClaim};
+use kube::api::{Api, DynamicObject, ListParams, Patch, PatchParams};
+use kube::{Client, ResourceExt};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter};
+use uuid::Uuid;
+
+use crate::kube::immutable::{delete_and_wait, dynamic_api, normalize_type};
+use crate::kube::operations::apply_resource_yaml;
+use crate::types::{ResourceRecreateProgress, ResourceRecreateResult};
+
+/// Whether `claim` is one a StatefulSet stamped from a template: `<template>-<statefulset>-<ordinal>`
+fn is_stateful_claim(claim: &str, template: &str, statefulset: &str) -> bool {
+    claim
+        .strip_prefix(&format!("{}-{}-", template, statefulset))
+        .map(|ordinal| !ordinal.is_empty() && ordinal.chars().all(|c| c.is_ascii_digit()))
+        .unwrap_or(false)
+}
+
+/// Claims that belong to an object: owned through ownerReferences, or, for a
+/// StatefulSet, created from its volumeClaimTemplates
+async fn claims_of(client: Client, kind: &str, live: &DynamicObject) -> Result<Vec<PersistentVolumeClaim>> {
+    let namespace = live.namespace().unwrap_or_default();
+    let uid = live.uid().unwrap_or_default();
+    let name = live.name_any();
+    let templates: Vec<String> = live
+        .data
+        .pointer("/spec/volumeClaimTemplates")
+        .and_then(|t| t.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|t| t.pointer("/metadata/name").and_then(|n| n.as_str()))
+        .map(|n| n.to_string())
+        .collect();
+
+    let claims = Api::<PersistentVolumeClaim>::namespaced(client, &namespace)
+        .list(&ListParams::default())
+        .await?
+        .items
+        .into_iter()
+        .filter(|claim| {
+            claim.owner_references().iter().any(|o| o.uid == uid)
+                || (kind == "statefulset" && templates.iter().any(|t| is_stateful_claim(&claim.name_any(), t, &name)))
+        })
+        .collect();
+    Ok(claims)
+}
+
+/// Delete an object and apply it again, for changes the API server refuses in place
+/// (a StatefulSet's volumeClaimTemplates, a Service's clusterIP, a claim's storage class)
+///
+/// `yaml_content` is the desired manifest; without one the live object is
+/// recreated as-is. With `preserve_pvcs` the object's claims survive and are
+/// picked up again by the new object: a StatefulSet's retention policy is set to
+/// Retain and claim ownerReferences to it are dropped, and a recreated claim is
+/// rebound to its old volume. Without it a StatefulSet's claims are deleted so
+/// new ones are made from the new templates.
+///
+/// Returns an operation id; progress arrives as `resource-recreate-progress-{id}`
+/// and the outcome as one `resource-recreate-complete-{id}`. There is no stop:
+/// interrupting between delete and apply would leave the object missing.
+pub async fn recreate_resource(
+    app: AppHandle,
+    client: Client,
+    resource_type: &str,
+    namespace: &str,
+    name: &str,
+    yaml_content: Option<&str>,
+    preserve_pvcs: bool,
+) -> Result<String> {
+    let live = dynamic_api(client.clone(), resource_type, namespace)?
+        .get_opt(name)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("{} {} not found", resource_type, name))?;
+
+    let manifest: Value = match yaml_content {
+        Some(yaml) => serde_yaml::from_str(yaml)?,
+        None => serde_json::to_value(&live)?,
+    };
+    let manifest_name = manifest.pointer("/metadata/name").and_then(|n| n.as_str());
+    if manifest_name != Some(name) {
+        return Err(anyhow::anyhow!(
+            "Manifest is for {}, not {}",
+            manifest_name.unwrap_or("an unnamed object"),
+            name
+        ));
+    }
+
+    let operation_id = Uuid::new_v4().to_string();
+    let id = operation_id.clone();
+    let resource_type = resource_type.to_string();
+    let namespace = namespace.to_string();
+    tokio::spawn(async move {
+        let progress_event = format!("resource-recreate-progress-{}", id);
+        let emit = |step: &str, message: String| {
+            let _ = app.emit(
+                &progress_event,
+                &ResourceRecreateProgress {
+                    step: step.to_string(),
+                    message,
+                },
+            );
+        };
+
+        let mut preserved_claims = Vec::new();
+        let mut deleted_claims = Vec::new();
+        let outcome = run(
+            &client,
+            &resource_type,
+            &namespace,
+            &live,
+            manifest,
+            preserve_pvcs,
+            &mut preserved_claims,
+            &mut deleted_claims,
+            &emit,
+        )
+        .await;
+
+        let name = live.name_any();
+        let result = match outcome {
+            Ok(()) => ResourceRecreateResult {
+                outcome: "success".to_string(),
+                message: format!("Recreated {} {}", resource_type, name),
+                preserved_claims,
+                deleted_claims,
+            },
+            Err(e) => {
+                emit("failed", e.to_string());
+                ResourceRecreateResult {
+                    outcome: "failed".to_string(),
+                    message: format!("Recreating {} {} failed: {}", resource_type, name, e),
+                    preserved_claims,
+                    deleted_claims,
+                }
+            }
+        };
+        let _ = app.emit(&format!("resource-recreate-complete-{}", id), &result);
+    });
+
+    Ok(operation_id)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run(
+    client: &Client,
+    resource_type: &str,
+    namespace: &str,
+    live: &DynamicObject,
+    mut manifest: Value,
+    preserve_pvcs: bool,
+    preserved_claims: &mut Vec<String>,
+    deleted_claims: &mut Vec<String>,
+    emit: &impl Fn(&str, String),
+) -> Result<()> {
+    let kind = normalize_type(resource_type);
+    let name = live.name_any();
+    let uid = live.uid().unwrap_or_default();
+    let claims = claims_of(client.clone(), &kind, live).await?;
+    let claims_api: Api<PersistentVolumeClaim> = Api::namespaced(client.clone(), namespace);
+    let volumes_api: Api<PersistentVolume> = Api::all(client.clone());
+
+    // Cut every tie that would let the delete cascade to the data
+    let mut released_volume = None;
+    if preserve_pvcs {
+        if kind == "statefulset" {
+            let patch = json!({ "spec": { "persistentVolumeClaimRetentionPolicy": { "whenDeleted": "Retain" } } });
+            dynamic_api(client.clone(), resource_type, namespace)?
+                .patch(&name, &PatchParams::default(), &Patch::Merge(&patch))
+                .await?;
+        }
+        for claim in &claims {
+            let owners: Vec<_> = claim.owner_references().iter().filter(|o| o.uid != uid).cloned().collect();
+            if owners.len() != claim.owner_references().len() {
+                let patch = json!({ "metadata": { "ownerReferences": owners } });
+                claims_api
+                    .patch(&claim.name_any(), &PatchParams::default(), &Patch::Merge(&patch))
+                    .await?;
+            }
+            preserved_claims.push(claim.name_any());
+        }
+        if kind == "persistentvolumeclaim" {
+            let claim: PersistentVolumeClaim = serde_json::from_value(serde_json::to_value(live)?)?;
+            if let Some(volume) = claim.spec.and_then(|s| s.volume_name) {
+                let patch = json!({ "spec": { "persistentVolumeReclaimPolicy": "Retain" } });
+                volumes_api.patch(&volume, &PatchParams::default(), &Patch::Merge(&patch)).await?;
+                preserved_claims.push(name.clone());
+                released_volume = Some(volume);
+            }
+        }
+        emit(
+            "retained",
+            format!("Kept {} claims out of the delete", preserved_claims.len()),
+        );
+    }
+
+    emit("deleting", format!("Deleting {} {}", resource_type, name));
+    delete_and_wait(client.clone(), resource_type, namespace, &name, false).await?;
+    emit("deleted", format!("{} {} is gone", resource_type, name));
+
+    if !preserve_pvcs {
+        for claim in &claims {
+            delete_and_wait(client.clone(), "persistentvolumeclaim", namespace, &claim.name_any(), false).await?;
+            deleted_claims.push(claim.name_any());
+            emit("claim-deleted", format!("Deleted claim {}", claim.name_any()));
+        }
+    }
+
+    // A Released volume only binds again once its old claimRef is cleared
+    if let Some(volume) = released_volume {
+        let patch = json!({ "spec": { "claimRef": null } });
+        volumes_api.patch(&volume, &PatchParams::default(), &Patch::Merge(&patch)).await?;
+        manifest["spec"]["volumeName"] = Value::String(volume.clone());
+        emit("volume-released", format!("Volume {} is available for the new claim", volume));
+    }
+
+    emit("applying", format!("Creating {} {}", resource_type, name));
+    apply_resource_yaml(
+        client.clone(),
+        resource_type,
+        namespace,
+        &serde_yaml::to_string(&manifest)?,
+        Some("recreate"),
+    )
+    .await?;
+    emit("applied", format!("Created {} {}", resource_type, name));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_stateful_claim() {
+        assert!(is_stateful_claim("data-web-0", "data", "web"));
+        assert!(is_stateful_claim("data-web-12", "data", "web"));
+        assert!(!is_stateful_claim("data-web-", "data", "web"));
+        assert!(!is_stateful_claim("data-web-api-0", "data", "web"));
+        assert!(!is_stateful_claim("logs-web-0", "data", "web"));
+    }
+}
@@ -0,0 +1,268 @@
+use anyhow::Result;
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, StatefulSet};
+use k8s_openapi::api::autoscaling::v2::HorizontalPodAutoscaler;
+use k8s_openapi::api::batch::v1::{CronJob, Job};
+use k8s_openapi::api::core::v1::{Pod, PodSpec, ServiceAccount};
+use k8s_openapi::api::networking::v1::{Ingress, IngressBackend};
+use k8s_openapi::api::rbac::v1::{ClusterRoleBinding, RoleBinding, Subject};
+use kube::api::{Api, ListParams};
+use kube::{Client, ResourceExt};
+use std::collections::HashMap;
+
+use crate::types::ResourceReference;
+
+/// Kinds `find_references` can answer for
+const REFERENCED_KINDS: &[&str] = &[
+    "ConfigMap",
+    "Secret",
+    "PersistentVolumeClaim",
+    "ServiceAccount",
+    "Service",
+    "Deployment",
+    "StatefulSet",
+];
+
+/// What refers to what within a namespace: (kind, name) -> the objects pointing at it
+pub(crate) struct ReferenceIndex {
+    namespace: String,
+    refs: HashMap<(String, String), Vec<ResourceReference>>,
+}
+
+impl ReferenceIndex {
+    /// Index pods, workloads, Ingresses, ServiceAccounts, role bindings and HPAs in `namespace`
+    pub(crate) async fn build(client: Client, namespace: &str) -> Result<Self> {
+        let lp = ListParams::default();
+        let (pods, deployments, statefulsets, daemonsets, jobs, cronjobs) = tokio::try_join!(
+            Api::<Pod>::namespaced(client.clone(), namespace).list(&lp),
+            Api::<Deployment>::namespaced(client.clone(), namespace).list(&lp),
+            Api::<StatefulSet>::namespaced(client.clone(), namespace).list(&lp),
+            Api::<DaemonSet>::namespaced(client.clone(), namespace).list(&lp),
+            Api::<Job>::namespaced(client.clone(), namespace).list(&lp),
+            Api::<CronJob>::namespaced(client.clone(), namespace).list(&lp),
+        )?;
+        let (ingresses, service_accounts, role_bindings, cluster_role_bindings, hpas) = tokio::try_join!(
+            Api::<Ingress>::namespaced(client.clone(), namespace).list(&lp),
+            Api::<ServiceAccount>::namespaced(client.clone(), namespace).list(&lp),
+            Api::<RoleBinding>::namespaced(client.clone(), namespace).list(&lp),
+            Api::<ClusterRoleBinding>::all(client.clone()).list(&lp),
+            Api::<HorizontalPodAutoscaler>::namespaced(client, namespace).list(&lp),
+        )?;
+
+        let mut index = Self {
+            namespace: namespace.to_string(),
+            refs: HashMap::new(),
+        };
+
+        for pod in &pods.items {
+            if let Some(spec) = &pod.spec {
+                index.add_pod_spec("Pod", &pod.name_any(), spec);
+            }
+        }
+        let templates = deployments
+            .items
+            .iter()
+            .map(|d| ("Deployment", d.name_any(), d.spec.as_ref().and_then(|s| s.template.spec.as_ref())))
+            .chain(
+                statefulsets
+                    .items
+                    .iter()
+                    .map(|s| ("StatefulSet", s.name_any(), s.spec.as_ref().and_then(|s| s.template.spec.as_ref()))),
+            )
+            .chain(
+                daemonsets
+                    .items
+                    .iter()
+                    .map(|d| ("DaemonSet", d.name_any(), d.spec.as_ref().and_then(|s| s.template.spec.as_ref()))),
+            )
+            .chain(
+                jobs.items
+                    .iter()
+                    .map(|j| ("Job", j.name_any(), j.spec.as_ref().and_then(|s| s.template.spec.as_ref()))),
+            )
+            .chain(cronjobs.items.iter().map(|c| {
+                let spec = c
+                    .spec
+                    .as_ref()
+                    .and_then(|s| s.job_template.spec.as_ref())
+                    .and_then(|s| s.template.spec.as_ref());
+                ("CronJob", c.name_any(), spec)
+            }));
+        for (kind, name, spec) in templates {
+            if let Some(spec) = spec {
+                index.add_pod_spec(kind, &name, spec);
+            }
+        }
+
+        for ingress in &ingresses.items {
+            let name = ingress.name_any();
+            let Some(spec) = &ingress.spec else { continue };
+            if let Some(backend) = &spec.default_backend {
+                index.add_backend(&name, backend, "default backend".to_string());
+            }
+            for rule in spec.rules.iter().flatten() {
+                let host = rule.host.clone().unwrap_or_else(|| "*".to_string());
+                for path in rule.http.iter().flat_map(|h| &h.paths) {
+                    let via = format!("rule {}{}", host, path.path.clone().unwrap_or_default());
+                    index.add_backend(&name, &path.backend, via);
+                }
+            }
+            for tls in spec.tls.iter().flatten() {
+                if let Some(secret) = &tls.secret_name {
+                    index.add("Secret", secret, "Ingress", &name, "tls".to_string());
+                }
+            }
+        }
+
+        for sa in &service_accounts.items {
+            let name = sa.name_any();
+            for secret in sa.secrets.iter().flatten().filter_map(|s| s.name.as_ref()) {
+                index.add("Secret", secret, "ServiceAccount", &name, "secrets".to_string());
+            }
+            for secret in sa.image_pull_secrets.iter().flatten() {
+                index.add("Secret", &secret.name, "ServiceAccount", &name, "imagePullSecrets".to_string());
+            }
+        }
+
+        for binding in &role_bindings.items {
+            index.add_subjects("RoleBinding", &binding.name_any(), &binding.role_ref.name, binding.subjects.as_deref());
+        }
+        for binding in &cluster_role_bindings.items {
+            index.add_subjects(
+                "ClusterRoleBinding",
+                &binding.name_any(),
+                &binding.role_ref.name,
+                binding.subjects.as_deref(),
+            );
+        }
+
+        for hpa in &hpas.items {
+            if let Some(spec) = &hpa.spec {
+                let target = &spec.scale_target_ref;
+                index.add(
+                    &target.kind,
+                    &target.name,
+                    "HorizontalPodAutoscaler",
+                    &hpa.name_any(),
+                    "scaleTargetRef".to_string(),
+                );
+            }
+        }
+
+        Ok(index)
+    }
+
+    fn add(&mut self, kind: &str, name: &str, from_kind: &str, from_name: &str, via: String) {
+        // Cluster-scoped referrers (ClusterRoleBindings) have no namespace of their own
+        let namespace = (from_kind != "ClusterRoleBinding").then(|| self.namespace.clone());
+        let referrers = self.refs.entry((kind.to_string(), name.to_string())).or_default();
+        if !referrers.iter().any(|r| r.kind == from_kind && r.name == from_name && r.via == via) {
+            referrers.push(ResourceReference {
+                kind: from_kind.to_string(),
+                namespace,
+                name: from_name.to_string(),
+                via,
+            });
+        }
+    }
+
+    fn add_pod_spec(&mut self, kind: &str, name: &str, spec: &PodSpec) {
+        let service_account = spec.service_account_name.as_deref().unwrap_or("default");
+        self.add("ServiceAccount", service_account, kind, name, "serviceAccountName".to_string());
+
+        let containers = spec.containers.iter().chain(spec.init_containers.iter().flatten());
+        for container in containers {
+            for env in container.env.iter().flatten() {
+                let Some(source) = &env.value_from else { continue };
+                let via = format!("env {} ({})", env.name, container.name);
+                if let Some(key_ref) = &source.config_map_key_ref {
+                    self.add("ConfigMap", &key_ref.name, kind, name, via.clone());
+                }
+                if let Some(key_ref) = &source.secret_key_ref {
+                    self.add("Secret", &key_ref.name, kind, name, via);
+                }
+            }
+            for env_from in container.env_from.iter().flatten() {
+                let via = format!("envFrom ({})", container.name);
+                if let Some(cm) = &env_from.config_map_ref {
+                    self.add("ConfigMap", &cm.name, kind, name, via.clone());
+                }
+                if let Some(secret) = &env_from.secret_ref {
+                    self.add("Secret", &secret.name, kind, name, via);
+                }
+            }
+        }
+
+        for volume in spec.volumes.iter().flatten() {
+            let via = format!("volume {}", volume.name);
+            if let Some(claim) = &volume.persistent_volume_claim {
+                self.add("PersistentVolumeClaim", &claim.claim_name, kind, name, via.clone());
+            }
+            if let Some(cm) = &volume.config_map {
+                self.add("ConfigMap", &cm.name, kind, name, via.clone());
+            }
+            if let Some(secret_name) = volume.secret.as_ref().and_then(|s| s.secret_name.as_ref()) {
+                self.add("Secret", secret_name, kind, name, via.clone());
+            }
+            for source in volume.projected.iter().flat_map(|p| p.sources.iter().flatten()) {
+                if let Some(cm) = &source.config_map {
+                    self.add("ConfigMap", &cm.name, kind, name, via.clone());
+                }
+                if let Some(secret) = &source.secret {
+                    self.add("Secret", &secret.name, kind, name, via.clone());
+                }
+            }
+        }
+
+        for secret in spec.image_pull_secrets.iter().flatten() {
+            self.add("Secret", &secret.name, kind, name, "imagePullSecrets".to_string());
+        }
+    }
+
+    fn add_backend(&mut self, ingress: &str, backend: &IngressBackend, via: String) {
+        if let Some(service) = &backend.service {
+            self.add("Service", &service.name, "Ingress", ingress, via);
+        }
+    }
+
+    fn add_subjects(&mut self, kind: &str, name: &str, role: &str, subjects: Option<&[Subject]>) {
+        for subject in subjects.into_iter().flatten() {
+            // Unset subject namespaces on RoleBindings mean the binding's own namespace
+            let in_namespace = match &subject.namespace {
+                Some(ns) => ns == &self.namespace,
+                None => kind == "RoleBinding",
+            };
+            if subject.kind == "ServiceAccount" && in_namespace {
+                self.add("ServiceAccount", &subject.name, kind, name, format!("subject (role {})", role));
+            }
+        }
+    }
+
+    pub(crate) fn referrers(&self, kind: &str, name: &str) -> Vec<ResourceReference> {
+        let mut referrers = self
+            .refs
+            .get(&(kind.to_string(), name.to_string()))
+            .cloned()
+            .unwrap_or_default();
+        referrers.sort_by(|a, b| (&a.kind, &a.name).cmp(&(&b.kind, &b.name)));
+        referrers
+    }
+}
+
+/// Everything in the namespace that refers to an object: pods mounting a PVC,
+/// workloads using a Secret or ServiceAccount, Ingresses targeting a Service,
+/// role bindings granting a ServiceAccount, HPAs scaling a workload
+///
+/// References come from specs, so the object needn't exist; that makes this
+/// useful for "what breaks if I delete this" as well as for dangling references.
+pub async fn find_references(client: Client, kind: &str, namespace: &str, name: &str) -> Result<Vec<ResourceReference>> {
+    if !REFERENCED_KINDS.contains(&kind) {
+        return Err(anyhow::anyhow!(
+            "Cannot find references to {}; supported kinds are {}",
+            kind,
+            REFERENCED_KINDS.join(", ")
+        ));
+    }
+
+    let index = ReferenceIndex::build(client, namespace).await?;
+    Ok(index.referrers(kind, name))
+}
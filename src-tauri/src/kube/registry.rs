@@ -0,0 +1,441 @@
+use anyhow::{Context, Result};
+use base64::Engine;
+use http_body_util::BodyExt;
+use k8s_openapi::api::core::v1::{Pod, Secret};
+use k8s_openapi::http::{header, HeaderMap, Method, Request, StatusCode};
+use kube::api::{Api, ListParams};
+use kube::{Client, ResourceExt};
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::grafana::percent_encode;
+use crate::kube::images::{parse_image_ref, ImageRef};
+use crate::types::{DeployedImageTag, RegistryTag, RegistryTags};
+
+const REQUEST_TIMEOUT_SECS: u64 = 15;
+
+/// Tags listed per page, and pages followed before giving up
+const TAGS_PAGE_SIZE: usize = 1000;
+const MAX_TAG_PAGES: usize = 5;
+
+/// Newest tags whose digests are looked up, on top of the deployed ones
+const DIGEST_LOOKUPS: usize = 10;
+
+/// Manifest media types, so multi-arch images report their index digest
+const MANIFEST_ACCEPT: &str = "application/vnd.oci.image.index.v1+json, \
+    application/vnd.docker.distribution.manifest.list.v2+json, \
+    application/vnd.oci.image.manifest.v1+json, \
+    application/vnd.docker.distribution.manifest.v2+json";
+
+type HttpsClient = hyper_util::client::legacy::Client<
+    hyper_rustls::HttpsConnector<hyper_util::client::legacy::connect::HttpConnector>,
+    String,
+>;
+
+/// Talks to one registry's v2 API, handling its token or basic auth challenge
+struct RegistryClient {
+    http: HttpsClient,
+    base_url: String,
+    basic: Option<String>,         // base64 user:password from a pull secret
+    authorization: Option<String>, // Header value once a challenge has been answered
+}
+
+impl RegistryClient {
+    fn new(registry: &str, basic: Option<String>) -> Result<Self> {
+        let connector = hyper_rustls::HttpsConnectorBuilder::new()
+            .with_native_roots()
+            .context("Failed to load system root certificates")?
+            .https_only()
+            .enable_http1()
+            .build();
+        let http = hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::new())
+            .build(connector);
+
+        // Docker Hub's API lives on a different host than its image references
+        let host = match registry {
+            "docker.io" | "index.docker.io" => "registry-1.docker.io",
+            other => other,
+        };
+
+        Ok(Self {
+            http,
+            base_url: format!("https://{}", host),
+            basic,
+            authorization: None,
+        })
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        url: &str,
+        accept: Option<&str>,
+        auth: Option<&str>,
+    ) -> Result<(StatusCode, HeaderMap, Vec<u8>)> {
+        let mut request = Request::builder().method(method).uri(url);
+        if let Some(accept) = accept {
+            request = request.header(header::ACCEPT, accept);
+        }
+        if let Some(auth) = auth {
+            request = request.header(header::AUTHORIZATION, auth);
+        }
+        let request = request.body(String::new())?;
+
+        let timeout = Duration::from_secs(REQUEST_TIMEOUT_SECS);
+        let response = tokio::time::timeout(timeout, self.http.request(request))
+            .await
+            .map_err(|_| anyhow::anyhow!("Request to {} timed out", url))?
+            .with_context(|| format!("Request to {} failed", url))?;
+        let (parts, body) = response.into_parts();
+        let body = body.collect().await?.to_bytes().to_vec();
+        Ok((parts.status, parts.headers, body))
+    }
+
+    /// Request a registry path, answering one auth challenge if the registry asks
+    async fn request(
+        &mut self,
+        method: Method,
+        path: &str,
+        accept: Option<&str>,
+    ) -> Result<(StatusCode, HeaderMap, Vec<u8>)> {
+        let url = format!("{}{}", self.base_url, path);
+        let response = self.send(method.clone(), &url, accept, self.authorization.as_deref()).await?;
+        if response.0 != StatusCode::UNAUTHORIZED {
+            return Ok(response);
+        }
+
+        let challenge = response
+            .1
+            .get(header::WWW_AUTHENTICATE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        self.authorization = Some(self.answer_challenge(&challenge).await?);
+        self.send(method, &url, accept, self.authorization.as_deref()).await
+    }
+
+    async fn answer_challenge(&self, challenge: &str) -> Result<String> {
+        let (scheme, params) = challenge.split_once(' ').unwrap_or((challenge, ""));
+        if scheme.eq_ignore_ascii_case("basic") {
+            // ECR and plain registries: the pull secret's credentials go in directly
+            return self
+                .basic
+                .as_ref()
+                .map(|basic| format!("Basic {}", basic))
+                .ok_or_else(|| anyhow::anyhow!("Registry requires credentials; select a pull secret"));
+        }
+        if !scheme.eq_ignore_ascii_case("bearer") {
+            return Err(anyhow::anyhow!("Unsupported registry auth challenge: {}", challenge));
+        }
+
+        let params = parse_challenge(params);
+        let realm = params
+            .get("realm")
+            .ok_or_else(|| anyhow::anyhow!("Registry auth challenge has no realm"))?;
+        let mut query: Vec<String> = Vec::new();
+        for key in ["service", "scope"] {
+            if let Some(value) = params.get(key) {
+                query.push(format!("{}={}", key, percent_encode(value)));
+            }
+        }
+        let url = format!("{}?{}", realm, query.join("&"));
+
+        let basic = self.basic.as_ref().map(|b| format!("Basic {}", b));
+        let (status, _, body) = self.send(Method::GET, &url, None, basic.as_deref()).await?;
+        if !status.is_success() {
+            return Err(anyhow::anyhow!(
+                "Registry token request failed ({}): {}",
+                status,
+                String::from_utf8_lossy(&body).chars().take(200).collect::<String>()
+            ));
+        }
+
+        let token: serde_json::Value = serde_json::from_slice(&body)?;
+        token
+            .get("token")
+            .or_else(|| token.get("access_token"))
+            .and_then(|t| t.as_str())
+            .map(|t| format!("Bearer {}", t))
+            .ok_or_else(|| anyhow::anyhow!("Registry token response has no token"))
+    }
+
+    /// Every tag of the repository, following `Link` pagination
+    async fn list_tags(&mut self, repository: &str) -> Result<(Vec<String>, bool)> {
+        let mut tags = Vec::new();
+        let mut path = format!("/v2/{}/tags/list?n={}", repository, TAGS_PAGE_SIZE);
+
+        for _ in 0..MAX_TAG_PAGES {
+            let (status, headers, body) = self.request(Method::GET, &path, None).await?;
+            if !status.is_success() {
+                return Err(anyhow::anyhow!(
+                    "Listing tags for {} failed ({}): {}",
+                    repository,
+                    status,
+                    String::from_utf8_lossy(&body).chars().take(200).collect::<String>()
+                ));
+            }
+
+            let page: serde_json::Value = serde_json::from_slice(&body)?;
+            tags.extend(
+                page.get("tags")
+                    .and_then(|t| t.as_array())
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|t| t.as_str().map(|t| t.to_string())),
+            );
+
+            // Link: </v2/repo/tags/list?last=x&n=1000>; rel="next"
+            match headers
+                .get(header::LINK)
+                .and_then(|l| l.to_str().ok())
+                .and_then(|l| l.split_once('<'))
+                .and_then(|(_, rest)| rest.split_once('>'))
+            {
+                Some((next, _)) => path = next.to_string(),
+                None => return Ok((tags, false)),
+            }
+        }
+
+        Ok((tags, true))
+    }
+
+    async fn tag_digest(&mut self, repository: &str, tag: &str) -> Option<String> {
+        let path = format!("/v2/{}/manifests/{}", repository, tag);
+        let (status, headers, _) = self.request(Method::HEAD, &path, Some(MANIFEST_ACCEPT)).await.ok()?;
+        if !status.is_success() {
+            return None;
+        }
+        headers
+            .get("docker-content-digest")
+            .and_then(|d| d.to_str().ok())
+            .map(|d| d.to_string())
+    }
+}
+
+/// `key="value", key2="value2"` from a WWW-Authenticate header
+fn parse_challenge(params: &str) -> HashMap<String, String> {
+    let mut result = HashMap::new();
+    let mut rest = params.trim();
+    while let Some((key, after)) = rest.split_once('=') {
+        let key = key.trim().trim_start_matches(',').trim().to_string();
+        let after = after.trim_start();
+        let (value, remaining) = match after.strip_prefix('"') {
+            Some(quoted) => match quoted.split_once('"') {
+                Some((value, remaining)) => (value, remaining),
+                None => (quoted, ""),
+            },
+            None => after.split_once(',').unwrap_or((after, "")),
+        };
+        result.insert(key, value.to_string());
+        rest = remaining.trim_start_matches(',').trim();
+    }
+    result
+}
+
+/// Numeric version parts and suffix of a tag like `v1.25.3-alpine`
+pub(crate) fn version_key(tag: &str) -> Option<(Vec<u64>, &str)> {
+    let tag = tag.strip_prefix('v').unwrap_or(tag);
+    let (version, suffix) = tag.split_once('-').unwrap_or((tag, ""));
+    let parts: Option<Vec<u64>> = version.split('.').map(|p| p.parse().ok()).collect();
+    parts.filter(|p| !p.is_empty()).map(|p| (p, suffix))
+}
+
+/// Tags of the same shape (part count and suffix) that sort above `current`
+fn newer_tags(tags: &[String], current: &str) -> Vec<String> {
+    let Some((current_parts, current_suffix)) = version_key(current) else {
+        return Vec::new();
+    };
+
+    let mut newer: Vec<(Vec<u64>, String)> = tags
+        .iter()
+        .filter_map(|tag| version_key(tag).map(|(parts, suffix)| (parts, suffix, tag)))
+        .filter(|(parts, suffix, _)| {
+            parts.len() == current_parts.len() && *suffix == current_suffix && *parts > current_parts
+        })
+        .map(|(parts, _, tag)| (parts, tag.clone()))
+        .collect();
+    newer.sort_by(|a, b| b.0.cmp(&a.0));
+    newer.into_iter().map(|(_, tag)| tag).collect()
+}
+
+/// base64 `user:password` for a registry from a dockerconfigjson pull secret
+fn secret_credentials(secret: &Secret, registry: &str) -> Option<String> {
+    let data = secret.data.as_ref()?;
+    let config = data.get(".dockerconfigjson").or_else(|| data.get(".dockercfg"))?;
+    let config: serde_json::Value = serde_json::from_slice(&config.0).ok()?;
+    let auths = config.get("auths").unwrap_or(&config).as_object()?;
+
+    let matches = |key: &str| {
+        let host = key
+            .trim_start_matches("https://")
+            .trim_start_matches("http://")
+            .split('/')
+            .next()
+            .unwrap_or_default();
+        host == registry
+            || (registry == "docker.io" && matches!(host, "index.docker.io" | "registry-1.docker.io" | "docker.io"))
+    };
+
+    let entry = auths.iter().find(|(key, _)| matches(key)).map(|(_, v)| v)?;
+    if let Some(auth) = entry.get("auth").and_then(|a| a.as_str()) {
+        return Some(auth.to_string());
+    }
+    let username = entry.get("username").and_then(|u| u.as_str())?;
+    let password = entry.get("password").and_then(|p| p.as_str())?;
+    Some(base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", username, password)))
+}
+
+/// List an image's tags and digests and compare them with what runs in the cluster
+///
+/// Works with any registry speaking the v2 distribution API, including
+/// Docker Hub, GHCR, GAR and ECR. Credentials come from `pull_secret` in
+/// `namespace`, or else from the first pull secret of a pod running the image;
+/// ECR needs one since its tokens are only issued through AWS APIs.
+pub async fn list_image_tags(
+    client: Client,
+    image: &str,
+    namespace: Option<&str>,
+    pull_secret: Option<&str>,
+) -> Result<RegistryTags> {
+    let reference = parse_image_ref(image);
+    let ImageRef { registry, repository, .. } = &reference;
+
+    let pods: Api<Pod> = match namespace.filter(|ns| !ns.is_empty()) {
+        Some(ns) => Api::namespaced(client.clone(), ns),
+        None => Api::all(client.clone()),
+    };
+    let pod_list = pods.list(&ListParams::default()).await?;
+
+    // Pods running any tag of this repository
+    let mut deployed = Vec::new();
+    let mut discovered_secret: Option<(String, String)> = None;
+    for pod in &pod_list.items {
+        let pod_ns = pod.namespace().unwrap_or_default();
+        let Some(spec) = &pod.spec else { continue };
+
+        for container in &spec.containers {
+            let Some(container_image) = &container.image else { continue };
+            let parsed = parse_image_ref(container_image);
+            if parsed.registry != *registry || parsed.repository != *repository {
+                continue;
+            }
+
+            if discovered_secret.is_none() {
+                discovered_secret = spec
+                    .image_pull_secrets
+                    .iter()
+                    .flatten()
+                    .next()
+                    .map(|s| (pod_ns.clone(), s.name.clone()));
+            }
+
+            // imageID is `<repo>@sha256:...` once the image has been pulled
+            let running_digest = pod
+                .status
+                .as_ref()
+                .and_then(|s| s.container_statuses.as_ref())
+                .and_then(|statuses| statuses.iter().find(|s| s.name == container.name))
+                .and_then(|s| s.image_id.split_once('@').map(|(_, digest)| digest.to_string()));
+
+            deployed.push(DeployedImageTag {
+                namespace: pod_ns.clone(),
+                pod: pod.name_any(),
+                container: container.name.clone(),
+                tag: parsed.tag.clone().or(parsed.digest.clone()).unwrap_or_else(|| "latest".to_string()),
+                running_digest,
+                registry_digest: None,
+                up_to_date: None,
+                newer_tags: Vec::new(),
+            });
+        }
+    }
+
+    let secret_ref = match (pull_secret, namespace) {
+        (Some(name), Some(ns)) => Some((ns.to_string(), name.to_string())),
+        (Some(_), None) => return Err(anyhow::anyhow!("A namespace is required with a pull secret")),
+        (None, _) => discovered_secret,
+    };
+    let basic = match &secret_ref {
+        Some((ns, name)) => {
+            let secret = Api::<Secret>::namespaced(client, ns)
+                .get(name)
+                .await
+                .with_context(|| format!("Failed to read pull secret {}/{}", ns, name))?;
+            secret_credentials(&secret, registry)
+        }
+        None => None,
+    };
+
+    let mut registry_client = RegistryClient::new(registry, basic)?;
+    let (tag_names, truncated) = registry_client.list_tags(repository).await?;
+
+    // Digests for deployed tags, then the newest versions
+    let mut digests: HashMap<String, Option<String>> = HashMap::new();
+    let mut lookups: Vec<String> = deployed
+        .iter()
+        .map(|d| d.tag.clone())
+        .filter(|t| !t.starts_with("sha256:"))
+        .collect();
+    let mut by_version: Vec<&String> = tag_names.iter().filter(|t| version_key(t).is_some()).collect();
+    by_version.sort_by(|a, b| version_key(b).cmp(&version_key(a)));
+    lookups.extend(by_version.into_iter().take(DIGEST_LOOKUPS).cloned());
+    if tag_names.iter().any(|t| t == "latest") {
+        lookups.push("latest".to_string());
+    }
+    for tag in lookups {
+        if !digests.contains_key(&tag) {
+            let digest = registry_client.tag_digest(repository, &tag).await;
+            digests.insert(tag, digest);
+        }
+    }
+
+    for entry in &mut deployed {
+        entry.registry_digest = digests.get(&entry.tag).cloned().flatten();
+        entry.up_to_date = match (&entry.running_digest, &entry.registry_digest) {
+            (Some(running), Some(current)) => Some(running == current),
+            _ => None,
+        };
+        entry.newer_tags = newer_tags(&tag_names, &entry.tag);
+    }
+
+    let mut tags: Vec<RegistryTag> = tag_names
+        .iter()
+        .map(|tag| RegistryTag {
+            tag: tag.clone(),
+            digest: digests.get(tag).cloned().flatten(),
+            deployed: deployed.iter().any(|d| d.tag == *tag),
+        })
+        .collect();
+    tags.sort_by(|a, b| version_key(&b.tag).cmp(&version_key(&a.tag)).then_with(|| a.tag.cmp(&b.tag)));
+
+    Ok(RegistryTags {
+        registry: registry.clone(),
+        repository: repository.clone(),
+        pull_secret: secret_ref.map(|(ns, name)| format!("{}/{}", ns, name)),
+        tags,
+        truncated,
+        deployed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_challenge_and_newer_tags() {
+        let params = parse_challenge(
+            r#"realm="https://ghcr.io/token",service="ghcr.io",scope="repository:org/app:pull""#,
+        );
+        assert_eq!(params.get("realm").map(|s| s.as_str()), Some("https://ghcr.io/token"));
+        assert_eq!(params.get("scope").map(|s| s.as_str()), Some("repository:org/app:pull"));
+
+        let tags: Vec<String> = ["1.24.0", "1.25.3", "1.25.3-alpine", "1.26.1-alpine", "1.26.0", "latest", "1.26"]
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+        assert_eq!(newer_tags(&tags, "1.25.3"), vec!["1.26.0"]);
+        assert_eq!(newer_tags(&tags, "v1.25.3-alpine"), vec!["1.26.1-alpine"]);
+        assert!(newer_tags(&tags, "latest").is_empty());
+    }
+}
@@ -0,0 +1,225 @@
+use chrono::Utc;
+use http_body_util::BodyExt;
+use k8s_openapi::http::{header, HeaderMap, Method, Request, Response, StatusCode};
+use kube::client::Body;
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tower::util::ServiceExt;
+use tower::{BoxError, Layer, Service};
+
+use crate::types::ApiRetryStats;
+
+/// Retries after the first attempt; with the delays below a request gives up after ~6s
+const MAX_RETRIES: u32 = 4;
+const BASE_DELAY_MS: u64 = 200;
+const MAX_DELAY_MS: u64 = 4000;
+
+/// Longest Retry-After honored; a server asking for more gets the request back as failed sooner
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(30);
+
+/// Patch content types that give the same result when applied twice; a JSON
+/// patch can append or remove by index, so it isn't retried
+const IDEMPOTENT_PATCH_TYPES: &[&str] = &[
+    "application/merge-patch+json",
+    "application/strategic-merge-patch+json",
+    "application/apply-patch+yaml",
+];
+
+static STATS: Mutex<ApiRetryStats> = Mutex::new(ApiRetryStats {
+    retried_requests: 0,
+    retries: 0,
+    gave_up: 0,
+    last_error: None,
+    last_retry_at: None,
+});
+
+/// How many requests needed retrying since startup, for a "flaky connection" indicator
+pub fn retry_stats() -> ApiRetryStats {
+    STATS.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Only requests that are safe to repeat: reads (lists, gets, watches) and
+/// merge or apply patches
+///
+/// Creates and deletes aren't retried, and neither are evictions, whose 429
+/// means a disruption budget said no rather than "slow down".
+fn is_retryable(method: &Method, headers: &HeaderMap) -> bool {
+    if *method == Method::PATCH {
+        let content_type = headers.get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok()).unwrap_or_default();
+        return IDEMPOTENT_PATCH_TYPES
+            .iter()
+            .any(|patch_type| content_type.starts_with(patch_type));
+    }
+    *method == Method::GET || *method == Method::HEAD
+}
+
+/// The delay a 429 or 503 asks for in Retry-After, when given in seconds
+fn retry_after(status: StatusCode, headers: &HeaderMap) -> Option<Duration> {
+    if status != StatusCode::TOO_MANY_REQUESTS && status != StatusCode::SERVICE_UNAVAILABLE {
+        return None;
+    }
+    let seconds: u64 = headers.get(header::RETRY_AFTER)?.to_str().ok()?.trim().parse().ok()?;
+    Some(Duration::from_secs(seconds).min(MAX_RETRY_AFTER))
+}
+
+/// Throttling and the gateway errors a load balancer or a restarting API server produce
+fn is_transient_status(status: StatusCode) -> bool {
+    matches!(status.as_u16(), 429 | 500 | 502 | 503 | 504)
+}
+
+/// Exponential backoff with equal jitter: half the delay fixed, half random
+fn backoff(retry: u32) -> Duration {
+    let ceiling = (BASE_DELAY_MS << retry.min(16)).min(MAX_DELAY_MS);
+    let jitter = RandomState::new().build_hasher().finish() % (ceiling / 2 + 1);
+    Duration::from_millis(ceiling / 2 + jitter)
+}
+
+fn record_retry(request: &str, reason: &str, retry: u32) {
+    tracing::warn!("Retrying {} ({} of {}): {}", request, retry, MAX_RETRIES, reason);
+    let mut stats = STATS.lock().unwrap_or_else(|e| e.into_inner());
+    if retry == 1 {
+        stats.retried_requests += 1;
+    }
+    stats.retries += 1;
+    stats.last_error = Some(reason.to_string());
+    stats.last_retry_at = Some(Utc::now().to_rfc3339());
+}
+
+fn record_gave_up() {
+    STATS.lock().unwrap_or_else(|e| e.into_inner()).gave_up += 1;
+}
+
+/// Tower layer retrying transient API failures; the wrapped service must be
+/// cloneable, so it goes above a buffer
+pub struct RetryLayer;
+
+impl<S> Layer<S> for RetryLayer {
+    type Service = Retry<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Retry { inner }
+    }
+}
+
+#[derive(Clone)]
+pub struct Retry<S> {
+    inner: S,
+}
+
+impl<S, B> Service<Request<Body>> for Retry<S>
+where
+    S: Service<Request<Body>, Response = Response<B>> + Clone + Send + 'static,
+    S::Future: Send,
+    S::Error: Into<BoxError>,
+    B: Send + 'static,
+{
+    type Response = Response<B>;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Response<B>, BoxError>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        // Swap out the service that was polled ready, as tower requires
+        let clone = self.inner.clone();
+        let mut ready = std::mem::replace(&mut self.inner, clone);
+
+        if !is_retryable(request.method(), request.headers()) {
+            let future = ready.call(request);
+            return Box::pin(async move { future.await.map_err(Into::into) });
+        }
+
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+            // Buffered once so every attempt can send the same body
+            let body = body.collect().await?.to_bytes().to_vec();
+            let label = format!("{} {}", parts.method, parts.uri.path());
+            let attempt = |parts: &k8s_openapi::http::request::Parts| {
+                let mut request = Request::builder()
+                    .method(parts.method.clone())
+                    .uri(parts.uri.clone())
+                    .version(parts.version)
+                    .body(Body::from(body.clone()))
+                    .expect("request parts were already valid");
+                *request.headers_mut() = parts.headers.clone();
+                *request.extensions_mut() = parts.extensions.clone();
+                request
+            };
+
+            let mut retry = 0;
+            loop {
+                // The first attempt uses the ready service; retries go through clones,
+                // which wait for readiness themselves
+                let result = if retry == 0 {
+                    ready.call(attempt(&parts)).await
+                } else {
+                    ready.clone().oneshot(attempt(&parts)).await
+                }
+                .map_err(Into::into);
+                let (reason, asked) = match &result {
+                    Ok(response) if is_transient_status(response.status()) => (
+                        response.status().to_string(),
+                        retry_after(response.status(), response.headers()),
+                    ),
+                    Ok(_) => {
+                        if retry > 0 {
+                            tracing::info!("{} succeeded after {} retries", label, retry);
+                        }
+                        return result;
+                    }
+                    Err(e) => (e.to_string(), None),
+                };
+                if retry == MAX_RETRIES {
+                    record_gave_up();
+                    return result;
+                }
+                retry += 1;
+                record_retry(&label, &reason, retry);
+                // A server's Retry-After wins when it asks for longer than the backoff
+                tokio::time::sleep(backoff(retry).max(asked.unwrap_or_default())).await;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_and_classification() {
+        for retry in 1..=MAX_RETRIES {
+            let ceiling = (BASE_DELAY_MS << retry).min(MAX_DELAY_MS);
+            let delay = backoff(retry).as_millis() as u64;
+            assert!(delay >= ceiling / 2 && delay <= ceiling);
+        }
+        assert!(is_transient_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_transient_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(!is_transient_status(StatusCode::NOT_FOUND));
+        assert!(!is_transient_status(StatusCode::NOT_IMPLEMENTED));
+        let patch = |content_type: &'static str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::CONTENT_TYPE, content_type.parse().unwrap());
+            headers
+        };
+        assert!(is_retryable(&Method::GET, &HeaderMap::new()));
+        assert!(is_retryable(&Method::PATCH, &patch("application/merge-patch+json")));
+        assert!(is_retryable(&Method::PATCH, &patch("application/apply-patch+yaml")));
+        assert!(!is_retryable(&Method::PATCH, &patch("application/json-patch+json")));
+        assert!(!is_retryable(&Method::POST, &HeaderMap::new()));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::RETRY_AFTER, "3".parse().unwrap());
+        assert_eq!(retry_after(StatusCode::TOO_MANY_REQUESTS, &headers), Some(Duration::from_secs(3)));
+        assert_eq!(retry_after(StatusCode::BAD_GATEWAY, &headers), None);
+        headers.insert(header::RETRY_AFTER, "3600".parse().unwrap());
+        assert_eq!(retry_after(StatusCode::SERVICE_UNAVAILABLE, &headers), Some(MAX_RETRY_AFTER));
+    }
+}
@@ -0,0 +1,325 @@
+use anyhow::Result;
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, StatefulSet};
+use k8s_openapi::api::core::v1::Pod;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
+use kube::api::{Api, ListParams};
+use kube::{Client, ResourceExt};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::{oneshot, Mutex};
+use uuid::Uuid;
+
+use crate::kube::operations::FAILING_WAIT_REASONS;
+use crate::types::{RolloutProblem, RolloutProgress, RolloutResult};
+
+const POLL_INTERVAL_SECS: u64 = 2;
+
+/// Used when the workload has no progressDeadlineSeconds of its own
+const DEFAULT_TIMEOUT_SECS: u64 = 600;
+
+/// How long a pod may sit in a failing state before the rollout is declared failed
+const FAILURE_GRACE_SECS: u64 = 90;
+
+/// Waiting reasons that never resolve on their own, so fail without a grace period
+const FATAL_WAIT_REASONS: &[&str] = &["InvalidImageName", "CreateContainerConfigError"];
+
+/// Follows Deployment, StatefulSet and DaemonSet rollouts until they settle
+pub struct RolloutWatchManager {
+    watches: Arc<Mutex<HashMap<String, oneshot::Sender<()>>>>,
+}
+
+impl RolloutWatchManager {
+    pub fn new() -> Self {
+        Self {
+            watches: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Poll a workload, emitting `rollout-progress-{watch_id}` on every change and
+    /// a single `rollout-complete-{watch_id}` with the outcome
+    ///
+    /// The outcome is `success`, `failed` (deadline exceeded or a pod stuck
+    /// crashing/pulling, with that pod's reason), `timeout` or `cancelled`.
+    pub async fn start_watch(
+        &self,
+        app: AppHandle,
+        client: Client,
+        kind: &str,
+        namespace: &str,
+        name: &str,
+        timeout_secs: Option<u64>,
+    ) -> Result<String> {
+        // Fail fast on a bad kind or name instead of from the background task
+        let first = check_rollout(&client, kind, namespace, name).await?;
+        let timeout = Duration::from_secs(timeout_secs.or(first.deadline_secs).unwrap_or(DEFAULT_TIMEOUT_SECS));
+
+        let watch_id = Uuid::new_v4().to_string();
+        let (cancel_tx, mut cancel_rx) = oneshot::channel::<()>();
+        self.watches.lock().await.insert(watch_id.clone(), cancel_tx);
+
+        let watches = self.watches.clone();
+        let id = watch_id.clone();
+        let (kind, namespace, name) = (kind.to_string(), namespace.to_string(), name.to_string());
+        tokio::spawn(async move {
+            let progress_event = format!("rollout-progress-{}", id);
+            let started = Instant::now();
+            let mut last: Option<RolloutProgress> = None;
+            let mut failing_since: HashMap<String, Instant> = HashMap::new();
+
+            let result = loop {
+                let progress = match check_rollout(&client, &kind, &namespace, &name).await {
+                    Ok(status) => status.progress,
+                    Err(e) => break outcome("failed", format!("Could not read {}/{}: {}", kind, name, e), None),
+                };
+
+                let changed = last
+                    .as_ref()
+                    .map(|l| l.message != progress.message || l.problems.len() != progress.problems.len())
+                    .unwrap_or(true);
+                if changed {
+                    let _ = app.emit(&progress_event, &progress);
+                }
+
+                if progress.done {
+                    break outcome("success", progress.message.clone(), None);
+                }
+                if let Some(failure) = &progress.failure {
+                    break outcome("failed", failure.clone(), progress.problems.first().cloned());
+                }
+
+                // Forget pods that recovered, then fail on one stuck past the grace period
+                failing_since.retain(|pod, _| progress.problems.iter().any(|p| &p.pod == pod));
+                let stuck = progress.problems.iter().find(|problem| {
+                    let since = *failing_since.entry(problem.pod.clone()).or_insert_with(Instant::now);
+                    FATAL_WAIT_REASONS.contains(&problem.reason.as_str())
+                        || since.elapsed() >= Duration::from_secs(FAILURE_GRACE_SECS)
+                });
+                if let Some(problem) = stuck {
+                    break outcome(
+                        "failed",
+                        format!("Pod {} is failing: {}", problem.pod, problem.reason),
+                        Some(problem.clone()),
+                    );
+                }
+
+                if started.elapsed() >= timeout {
+                    break outcome(
+                        "timeout",
+                        format!("Rollout did not finish within {}s: {}", timeout.as_secs(), progress.message),
+                        progress.problems.first().cloned(),
+                    );
+                }
+                last = Some(progress);
+
+                tokio::select! {
+                    _ = tokio::time::sleep(Duration::from_secs(POLL_INTERVAL_SECS)) => {}
+                    _ = &mut cancel_rx => break outcome("cancelled", "Stopped watching".to_string(), None),
+                }
+            };
+
+            let _ = app.emit(&format!("rollout-complete-{}", id), &result);
+            watches.lock().await.remove(&id);
+        });
+
+        Ok(watch_id)
+    }
+
+    pub async fn stop_watch(&self, watch_id: &str) -> Result<()> {
+        if let Some(cancel) = self.watches.lock().await.remove(watch_id) {
+            let _ = cancel.send(());
+        }
+        Ok(())
+    }
+}
+
+impl Default for RolloutWatchManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn outcome(outcome: &str, message: String, problem: Option<RolloutProblem>) -> RolloutResult {
+    RolloutResult {
+        outcome: outcome.to_string(),
+        message,
+        problem,
+    }
+}
+
+pub(crate) struct RolloutStatus {
+    pub(crate) progress: RolloutProgress,
+    pub(crate) deadline_secs: Option<u64>,
+}
+
+/// One look at a rollout, following `kubectl rollout status`'s rules per kind
+pub(crate) async fn check_rollout(client: &Client, kind: &str, namespace: &str, name: &str) -> Result<RolloutStatus> {
+    let mut failure = None;
+    let mut deadline_secs = None;
+
+    let (desired, updated, ready, available, message, selector) = match kind {
+        "Deployment" => {
+            let deployment = Api::<Deployment>::namespaced(client.clone(), namespace).get(name).await?;
+            let spec = deployment.spec.as_ref();
+            let status = deployment.status.clone().unwrap_or_default();
+            let desired = spec.and_then(|s| s.replicas).unwrap_or(1);
+            let updated = status.updated_replicas.unwrap_or(0);
+            let total = status.replicas.unwrap_or(0);
+            let available = status.available_replicas.unwrap_or(0);
+            deadline_secs = spec.and_then(|s| s.progress_deadline_seconds).map(|s| s as u64);
+
+            if status
+                .conditions
+                .iter()
+                .flatten()
+                .any(|c| c.type_ == "Progressing" && c.reason.as_deref() == Some("ProgressDeadlineExceeded"))
+            {
+                failure = Some(format!("Deployment {} exceeded its progress deadline", name));
+            }
+
+            let message = if status.observed_generation < deployment.metadata.generation {
+                "Waiting for the rollout to be observed".to_string()
+            } else if updated < desired {
+                format!("{} of {} new replicas updated", updated, desired)
+            } else if total > updated {
+                format!("{} old replicas pending termination", total - updated)
+            } else if available < updated {
+                format!("{} of {} updated replicas available", available, updated)
+            } else {
+                format!("Deployment {} successfully rolled out", name)
+            };
+            let selector = spec.map(|s| s.selector.clone());
+            (desired, updated, status.ready_replicas.unwrap_or(0), available, message, selector)
+        }
+        "StatefulSet" => {
+            let sts = Api::<StatefulSet>::namespaced(client.clone(), namespace).get(name).await?;
+            let spec = sts.spec.as_ref();
+            let status = sts.status.clone().unwrap_or_default();
+            let desired = spec.and_then(|s| s.replicas).unwrap_or(1);
+            let ready = status.ready_replicas.unwrap_or(0);
+            let updated = status.updated_replicas.unwrap_or(0);
+            let partition = spec
+                .and_then(|s| s.update_strategy.as_ref())
+                .and_then(|u| u.rolling_update.as_ref())
+                .and_then(|r| r.partition)
+                .unwrap_or(0);
+
+            let message = if status.observed_generation < sts.metadata.generation {
+                "Waiting for the rollout to be observed".to_string()
+            } else if ready < desired {
+                format!("{} of {} pods ready", ready, desired)
+            } else if partition > 0 && updated < desired - partition {
+                format!("{} of {} pods updated (partition {})", updated, desired - partition, partition)
+            } else if partition == 0 && status.update_revision != status.current_revision {
+                format!("{} of {} pods updated", updated, desired)
+            } else {
+                format!("StatefulSet {} successfully rolled out", name)
+            };
+            let selector = spec.map(|s| s.selector.clone());
+            (desired, updated, ready, status.available_replicas.unwrap_or(0), message, selector)
+        }
+        "DaemonSet" => {
+            let ds = Api::<DaemonSet>::namespaced(client.clone(), namespace).get(name).await?;
+            let status = ds.status.clone().unwrap_or_default();
+            let desired = status.desired_number_scheduled;
+            let updated = status.updated_number_scheduled.unwrap_or(0);
+            let available = status.number_available.unwrap_or(0);
+
+            let message = if status.observed_generation < ds.metadata.generation {
+                "Waiting for the rollout to be observed".to_string()
+            } else if updated < desired {
+                format!("{} of {} nodes updated", updated, desired)
+            } else if available < desired {
+                format!("{} of {} updated pods available", available, desired)
+            } else {
+                format!("DaemonSet {} successfully rolled out", name)
+            };
+            let selector = ds.spec.as_ref().map(|s| s.selector.clone());
+            (desired, updated, status.number_ready, available, message, selector)
+        }
+        other => return Err(anyhow::anyhow!("Cannot watch rollouts of {}", other)),
+    };
+
+    let done = message.ends_with("successfully rolled out");
+    let problems = if done {
+        Vec::new()
+    } else {
+        pod_problems(client, namespace, selector.as_ref()).await
+    };
+
+    Ok(RolloutStatus {
+        progress: RolloutProgress {
+            kind: kind.to_string(),
+            namespace: namespace.to_string(),
+            name: name.to_string(),
+            desired,
+            updated,
+            ready,
+            available,
+            done,
+            message,
+            failure,
+            problems,
+        },
+        deadline_secs,
+    })
+}
+
+/// Pods of the workload that are crashing, failing to pull or unschedulable
+async fn pod_problems(client: &Client, namespace: &str, selector: Option<&LabelSelector>) -> Vec<RolloutProblem> {
+    let Some(labels) = selector.and_then(|s| s.match_labels.as_ref()) else {
+        return Vec::new();
+    };
+    let selector: Vec<String> = labels.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+    let pods = match Api::<Pod>::namespaced(client.clone(), namespace)
+        .list(&ListParams::default().labels(&selector.join(",")))
+        .await
+    {
+        Ok(pods) => pods.items,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut problems = Vec::new();
+    for pod in &pods {
+        let Some(status) = &pod.status else { continue };
+
+        let waiting = status
+            .container_statuses
+            .iter()
+            .chain(status.init_container_statuses.iter())
+            .flatten()
+            .find_map(|cs| {
+                let waiting = cs.state.as_ref()?.waiting.as_ref()?;
+                let reason = waiting.reason.clone()?;
+                FAILING_WAIT_REASONS
+                    .contains(&reason.as_str())
+                    .then(|| (cs.name.clone(), reason, waiting.message.clone()))
+            });
+        if let Some((container, reason, message)) = waiting {
+            problems.push(RolloutProblem {
+                pod: pod.name_any(),
+                container: Some(container),
+                reason,
+                message,
+            });
+            continue;
+        }
+
+        let unschedulable = status
+            .conditions
+            .iter()
+            .flatten()
+            .find(|c| c.type_ == "PodScheduled" && c.status == "False");
+        if let Some(condition) = unschedulable {
+            problems.push(RolloutProblem {
+                pod: pod.name_any(),
+                container: None,
+                reason: condition.reason.clone().unwrap_or_else(|| "Unschedulable".to_string()),
+                message: condition.message.clone(),
+            });
+        }
+    }
+
+    problems
+}
@@ -0,0 +1,162 @@
+use anyhow::{Context, Result};
+use base64::Engine;
+use chrono::{Duration, Utc};
+use k8s_openapi::api::authentication::v1::{TokenRequest, TokenRequestSpec};
+use k8s_openapi::api::core::v1::ServiceAccount;
+use k8s_openapi::api::rbac::v1::{RoleBinding, RoleRef, Subject};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::api::{Api, PostParams};
+use kube::Client;
+
+use crate::kube::config::{get_current_context, load_kubeconfig};
+use crate::kube::events::parse_duration;
+use crate::types::ScopedKubeconfig;
+
+const DEFAULT_TOKEN_DURATION: &str = "8h";
+
+/// The API server rejects TokenRequests shorter than this
+const MIN_TOKEN_SECS: i64 = 600;
+
+/// ClusterRoles that may be bound into the namespace
+const BINDABLE_ROLES: &[&str] = &["view", "edit", "admin"];
+
+/// Build a kubeconfig that authenticates as a ServiceAccount limited to one namespace
+///
+/// With `role`, the ServiceAccount is created if needed and bound to that
+/// built-in ClusterRole with a RoleBinding in `namespace`; without it, the
+/// ServiceAccount and its permissions must already exist. The token comes
+/// from the TokenRequest API, so it expires on its own and no long-lived
+/// Secret is created. Server and CA are taken from the current context.
+pub async fn generate_scoped_kubeconfig(
+    client: Client,
+    namespace: &str,
+    service_account: &str,
+    duration: Option<&str>,
+    role: Option<&str>,
+) -> Result<ScopedKubeconfig> {
+    let seconds = parse_duration(duration.unwrap_or(DEFAULT_TOKEN_DURATION))?.num_seconds();
+    if seconds < MIN_TOKEN_SECS {
+        return Err(anyhow::anyhow!("Token duration must be at least 10m"));
+    }
+
+    let (context_name, server, ca_data) = current_cluster()?;
+    let mut created = Vec::new();
+
+    let accounts: Api<ServiceAccount> = Api::namespaced(client.clone(), namespace);
+    if let Some(role) = role {
+        if !BINDABLE_ROLES.contains(&role) {
+            return Err(anyhow::anyhow!("Role must be one of {}", BINDABLE_ROLES.join(", ")));
+        }
+
+        if accounts.get_opt(service_account).await?.is_none() {
+            let account = ServiceAccount {
+                metadata: ObjectMeta {
+                    name: Some(service_account.to_string()),
+                    namespace: Some(namespace.to_string()),
+                    labels: Some([("app.kubernetes.io/managed-by".to_string(), "kubesail".to_string())].into()),
+                    ..ObjectMeta::default()
+                },
+                ..ServiceAccount::default()
+            };
+            accounts.create(&PostParams::default(), &account).await?;
+            created.push(format!("ServiceAccount/{}", service_account));
+        }
+
+        let bindings: Api<RoleBinding> = Api::namespaced(client.clone(), namespace);
+        let binding_name = format!("{}-{}", service_account, role);
+        if bindings.get_opt(&binding_name).await?.is_none() {
+            let binding = RoleBinding {
+                metadata: ObjectMeta {
+                    name: Some(binding_name.clone()),
+                    namespace: Some(namespace.to_string()),
+                    labels: Some([("app.kubernetes.io/managed-by".to_string(), "kubesail".to_string())].into()),
+                    ..ObjectMeta::default()
+                },
+                role_ref: RoleRef {
+                    api_group: "rbac.authorization.k8s.io".to_string(),
+                    kind: "ClusterRole".to_string(),
+                    name: role.to_string(),
+                },
+                subjects: Some(vec![Subject {
+                    kind: "ServiceAccount".to_string(),
+                    name: service_account.to_string(),
+                    namespace: Some(namespace.to_string()),
+                    ..Subject::default()
+                }]),
+            };
+            bindings.create(&PostParams::default(), &binding).await?;
+            created.push(format!("RoleBinding/{}", binding_name));
+        }
+    } else if accounts.get_opt(service_account).await?.is_none() {
+        return Err(anyhow::anyhow!(
+            "ServiceAccount {}/{} does not exist; pick a role to create it",
+            namespace,
+            service_account
+        ));
+    }
+
+    let request = TokenRequest {
+        spec: TokenRequestSpec {
+            expiration_seconds: Some(seconds),
+            ..TokenRequestSpec::default()
+        },
+        ..TokenRequest::default()
+    };
+    let response = accounts
+        .create_token_request(service_account, &PostParams::default(), &request)
+        .await?;
+    let status = response
+        .status
+        .ok_or_else(|| anyhow::anyhow!("TokenRequest returned no token"))?;
+
+    let name = format!("{}-{}", service_account, context_name);
+    let mut cluster = serde_json::json!({ "server": server });
+    if let Some(ca_data) = ca_data {
+        cluster["certificate-authority-data"] = serde_json::json!(ca_data);
+    }
+    let kubeconfig = serde_json::json!({
+        "apiVersion": "v1",
+        "kind": "Config",
+        "current-context": name,
+        "clusters": [{ "name": context_name, "cluster": cluster }],
+        "users": [{ "name": name, "user": { "token": status.token } }],
+        "contexts": [{
+            "name": name,
+            "context": { "cluster": context_name, "user": name, "namespace": namespace }
+        }]
+    });
+
+    Ok(ScopedKubeconfig {
+        context_name: name,
+        namespace: namespace.to_string(),
+        service_account: service_account.to_string(),
+        // The server may shorten the lifetime, so prefer what it reports
+        expires_at: status.expiration_timestamp.0.to_rfc3339(),
+        requested_expires_at: (Utc::now() + Duration::seconds(seconds)).to_rfc3339(),
+        created,
+        kubeconfig: serde_yaml::to_string(&kubeconfig)?,
+    })
+}
+
+/// Current context name, server URL and inline CA data
+fn current_cluster() -> Result<(String, String, Option<String>)> {
+    let config = load_kubeconfig()?;
+    let context = get_current_context(&config).ok_or_else(|| anyhow::anyhow!("No current context"))?;
+    let cluster = config
+        .clusters
+        .iter()
+        .find(|c| c.name == context.context.cluster)
+        .ok_or_else(|| anyhow::anyhow!("Cluster {} not found in kubeconfig", context.context.cluster))?;
+
+    // A CA file path means nothing on the recipient's machine, so inline it
+    let ca_data = match (&cluster.cluster.certificate_authority_data, &cluster.cluster.certificate_authority) {
+        (Some(data), _) => Some(data.clone()),
+        (None, Some(path)) => {
+            let pem = std::fs::read(path).with_context(|| format!("Failed to read CA file {}", path))?;
+            Some(base64::engine::general_purpose::STANDARD.encode(pem))
+        }
+        (None, None) => None,
+    };
+
+    Ok((context.name.clone(), cluster.cluster.server.clone(), ca_data))
+}
@@ -0,0 +1,227 @@
+use anyhow::Result;
+use k8s_openapi::api::apps::v1::{Deployment, ReplicaSet, StatefulSet};
+use k8s_openapi::api::core::v1::{Node, Pod, PodTemplateSpec};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
+use kube::api::{Api, ListParams};
+use kube::{Client, ResourceExt};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::types::{DomainCount, SpreadAnalysis, SpreadConstraintStatus};
+
+const ZONE_LABEL: &str = "topology.kubernetes.io/zone";
+const LEGACY_ZONE_LABEL: &str = "failure-domain.beta.kubernetes.io/zone";
+const HOSTNAME_LABEL: &str = "kubernetes.io/hostname";
+
+/// Where a node sits for a topology key; hostname falls back to the node name
+fn topology_value(node: &Node, key: &str) -> Option<String> {
+    let labels = node.labels();
+    match key {
+        ZONE_LABEL => labels.get(ZONE_LABEL).or_else(|| labels.get(LEGACY_ZONE_LABEL)).cloned(),
+        HOSTNAME_LABEL => labels.get(HOSTNAME_LABEL).cloned().or_else(|| Some(node.name_any())),
+        other => labels.get(other).cloned(),
+    }
+}
+
+fn counts(map: BTreeMap<String, usize>) -> Vec<DomainCount> {
+    let mut counts: Vec<DomainCount> = map.into_iter().map(|(name, pods)| DomainCount { name, pods }).collect();
+    counts.sort_by(|a, b| b.pods.cmp(&a.pods).then_with(|| a.name.cmp(&b.name)));
+    counts
+}
+
+/// How a workload's pods are spread over nodes and zones, against what it asks for
+///
+/// Skew for each topologySpreadConstraint is computed the way the scheduler
+/// does: max minus min pods over every domain that has a schedulable node.
+/// Single-node and single-zone placement is flagged as a risk.
+pub async fn analyze_spread(client: Client, kind: &str, namespace: &str, name: &str) -> Result<SpreadAnalysis> {
+    let (template, selector, replicas) = match kind {
+        "Deployment" => {
+            let spec = Api::<Deployment>::namespaced(client.clone(), namespace)
+                .get(name)
+                .await?
+                .spec
+                .ok_or_else(|| anyhow::anyhow!("Deployment {} has no spec", name))?;
+            (spec.template, spec.selector, spec.replicas.unwrap_or(1))
+        }
+        "StatefulSet" => {
+            let spec = Api::<StatefulSet>::namespaced(client.clone(), namespace)
+                .get(name)
+                .await?
+                .spec
+                .ok_or_else(|| anyhow::anyhow!("StatefulSet {} has no spec", name))?;
+            (spec.template, spec.selector, spec.replicas.unwrap_or(1))
+        }
+        "ReplicaSet" => {
+            let spec = Api::<ReplicaSet>::namespaced(client.clone(), namespace)
+                .get(name)
+                .await?
+                .spec
+                .ok_or_else(|| anyhow::anyhow!("ReplicaSet {} has no spec", name))?;
+            let template = spec.template.unwrap_or_default();
+            (template, spec.selector, spec.replicas.unwrap_or(1))
+        }
+        other => return Err(anyhow::anyhow!("Cannot analyze spread for {}", other)),
+    };
+
+    let label_selector = selector_string(&selector);
+    let (pod_list, node_list) = tokio::try_join!(
+        Api::<Pod>::namespaced(client.clone(), namespace).list(&ListParams::default().labels(&label_selector)),
+        Api::<Node>::all(client).list(&ListParams::default()),
+    )?;
+    let nodes: HashMap<String, &Node> = node_list.items.iter().map(|n| (n.name_any(), n)).collect();
+
+    let pods: Vec<&Pod> = pod_list
+        .items
+        .iter()
+        .filter(|p| p.metadata.deletion_timestamp.is_none())
+        .filter(|p| p.spec.as_ref().and_then(|s| s.node_name.as_ref()).is_some())
+        .collect();
+
+    let mut by_node: BTreeMap<String, usize> = BTreeMap::new();
+    let mut by_zone: BTreeMap<String, usize> = BTreeMap::new();
+    for pod in &pods {
+        let node_name = pod.spec.as_ref().and_then(|s| s.node_name.clone()).unwrap_or_default();
+        let zone = nodes
+            .get(&node_name)
+            .and_then(|n| topology_value(n, ZONE_LABEL))
+            .unwrap_or_else(|| "(no zone)".to_string());
+        *by_node.entry(node_name).or_default() += 1;
+        *by_zone.entry(zone).or_default() += 1;
+    }
+
+    let schedulable: Vec<&Node> = node_list
+        .items
+        .iter()
+        .filter(|n| !n.spec.as_ref().and_then(|s| s.unschedulable).unwrap_or(false))
+        .collect();
+    let mut cluster_zones: Vec<String> = schedulable.iter().filter_map(|n| topology_value(n, ZONE_LABEL)).collect();
+    cluster_zones.sort();
+    cluster_zones.dedup();
+
+    let constraints = spread_constraints(&template, &pods, &nodes, &schedulable);
+    let anti_affinity = anti_affinity_rules(&template);
+
+    let mut risks = Vec::new();
+    if pods.len() > 1 && by_node.len() == 1 {
+        risks.push(format!(
+            "All {} pods run on node {}; losing it takes the workload down",
+            pods.len(),
+            by_node.keys().next().cloned().unwrap_or_default()
+        ));
+    }
+    if pods.len() > 1 && by_zone.len() == 1 && cluster_zones.len() > 1 {
+        risks.push(format!(
+            "All {} pods run in zone {} although the cluster spans {} zones",
+            pods.len(),
+            by_zone.keys().next().cloned().unwrap_or_default(),
+            cluster_zones.len()
+        ));
+    }
+    for constraint in constraints.iter().filter(|c| !c.satisfied) {
+        risks.push(format!(
+            "Spread over {} has skew {} (maxSkew {}, {})",
+            constraint.topology_key, constraint.skew, constraint.max_skew, constraint.when_unsatisfiable
+        ));
+    }
+    if replicas > 1 && constraints.is_empty() && anti_affinity.is_empty() {
+        risks.push("No topologySpreadConstraints or pod anti-affinity; placement is left to chance".to_string());
+    }
+    if (pods.len() as i32) < replicas {
+        risks.push(format!("Only {} of {} replicas are scheduled", pods.len(), replicas));
+    }
+
+    Ok(SpreadAnalysis {
+        kind: kind.to_string(),
+        namespace: namespace.to_string(),
+        name: name.to_string(),
+        replicas,
+        scheduled_pods: pods.len(),
+        nodes: counts(by_node),
+        zones: counts(by_zone),
+        cluster_zones,
+        constraints,
+        anti_affinity,
+        risks,
+    })
+}
+
+fn selector_string(selector: &LabelSelector) -> String {
+    selector
+        .match_labels
+        .iter()
+        .flatten()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn spread_constraints(
+    template: &PodTemplateSpec,
+    pods: &[&Pod],
+    nodes: &HashMap<String, &Node>,
+    schedulable: &[&Node],
+) -> Vec<SpreadConstraintStatus> {
+    let Some(constraints) = template.spec.as_ref().and_then(|s| s.topology_spread_constraints.as_ref()) else {
+        return Vec::new();
+    };
+
+    constraints
+        .iter()
+        .map(|constraint| {
+            // Every domain with a schedulable node counts, even if it has no pods yet
+            let mut domains: BTreeMap<String, usize> = schedulable
+                .iter()
+                .filter_map(|n| topology_value(n, &constraint.topology_key))
+                .map(|d| (d, 0))
+                .collect();
+            for pod in pods {
+                let domain = pod
+                    .spec
+                    .as_ref()
+                    .and_then(|s| s.node_name.as_ref())
+                    .and_then(|n| nodes.get(n))
+                    .and_then(|n| topology_value(n, &constraint.topology_key));
+                if let Some(domain) = domain {
+                    *domains.entry(domain).or_default() += 1;
+                }
+            }
+
+            let max = domains.values().max().copied().unwrap_or(0);
+            let min = domains.values().min().copied().unwrap_or(0);
+            let skew = (max - min) as i32;
+
+            SpreadConstraintStatus {
+                topology_key: constraint.topology_key.clone(),
+                max_skew: constraint.max_skew,
+                when_unsatisfiable: constraint.when_unsatisfiable.clone(),
+                skew,
+                satisfied: skew <= constraint.max_skew,
+                domains: counts(domains),
+            }
+        })
+        .collect()
+}
+
+/// Pod anti-affinity terms, e.g. `required: kubernetes.io/hostname`
+fn anti_affinity_rules(template: &PodTemplateSpec) -> Vec<String> {
+    let Some(anti) = template
+        .spec
+        .as_ref()
+        .and_then(|s| s.affinity.as_ref())
+        .and_then(|a| a.pod_anti_affinity.as_ref())
+    else {
+        return Vec::new();
+    };
+
+    let required = anti
+        .required_during_scheduling_ignored_during_execution
+        .iter()
+        .flatten()
+        .map(|term| format!("required: {}", term.topology_key));
+    let preferred = anti
+        .preferred_during_scheduling_ignored_during_execution
+        .iter()
+        .flatten()
+        .map(|term| format!("preferred (weight {}): {}", term.weight, term.pod_affinity_term.topology_key));
+    required.chain(preferred).collect()
+}
@@ -0,0 +1,544 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use k8s_openapi::api::core::v1::{PersistentVolume, PersistentVolumeClaim, Pod, Volume};
+use k8s_openapi::api::storage::v1::{CSIDriver, CSINode, StorageClass, VolumeAttachment};
+use kube::api::{Api, ListParams};
+use kube::{Client, ResourceExt};
+use serde::Deserialize;
+use std::collections::{BTreeSet, HashMap};
+
+use crate::kube::throttle::{join_bounded, FAN_OUT_LIMIT};
+use crate::types::{
+    ClaimStorage, CsiDriverInfo, CsiNodeDriverInfo, CsiNodeInfo, EphemeralStorageUsage, PodStorage, PodVolumeStorage,
+    StorageClassSummary, StorageTopology, VolumeAttachmentInfo, VolumeStorage, VolumeUsage, VolumeUsageEntry,
+    VolumeUsageReport,
+};
+
+/// Default fill level, in percent, above which volumes and containers are flagged
+const DEFAULT_USAGE_THRESHOLD: f64 = 80.0;
+
+/// An attachment that hasn't completed in this long is reported as stuck
+const ATTACH_STUCK_AFTER_SECS: i64 = 120;
+
+pub async fn list_csi_drivers(client: Client) -> Result<Vec<CsiDriverInfo>> {
+    let drivers: Api<CSIDriver> = Api::all(client.clone());
+    let nodes: Api<CSINode> = Api::all(client);
+    let lp = ListParams::default();
+    let (drivers, nodes) = tokio::try_join!(drivers.list(&lp), nodes.list(&lp))?;
+
+    // How many nodes have registered each driver
+    let mut registered: HashMap<String, usize> = HashMap::new();
+    for node in &nodes.items {
+        for driver in &node.spec.drivers {
+            *registered.entry(driver.name.clone()).or_default() += 1;
+        }
+    }
+
+    Ok(drivers
+        .items
+        .into_iter()
+        .map(|driver| {
+            let name = driver.name_any();
+            let spec = driver.spec;
+            CsiDriverInfo {
+                node_count: registered.get(&name).copied().unwrap_or(0),
+                name,
+                attach_required: spec.attach_required.unwrap_or(true),
+                pod_info_on_mount: spec.pod_info_on_mount.unwrap_or(false),
+                storage_capacity: spec.storage_capacity.unwrap_or(false),
+                fs_group_policy: spec.fs_group_policy,
+                volume_lifecycle_modes: spec.volume_lifecycle_modes.unwrap_or_default(),
+            }
+        })
+        .collect())
+}
+
+pub async fn list_csi_nodes(client: Client) -> Result<Vec<CsiNodeInfo>> {
+    let nodes: Api<CSINode> = Api::all(client);
+    let nodes = nodes.list(&ListParams::default()).await?;
+
+    Ok(nodes
+        .items
+        .into_iter()
+        .map(|node| CsiNodeInfo {
+            name: node.name_any(),
+            drivers: node
+                .spec
+                .drivers
+                .into_iter()
+                .map(|driver| CsiNodeDriverInfo {
+                    name: driver.name,
+                    node_id: driver.node_id,
+                    allocatable_count: driver.allocatable.and_then(|a| a.count),
+                    topology_keys: driver.topology_keys.unwrap_or_default(),
+                })
+                .collect(),
+        })
+        .collect())
+}
+
+/// VolumeAttachments with attach/detach errors and the claim each volume belongs to
+///
+/// Attachments that are still detached after a couple of minutes, carry an
+/// error, or are stuck deleting are flagged; these are what keep pods in
+/// ContainerCreating.
+pub async fn list_volume_attachments(client: Client) -> Result<Vec<VolumeAttachmentInfo>> {
+    let attachments: Api<VolumeAttachment> = Api::all(client.clone());
+    let volumes: Api<PersistentVolume> = Api::all(client);
+    let lp = ListParams::default();
+    let (attachments, volumes) = tokio::try_join!(attachments.list(&lp), volumes.list(&lp))?;
+
+    let claims: HashMap<String, String> = volumes
+        .items
+        .iter()
+        .filter_map(|pv| {
+            let claim = pv.spec.as_ref()?.claim_ref.as_ref()?;
+            Some((
+                pv.name_any(),
+                format!(
+                    "{}/{}",
+                    claim.namespace.clone().unwrap_or_default(),
+                    claim.name.clone().unwrap_or_default()
+                ),
+            ))
+        })
+        .collect();
+
+    let now = Utc::now();
+    let mut result: Vec<VolumeAttachmentInfo> = attachments
+        .items
+        .into_iter()
+        .map(|va| {
+            let persistent_volume = va.spec.source.persistent_volume_name.clone();
+            let status = va.status.as_ref();
+            let attached = status.map(|s| s.attached).unwrap_or(false);
+            let attach_error = status
+                .and_then(|s| s.attach_error.as_ref())
+                .and_then(|e| e.message.clone());
+            let detach_error = status
+                .and_then(|s| s.detach_error.as_ref())
+                .and_then(|e| e.message.clone());
+            let deleting = va.metadata.deletion_timestamp.is_some();
+            let age_secs = va
+                .metadata
+                .creation_timestamp
+                .as_ref()
+                .map(|ts| (now - ts.0).num_seconds())
+                .unwrap_or(0);
+
+            let stuck = attach_error.is_some()
+                || detach_error.is_some()
+                || (!attached && !deleting && age_secs > ATTACH_STUCK_AFTER_SECS)
+                || (deleting && age_secs > ATTACH_STUCK_AFTER_SECS);
+
+            VolumeAttachmentInfo {
+                name: va.name_any(),
+                attacher: va.spec.attacher.clone(),
+                node: va.spec.node_name.clone(),
+                claim: persistent_volume.as_ref().and_then(|pv| claims.get(pv).cloned()),
+                persistent_volume,
+                attached,
+                attach_error,
+                detach_error,
+                deleting,
+                stuck,
+                created_at: va.metadata.creation_timestamp.as_ref().map(|ts| ts.0.to_rfc3339()),
+            }
+        })
+        .collect();
+
+    // Problems first
+    result.sort_by(|a, b| b.stuck.cmp(&a.stuck).then_with(|| a.name.cmp(&b.name)));
+    Ok(result)
+}
+
+/// The parts of the kubelet's `/stats/summary` we use
+#[derive(Debug, Deserialize)]
+pub(crate) struct StatsSummary {
+    #[serde(default)]
+    pub pods: Vec<PodStats>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PodStats {
+    pub pod_ref: PodRef,
+    #[serde(default)]
+    pub containers: Vec<ContainerStats>,
+    #[serde(default)]
+    pub volume: Vec<VolumeStats>,
+    pub ephemeral_storage: Option<FsStats>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct PodRef {
+    pub name: String,
+    pub namespace: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ContainerStats {
+    pub name: String,
+    pub rootfs: Option<FsStats>,
+    pub logs: Option<FsStats>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct VolumeStats {
+    pub name: String,
+    pub pvc_ref: Option<PodRef>,
+    #[serde(flatten)]
+    pub fs: FsStats,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct FsStats {
+    pub used_bytes: Option<u64>,
+    pub capacity_bytes: Option<u64>,
+    pub available_bytes: Option<u64>,
+}
+
+impl FsStats {
+    pub(crate) fn usage(&self) -> VolumeUsage {
+        VolumeUsage {
+            used_bytes: self.used_bytes,
+            capacity_bytes: self.capacity_bytes,
+            available_bytes: self.available_bytes,
+            used_percent: match (self.used_bytes, self.capacity_bytes) {
+                (Some(used), Some(capacity)) => crate::quantity::percentage(used as f64, capacity as f64),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// A node's kubelet stats summary, read through the API server's node proxy
+pub(crate) async fn kubelet_stats_summary(client: &Client, node: &str) -> Result<StatsSummary> {
+    let url = format!("/api/v1/nodes/{}/proxy/stats/summary", node);
+    let request = k8s_openapi::http::Request::get(url).body(Vec::new())?;
+    let body = client
+        .request_text(request)
+        .await
+        .with_context(|| format!("Failed to read kubelet stats from {}", node))?;
+    Ok(serde_json::from_str(&body)?)
+}
+
+/// Stats summaries for `nodes`, skipping nodes whose kubelet can't be reached
+pub(crate) async fn kubelet_stats_summaries(client: &Client, nodes: &BTreeSet<String>) -> HashMap<String, StatsSummary> {
+    let summaries = join_bounded(
+        nodes.iter().map(|node| async move { (node.clone(), kubelet_stats_summary(client, node).await) }),
+        FAN_OUT_LIMIT,
+    )
+    .await;
+    summaries
+        .into_iter()
+        .filter_map(|(node, summary)| summary.ok().map(|s| (node, s)))
+        .collect()
+}
+
+/// Volume source type as it appears in the pod spec, e.g. `persistentVolumeClaim` or `emptyDir`
+fn volume_source(volume: &Volume) -> Option<&'static str> {
+    if volume.persistent_volume_claim.is_some() {
+        Some("persistentVolumeClaim")
+    } else if volume.ephemeral.is_some() {
+        Some("ephemeral")
+    } else if volume.empty_dir.is_some() {
+        Some("emptyDir")
+    } else if volume.host_path.is_some() {
+        Some("hostPath")
+    } else if volume.csi.is_some() {
+        Some("csi")
+    } else if volume.nfs.is_some() {
+        Some("nfs")
+    } else {
+        // ConfigMaps, Secrets, projected and downward API volumes hold no data worth mapping
+        None
+    }
+}
+
+/// Pods and the storage behind their volumes: PVC, PV, StorageClass and, where
+/// the kubelet reports it, actual usage
+///
+/// Generic ephemeral volumes resolve to their `<pod>-<volume>` claim. Claims
+/// not mounted by any pod are listed separately so orphaned storage shows up.
+pub async fn get_storage_topology(client: Client, namespace: Option<&str>) -> Result<StorageTopology> {
+    let namespace = namespace.filter(|ns| !ns.is_empty());
+    let (pods, claims): (Api<Pod>, Api<PersistentVolumeClaim>) = match namespace {
+        Some(ns) => (Api::namespaced(client.clone(), ns), Api::namespaced(client.clone(), ns)),
+        None => (Api::all(client.clone()), Api::all(client.clone())),
+    };
+    let lp = ListParams::default();
+    let (pods, claims, volumes, classes) = tokio::try_join!(
+        pods.list(&lp),
+        claims.list(&lp),
+        Api::<PersistentVolume>::all(client.clone()).list(&lp),
+        Api::<StorageClass>::all(client.clone()).list(&lp),
+    )?;
+
+    let classes: HashMap<String, StorageClassSummary> = classes
+        .items
+        .iter()
+        .map(|sc| {
+            (
+                sc.name_any(),
+                StorageClassSummary {
+                    name: sc.name_any(),
+                    provisioner: sc.provisioner.clone(),
+                    reclaim_policy: sc.reclaim_policy.clone(),
+                    volume_binding_mode: sc.volume_binding_mode.clone(),
+                    allow_volume_expansion: sc.allow_volume_expansion.unwrap_or(false),
+                },
+            )
+        })
+        .collect();
+    let volumes: HashMap<String, &PersistentVolume> = volumes.items.iter().map(|pv| (pv.name_any(), pv)).collect();
+
+    // Usage per (namespace, pod, volume) and per (namespace, claim) from the nodes running these pods
+    let nodes: BTreeSet<String> = pods
+        .items
+        .iter()
+        .filter_map(|p| p.spec.as_ref()?.node_name.clone())
+        .collect();
+    let summaries = kubelet_stats_summaries(&client, &nodes).await;
+    let mut volume_usage: HashMap<(String, String, String), VolumeUsage> = HashMap::new();
+    let mut claim_usage: HashMap<(String, String), VolumeUsage> = HashMap::new();
+    for pod in summaries.values().flat_map(|s| &s.pods) {
+        for volume in &pod.volume {
+            let usage = volume.fs.usage();
+            if let Some(pvc) = &volume.pvc_ref {
+                claim_usage.insert((pvc.namespace.clone(), pvc.name.clone()), usage.clone());
+            }
+            volume_usage.insert(
+                (pod.pod_ref.namespace.clone(), pod.pod_ref.name.clone(), volume.name.clone()),
+                usage,
+            );
+        }
+    }
+
+    let claim_storage = |pvc: &PersistentVolumeClaim| {
+        let spec = pvc.spec.as_ref();
+        let status = pvc.status.as_ref();
+        let class_name = spec.and_then(|s| s.storage_class_name.clone());
+        let volume = spec
+            .and_then(|s| s.volume_name.as_ref())
+            .and_then(|name| volumes.get(name))
+            .map(|pv| {
+                let pv_spec = pv.spec.as_ref();
+                VolumeStorage {
+                    name: pv.name_any(),
+                    phase: pv.status.as_ref().and_then(|s| s.phase.clone()),
+                    capacity: pv_spec
+                        .and_then(|s| s.capacity.as_ref())
+                        .and_then(|c| c.get("storage"))
+                        .map(|q| q.0.clone()),
+                    reclaim_policy: pv_spec.and_then(|s| s.persistent_volume_reclaim_policy.clone()),
+                    driver: pv_spec.and_then(|s| s.csi.as_ref()).map(|csi| csi.driver.clone()),
+                    volume_handle: pv_spec.and_then(|s| s.csi.as_ref()).map(|csi| csi.volume_handle.clone()),
+                }
+            });
+        let namespace = pvc.namespace().unwrap_or_default();
+        ClaimStorage {
+            usage: claim_usage.get(&(namespace.clone(), pvc.name_any())).cloned(),
+            name: pvc.name_any(),
+            namespace,
+            phase: status.and_then(|s| s.phase.clone()),
+            requested: spec
+                .and_then(|s| s.resources.as_ref())
+                .and_then(|r| r.requests.as_ref())
+                .and_then(|r| r.get("storage"))
+                .map(|q| q.0.clone()),
+            capacity: status
+                .and_then(|s| s.capacity.as_ref())
+                .and_then(|c| c.get("storage"))
+                .map(|q| q.0.clone()),
+            access_modes: spec.and_then(|s| s.access_modes.clone()).unwrap_or_default(),
+            storage_class: class_name.as_ref().and_then(|name| classes.get(name)).cloned(),
+            storage_class_name: class_name,
+            volume,
+        }
+    };
+    let claims: HashMap<(String, String), &PersistentVolumeClaim> = claims
+        .items
+        .iter()
+        .map(|pvc| ((pvc.namespace().unwrap_or_default(), pvc.name_any()), pvc))
+        .collect();
+
+    let mut mounted: BTreeSet<(String, String)> = BTreeSet::new();
+    let mut pod_storage = Vec::new();
+    for pod in &pods.items {
+        let Some(spec) = &pod.spec else { continue };
+        let namespace = pod.namespace().unwrap_or_default();
+        let pod_name = pod.name_any();
+
+        let volumes: Vec<PodVolumeStorage> = spec
+            .volumes
+            .iter()
+            .flatten()
+            .filter_map(|volume| {
+                let source = volume_source(volume)?;
+                let claim_name = match source {
+                    "persistentVolumeClaim" => volume.persistent_volume_claim.as_ref().map(|c| c.claim_name.clone()),
+                    "ephemeral" => Some(format!("{}-{}", pod_name, volume.name)),
+                    _ => None,
+                };
+                let claim = claim_name.and_then(|name| {
+                    let key = (namespace.clone(), name);
+                    let claim = claims.get(&key).map(|pvc| claim_storage(*pvc));
+                    mounted.insert(key);
+                    claim
+                });
+                let mount_paths = spec
+                    .containers
+                    .iter()
+                    .flat_map(|c| c.volume_mounts.iter().flatten().map(move |m| (c, m)))
+                    .filter(|(_, m)| m.name == volume.name)
+                    .map(|(c, m)| format!("{}:{}", c.name, m.mount_path))
+                    .collect();
+                Some(PodVolumeStorage {
+                    name: volume.name.clone(),
+                    source: source.to_string(),
+                    mount_paths,
+                    usage: volume_usage
+                        .get(&(namespace.clone(), pod_name.clone(), volume.name.clone()))
+                        .cloned(),
+                    claim,
+                })
+            })
+            .collect();
+
+        if !volumes.is_empty() {
+            pod_storage.push(PodStorage {
+                namespace: namespace.clone(),
+                name: pod_name,
+                node: spec.node_name.clone(),
+                phase: pod.status.as_ref().and_then(|s| s.phase.clone()),
+                volumes,
+            });
+        }
+    }
+
+    let mut unmounted_claims: Vec<ClaimStorage> = claims
+        .iter()
+        .filter(|(key, _)| !mounted.contains(*key))
+        .map(|(_, pvc)| claim_storage(*pvc))
+        .collect();
+    unmounted_claims.sort_by(|a, b| (&a.namespace, &a.name).cmp(&(&b.namespace, &b.name)));
+    pod_storage.sort_by(|a, b| (&a.namespace, &a.name).cmp(&(&b.namespace, &b.name)));
+
+    Ok(StorageTopology {
+        pods: pod_storage,
+        unmounted_claims,
+        usage_available: !summaries.is_empty(),
+        nodes_without_stats: nodes.iter().filter(|n| !summaries.contains_key(*n)).cloned().collect(),
+    })
+}
+
+/// Actual filesystem usage per volume and per container's ephemeral storage, from kubelet stats
+///
+/// Volumes (PVCs and emptyDirs) are measured against their filesystem
+/// capacity and containers against their `ephemeral-storage` limit; either
+/// above `threshold_percent` (default 80) is flagged. Containers without a
+/// limit are reported but never flagged. Fullest entries come first.
+pub async fn get_volume_usage(
+    client: Client,
+    namespace: Option<&str>,
+    threshold_percent: Option<f64>,
+) -> Result<VolumeUsageReport> {
+    let threshold = threshold_percent.unwrap_or(DEFAULT_USAGE_THRESHOLD);
+    let namespace = namespace.filter(|ns| !ns.is_empty());
+    let pods: Api<Pod> = match namespace {
+        Some(ns) => Api::namespaced(client.clone(), ns),
+        None => Api::all(client.clone()),
+    };
+    let pods = pods.list(&ListParams::default()).await?;
+
+    // (namespace, pod) -> node, (namespace, pod, container) -> ephemeral-storage limit
+    let mut pod_nodes: HashMap<(String, String), String> = HashMap::new();
+    let mut limits: HashMap<(String, String, String), u64> = HashMap::new();
+    let mut empty_dirs: BTreeSet<(String, String, String)> = BTreeSet::new();
+    for pod in &pods.items {
+        let Some(spec) = &pod.spec else { continue };
+        let Some(node) = &spec.node_name else { continue };
+        let (ns, name) = (pod.namespace().unwrap_or_default(), pod.name_any());
+        for volume in spec.volumes.iter().flatten().filter(|v| v.empty_dir.is_some()) {
+            empty_dirs.insert((ns.clone(), name.clone(), volume.name.clone()));
+        }
+        for container in &spec.containers {
+            let limit = container
+                .resources
+                .as_ref()
+                .and_then(|r| r.limits.as_ref())
+                .and_then(|l| l.get("ephemeral-storage"))
+                .and_then(|q| crate::quantity::memory_to_bytes(&q.0));
+            if let Some(limit) = limit {
+                limits.insert((ns.clone(), name.clone(), container.name.clone()), limit);
+            }
+        }
+        pod_nodes.insert((ns, name), node.clone());
+    }
+
+    let nodes: BTreeSet<String> = pod_nodes.values().cloned().collect();
+    let summaries = kubelet_stats_summaries(&client, &nodes).await;
+
+    let mut volumes = Vec::new();
+    let mut containers = Vec::new();
+    for pod in summaries.values().flat_map(|s| &s.pods) {
+        let key = (pod.pod_ref.namespace.clone(), pod.pod_ref.name.clone());
+        // Summaries cover every pod on the node; keep the ones in scope
+        let Some(node) = pod_nodes.get(&key) else { continue };
+
+        for volume in &pod.volume {
+            // Only claims and emptyDirs; configMaps and secrets report tiny tmpfs usage
+            let empty_dir = empty_dirs.contains(&(key.0.clone(), key.1.clone(), volume.name.clone()));
+            if volume.pvc_ref.is_none() && !empty_dir {
+                continue;
+            }
+
+            let usage = volume.fs.usage();
+            volumes.push(VolumeUsageEntry {
+                namespace: key.0.clone(),
+                pod: key.1.clone(),
+                node: node.clone(),
+                volume: volume.name.clone(),
+                claim: volume.pvc_ref.as_ref().map(|r| r.name.clone()),
+                over_threshold: usage.used_percent.map(|p| p >= threshold).unwrap_or(false),
+                usage,
+            });
+        }
+
+        for container in &pod.containers {
+            let rootfs = container.rootfs.as_ref().and_then(|fs| fs.used_bytes);
+            let logs = container.logs.as_ref().and_then(|fs| fs.used_bytes);
+            let used = rootfs.unwrap_or(0) + logs.unwrap_or(0);
+            let limit = limits
+                .get(&(key.0.clone(), key.1.clone(), container.name.clone()))
+                .copied();
+            let used_percent = limit.and_then(|limit| crate::quantity::percentage(used as f64, limit as f64));
+            containers.push(EphemeralStorageUsage {
+                namespace: key.0.clone(),
+                pod: key.1.clone(),
+                node: node.clone(),
+                container: container.name.clone(),
+                rootfs_used_bytes: rootfs,
+                logs_used_bytes: logs,
+                used_bytes: used,
+                limit_bytes: limit,
+                used_percent,
+                over_threshold: used_percent.map(|p| p >= threshold).unwrap_or(false),
+            });
+        }
+    }
+
+    let by_fill = |a: Option<f64>, b: Option<f64>| b.unwrap_or(-1.0).total_cmp(&a.unwrap_or(-1.0));
+    volumes.sort_by(|a, b| by_fill(a.usage.used_percent, b.usage.used_percent));
+    containers.sort_by(|a, b| by_fill(a.used_percent, b.used_percent).then_with(|| b.used_bytes.cmp(&a.used_bytes)));
+
+    Ok(VolumeUsageReport {
+        threshold_percent: threshold,
+        volumes,
+        containers,
+        nodes_without_stats: nodes.iter().filter(|n| !summaries.contains_key(*n)).cloned().collect(),
+    })
+}
@@ -0,0 +1,120 @@
+use anyhow::Result;
+use k8s_openapi::api::core::v1::Pod;
+use kube::api::{Api, AttachParams};
+use kube::Client;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::sync::{oneshot, Mutex};
+use uuid::Uuid;
+
+/// Lines of existing content sent before following
+const INITIAL_LINES: u32 = 100;
+
+/// Follows files inside containers for apps that log to disk instead of stdout
+pub struct FileTailManager {
+    tails: Arc<Mutex<HashMap<String, oneshot::Sender<()>>>>,
+}
+
+impl FileTailManager {
+    pub fn new() -> Self {
+        Self {
+            tails: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Run `tail -F` on `path` in the container, emitting `file-tail-{tail_id}` per
+    /// line and `file-tail-end-{tail_id}` when it stops
+    ///
+    /// `-F` keeps following across log rotation. tail's own complaints (file
+    /// missing, permission denied) come through as lines too, since it keeps
+    /// retrying rather than exiting.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn start_tail(
+        &self,
+        app: AppHandle,
+        client: Client,
+        namespace: &str,
+        pod_name: &str,
+        container: Option<&str>,
+        path: &str,
+        lines: Option<u32>,
+    ) -> Result<String> {
+        if path.is_empty() {
+            return Err(anyhow::anyhow!("No file path given"));
+        }
+
+        let pods: Api<Pod> = Api::namespaced(client, namespace);
+        let mut attach_params = AttachParams::default()
+            .stdin(false)
+            .stdout(true)
+            .stderr(true);
+        if let Some(container) = container {
+            attach_params = attach_params.container(container);
+        }
+
+        let command = vec![
+            "tail".to_string(),
+            "-n".to_string(),
+            lines.unwrap_or(INITIAL_LINES).to_string(),
+            "-F".to_string(),
+            path.to_string(),
+        ];
+        let mut attached = pods.exec(pod_name, command, &attach_params).await?;
+
+        let stdout = attached
+            .stdout()
+            .ok_or_else(|| anyhow::anyhow!("No stdout stream"))?;
+        let stderr = attached
+            .stderr()
+            .ok_or_else(|| anyhow::anyhow!("No stderr stream"))?;
+
+        let tail_id = Uuid::new_v4().to_string();
+        let (cancel_tx, mut cancel_rx) = oneshot::channel::<()>();
+        self.tails.lock().await.insert(tail_id.clone(), cancel_tx);
+
+        let tails = self.tails.clone();
+        let id = tail_id.clone();
+        tokio::spawn(async move {
+            let event = format!("file-tail-{}", id);
+            let mut lines = BufReader::new(stdout).lines();
+            let mut errors = BufReader::new(stderr).lines();
+
+            loop {
+                tokio::select! {
+                    line = lines.next_line() => match line {
+                        Ok(Some(line)) => {
+                            let _ = app.emit(&event, line);
+                        }
+                        _ => break,
+                    },
+                    Ok(Some(line)) = errors.next_line() => {
+                        let _ = app.emit(&event, line);
+                    }
+                    _ = &mut cancel_rx => break,
+                }
+            }
+
+            // Dropping the exec closes the stream and ends tail in the container
+            drop(attached);
+            let _ = app.emit(&format!("file-tail-end-{}", id), ());
+            tails.lock().await.remove(&id);
+        });
+
+        Ok(tail_id)
+    }
+
+    pub async fn stop_tail(&self, tail_id: &str) -> Result<()> {
+        if let Some(cancel) = self.tails.lock().await.remove(tail_id) {
+            let _ = cancel.send(());
+        }
+        Ok(())
+    }
+}
+
+impl Default for FileTailManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,348 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use futures::{AsyncBufReadExt, TryStreamExt};
+use k8s_openapi::api::core::v1::Pod;
+use kube::api::{Api, DynamicObject, ListParams, LogParams, Patch, PatchParams, PostParams};
+use kube::discovery::ApiResource;
+use kube::{Client, ResourceExt};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::{oneshot, Mutex};
+use uuid::Uuid;
+
+use crate::types::{PipelineRunInfo, TaskRunInfo, TaskRunLogLine, TektonStepInfo};
+
+/// Tekton API versions in order of preference
+const TEKTON_VERSIONS: &[&str] = &["v1", "v1beta1"];
+
+/// Resolve PipelineRun/TaskRun to the newest version the cluster serves
+async fn tekton_api_resource(client: &Client, kind: &str) -> Result<ApiResource> {
+    let group = kube::discovery::group(client, "tekton.dev")
+        .await
+        .context("Tekton Pipelines does not appear to be installed")?;
+
+    for version in TEKTON_VERSIONS {
+        if let Some((resource, _)) = group
+            .versioned_resources(version)
+            .into_iter()
+            .find(|(r, _)| r.kind == kind)
+        {
+            return Ok(resource);
+        }
+    }
+
+    Err(anyhow::anyhow!("tekton.dev does not serve {}", kind))
+}
+
+fn run_api(client: Client, namespace: &str, resource: &ApiResource) -> Api<DynamicObject> {
+    if namespace.is_empty() {
+        Api::all_with(client, resource)
+    } else {
+        Api::namespaced_with(client, namespace, resource)
+    }
+}
+
+pub async fn list_pipeline_runs(client: Client, namespace: &str) -> Result<Vec<PipelineRunInfo>> {
+    let resource = tekton_api_resource(&client, "PipelineRun").await?;
+    let runs = run_api(client, namespace, &resource).list(&ListParams::default()).await?;
+
+    let mut result: Vec<PipelineRunInfo> = runs
+        .items
+        .iter()
+        .map(|run| {
+            let (status, reason, message) = run_status(run);
+            let (started_at, completed_at, duration_seconds) = run_times(run);
+            PipelineRunInfo {
+                name: run.name_any(),
+                namespace: run.namespace().unwrap_or_default(),
+                pipeline: run
+                    .data
+                    .pointer("/spec/pipelineRef/name")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string()),
+                status,
+                reason,
+                message,
+                started_at,
+                completed_at,
+                duration_seconds,
+                created_at: run.metadata.creation_timestamp.as_ref().map(|ts| ts.0.to_rfc3339()),
+            }
+        })
+        .collect();
+
+    // Newest first, like tkn pipelinerun list
+    result.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(result)
+}
+
+/// TaskRuns in a namespace, optionally only those belonging to one PipelineRun
+pub async fn list_task_runs(
+    client: Client,
+    namespace: &str,
+    pipeline_run: Option<&str>,
+) -> Result<Vec<TaskRunInfo>> {
+    let resource = tekton_api_resource(&client, "TaskRun").await?;
+    let mut lp = ListParams::default();
+    if let Some(run) = pipeline_run {
+        lp = lp.labels(&format!("tekton.dev/pipelineRun={}", run));
+    }
+    let runs = run_api(client, namespace, &resource).list(&lp).await?;
+
+    let mut result: Vec<TaskRunInfo> = runs.items.iter().map(task_run_info).collect();
+    result.sort_by(|a, b| (&a.started_at, &a.name).cmp(&(&b.started_at, &b.name)));
+    Ok(result)
+}
+
+fn task_run_info(run: &DynamicObject) -> TaskRunInfo {
+    let (status, reason, message) = run_status(run);
+    let (started_at, completed_at, duration_seconds) = run_times(run);
+
+    let steps = run
+        .data
+        .pointer("/status/steps")
+        .and_then(|s| s.as_array())
+        .map(|steps| {
+            steps
+                .iter()
+                .map(|step| {
+                    let terminated = step.get("terminated");
+                    let state = if terminated.is_some() {
+                        "Terminated"
+                    } else if step.get("running").is_some() {
+                        "Running"
+                    } else {
+                        "Waiting"
+                    };
+                    TektonStepInfo {
+                        name: str_field(step, "name").unwrap_or_default(),
+                        container: str_field(step, "container").unwrap_or_default(),
+                        state: state.to_string(),
+                        exit_code: terminated
+                            .and_then(|t| t.get("exitCode"))
+                            .and_then(|c| c.as_i64())
+                            .map(|c| c as i32),
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    TaskRunInfo {
+        name: run.name_any(),
+        namespace: run.namespace().unwrap_or_default(),
+        pipeline_run: run.labels().get("tekton.dev/pipelineRun").cloned(),
+        task: run
+            .labels()
+            .get("tekton.dev/pipelineTask")
+            .or_else(|| run.labels().get("tekton.dev/task"))
+            .cloned(),
+        status,
+        reason,
+        message,
+        pod_name: run.data.pointer("/status/podName").and_then(|p| p.as_str()).map(|p| p.to_string()),
+        steps,
+        started_at,
+        completed_at,
+        duration_seconds,
+    }
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(|v| v.as_str()).map(|s| s.to_string())
+}
+
+/// Summarize the Succeeded condition as (status, reason, message)
+fn run_status(run: &DynamicObject) -> (String, Option<String>, Option<String>) {
+    let condition = run
+        .data
+        .pointer("/status/conditions")
+        .and_then(|c| c.as_array())
+        .and_then(|conditions| {
+            conditions
+                .iter()
+                .find(|c| c.get("type").and_then(|t| t.as_str()) == Some("Succeeded"))
+        });
+
+    let Some(condition) = condition else {
+        return ("Pending".to_string(), None, None);
+    };
+
+    let reason = str_field(condition, "reason");
+    let message = str_field(condition, "message");
+    let status = match condition.get("status").and_then(|s| s.as_str()) {
+        Some("True") => "Succeeded",
+        Some("False") if reason.as_deref().map(|r| r.contains("Cancelled")).unwrap_or(false) => "Cancelled",
+        Some("False") => "Failed",
+        _ => "Running",
+    };
+
+    (status.to_string(), reason, message)
+}
+
+/// Start/completion times and the duration so far (or total, once finished)
+fn run_times(run: &DynamicObject) -> (Option<String>, Option<String>, Option<i64>) {
+    let parse = |path: &str| -> Option<DateTime<Utc>> {
+        run.data
+            .pointer(path)
+            .and_then(|t| t.as_str())
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.with_timezone(&Utc))
+    };
+
+    let started = parse("/status/startTime");
+    let completed = parse("/status/completionTime");
+    let duration = started.map(|start| (completed.unwrap_or_else(Utc::now) - start).num_seconds());
+
+    (
+        started.map(|t| t.to_rfc3339()),
+        completed.map(|t| t.to_rfc3339()),
+        duration,
+    )
+}
+
+/// Cancel a running PipelineRun or TaskRun
+pub async fn cancel_tekton_run(client: Client, namespace: &str, kind: &str, name: &str) -> Result<()> {
+    let resource = tekton_api_resource(&client, kind).await?;
+    let api: Api<DynamicObject> = Api::namespaced_with(client, namespace, &resource);
+
+    let status = match kind {
+        "PipelineRun" if resource.version == "v1beta1" => "PipelineRunCancelled",
+        "PipelineRun" => "Cancelled",
+        "TaskRun" => "TaskRunCancelled",
+        other => return Err(anyhow::anyhow!("Cannot cancel {}", other)),
+    };
+
+    let patch = serde_json::json!({ "spec": { "status": status } });
+    api.patch(name, &PatchParams::default(), &Patch::Merge(&patch)).await?;
+    Ok(())
+}
+
+/// Start a new run with the same spec as an existing one, returning its name
+pub async fn rerun_tekton_run(client: Client, namespace: &str, kind: &str, name: &str) -> Result<String> {
+    if kind != "PipelineRun" && kind != "TaskRun" {
+        return Err(anyhow::anyhow!("Cannot rerun {}", kind));
+    }
+
+    let resource = tekton_api_resource(&client, kind).await?;
+    let api: Api<DynamicObject> = Api::namespaced_with(client, namespace, &resource);
+    let original = api.get(name).await?;
+
+    let mut spec = original.data.get("spec").cloned().unwrap_or_else(|| serde_json::json!({}));
+    if let Some(spec) = spec.as_object_mut() {
+        spec.remove("status");
+    }
+
+    // Keep user labels but drop the ones Tekton stamps on runs it creates
+    let labels: std::collections::BTreeMap<String, String> = original
+        .labels()
+        .iter()
+        .filter(|(k, _)| !k.starts_with("tekton.dev/"))
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+
+    let base = name.split("-rerun-").next().unwrap_or(name);
+    let mut run = DynamicObject::new("", &resource).within(namespace);
+    run.metadata.name = None;
+    run.metadata.generate_name = Some(format!("{}-rerun-", base));
+    run.metadata.labels = (!labels.is_empty()).then_some(labels);
+    run.data = serde_json::json!({ "spec": spec });
+
+    let created = api.create(&PostParams::default(), &run).await?;
+    Ok(created.name_any())
+}
+
+/// Streams TaskRun step logs to the frontend
+pub struct TektonLogManager {
+    streams: Arc<Mutex<HashMap<String, oneshot::Sender<()>>>>,
+}
+
+impl TektonLogManager {
+    pub fn new() -> Self {
+        Self {
+            streams: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Follow every step of a TaskRun's pod in order, emitting `tekton-log-{stream_id}`
+    /// events per line and `tekton-log-end-{stream_id}` when done
+    pub async fn start_stream(
+        &self,
+        app: AppHandle,
+        client: Client,
+        namespace: &str,
+        task_run: &str,
+    ) -> Result<String> {
+        let resource = tekton_api_resource(&client, "TaskRun").await?;
+        let runs: Api<DynamicObject> = Api::namespaced_with(client.clone(), namespace, &resource);
+        let run = task_run_info(&runs.get(task_run).await?);
+        let pod_name = run
+            .pod_name
+            .ok_or_else(|| anyhow::anyhow!("TaskRun {} has no pod yet", task_run))?;
+
+        let pods: Api<Pod> = Api::namespaced(client, namespace);
+        let pod = pods.get(&pod_name).await?;
+        let steps: Vec<String> = pod
+            .spec
+            .map(|s| s.containers.into_iter().map(|c| c.name).filter(|n| n.starts_with("step-")).collect())
+            .unwrap_or_default();
+
+        let stream_id = Uuid::new_v4().to_string();
+        let (cancel_tx, mut cancel_rx) = oneshot::channel::<()>();
+        self.streams.lock().await.insert(stream_id.clone(), cancel_tx);
+
+        let streams = self.streams.clone();
+        let id = stream_id.clone();
+        tokio::spawn(async move {
+            let event = format!("tekton-log-{}", id);
+
+            'steps: for step in steps {
+                let params = LogParams {
+                    container: Some(step.clone()),
+                    follow: true,
+                    ..LogParams::default()
+                };
+
+                let mut lines = match pods.log_stream(&pod_name, &params).await {
+                    Ok(stream) => stream.lines(),
+                    Err(e) => {
+                        let _ = app.emit(&event, TaskRunLogLine { step, line: format!("<failed to read logs: {}>", e) });
+                        continue;
+                    }
+                };
+
+                loop {
+                    tokio::select! {
+                        line = lines.try_next() => match line {
+                            Ok(Some(line)) => {
+                                let _ = app.emit(&event, TaskRunLogLine { step: step.clone(), line });
+                            }
+                            _ => break,
+                        },
+                        _ = &mut cancel_rx => break 'steps,
+                    }
+                }
+            }
+
+            let _ = app.emit(&format!("tekton-log-end-{}", id), ());
+            streams.lock().await.remove(&id);
+        });
+
+        Ok(stream_id)
+    }
+
+    pub async fn stop_stream(&self, stream_id: &str) -> Result<()> {
+        if let Some(cancel) = self.streams.lock().await.remove(stream_id) {
+            let _ = cancel.send(());
+        }
+        Ok(())
+    }
+}
+
+impl Default for TektonLogManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,265 @@
+use anyhow::Result;
+use chrono::Utc;
+use k8s_openapi::api::core::v1::Namespace;
+use kube::api::{Api, DynamicObject, ListParams, Patch, PatchParams};
+use kube::discovery::{verbs, ApiResource, Discovery, Scope};
+use kube::{Client, ResourceExt};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::{oneshot, Mutex};
+use uuid::Uuid;
+
+use crate::kube::throttle::{join_bounded, FAN_OUT_LIMIT};
+use crate::types::{NamespaceTerminationResult, NamespaceTerminationStatus, TerminatingObject};
+
+const POLL_INTERVAL_SECS: u64 = 3;
+
+/// Finalizers that can be removed from a terminating namespace's objects without
+/// leaking anything outside the cluster: (kind, finalizer), "*" for any kind
+///
+/// Cloud finalizers (load balancers, volumes, DNS records) are deliberately
+/// absent; removing those orphans the external resource.
+const SAFE_FINALIZERS: &[(&str, &str)] = &[
+    // Only holds a claim while pods mount it, and the namespace's pods are going too
+    ("PersistentVolumeClaim", "kubernetes.io/pvc-protection"),
+    // Job pod tracking; stuck when the Job was deleted before counting the pod
+    ("Pod", "batch.kubernetes.io/job-tracking"),
+    // Garbage collector bookkeeping for cascading deletes
+    ("*", "foregroundDeletion"),
+    ("*", "orphan"),
+];
+
+fn is_safe_finalizer(kind: &str, finalizer: &str) -> bool {
+    SAFE_FINALIZERS
+        .iter()
+        .any(|(k, f)| (*k == "*" || k.eq_ignore_ascii_case(kind)) && *f == finalizer)
+}
+
+/// Follows namespaces through termination, reporting what is still holding them up
+pub struct NamespaceTerminationManager {
+    watches: Arc<Mutex<HashMap<String, oneshot::Sender<()>>>>,
+}
+
+impl NamespaceTerminationManager {
+    pub fn new() -> Self {
+        Self {
+            watches: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Poll a terminating namespace, emitting `namespace-termination-progress-{watch_id}`
+    /// whenever the remaining objects change and one `namespace-termination-complete-{watch_id}`
+    /// once it is gone or the watch is stopped
+    pub async fn start_watch(&self, app: AppHandle, client: Client, name: &str) -> Result<String> {
+        // Discovery is slow, so the namespaced kinds are looked up once per watch
+        let resources = namespaced_resources(client.clone()).await?;
+        let first = namespace_termination_status(&client, &resources, name)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Namespace {} not found", name))?;
+
+        let watch_id = Uuid::new_v4().to_string();
+        let (cancel_tx, mut cancel_rx) = oneshot::channel::<()>();
+        self.watches.lock().await.insert(watch_id.clone(), cancel_tx);
+
+        let watches = self.watches.clone();
+        let id = watch_id.clone();
+        let name = name.to_string();
+        tokio::spawn(async move {
+            let progress_event = format!("namespace-termination-progress-{}", id);
+            let _ = app.emit(&progress_event, &first);
+            let mut last = first;
+
+            let result = loop {
+                tokio::select! {
+                    _ = tokio::time::sleep(Duration::from_secs(POLL_INTERVAL_SECS)) => {}
+                    _ = &mut cancel_rx => break outcome("cancelled", "Stopped watching".to_string()),
+                }
+
+                match namespace_termination_status(&client, &resources, &name).await {
+                    Ok(Some(status)) => {
+                        let changed = status.remaining != last.remaining
+                            || status.phase != last.phase
+                            || status.conditions != last.conditions;
+                        if changed {
+                            let _ = app.emit(&progress_event, &status);
+                        }
+                        last = status;
+                    }
+                    Ok(None) => break outcome("deleted", format!("Namespace {} is gone", name)),
+                    Err(e) => break outcome("failed", format!("Could not read namespace {}: {}", name, e)),
+                }
+            };
+
+            let _ = app.emit(&format!("namespace-termination-complete-{}", id), &result);
+            watches.lock().await.remove(&id);
+        });
+
+        Ok(watch_id)
+    }
+
+    pub async fn stop_watch(&self, watch_id: &str) -> Result<()> {
+        if let Some(cancel) = self.watches.lock().await.remove(watch_id) {
+            let _ = cancel.send(());
+        }
+        Ok(())
+    }
+}
+
+impl Default for NamespaceTerminationManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn outcome(outcome: &str, message: String) -> NamespaceTerminationResult {
+    NamespaceTerminationResult {
+        outcome: outcome.to_string(),
+        message,
+    }
+}
+
+/// Every namespaced kind that can be listed, preferring each group's recommended version
+async fn namespaced_resources(client: Client) -> Result<Vec<ApiResource>> {
+    let discovery = Discovery::new(client).run().await?;
+    Ok(discovery
+        .groups()
+        .flat_map(|group| group.recommended_resources())
+        .filter(|(_, caps)| caps.scope == Scope::Namespaced && caps.supports_operation(verbs::LIST))
+        .map(|(resource, _)| resource)
+        .collect())
+}
+
+/// What is left in a namespace, or None once the namespace itself is gone
+async fn namespace_termination_status(
+    client: &Client,
+    resources: &[ApiResource],
+    name: &str,
+) -> Result<Option<NamespaceTerminationStatus>> {
+    let Some(namespace) = Api::<Namespace>::all(client.clone()).get_opt(name).await? else {
+        return Ok(None);
+    };
+
+    let lists = join_bounded(
+        resources.iter().map(|resource| async move {
+            let api: Api<DynamicObject> = Api::namespaced_with(client.clone(), name, resource);
+            (resource, api.list(&ListParams::default()).await)
+        }),
+        FAN_OUT_LIMIT,
+    )
+    .await;
+
+    let mut remaining = Vec::new();
+    let mut unreadable = Vec::new();
+    for (resource, list) in lists {
+        let list = match list {
+            Ok(list) => list,
+            // A kind we can't list still blocks deletion; say so instead of hiding it
+            Err(_) => {
+                unreadable.push(resource.kind.clone());
+                continue;
+            }
+        };
+        for object in list.items {
+            let finalizers = object.finalizers().to_vec();
+            let removable_finalizers = finalizers
+                .iter()
+                .filter(|f| is_safe_finalizer(&resource.kind, f))
+                .cloned()
+                .collect();
+            remaining.push(TerminatingObject {
+                kind: resource.kind.clone(),
+                api_version: resource.api_version.clone(),
+                name: object.name_any(),
+                deleting: object.metadata.deletion_timestamp.is_some(),
+                finalizers,
+                removable_finalizers,
+            });
+        }
+    }
+    remaining.sort_by(|a, b| (&a.kind, &a.name).cmp(&(&b.kind, &b.name)));
+
+    let deleting_since = namespace.metadata.deletion_timestamp.as_ref().map(|t| t.0);
+    let status = namespace.status.as_ref();
+    Ok(Some(NamespaceTerminationStatus {
+        namespace: name.to_string(),
+        phase: status.and_then(|s| s.phase.clone()).unwrap_or_else(|| "Unknown".to_string()),
+        deleting_since: deleting_since.map(|t| t.to_rfc3339()),
+        elapsed_secs: deleting_since.map(|t| (Utc::now() - t).num_seconds()),
+        namespace_finalizers: namespace.spec.as_ref().and_then(|s| s.finalizers.clone()).unwrap_or_default(),
+        // The namespace controller explains what it's waiting on in these conditions
+        conditions: status
+            .and_then(|s| s.conditions.as_ref())
+            .into_iter()
+            .flatten()
+            .filter(|c| c.status == "True")
+            .map(|c| format!("{}: {}", c.type_, c.message.clone().unwrap_or_default()))
+            .collect(),
+        remaining,
+        unreadable_kinds: unreadable,
+    }))
+}
+
+/// One-off snapshot of a namespace's termination, without starting a watch
+pub async fn get_namespace_termination_status(client: Client, name: &str) -> Result<NamespaceTerminationStatus> {
+    let resources = namespaced_resources(client.clone()).await?;
+    namespace_termination_status(&client, &resources, name)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Namespace {} not found", name))
+}
+
+/// Remove one finalizer from an object in a terminating namespace
+///
+/// Only finalizers listed in `SAFE_FINALIZERS` are accepted, and only on objects
+/// already being deleted, so this can't be used to strip arbitrary protection.
+pub async fn remove_stuck_finalizer(
+    client: Client,
+    namespace: &str,
+    kind: &str,
+    name: &str,
+    finalizer: &str,
+) -> Result<()> {
+    if !is_safe_finalizer(kind, finalizer) {
+        return Err(anyhow::anyhow!(
+            "Removing {} from a {} is not known to be safe; remove it with kubectl if you are sure",
+            finalizer,
+            kind
+        ));
+    }
+
+    let (resource, _) = crate::kube::columns::resolve_kind(client.clone(), kind).await?;
+    let api: Api<DynamicObject> = Api::namespaced_with(client, namespace, &resource);
+    let object = api.get(name).await?;
+    if object.metadata.deletion_timestamp.is_none() {
+        return Err(anyhow::anyhow!("{} {} is not being deleted", kind, name));
+    }
+
+    let finalizers: Vec<&String> = object.finalizers().iter().filter(|f| *f != finalizer).collect();
+    if finalizers.len() == object.finalizers().len() {
+        return Ok(());
+    }
+    // resourceVersion makes the patch fail rather than drop a finalizer added meanwhile
+    let patch = json!({
+        "metadata": {
+            "finalizers": finalizers,
+            "resourceVersion": object.resource_version(),
+        }
+    });
+    api.patch(name, &PatchParams::default(), &Patch::Merge(&patch)).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_safe_finalizer() {
+        assert!(is_safe_finalizer("PersistentVolumeClaim", "kubernetes.io/pvc-protection"));
+        assert!(is_safe_finalizer("Deployment", "foregroundDeletion"));
+        assert!(!is_safe_finalizer("Pod", "kubernetes.io/pvc-protection"));
+        assert!(!is_safe_finalizer("Service", "service.kubernetes.io/load-balancer-cleanup"));
+    }
+}
@@ -0,0 +1,185 @@
+use futures::StreamExt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{ready, Context, Poll};
+use std::time::{Duration, Instant};
+use tower::{Layer, Service};
+
+/// Requests per second a context's client may make when none is configured
+///
+/// Higher than client-go's 5 as the UI fans out lists on every view, but low
+/// enough that a refresh loop can't trip API priority and fairness on its own.
+pub const DEFAULT_QPS: f64 = 50.0;
+pub const DEFAULT_BURST: u32 = 100;
+
+/// Most requests a single operation keeps in flight when it fans out over many objects
+pub(crate) const FAN_OUT_LIMIT: usize = 8;
+
+/// Token bucket: refills at `qps` per second up to `burst`, one token per request
+struct Bucket {
+    qps: f64,
+    burst: f64,
+    tokens: f64,
+    last: Instant,
+}
+
+impl Bucket {
+    fn new(qps: f64, burst: u32, now: Instant) -> Self {
+        Self {
+            qps,
+            burst: burst as f64,
+            tokens: burst as f64,
+            last: now,
+        }
+    }
+
+    /// Take a token, or say how long until one is available
+    fn take(&mut self, now: Instant) -> Option<Duration> {
+        let elapsed = now.saturating_duration_since(self.last).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.qps).min(self.burst);
+        self.last = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - self.tokens) / self.qps))
+        }
+    }
+}
+
+/// Client-side request throttle shared by every client built for one context
+///
+/// Limits can be changed while clients are using it.
+pub struct RateLimiter {
+    bucket: Mutex<Bucket>,
+}
+
+impl RateLimiter {
+    pub fn new(qps: Option<f64>, burst: Option<u32>) -> Self {
+        let (qps, burst) = sanitize(qps, burst);
+        Self {
+            bucket: Mutex::new(Bucket::new(qps, burst, Instant::now())),
+        }
+    }
+
+    pub fn configure(&self, qps: Option<f64>, burst: Option<u32>) {
+        let (qps, burst) = sanitize(qps, burst);
+        let mut bucket = self.bucket.lock().unwrap_or_else(|e| e.into_inner());
+        bucket.qps = qps;
+        bucket.burst = burst as f64;
+        bucket.tokens = bucket.tokens.min(bucket.burst);
+    }
+
+    /// Wait until a request may be sent
+    pub async fn acquire(&self) {
+        loop {
+            let wait = self
+                .bucket
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .take(Instant::now());
+            match wait {
+                Some(wait) => tokio::time::sleep(wait).await,
+                None => return,
+            }
+        }
+    }
+}
+
+/// Fall back to the defaults for missing or nonsensical values
+fn sanitize(qps: Option<f64>, burst: Option<u32>) -> (f64, u32) {
+    let qps = qps.filter(|q| q.is_finite() && *q > 0.0).unwrap_or(DEFAULT_QPS);
+    let burst = burst.filter(|b| *b > 0).unwrap_or(DEFAULT_BURST);
+    (qps, burst)
+}
+
+/// Tower layer making a kube client wait on a [`RateLimiter`] before each request
+pub struct ThrottleLayer {
+    limiter: Arc<RateLimiter>,
+}
+
+impl ThrottleLayer {
+    pub fn new(limiter: Arc<RateLimiter>) -> Self {
+        Self { limiter }
+    }
+}
+
+impl<S> Layer<S> for ThrottleLayer {
+    type Service = Throttled<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Throttled {
+            inner,
+            limiter: self.limiter.clone(),
+            acquire: None,
+            permitted: false,
+        }
+    }
+}
+
+pub struct Throttled<S> {
+    inner: S,
+    limiter: Arc<RateLimiter>,
+    acquire: Option<Pin<Box<dyn Future<Output = ()> + Send>>>,
+    permitted: bool,  // A token is held for the next call
+}
+
+impl<S, Req> Service<Req> for Throttled<S>
+where
+    S: Service<Req>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if !self.permitted {
+            let acquire = self.acquire.get_or_insert_with(|| {
+                let limiter = self.limiter.clone();
+                Box::pin(async move { limiter.acquire().await })
+            });
+            ready!(acquire.as_mut().poll(cx));
+            self.acquire = None;
+            self.permitted = true;
+        }
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Req) -> Self::Future {
+        self.permitted = false;
+        self.inner.call(req)
+    }
+}
+
+/// Like `join_all`, but with at most `limit` futures running at once; results keep input order
+pub(crate) async fn join_bounded<F>(futures: impl IntoIterator<Item = F>, limit: usize) -> Vec<F::Output>
+where
+    F: Future,
+{
+    futures::stream::iter(futures).buffered(limit.max(1)).collect().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_take() {
+        let start = Instant::now();
+        let mut bucket = Bucket::new(10.0, 2, start);
+        assert_eq!(bucket.take(start), None);
+        assert_eq!(bucket.take(start), None);
+
+        let wait = bucket.take(start).unwrap();
+        assert!((wait.as_secs_f64() - 0.1).abs() < 1e-9);
+
+        // Refills at qps but never beyond the burst
+        assert_eq!(bucket.take(start + Duration::from_millis(100)), None);
+        let later = start + Duration::from_secs(60);
+        assert_eq!(bucket.take(later), None);
+        assert_eq!(bucket.take(later), None);
+        assert!(bucket.take(later).is_some());
+    }
+}
@@ -0,0 +1,339 @@
+use anyhow::Result;
+use futures::StreamExt;
+use k8s_openapi::api::core::v1::Pod;
+use kube::api::{Api, DynamicObject};
+use kube::discovery::{ApiResource, Scope};
+use kube::runtime::{watcher, WatchStreamExt};
+use kube::{Client, ResourceExt};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::{oneshot, Mutex};
+use uuid::Uuid;
+
+use crate::kube::columns::resolve_kind;
+use crate::kube::operations::pod_info;
+use crate::types::{PodInfo, PodWatchEvent, ResourceWatchEvent, ResourceWatchStarted};
+
+/// Event suffix for a watch over every namespace; `_` can't appear in a namespace name
+const ALL_NAMESPACES: &str = "_all";
+
+struct PodWatch {
+    cancel: oneshot::Sender<()>,
+    subscribers: usize,  // Views sharing this watch; it stops when the last one leaves
+}
+
+/// Watches are per (context, namespace), so the same namespace in two clusters never shares one
+type PodWatchKey = (String, String);
+
+/// Streams pod changes to the frontend instead of it polling `get_pods`
+pub struct PodWatchManager {
+    watches: Arc<Mutex<HashMap<PodWatchKey, PodWatch>>>,
+}
+
+/// `pods-changed-{context}/{namespace}`, with characters event names can't hold in the context replaced by `_`
+pub fn pod_watch_event_name(context: &str, namespace: &str) -> String {
+    let context: String = context
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '-' | '/' | ':' | '_') { c } else { '_' })
+        .collect();
+    let namespace = if namespace.is_empty() { ALL_NAMESPACES } else { namespace };
+    format!("pods-changed-{}/{}", context, namespace)
+}
+
+impl PodWatchManager {
+    pub fn new() -> Self {
+        Self {
+            watches: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Watch pods in `namespace` (all namespaces when empty) of `context`, emitting
+    /// `pods-changed-{context}/{namespace}` (`.../_all`) with a [`PodWatchEvent`]
+    ///
+    /// The first event is `synced` with the full list, and another follows
+    /// whenever the watch has to re-list, so the frontend replaces rather than
+    /// patches its list then. Watching a namespace already watched shares the
+    /// running watch; each start needs a matching [`stop_watch`](Self::stop_watch).
+    pub async fn start_watch(&self, app: AppHandle, client: Client, context: &str, namespace: &str) -> Result<String> {
+        let event = pod_watch_event_name(context, namespace);
+        let key = (context.to_string(), namespace.to_string());
+        let mut watches = self.watches.lock().await;
+        if let Some(watch) = watches.get_mut(&key) {
+            watch.subscribers += 1;
+            return Ok(event);
+        }
+
+        let (cancel_tx, mut cancel_rx) = oneshot::channel::<()>();
+        watches.insert(
+            key.clone(),
+            PodWatch {
+                cancel: cancel_tx,
+                subscribers: 1,
+            },
+        );
+
+        let pods: Api<Pod> = if namespace.is_empty() {
+            Api::all(client)
+        } else {
+            Api::namespaced(client, namespace)
+        };
+        let watches = self.watches.clone();
+        let event_name = event.clone();
+        tokio::spawn(async move {
+            let stream = watcher(pods, watcher::Config::default()).default_backoff();
+            futures::pin_mut!(stream);
+
+            // Apply events don't say whether a pod is new, so remember what was seen
+            let mut known: HashSet<String> = HashSet::new();
+            let mut relisted: Vec<Pod> = Vec::new();
+
+            loop {
+                let next = tokio::select! {
+                    next = stream.next() => next,
+                    _ = &mut cancel_rx => break,
+                };
+                let Some(next) = next else { break };
+
+                let payload = match next {
+                    Ok(watcher::Event::Init) => {
+                        relisted.clear();
+                        continue;
+                    }
+                    Ok(watcher::Event::InitApply(pod)) => {
+                        relisted.push(pod);
+                        continue;
+                    }
+                    Ok(watcher::Event::InitDone) => {
+                        known = relisted.iter().map(pod_key).collect();
+                        let pods = relisted.drain(..).map(pod_info).collect();
+                        watch_event("synced", None, Some(pods), None)
+                    }
+                    Ok(watcher::Event::Apply(pod)) => {
+                        let event_type = if known.insert(pod_key(&pod)) { "added" } else { "modified" };
+                        watch_event(event_type, Some(pod_info(pod)), None, None)
+                    }
+                    Ok(watcher::Event::Delete(pod)) => {
+                        known.remove(&pod_key(&pod));
+                        watch_event("deleted", Some(pod_info(pod)), None, None)
+                    }
+                    // The backoff retries on its own; the frontend just shows the watch is unhealthy
+                    Err(e) => watch_event("error", None, None, Some(e.to_string())),
+                };
+                let _ = app.emit(&event_name, &payload);
+            }
+
+            drop(cancel_rx);
+            let mut watches = watches.lock().await;
+            // A cancelled watch was already removed; only clean up when the stream ended by itself
+            if watches.get(&key).is_some_and(|w| w.cancel.is_closed()) {
+                watches.remove(&key);
+            }
+        });
+
+        Ok(event)
+    }
+
+    /// Drop one subscriber from a namespace's watch, stopping it with the last
+    pub async fn stop_watch(&self, context: &str, namespace: &str) -> Result<()> {
+        let key = (context.to_string(), namespace.to_string());
+        let mut watches = self.watches.lock().await;
+        if let Some(watch) = watches.get_mut(&key) {
+            watch.subscribers = watch.subscribers.saturating_sub(1);
+            if watch.subscribers == 0 {
+                if let Some(watch) = watches.remove(&key) {
+                    let _ = watch.cancel.send(());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Stop every watch regardless of subscribers, e.g. when the current context changes
+    pub async fn stop_all(&self) {
+        for (_, watch) in self.watches.lock().await.drain() {
+            let _ = watch.cancel.send(());
+        }
+    }
+}
+
+impl Default for PodWatchManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Streams changes to any kind found through discovery, CRDs included, without per-kind code
+pub struct ResourceWatchManager {
+    watches: Arc<Mutex<HashMap<String, oneshot::Sender<()>>>>,
+}
+
+impl ResourceWatchManager {
+    pub fn new() -> Self {
+        Self {
+            watches: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Watch `resource_type` (kind or plural, any case) in `namespace`, or
+    /// cluster-wide when it is empty or the kind isn't namespaced
+    ///
+    /// Emits `resource-watch-{watch_id}` with a [`ResourceWatchEvent`]: `synced`
+    /// with every object first and after each re-list, then `added`, `modified`
+    /// and `deleted` per object.
+    pub async fn start_watch(
+        &self,
+        app: AppHandle,
+        client: Client,
+        resource_type: &str,
+        namespace: &str,
+    ) -> Result<ResourceWatchStarted> {
+        let (resource, scope) = resolve_kind(client.clone(), resource_type).await?;
+        let namespaced = scope == Scope::Namespaced;
+        let api: Api<DynamicObject> = if namespaced && !namespace.is_empty() {
+            Api::namespaced_with(client, namespace, &resource)
+        } else {
+            Api::all_with(client, &resource)
+        };
+
+        let watch_id = Uuid::new_v4().to_string();
+        let (cancel_tx, mut cancel_rx) = oneshot::channel::<()>();
+        self.watches.lock().await.insert(watch_id.clone(), cancel_tx);
+
+        let started = ResourceWatchStarted {
+            watch_id: watch_id.clone(),
+            kind: resource.kind.clone(),
+            api_version: resource.api_version.clone(),
+            namespaced,
+        };
+
+        let watches = self.watches.clone();
+        let id = watch_id.clone();
+        tokio::spawn(async move {
+            let event_name = format!("resource-watch-{}", id);
+            let stream = watcher(api, watcher::Config::default()).default_backoff();
+            futures::pin_mut!(stream);
+
+            let mut known: HashSet<String> = HashSet::new();
+            let mut relisted: Vec<DynamicObject> = Vec::new();
+
+            loop {
+                let next = tokio::select! {
+                    next = stream.next() => next,
+                    _ = &mut cancel_rx => break,
+                };
+                let Some(next) = next else { break };
+
+                let payload = match next {
+                    Ok(watcher::Event::Init) => {
+                        relisted.clear();
+                        continue;
+                    }
+                    Ok(watcher::Event::InitApply(object)) => {
+                        relisted.push(object);
+                        continue;
+                    }
+                    Ok(watcher::Event::InitDone) => {
+                        known = relisted.iter().map(object_key).collect();
+                        let objects = relisted.drain(..).map(|o| object_json(o, &resource)).collect();
+                        resource_event("synced", None, Some(objects), None)
+                    }
+                    Ok(watcher::Event::Apply(object)) => {
+                        let event_type = if known.insert(object_key(&object)) { "added" } else { "modified" };
+                        resource_event(event_type, Some(object_json(object, &resource)), None, None)
+                    }
+                    Ok(watcher::Event::Delete(object)) => {
+                        known.remove(&object_key(&object));
+                        resource_event("deleted", Some(object_json(object, &resource)), None, None)
+                    }
+                    Err(e) => resource_event("error", None, None, Some(e.to_string())),
+                };
+                let _ = app.emit(&event_name, &payload);
+            }
+
+            watches.lock().await.remove(&id);
+        });
+
+        Ok(started)
+    }
+
+    pub async fn stop_watch(&self, watch_id: &str) -> Result<()> {
+        if let Some(cancel) = self.watches.lock().await.remove(watch_id) {
+            let _ = cancel.send(());
+        }
+        Ok(())
+    }
+}
+
+impl Default for ResourceWatchManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An object as the frontend gets it: apiVersion and kind filled in, managedFields dropped
+fn object_json(object: DynamicObject, resource: &ApiResource) -> Value {
+    let mut value = serde_json::to_value(object).unwrap_or(Value::Null);
+    if let Some(map) = value.as_object_mut() {
+        // Watch events for DynamicObjects don't always carry these
+        map.insert("apiVersion".to_string(), Value::String(resource.api_version.clone()));
+        map.insert("kind".to_string(), Value::String(resource.kind.clone()));
+        // Often larger than the object itself, and no view shows it
+        if let Some(metadata) = map.get_mut("metadata").and_then(|m| m.as_object_mut()) {
+            metadata.remove("managedFields");
+        }
+    }
+    value
+}
+
+fn object_key(object: &DynamicObject) -> String {
+    format!("{}/{}", object.namespace().unwrap_or_default(), object.name_any())
+}
+
+fn resource_event(
+    event_type: &str,
+    object: Option<Value>,
+    objects: Option<Vec<Value>>,
+    message: Option<String>,
+) -> ResourceWatchEvent {
+    ResourceWatchEvent {
+        event_type: event_type.to_string(),
+        object,
+        objects,
+        message,
+    }
+}
+
+fn watch_event(
+    event_type: &str,
+    pod: Option<PodInfo>,
+    pods: Option<Vec<PodInfo>>,
+    message: Option<String>,
+) -> PodWatchEvent {
+    PodWatchEvent {
+        event_type: event_type.to_string(),
+        pod,
+        pods,
+        message,
+    }
+}
+
+fn pod_key(pod: &Pod) -> String {
+    format!("{}/{}", pod.namespace().unwrap_or_default(), pod.name_any())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pod_watch_event_name() {
+        assert_eq!(pod_watch_event_name("prod", "web"), "pods-changed-prod/web");
+        assert_eq!(pod_watch_event_name("prod", ""), "pods-changed-prod/_all");
+        assert_eq!(
+            pod_watch_event_name("arn:aws:eks:us-east-1:1:cluster/a b", "web"),
+            "pods-changed-arn:aws:eks:us-east-1:1:cluster/a_b/web"
+        );
+    }
+}
@@ -0,0 +1,273 @@
+use anyhow::Result;
+use k8s_openapi::api::apps::v1::Deployment;
+use k8s_openapi::api::core::v1::Service;
+use k8s_openapi::api::networking::v1::Ingress;
+use kube::api::{Api, PostParams};
+use kube::Client;
+use serde_json::json;
+use std::collections::BTreeMap;
+
+use crate::types::{SimpleDeploymentSpec, SimpleIngressSpec, SimpleServiceSpec};
+
+/// Create a Deployment from a handful of fields
+///
+/// Labels default to `app: <name>`, which also becomes the selector. Returns
+/// the created manifest as YAML; with `dry_run` nothing is persisted.
+pub async fn create_deployment_simple(
+    client: Client,
+    spec: SimpleDeploymentSpec,
+    dry_run: bool,
+) -> Result<String> {
+    validate_name(&spec.name)?;
+    if spec.image.trim().is_empty() {
+        return Err(anyhow::anyhow!("Image is required"));
+    }
+
+    let mut labels: BTreeMap<String, String> = spec.labels.clone().into_iter().collect();
+    labels.entry("app".to_string()).or_insert_with(|| spec.name.clone());
+
+    let env: Vec<_> = {
+        let mut env: Vec<(&String, &String)> = spec.env.iter().collect();
+        env.sort();
+        env.into_iter()
+            .map(|(name, value)| json!({ "name": name, "value": value }))
+            .collect()
+    };
+
+    let ports: Vec<_> = spec
+        .ports
+        .iter()
+        .map(|port| json!({ "containerPort": port, "protocol": "TCP" }))
+        .collect();
+
+    let mut resources = json!({});
+    if spec.cpu_request.is_some() || spec.memory_request.is_some() {
+        resources["requests"] = json!({});
+        if let Some(cpu) = &spec.cpu_request {
+            resources["requests"]["cpu"] = json!(cpu);
+        }
+        if let Some(memory) = &spec.memory_request {
+            resources["requests"]["memory"] = json!(memory);
+        }
+    }
+    if spec.cpu_limit.is_some() || spec.memory_limit.is_some() {
+        resources["limits"] = json!({});
+        if let Some(cpu) = &spec.cpu_limit {
+            resources["limits"]["cpu"] = json!(cpu);
+        }
+        if let Some(memory) = &spec.memory_limit {
+            resources["limits"]["memory"] = json!(memory);
+        }
+    }
+
+    let mut container = json!({
+        "name": spec.name,
+        "image": spec.image,
+        "ports": ports,
+        "env": env,
+        "resources": resources,
+    });
+    if let Some(command) = &spec.command {
+        container["command"] = json!(command);
+    }
+
+    let deployment: Deployment = serde_json::from_value(json!({
+        "apiVersion": "apps/v1",
+        "kind": "Deployment",
+        "metadata": {
+            "name": spec.name,
+            "namespace": spec.namespace,
+            "labels": labels,
+        },
+        "spec": {
+            "replicas": spec.replicas.unwrap_or(1),
+            "selector": { "matchLabels": labels },
+            "template": {
+                "metadata": { "labels": labels },
+                "spec": { "containers": [container] }
+            }
+        }
+    }))?;
+
+    let api: Api<Deployment> = Api::namespaced(client, &spec.namespace);
+    let created = api.create(&post_params(dry_run), &deployment).await?;
+
+    Ok(serde_yaml::to_string(&created)?)
+}
+
+/// Create a Service that selects an existing Deployment's pods
+///
+/// The selector is copied from the Deployment, and when no ports are given
+/// every declared container port is exposed on the same number and protocol. A
+/// `target_port` override only makes sense for a single port, so it is
+/// rejected when there are several.
+pub async fn create_service_for_workload(
+    client: Client,
+    spec: SimpleServiceSpec,
+    dry_run: bool,
+) -> Result<String> {
+    let deployments: Api<Deployment> = Api::namespaced(client.clone(), &spec.namespace);
+    let deployment = deployments.get(&spec.workload_name).await?;
+
+    let deployment_spec = deployment
+        .spec
+        .ok_or_else(|| anyhow::anyhow!("Deployment has no spec"))?;
+    let selector = deployment_spec
+        .selector
+        .match_labels
+        .filter(|labels| !labels.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("Deployment {} has no matchLabels to select on", spec.workload_name))?;
+
+    // A port is identified by number and protocol: 53/TCP and 53/UDP are two
+    // ports, while the same port declared by two containers is one
+    let declared: Vec<(i32, String)> = if spec.ports.is_empty() {
+        deployment_spec
+            .template
+            .spec
+            .map(|pod| {
+                pod.containers
+                    .iter()
+                    .flat_map(|c| c.ports.iter().flatten())
+                    .map(|p| (p.container_port, p.protocol.clone().unwrap_or_else(|| "TCP".to_string())))
+                    .collect()
+            })
+            .unwrap_or_default()
+    } else {
+        spec.ports.iter().map(|port| (*port, "TCP".to_string())).collect()
+    };
+    let mut ports: Vec<(i32, String)> = Vec::new();
+    for port in declared {
+        if !ports.contains(&port) {
+            ports.push(port);
+        }
+    }
+
+    if ports.is_empty() {
+        return Err(anyhow::anyhow!("No ports given and the workload declares no container ports"));
+    }
+    if spec.target_port.is_some() && ports.len() > 1 {
+        return Err(anyhow::anyhow!(
+            "A target port can only be set for a single port, but {} ports would be exposed",
+            ports.len()
+        ));
+    }
+
+    let name = spec.name.clone().unwrap_or_else(|| spec.workload_name.clone());
+    validate_name(&name)?;
+
+    let service_ports: Vec<_> = ports
+        .iter()
+        .map(|(port, protocol)| {
+            let name = if protocol == "TCP" {
+                format!("port-{}", port)
+            } else {
+                format!("port-{}-{}", port, protocol.to_lowercase())
+            };
+            json!({
+                "name": name,
+                "port": port,
+                "targetPort": spec.target_port.unwrap_or(*port),
+                "protocol": protocol,
+            })
+        })
+        .collect();
+
+    let service: Service = serde_json::from_value(json!({
+        "apiVersion": "v1",
+        "kind": "Service",
+        "metadata": {
+            "name": name,
+            "namespace": spec.namespace,
+        },
+        "spec": {
+            "type": spec.service_type.as_deref().unwrap_or("ClusterIP"),
+            "selector": selector,
+            "ports": service_ports,
+        }
+    }))?;
+
+    let api: Api<Service> = Api::namespaced(client, &spec.namespace);
+    let created = api.create(&post_params(dry_run), &service).await?;
+
+    Ok(serde_yaml::to_string(&created)?)
+}
+
+/// Create an Ingress routing one host/path to a Service
+pub async fn create_ingress_simple(
+    client: Client,
+    spec: SimpleIngressSpec,
+    dry_run: bool,
+) -> Result<String> {
+    validate_name(&spec.name)?;
+    if spec.host.trim().is_empty() {
+        return Err(anyhow::anyhow!("Host is required"));
+    }
+
+    let mut ingress_spec = json!({
+        "rules": [{
+            "host": spec.host,
+            "http": {
+                "paths": [{
+                    "path": spec.path.as_deref().unwrap_or("/"),
+                    "pathType": spec.path_type.as_deref().unwrap_or("Prefix"),
+                    "backend": {
+                        "service": {
+                            "name": spec.service_name,
+                            "port": { "number": spec.service_port }
+                        }
+                    }
+                }]
+            }
+        }]
+    });
+
+    if let Some(class) = &spec.ingress_class {
+        ingress_spec["ingressClassName"] = json!(class);
+    }
+    if let Some(secret) = &spec.tls_secret {
+        ingress_spec["tls"] = json!([{ "hosts": [spec.host], "secretName": secret }]);
+    }
+
+    let ingress: Ingress = serde_json::from_value(json!({
+        "apiVersion": "networking.k8s.io/v1",
+        "kind": "Ingress",
+        "metadata": {
+            "name": spec.name,
+            "namespace": spec.namespace,
+            "annotations": spec.annotations,
+        },
+        "spec": ingress_spec,
+    }))?;
+
+    let api: Api<Ingress> = Api::namespaced(client, &spec.namespace);
+    let created = api.create(&post_params(dry_run), &ingress).await?;
+
+    Ok(serde_yaml::to_string(&created)?)
+}
+
+fn post_params(dry_run: bool) -> PostParams {
+    PostParams {
+        dry_run,
+        ..Default::default()
+    }
+}
+
+/// Kubernetes object names must be DNS-1123 labels for these kinds
+fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= 63
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        && !name.starts_with('-')
+        && !name.ends_with('-');
+
+    if valid {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!(
+            "Invalid name '{}': use lowercase letters, digits and '-', at most 63 characters",
+            name
+        ))
+    }
+}
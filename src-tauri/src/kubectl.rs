@@ -0,0 +1,194 @@
+use crate::kube::{load_custom_kubeconfig, load_kubeconfig};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KubectlRunResult {
+    pub context: String,
+    pub kubeconfig: Option<String>,
+    pub args: Vec<String>,
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: Option<i32>,
+}
+
+/// Run kubectl pinned to a specific context
+///
+/// The context is always passed explicitly with `--context` (defaulting to
+/// the kubeconfig's current context), so the command can never silently run
+/// against a different cluster than the one the UI shows.
+pub async fn run_kubectl(
+    args: Vec<String>,
+    context: Option<&str>,
+    kubeconfig: Option<&str>,
+) -> Result<KubectlRunResult> {
+    let args = sanitize_args(args)?;
+    let context = effective_context(context, kubeconfig)?;
+
+    let mut cmd = Command::new("kubectl");
+    if let Some(path) = kubeconfig {
+        cmd.arg("--kubeconfig").arg(path);
+    }
+    cmd.arg("--context").arg(&context);
+    cmd.args(&args);
+
+    tracing::info!("Running kubectl --context {} {}", context, args.join(" "));
+
+    let output = cmd
+        .output()
+        .await
+        .map_err(|e| anyhow!("Failed to run kubectl: {}", e))?;
+
+    Ok(KubectlRunResult {
+        context,
+        kubeconfig: kubeconfig.map(|s| s.to_string()),
+        args,
+        stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+        stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        exit_code: output.status.code(),
+    })
+}
+
+/// The context kubectl will run against: the one asked for, otherwise the
+/// current context of the given kubeconfig, or of the default one
+pub fn effective_context(context: Option<&str>, kubeconfig: Option<&str>) -> Result<String> {
+    let context = match (context, kubeconfig) {
+        (Some(ctx), _) => ctx.to_string(),
+        (None, Some(path)) => load_custom_kubeconfig(path)?.current_context,
+        (None, None) => load_kubeconfig()?.current_context,
+    };
+    if context.is_empty() {
+        return Err(anyhow!("No kubectl context selected"));
+    }
+    Ok(context)
+}
+
+/// kubectl subcommands that change cluster state
+///
+/// `exec`, `cp`, `attach` and `debug` run code in or write files to a container,
+/// and `certificate` approves or denies CSRs, so they count too.
+const MUTATING_VERBS: &[&str] = &[
+    "apply", "create", "delete", "edit", "patch", "replace", "scale", "rollout", "drain",
+    "cordon", "uncordon", "taint", "label", "annotate", "set", "expose", "autoscale", "run",
+    "exec", "cp", "attach", "debug", "certificate",
+];
+
+/// Global and common flags whose value is the next argument when not given as `--flag=value`
+const FLAGS_WITH_VALUE: &[&str] = &[
+    "-n", "--namespace", "-l", "--selector", "-o", "--output", "-f", "--filename", "-c",
+    "--container", "--field-selector", "--request-timeout", "-v", "--v", "--cache-dir",
+    "--tls-server-name", "--client-certificate", "--client-key",
+];
+
+/// Whether the arguments invoke a subcommand that modifies the cluster
+pub fn is_mutating(args: &[String]) -> bool {
+    let mut args = args.iter().skip_while(|a| a.as_str() == "kubectl");
+    let mut verb = None;
+    while let Some(arg) = args.next() {
+        if !arg.starts_with('-') {
+            verb = Some(arg);
+            break;
+        }
+        if FLAGS_WITH_VALUE.contains(&arg.as_str()) {
+            args.next();
+        }
+    }
+    verb.map(|v| MUTATING_VERBS.contains(&v.as_str())).unwrap_or(false)
+}
+
+/// Flags that would point kubectl at another cluster or identity than the pinned context
+const OVERRIDE_FLAGS: &[&str] = &[
+    "--context", "--kubeconfig", "--server", "-s", "--cluster", "--user", "--token", "--as",
+    "--as-group", "--as-uid", "--certificate-authority", "--client-certificate", "--client-key",
+    "--username", "--password", "--tls-server-name", "--insecure-skip-tls-verify",
+];
+
+/// The override flag `arg` sets, in `--flag value`, `--flag=value`, `-x value`,
+/// `-x=value` or `-xVALUE` form
+fn override_flag(arg: &str) -> Option<&'static str> {
+    let name = if arg.starts_with("--") {
+        arg.split('=').next().unwrap_or(arg)
+    } else if arg.starts_with('-') {
+        arg.get(..2).unwrap_or(arg)
+    } else {
+        return None;
+    };
+    OVERRIDE_FLAGS.iter().copied().find(|flag| *flag == name)
+}
+
+/// Drop a leading `kubectl` and reject flags that would override the pinned
+/// context, kubeconfig or identity
+fn sanitize_args(mut args: Vec<String>) -> Result<Vec<String>> {
+    if args.first().map(|a| a == "kubectl").unwrap_or(false) {
+        args.remove(0);
+    }
+
+    if args.is_empty() {
+        return Err(anyhow!("No kubectl arguments given"));
+    }
+
+    // Whatever follows `--` goes to the command run in a container, not to kubectl
+    for arg in args.iter().take_while(|a| a.as_str() != "--") {
+        if let Some(flag) = override_flag(arg) {
+            return Err(anyhow!(
+                "{} cannot be passed directly; choose the context in the app instead",
+                flag
+            ));
+        }
+    }
+
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_is_mutating() {
+        assert!(is_mutating(&args("delete pod web-0")));
+        assert!(is_mutating(&args("kubectl -n shop delete pod web-0")));
+        assert!(is_mutating(&args("--namespace shop -l app=web scale deploy --replicas=0")));
+        assert!(is_mutating(&args("-o=json label pod web-0 tier=front")));
+        assert!(is_mutating(&args("exec web-0 -- rm -rf /data")));
+        assert!(is_mutating(&args("cp ./dump.sql shop/db-0:/tmp")));
+        assert!(is_mutating(&args("-nshop debug web-0 -it --image=busybox")));
+        assert!(is_mutating(&args("attach web-0 -i")));
+        assert!(is_mutating(&args("certificate approve csr-1")));
+        assert!(!is_mutating(&args("-n delete get pods")));
+        assert!(!is_mutating(&args("get pods -n shop")));
+        assert!(!is_mutating(&args("--output yaml get deploy")));
+        assert!(!is_mutating(&args("")));
+    }
+
+    #[test]
+    fn test_sanitize_args() {
+        assert_eq!(sanitize_args(args("kubectl get pods")).unwrap(), args("get pods"));
+        for line in [
+            "get pods --context prod",
+            "get pods --kubeconfig=/tmp/other",
+            "get pods --server https://10.0.0.1",
+            "get pods -s=https://10.0.0.1",
+            "get pods --cluster=prod",
+            "get pods --user admin",
+            "get pods --token=abc",
+            "delete ns shop --as=system:admin",
+            "get pods --certificate-authority /tmp/ca.crt",
+            "get pods -shttps://evil:6443",
+            "get pods -s=https://evil:6443",
+            "get pods --client-key=/tmp/key.pem",
+            "get pods --username admin --password hunter2",
+            "delete ns shop --as-group=system:masters",
+        ] {
+            assert!(sanitize_args(args(line)).is_err(), "{} was accepted", line);
+        }
+        assert!(sanitize_args(args("exec web-0 -- curl -s --user a:b http://x")).is_ok());
+        assert!(sanitize_args(args("get pods -nshop --selector=app=web")).is_ok());
+        assert!(sanitize_args(Vec::new()).is_err());
+    }
+}
@@ -1,22 +1,50 @@
+mod audit;
+mod automation;
 mod commands;
 mod database;
+mod deeplink;
+pub mod extensions;
+mod grafana;
 mod helm;
 mod kube;
+mod kubectl;
+mod logging;
 mod metrics;
+mod plugins;
+mod policy;
 mod portforward;
+mod preferences;
+mod quantity;
+mod recording;
+mod settings;
 mod shell;
+mod shutdown;
+mod templates;
+mod tray;
 mod types;
+mod usage;
 
+use automation::AutomationManager;
 use database::ConnectionManager;
-use kube::KubeClientManager;
+use extensions::{Extension, ExtensionRegistry};
+use kube::{
+    FileTailManager, ImageRestartManager, KubeClientManager, ListCache, NamespaceTerminationManager,
+    NodeAlertMonitor, NodeDrainManager, PodWatchManager, ResourceCache, ResourceWatchManager, RolloutWatchManager,
+    TektonLogManager,
+};
+use metrics::{MetricsSampler, TopStreamManager};
+use plugins::PluginManager;
 use portforward::PortForwardManager;
 use shell::ShellManager;
 use std::collections::HashMap;
 use std::sync::Arc;
+use tauri::Manager;
 use tokio::sync::RwLock;
 
 /// Set up PATH environment variable to include common locations for kubectl and its plugins
-fn setup_path_env() {
+///
+/// Runs before logging is up, so the new PATH is returned for `setup` to log.
+fn setup_path_env() -> String {
     use std::env;
 
     let current_path = env::var("PATH").unwrap_or_default();
@@ -59,62 +87,200 @@ fn setup_path_env() {
     let new_path = paths.join(separator);
 
     env::set_var("PATH", &new_path);
-    tracing::info!("Set PATH to: {}", new_path);
+    new_path
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    // Initialize logging
-    tracing_subscriber::fmt::init();
+    run_with_extensions(Vec::new())
+}
 
+/// Start the app with extra commands and views compiled in; see [`extensions::Extension`]
+pub fn run_with_extensions(extensions: Vec<Arc<dyn Extension>>) {
     // Set up PATH to include common locations for kubectl and its plugins
-    setup_path_env();
+    let path = setup_path_env();
 
     let client_manager = KubeClientManager::new();
     let portforward_manager = PortForwardManager::new();
     let shell_manager = ShellManager::new();
+    let plugin_manager = PluginManager::new();
+    let metrics_sampler = MetricsSampler::new();
+    let top_stream_manager = TopStreamManager::new();
+    let tekton_log_manager = TektonLogManager::new();
+    let file_tail_manager = FileTailManager::new();
+    let rollout_watch_manager = RolloutWatchManager::new();
+    let node_drain_manager = NodeDrainManager::new();
+    let node_alert_monitor = NodeAlertMonitor::new();
+    let namespace_termination_manager = NamespaceTerminationManager::new();
+    let image_restart_manager = ImageRestartManager::new();
+    let pod_watch_manager = PodWatchManager::new();
+    let resource_watch_manager = ResourceWatchManager::new();
+    let resource_cache = ResourceCache::new();
+    let list_cache = ListCache::new();
+    let tray_state = tray::TrayState::new();
     let connection_manager: ConnectionManager = Arc::new(RwLock::new(HashMap::new()));
 
     tauri::Builder::default()
+        // Must come first: a second launch only focuses this window (and forwards its
+        // deep link) instead of starting another set of port-forwards and watchers
+        .plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| {
+            tray::show_window(app);
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_store::Builder::new().build())
+        .plugin(tauri_plugin_clipboard_manager::init())
         .manage(client_manager)
         .manage(portforward_manager)
         .manage(shell_manager)
         .manage(connection_manager)
-        .invoke_handler(tauri::generate_handler![
+        .manage(plugin_manager)
+        .manage(metrics_sampler)
+        .manage(top_stream_manager)
+        .manage(tekton_log_manager)
+        .manage(file_tail_manager)
+        .manage(rollout_watch_manager)
+        .manage(node_drain_manager)
+        .manage(node_alert_monitor)
+        .manage(namespace_termination_manager)
+        .manage(image_restart_manager)
+        .manage(pod_watch_manager)
+        .manage(resource_watch_manager)
+        .manage(resource_cache)
+        .manage(list_cache)
+        .manage(tray_state)
+        .manage(deeplink::PendingDeepLink::default())
+        .manage(ExtensionRegistry::new(extensions))
+        .manage(AutomationManager::new())
+        .setup(move |app| {
+            // Here rather than first thing in run(): the log dir comes from the app's path resolver
+            logging::init(app.handle())?;
+            tracing::info!("Set PATH to: {}", path);
+            usage::init(app.handle())?;
+            // Before any forward starts: a crashed session's kubectl processes may still hold ports
+            let lockfile = app.path().app_data_dir()?.join(portforward::LOCKFILE);
+            portforward::reap_orphans(&lockfile);
+            app.state::<PortForwardManager>().set_lockfile(lockfile);
+            settings::apply_rate_limits(app.handle(), &app.state::<KubeClientManager>())?;
+            // A broken extensions.json shouldn't keep the app from starting
+            let registry = app.state::<ExtensionRegistry>();
+            if let Err(e) = tauri::async_runtime::block_on(registry.load_external(app.handle())) {
+                tracing::warn!("Failed to load external extensions: {}", e);
+            }
+            tray::init(app.handle())?;
+            deeplink::setup(app.handle())?;
+            Ok(())
+        })
+        .invoke_handler(usage::counted(tauri::generate_handler![
             commands::get_kubeconfig_contexts,
+            commands::get_context_tags,
+            commands::set_context_tag,
             commands::get_clusters,
+            commands::get_all_contexts_health,
+            commands::get_control_plane_health,
+            commands::get_apiserver_metrics,
+            commands::proxy_service_request,
+            commands::proxy_pod_request,
+            commands::detect_admission_problems,
             commands::get_namespaces,
+            commands::get_namespace_termination_status,
+            commands::watch_namespace_termination,
+            commands::stop_namespace_termination_watch,
+            commands::remove_stuck_finalizer,
+            commands::clone_namespace,
+            commands::copy_resource,
+            commands::create_deployment_simple,
+            commands::create_service_for_workload,
+            commands::create_ingress_simple,
             commands::get_pods,
+            commands::get_pods_page,
+            commands::start_pod_watch,
+            commands::stop_pod_watch,
+            commands::get_resource_list,
+            commands::start_resource_watch,
+            commands::stop_resource_watch,
             commands::get_deployments,
+            commands::get_deployments_page,
             commands::get_services,
+            commands::get_services_page,
             commands::get_pod_logs,
+            commands::search_logs,
+            commands::get_all_container_logs,
             commands::delete_pod,
+            commands::restart_container,
+            commands::exec_command,
+            commands::tail_container_file,
+            commands::stop_container_file_tail,
+            commands::capture_runtime_diagnostic,
             commands::scale_deployment,
+            commands::bulk_scale,
+            commands::restore_bulk_scale,
             commands::restart_deployment,
+            commands::preview_image_restart,
+            commands::restart_workloads_using_image,
+            commands::stop_image_restart,
+            commands::watch_rollout,
+            commands::stop_rollout_watch,
+            commands::analyze_spread,
+            commands::canary_deploy,
+            commands::promote_canary,
+            commands::rollback_canary,
             commands::delete_deployment,
             commands::delete_service,
+            commands::patch_service_type,
+            commands::set_service_annotations,
+            commands::get_load_balancer_annotation_presets,
             commands::delete_configmap,
             commands::delete_secret,
+            commands::create_docker_registry_secret,
+            commands::attach_pull_secret_to_serviceaccount,
+            commands::search_config_data,
+            commands::get_image_inventory,
+            commands::get_addon_versions,
+            commands::list_image_tags,
             commands::reinit_kube_client,
+            commands::clear_credential_cache,
             commands::switch_kube_context,
+            commands::reset_context_clients,
+            commands::get_api_retry_stats,
             commands::load_custom_kubeconfig_file,
             commands::get_current_context_info,
+            commands::get_allowed_actions,
+            commands::generate_scoped_kubeconfig,
             commands::get_ingresses,
+            commands::test_ingress_route,
             commands::get_istio_virtual_services,
+            commands::set_virtualservice_weights,
+            commands::get_proxy_diagnostics,
+            commands::get_proxy_stats,
+            commands::get_proxy_config_dump,
             commands::get_istio_gateways,
             commands::get_resource_yaml,
             commands::get_configmaps,
             commands::get_secrets,
             commands::get_statefulsets,
+            commands::get_statefulsets_page,
             commands::get_daemonsets,
+            commands::get_daemonsets_page,
             commands::get_jobs,
+            commands::get_jobs_page,
             commands::get_cronjobs,
             commands::get_nodes,
+            commands::get_node_groups_summary,
+            commands::get_autoscaler_insight,
+            commands::get_node_alerts,
+            commands::start_node_alert_monitor,
+            commands::stop_node_alert_monitor,
             commands::get_events,
+            commands::get_events_for_object,
+            commands::export_events,
+            commands::render_resource_table,
+            commands::query_resources,
+            commands::find_references,
+            commands::get_workload_timeline,
+            commands::debug_dns,
             commands::get_persistent_volumes,
             commands::get_persistent_volume_claims,
             commands::get_roles,
@@ -123,9 +289,24 @@ pub fn run() {
             commands::get_cluster_role_bindings,
             commands::get_service_accounts,
             commands::apply_resource_yaml,
+            commands::check_immutable_fields,
+            commands::recreate_resource,
+            commands::lint_manifest,
+            commands::list_resource_templates,
+            commands::render_template,
+            commands::check_manifest_policies,
+            commands::get_policy_gate,
+            commands::set_policy_gate,
             commands::scale_statefulset,
             commands::restart_statefulset,
             commands::delete_statefulset,
+            commands::get_statefulset_pvcs,
+            commands::delete_orphaned_pvcs,
+            commands::get_csi_drivers,
+            commands::get_csi_nodes,
+            commands::get_volume_attachments,
+            commands::get_storage_topology,
+            commands::get_volume_usage,
             commands::restart_daemonset,
             commands::delete_daemonset,
             commands::delete_job,
@@ -135,16 +316,46 @@ pub fn run() {
             commands::get_pods_for_resource,
             commands::start_port_forward,
             commands::stop_port_forward,
+            commands::stop_all_port_forwards,
             commands::list_port_forwards,
+            commands::get_tray_status,
+            commands::set_last_namespace,
+            commands::refresh_tray,
+            commands::take_pending_deep_link,
+            commands::get_app_logs,
+            commands::set_log_level,
+            commands::get_usage_report,
+            commands::set_usage_stats_enabled,
+            commands::reset_usage_stats,
+            commands::capture_profile,
             commands::cordon_node,
             commands::uncordon_node,
             commands::drain_node,
+            commands::drain_node_group,
+            commands::stop_node_group_drain,
             commands::delete_node,
             commands::describe_node,
             commands::describe_resource,
+            commands::get_cluster_integrations,
+            commands::set_cluster_integrations,
+            commands::get_prometheus_alerts,
+            commands::get_prometheus_rules,
+            commands::get_alertmanager_silences,
+            commands::get_grafana_links,
             commands::start_shell_session,
             commands::send_shell_input,
             commands::close_shell_session,
+            commands::list_shell_recordings,
+            commands::delete_shell_recording,
+            commands::get_recording_retention_days,
+            commands::set_recording_retention_days,
+            commands::get_shell_policy,
+            commands::set_shell_policy,
+            commands::get_context_settings,
+            commands::set_context_settings,
+            commands::export_settings,
+            commands::import_settings,
+            commands::get_audit_log,
             commands::get_pod_containers,
             commands::get_crds,
             commands::get_custom_resources,
@@ -162,11 +373,26 @@ pub fn run() {
             commands::helm_rollback_release,
             commands::helm_get_history,
             commands::helm_get_chart_values,
+            commands::helm_get_values_schema,
             commands::helm_upgrade_release,
             commands::detect_metrics_capabilities,
             commands::get_cluster_metrics_data,
             commands::get_namespace_pod_metrics,
-            commands::get_cnpg_cluster_connection,
+            commands::get_container_usage_history,
+            commands::normalize_quantity,
+            commands::get_pipeline_runs,
+            commands::get_task_runs,
+            commands::cancel_tekton_run,
+            commands::rerun_tekton_run,
+            commands::start_task_run_logs,
+            commands::stop_task_run_logs,
+            commands::get_knative_services,
+            commands::get_knative_revisions,
+            commands::set_knative_traffic,
+            commands::start_top_stream,
+            commands::stop_top_stream,
+            commands::get_cnpg_connection_details,
+            commands::copy_cnpg_connection_uri,
             commands::db_connect,
             commands::db_disconnect,
             commands::db_list_connections,
@@ -179,7 +405,31 @@ pub fn run() {
             commands::db_health_check,
             commands::db_current_database,
             commands::db_version,
-        ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+            commands::db_list_activity,
+            commands::db_list_locks,
+            commands::db_terminate_backend,
+            commands::db_dump_database,
+            commands::list_kubectl_plugins,
+            commands::run_kubectl_plugin,
+            commands::start_kubectl_plugin,
+            commands::stop_kubectl_plugin,
+            commands::list_extensions,
+            commands::invoke_extension,
+            commands::reload_extensions,
+            commands::start_automation,
+            commands::stop_automation,
+            commands::run_kubectl,
+            commands::get_cluster_version_info,
+            commands::scan_deprecated_apis,
+            commands::get_cluster_overview,
+            commands::get_cluster_problems,
+        ]))
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Kill kubectl children and close pools before the process goes away
+            if let tauri::RunEvent::Exit = event {
+                tauri::async_runtime::block_on(shutdown::shutdown(app));
+            }
+        });
 }
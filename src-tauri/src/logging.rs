@@ -0,0 +1,166 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager, Runtime};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
+
+use crate::types::AppLogEntry;
+
+const LOG_FILE_PREFIX: &str = "kubesail";
+const LOG_FILE_SUFFIX: &str = "log";
+
+/// Daily files kept before the oldest is deleted
+const MAX_LOG_FILES: usize = 7;
+
+/// Entries returned by `read_logs` when no limit is given
+const DEFAULT_LOG_LIMIT: usize = 1000;
+
+/// Levels `set_log_level` accepts, most verbose first
+const LEVELS: &[&str] = &["trace", "debug", "info", "warn", "error"];
+
+static FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+static LOG_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Dependencies (kube, hyper, rustls) stay at warn; their debug output drowns ours
+fn directive(level: &str) -> String {
+    format!("warn,kubesail_lib={}", level)
+}
+
+fn validate_level(level: &str) -> Result<String> {
+    let level = level.trim().to_ascii_lowercase();
+    if LEVELS.contains(&level.as_str()) {
+        Ok(level)
+    } else {
+        Err(anyhow!("Unknown log level {}; expected one of {}", level, LEVELS.join(", ")))
+    }
+}
+
+/// Human-readable logs on stdout plus JSON lines in a daily-rotated file in the app log dir
+///
+/// RUST_LOG overrides the starting level, as it did before.
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> Result<()> {
+    let dir = app.path().app_log_dir()?;
+    std::fs::create_dir_all(&dir)?;
+    let file = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(&dir)?;
+
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(directive("info")));
+    let (filter, handle) = reload::Layer::new(filter);
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer())
+        .with(fmt::layer().json().with_ansi(false).with_writer(file))
+        .try_init()?;
+
+    let _ = FILTER.set(handle);
+    let _ = LOG_DIR.set(dir);
+    Ok(())
+}
+
+/// Change how much is logged from now on, without a restart
+pub fn set_level(level: &str) -> Result<()> {
+    let level = validate_level(level)?;
+    let handle = FILTER.get().ok_or_else(|| anyhow!("Logging is not initialized"))?;
+    handle.reload(EnvFilter::new(directive(&level)))?;
+    tracing::info!("Log level set to {}", level);
+    Ok(())
+}
+
+fn severity(level: &str) -> usize {
+    LEVELS
+        .iter()
+        .position(|l| l.eq_ignore_ascii_case(level))
+        .unwrap_or(0)
+}
+
+/// One JSON log line as the file layer writes it, or None for anything else
+fn parse_line(line: &str) -> Option<AppLogEntry> {
+    let mut value: serde_json::Value = serde_json::from_str(line).ok()?;
+    let object = value.as_object_mut()?;
+    let mut fields = object.remove("fields").unwrap_or_default();
+    let message = fields
+        .as_object_mut()
+        .and_then(|f| f.remove("message"))
+        .and_then(|m| m.as_str().map(str::to_string))
+        .unwrap_or_default();
+    let text = |key: &str| object.get(key).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+
+    Some(AppLogEntry {
+        timestamp: text("timestamp"),
+        level: text("level"),
+        target: text("target"),
+        message,
+        fields: fields.as_object().filter(|f| !f.is_empty()).map(|f| serde_json::Value::Object(f.clone())),
+    })
+}
+
+/// Logged entries at `level` or more severe, newer than `since` (RFC 3339), oldest first
+///
+/// Only the newest `limit` matches are returned.
+pub fn read_logs(level: Option<&str>, since: Option<&str>, limit: Option<usize>) -> Result<Vec<AppLogEntry>> {
+    let dir = LOG_DIR.get().ok_or_else(|| anyhow!("Logging is not initialized"))?;
+    let min_severity = match level {
+        Some(level) => severity(&validate_level(level)?),
+        None => 0,
+    };
+    let since = since
+        .map(|s| DateTime::parse_from_rfc3339(s).map(|t| t.with_timezone(&Utc)))
+        .transpose()
+        .map_err(|e| anyhow!("Invalid since timestamp: {}", e))?;
+
+    // Rotated files are named prefix.YYYY-MM-DD.suffix, so name order is time order
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(LOG_FILE_PREFIX) && n.ends_with(LOG_FILE_SUFFIX))
+        })
+        .collect();
+    files.sort();
+
+    let mut entries = Vec::new();
+    for file in files {
+        let content = std::fs::read_to_string(&file)?;
+        entries.extend(content.lines().filter_map(parse_line).filter(|entry| {
+            severity(&entry.level) >= min_severity
+                && since.is_none_or(|since| {
+                    DateTime::parse_from_rfc3339(&entry.timestamp).is_ok_and(|t| t.with_timezone(&Utc) > since)
+                })
+        }));
+    }
+
+    let limit = limit.unwrap_or(DEFAULT_LOG_LIMIT);
+    if entries.len() > limit {
+        entries.drain(..entries.len() - limit);
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        let entry = parse_line(
+            r#"{"timestamp":"2026-10-15T09:30:00.123456Z","level":"WARN","fields":{"message":"Tray action failed","action":"show"},"target":"kubesail_lib::tray"}"#,
+        )
+        .unwrap();
+        assert_eq!(entry.level, "WARN");
+        assert_eq!(entry.message, "Tray action failed");
+        assert_eq!(entry.fields.unwrap()["action"], "show");
+        assert!(severity(&entry.level) >= severity("info"));
+        assert!(parse_line("not json").is_none());
+        assert!(validate_level("verbose").is_err());
+    }
+}
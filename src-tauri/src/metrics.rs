@@ -1,8 +1,13 @@
 use anyhow::Result;
 use kube::Client;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::process::Command;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::{oneshot, Mutex, RwLock};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetricsSource {
@@ -25,6 +30,8 @@ pub struct NodeMetrics {
     pub cpu_usage_cores: f64,     // Converted to cores
     pub memory_usage: String,      // e.g., "1024Mi" or "2Gi"
     pub memory_usage_bytes: u64,  // Converted to bytes
+    pub cpu_percent: Option<f64>,     // Of allocatable, as reported by metrics-server
+    pub memory_percent: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,6 +44,40 @@ pub struct PodMetrics {
     pub memory_usage_bytes: u64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerMetrics {
+    pub pod: String,
+    pub namespace: String,
+    pub container: String,
+    pub cpu_usage_cores: f64,
+    pub memory_usage_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageSample {
+    pub timestamp: i64,  // Unix millis
+    pub cpu_usage_cores: f64,
+    pub memory_usage_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerUsageHistory {
+    pub namespace: String,
+    pub pod: String,
+    pub container: String,
+    pub samples: Vec<UsageSample>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopSnapshot {
+    pub scope: String,    // pods or nodes
+    pub sort_by: String,  // cpu or memory
+    pub timestamp: i64,
+    pub pods: Vec<PodMetrics>,
+    pub nodes: Vec<NodeMetrics>,
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClusterMetricsData {
     pub total_cpu_capacity: f64,
@@ -154,9 +195,9 @@ pub async fn get_node_metrics() -> Result<Vec<NodeMetrics>> {
         if parts.len() >= 5 {
             let name = parts[0].to_string();
             let cpu = parts[1].to_string();
-            let _cpu_percent = parts[2];
+            let cpu_percent = parse_percent(parts[2]);
             let memory = parts[3].to_string();
-            let _memory_percent = parts[4];
+            let memory_percent = parse_percent(parts[4]);
 
             let cpu_cores = parse_cpu_to_cores(&cpu);
             let memory_bytes = parse_memory_to_bytes(&memory);
@@ -167,6 +208,8 @@ pub async fn get_node_metrics() -> Result<Vec<NodeMetrics>> {
                 cpu_usage_cores: cpu_cores,
                 memory_usage: memory,
                 memory_usage_bytes: memory_bytes,
+                cpu_percent,
+                memory_percent,
             });
         }
     }
@@ -174,6 +217,11 @@ pub async fn get_node_metrics() -> Result<Vec<NodeMetrics>> {
     Ok(metrics)
 }
 
+/// Parse kubectl's "12%" columns; "<unknown>" means no data yet
+fn parse_percent(value: &str) -> Option<f64> {
+    value.strip_suffix('%')?.parse().ok()
+}
+
 /// Get pod metrics using kubectl top pods
 pub async fn get_pod_metrics(namespace: Option<&str>) -> Result<Vec<PodMetrics>> {
     let mut args = vec!["top", "pods", "--no-headers"];
@@ -226,6 +274,304 @@ pub async fn get_pod_metrics(namespace: Option<&str>) -> Result<Vec<PodMetrics>>
     Ok(metrics)
 }
 
+/// Get per-container metrics for a pod using kubectl top pod --containers
+pub async fn get_container_metrics(namespace: &str, pod: &str) -> Result<Vec<ContainerMetrics>> {
+    let output = Command::new("kubectl")
+        .args(&["top", "pod", pod, "-n", namespace, "--containers", "--no-headers"])
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "kubectl top pod failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut metrics = Vec::new();
+
+    // POD NAME CPU MEMORY
+    for line in stdout.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() >= 4 {
+            metrics.push(ContainerMetrics {
+                pod: parts[0].to_string(),
+                namespace: namespace.to_string(),
+                container: parts[1].to_string(),
+                cpu_usage_cores: parse_cpu_to_cores(parts[2]),
+                memory_usage_bytes: parse_memory_to_bytes(parts[3]),
+            });
+        }
+    }
+
+    Ok(metrics)
+}
+
+/// How often watched pods are sampled (metrics-server resolution is ~15s)
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Samples kept per container (30 minutes at the default interval)
+const MAX_SAMPLES: usize = 120;
+
+/// Pods stop being sampled when nobody asked for their history for this long
+const WATCH_TTL: Duration = Duration::from_secs(300);
+
+type PodKey = (String, String);  // (namespace, pod)
+
+/// Collects per-container usage samples in the background for pods the UI is looking at
+pub struct MetricsSampler {
+    history: Arc<RwLock<HashMap<PodKey, HashMap<String, VecDeque<UsageSample>>>>>,
+    watched: Arc<RwLock<HashMap<PodKey, Instant>>>,
+    running: Arc<AtomicBool>,
+}
+
+impl MetricsSampler {
+    pub fn new() -> Self {
+        Self {
+            history: Arc::new(RwLock::new(HashMap::new())),
+            watched: Arc::new(RwLock::new(HashMap::new())),
+            running: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Return the usage history for a container and keep sampling its pod
+    ///
+    /// The first call for a pod takes an immediate sample so charts are never empty.
+    pub async fn get_container_usage_history(
+        &self,
+        namespace: &str,
+        pod: &str,
+        container: &str,
+    ) -> Result<ContainerUsageHistory> {
+        let key = (namespace.to_string(), pod.to_string());
+
+        let newly_watched = {
+            let mut watched = self.watched.write().await;
+            watched.insert(key.clone(), Instant::now()).is_none()
+        };
+
+        if newly_watched {
+            sample_pod(&self.history, &key).await?;
+        }
+        self.ensure_running();
+
+        let history = self.history.read().await;
+        let samples = history
+            .get(&key)
+            .and_then(|containers| containers.get(container))
+            .map(|samples| samples.iter().cloned().collect())
+            .unwrap_or_default();
+
+        Ok(ContainerUsageHistory {
+            namespace: namespace.to_string(),
+            pod: pod.to_string(),
+            container: container.to_string(),
+            samples,
+        })
+    }
+
+    fn ensure_running(&self) {
+        if self
+            .running
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            return;
+        }
+
+        let history = self.history.clone();
+        let watched = self.watched.clone();
+        let running_flag = self.running.clone();
+
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(SAMPLE_INTERVAL).await;
+
+                // Forget pods nobody is looking at any more. The flag is cleared
+                // under the watched lock, so a pod added right after still finds
+                // the sampler stopped and starts a new one. History is pruned
+                // under the same lock, so a pod watched (and sampled) after the
+                // prune keeps its first sample
+                let keys: Vec<PodKey> = {
+                    let mut watched = watched.write().await;
+                    watched.retain(|_, last| last.elapsed() < WATCH_TTL);
+                    if watched.is_empty() {
+                        running_flag.store(false, Ordering::SeqCst);
+                    }
+                    history.write().await.retain(|key, _| watched.contains_key(key));
+                    watched.keys().cloned().collect()
+                };
+
+                if keys.is_empty() {
+                    break;
+                }
+
+                for key in &keys {
+                    if let Err(e) = sample_pod(&history, key).await {
+                        tracing::debug!("Failed to sample metrics for {}/{}: {}", key.0, key.1, e);
+                    }
+                }
+            }
+        });
+    }
+}
+
+impl Default for MetricsSampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+async fn sample_pod(
+    history: &RwLock<HashMap<PodKey, HashMap<String, VecDeque<UsageSample>>>>,
+    key: &PodKey,
+) -> Result<()> {
+    let metrics = get_container_metrics(&key.0, &key.1).await?;
+    let timestamp = chrono::Utc::now().timestamp_millis();
+
+    let mut history = history.write().await;
+    let containers = history.entry(key.clone()).or_default();
+
+    for m in metrics {
+        let samples = containers.entry(m.container).or_default();
+        samples.push_back(UsageSample {
+            timestamp,
+            cpu_usage_cores: m.cpu_usage_cores,
+            memory_usage_bytes: m.memory_usage_bytes,
+        });
+        while samples.len() > MAX_SAMPLES {
+            samples.pop_front();
+        }
+    }
+
+    Ok(())
+}
+
+/// Default and minimum refresh period for top streams
+const TOP_DEFAULT_INTERVAL: Duration = Duration::from_secs(5);
+const TOP_MIN_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Runs live "top" views that push sorted snapshots as `top-stream-{id}` events
+pub struct TopStreamManager {
+    streams: Arc<Mutex<HashMap<String, oneshot::Sender<()>>>>,
+}
+
+impl TopStreamManager {
+    pub fn new() -> Self {
+        Self {
+            streams: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub async fn start_stream(
+        &self,
+        app: AppHandle,
+        scope: &str,
+        namespace: Option<String>,
+        sort_by: Option<&str>,
+        limit: Option<usize>,
+        interval_secs: Option<u64>,
+    ) -> Result<String> {
+        let scope = match scope {
+            "pods" | "nodes" => scope.to_string(),
+            other => anyhow::bail!("Unknown top scope: {} (expected pods or nodes)", other),
+        };
+        let sort_by = match sort_by.unwrap_or("cpu") {
+            key @ ("cpu" | "memory") => key.to_string(),
+            other => anyhow::bail!("Unknown sort key: {} (expected cpu or memory)", other),
+        };
+        let limit = limit.unwrap_or(20);
+        let interval = interval_secs
+            .map(Duration::from_secs)
+            .unwrap_or(TOP_DEFAULT_INTERVAL)
+            .max(TOP_MIN_INTERVAL);
+
+        let stream_id = uuid::Uuid::new_v4().to_string();
+        let (cancel_tx, mut cancel_rx) = oneshot::channel::<()>();
+        self.streams.lock().await.insert(stream_id.clone(), cancel_tx);
+
+        let streams = self.streams.clone();
+        let id = stream_id.clone();
+        tokio::spawn(async move {
+            let event_name = format!("top-stream-{}", id);
+
+            loop {
+                let snapshot = top_snapshot(&scope, namespace.as_deref(), &sort_by, limit).await;
+                if app.emit(&event_name, snapshot).is_err() {
+                    break;
+                }
+
+                tokio::select! {
+                    _ = tokio::time::sleep(interval) => {}
+                    _ = &mut cancel_rx => break,
+                }
+            }
+
+            streams.lock().await.remove(&id);
+        });
+
+        Ok(stream_id)
+    }
+
+    pub async fn stop_stream(&self, stream_id: &str) -> Result<()> {
+        match self.streams.lock().await.remove(stream_id) {
+            Some(cancel) => {
+                let _ = cancel.send(());
+                Ok(())
+            }
+            None => anyhow::bail!("Top stream not found"),
+        }
+    }
+}
+
+impl Default for TopStreamManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+async fn top_snapshot(scope: &str, namespace: Option<&str>, sort_by: &str, limit: usize) -> TopSnapshot {
+    let mut snapshot = TopSnapshot {
+        scope: scope.to_string(),
+        sort_by: sort_by.to_string(),
+        timestamp: chrono::Utc::now().timestamp_millis(),
+        pods: Vec::new(),
+        nodes: Vec::new(),
+        error: None,
+    };
+
+    if scope == "nodes" {
+        match get_node_metrics().await {
+            Ok(mut nodes) => {
+                if sort_by == "memory" {
+                    nodes.sort_by(|a, b| b.memory_usage_bytes.cmp(&a.memory_usage_bytes));
+                } else {
+                    nodes.sort_by(|a, b| b.cpu_usage_cores.total_cmp(&a.cpu_usage_cores));
+                }
+                nodes.truncate(limit);
+                snapshot.nodes = nodes;
+            }
+            Err(e) => snapshot.error = Some(e.to_string()),
+        }
+    } else {
+        match get_pod_metrics(namespace).await {
+            Ok(mut pods) => {
+                if sort_by == "memory" {
+                    pods.sort_by(|a, b| b.memory_usage_bytes.cmp(&a.memory_usage_bytes));
+                } else {
+                    pods.sort_by(|a, b| b.cpu_usage_cores.total_cmp(&a.cpu_usage_cores));
+                }
+                pods.truncate(limit);
+                snapshot.pods = pods;
+            }
+            Err(e) => snapshot.error = Some(e.to_string()),
+        }
+    }
+
+    snapshot
+}
+
 /// Get comprehensive cluster metrics
 pub async fn get_cluster_metrics(client: Client) -> Result<ClusterMetricsData> {
     use k8s_openapi::api::core::v1::Node;
@@ -306,52 +652,10 @@ pub async fn get_cluster_metrics(client: Client) -> Result<ClusterMetricsData> {
 
 /// Parse CPU string to cores (e.g., "250m" -> 0.25, "2" -> 2.0)
 fn parse_cpu_to_cores(cpu: &str) -> f64 {
-    if cpu.ends_with('m') {
-        cpu.trim_end_matches('m')
-            .parse::<f64>()
-            .unwrap_or(0.0)
-            / 1000.0
-    } else {
-        cpu.parse::<f64>().unwrap_or(0.0)
-    }
+    crate::quantity::parse_quantity(cpu).unwrap_or(0.0)
 }
 
 /// Parse memory string to bytes (e.g., "1024Mi" -> bytes, "2Gi" -> bytes)
 fn parse_memory_to_bytes(memory: &str) -> u64 {
-    let memory = memory.trim();
-
-    if memory.ends_with("Ki") {
-        memory
-            .trim_end_matches("Ki")
-            .parse::<u64>()
-            .unwrap_or(0)
-            * 1024
-    } else if memory.ends_with("Mi") {
-        memory
-            .trim_end_matches("Mi")
-            .parse::<u64>()
-            .unwrap_or(0)
-            * 1024
-            * 1024
-    } else if memory.ends_with("Gi") {
-        memory
-            .trim_end_matches("Gi")
-            .parse::<u64>()
-            .unwrap_or(0)
-            * 1024
-            * 1024
-            * 1024
-    } else if memory.ends_with("Ti") {
-        memory
-            .trim_end_matches("Ti")
-            .parse::<u64>()
-            .unwrap_or(0)
-            * 1024
-            * 1024
-            * 1024
-            * 1024
-    } else {
-        // Assume bytes
-        memory.parse::<u64>().unwrap_or(0)
-    }
+    crate::quantity::memory_to_bytes(memory).unwrap_or(0)
 }
@@ -0,0 +1,334 @@
+use crate::types::CommandOutput;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::{oneshot, Mutex};
+use uuid::Uuid;
+
+const PLUGIN_PREFIX: &str = "kubectl-";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KubectlPlugin {
+    pub name: String,  // e.g. "view-secret", as typed after `kubectl`
+    pub path: String,
+    pub shadowed: bool,  // another binary with the same name appears earlier in PATH
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginOutputLine {
+    pub stream: String, // stdout or stderr
+    pub line: String,
+}
+
+/// Tracks streaming plugin runs so they can be cancelled
+pub struct PluginManager {
+    runs: Arc<Mutex<HashMap<String, oneshot::Sender<()>>>>,
+    tasks: Arc<Mutex<Vec<tokio::task::JoinHandle<()>>>>,  // So shutdown can wait for children to be killed
+}
+
+impl PluginManager {
+    pub fn new() -> Self {
+        Self {
+            runs: Arc::new(Mutex::new(HashMap::new())),
+            tasks: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Start a plugin and stream its output as `plugin-output-{run_id}` events
+    ///
+    /// A final `plugin-exit-{run_id}` event carries the exit code (or null if
+    /// the run was cancelled).
+    pub async fn start_plugin(
+        &self,
+        app: AppHandle,
+        name: &str,
+        args: Vec<String>,
+        namespace: Option<&str>,
+        context: Option<&str>,
+    ) -> Result<String> {
+        let plugin = find_plugin(name)?;
+        let run_id = Uuid::new_v4().to_string();
+
+        let mut child = plugin_command(&plugin, &args, namespace, context)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("Failed to start kubectl plugin '{}'", plugin.name))?;
+
+        let stdout = child.stdout.take().context("Plugin has no stdout")?;
+        let stderr = child.stderr.take().context("Plugin has no stderr")?;
+
+        let (cancel_tx, cancel_rx) = oneshot::channel::<()>();
+        {
+            let mut runs = self.runs.lock().await;
+            runs.insert(run_id.clone(), cancel_tx);
+        }
+
+        let runs = self.runs.clone();
+        let id = run_id.clone();
+        let task = tokio::spawn(async move {
+            let output_event = format!("plugin-output-{}", id);
+
+            let stdout_task = forward_lines(app.clone(), output_event.clone(), "stdout", stdout);
+            let stderr_task = forward_lines(app.clone(), output_event, "stderr", stderr);
+
+            let exit_code = tokio::select! {
+                status = child.wait() => status.ok().and_then(|s| s.code()),
+                _ = cancel_rx => {
+                    let _ = child.kill().await;
+                    None
+                }
+            };
+
+            let _ = tokio::join!(stdout_task, stderr_task);
+            let _ = app.emit(&format!("plugin-exit-{}", id), exit_code);
+
+            runs.lock().await.remove(&id);
+        });
+
+        let mut tasks = self.tasks.lock().await;
+        tasks.retain(|t| !t.is_finished());
+        tasks.push(task);
+
+        Ok(run_id)
+    }
+
+    pub async fn stop_plugin(&self, run_id: &str) -> Result<()> {
+        let mut runs = self.runs.lock().await;
+        match runs.remove(run_id) {
+            Some(cancel) => {
+                let _ = cancel.send(());
+                Ok(())
+            }
+            None => anyhow::bail!("Plugin run not found"),
+        }
+    }
+
+    /// Cancel every run and wait (up to `timeout`) for their processes to be killed
+    pub async fn stop_all(&self, timeout: std::time::Duration) {
+        for (_, cancel) in self.runs.lock().await.drain() {
+            let _ = cancel.send(());
+        }
+
+        let tasks: Vec<_> = self.tasks.lock().await.drain(..).collect();
+        let _ = tokio::time::timeout(timeout, futures::future::join_all(tasks)).await;
+    }
+}
+
+impl Default for PluginManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn forward_lines<R>(
+    app: AppHandle,
+    event: String,
+    stream: &'static str,
+    reader: R,
+) -> tokio::task::JoinHandle<()>
+where
+    R: tokio::io::AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let _ = app.emit(
+                &event,
+                PluginOutputLine {
+                    stream: stream.to_string(),
+                    line,
+                },
+            );
+        }
+    })
+}
+
+/// Discover kubectl plugins on PATH, the same way `kubectl plugin list` does
+pub fn list_plugins() -> Vec<KubectlPlugin> {
+    let path_var = std::env::var_os("PATH").unwrap_or_default();
+    let mut plugins: Vec<KubectlPlugin> = Vec::new();
+
+    for dir in std::env::split_paths(&path_var) {
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+
+        let mut found: Vec<(String, PathBuf)> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| is_executable(p))
+            .filter_map(|p| {
+                let file_name = p.file_name()?.to_str()?.to_string();
+                plugin_name_from_file(&file_name).map(|name| (name, p))
+            })
+            .collect();
+        found.sort_by(|a, b| a.0.cmp(&b.0));
+
+        for (name, path) in found {
+            let shadowed = plugins.iter().any(|p| p.name == name);
+            plugins.push(KubectlPlugin {
+                name,
+                path: path.to_string_lossy().to_string(),
+                shadowed,
+            });
+        }
+    }
+
+    plugins
+}
+
+/// Run a plugin to completion and capture its output
+pub async fn run_plugin(
+    name: &str,
+    args: &[String],
+    namespace: Option<&str>,
+    context: Option<&str>,
+) -> Result<CommandOutput> {
+    let plugin = find_plugin(name)?;
+
+    let output = plugin_command(&plugin, args, namespace, context)
+        .output()
+        .await
+        .with_context(|| format!("Failed to run kubectl plugin '{}'", plugin.name))?;
+
+    Ok(CommandOutput {
+        stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+        stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        exit_code: output.status.code(),
+    })
+}
+
+fn find_plugin(name: &str) -> Result<KubectlPlugin> {
+    let wanted = name.trim_start_matches(PLUGIN_PREFIX).replace(' ', "-");
+
+    list_plugins()
+        .into_iter()
+        .find(|p| !p.shadowed && (p.name == wanted || p.name.replace(' ', "-") == wanted))
+        .ok_or_else(|| anyhow::anyhow!("kubectl plugin '{}' not found on PATH", name))
+}
+
+fn plugin_command(
+    plugin: &KubectlPlugin,
+    args: &[String],
+    namespace: Option<&str>,
+    context: Option<&str>,
+) -> Command {
+    let mut cmd = Command::new(&plugin.path);
+    cmd.args(scoped_args(args, namespace, context));
+    cmd
+}
+
+/// Add `--context`/`--namespace` unless the user already passed them
+///
+/// They go before a `--` separator, after which the plugin would treat them as
+/// positional arguments.
+fn scoped_args(args: &[String], namespace: Option<&str>, context: Option<&str>) -> Vec<String> {
+    let end = args.iter().position(|a| a == "--").unwrap_or(args.len());
+    let (flags, rest) = args.split_at(end);
+
+    let mut scoped = flags.to_vec();
+    if let Some(ctx) = context {
+        if !has_flag(flags, &["--context"]) {
+            scoped.extend(["--context".to_string(), ctx.to_string()]);
+        }
+    }
+    if let Some(ns) = namespace {
+        if !has_flag(flags, &["-n", "--namespace", "-A", "--all-namespaces"]) {
+            scoped.extend(["--namespace".to_string(), ns.to_string()]);
+        }
+    }
+    scoped.extend_from_slice(rest);
+    scoped
+}
+
+fn has_flag(args: &[String], names: &[&str]) -> bool {
+    args.iter().any(|arg| {
+        names.iter().any(|name| {
+            arg == name
+                || arg.strip_prefix(name).is_some_and(|value| {
+                    value.starts_with('=') || (!name.starts_with("--") && !value.is_empty())
+                })
+        })
+    })
+}
+
+/// Map a binary name to the plugin command name
+///
+/// `kubectl-view_secret` becomes `view-secret` and `kubectl-foo-bar` becomes
+/// `foo bar`, mirroring kubectl's own dispatch rules.
+fn plugin_name_from_file(file_name: &str) -> Option<String> {
+    let stem = if cfg!(target_os = "windows") {
+        file_name.strip_suffix(".exe").unwrap_or(file_name)
+    } else {
+        file_name
+    };
+
+    let name = stem.strip_prefix(PLUGIN_PREFIX)?;
+    if name.is_empty() {
+        return None;
+    }
+
+    Some(name.replace('-', " ").replace('_', "-"))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    std::fs::metadata(path)
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plugin_name_from_file() {
+        assert_eq!(plugin_name_from_file("kubectl-ctx"), Some("ctx".to_string()));
+        assert_eq!(
+            plugin_name_from_file("kubectl-view_secret"),
+            Some("view-secret".to_string())
+        );
+        assert_eq!(plugin_name_from_file("kubectl-foo-bar"), Some("foo bar".to_string()));
+        assert_eq!(plugin_name_from_file("kubectl-"), None);
+        assert_eq!(plugin_name_from_file("helm"), None);
+    }
+
+    #[test]
+    fn test_scoped_args() {
+        let args = |list: &[&str]| list.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            scoped_args(&args(&["pods"]), Some("default"), Some("prod")),
+            args(&["pods", "--context", "prod", "--namespace", "default"])
+        );
+        assert_eq!(
+            scoped_args(&args(&["-n", "kube-system", "--context=dev"]), Some("default"), Some("prod")),
+            args(&["-n", "kube-system", "--context=dev"])
+        );
+        assert_eq!(
+            scoped_args(&args(&["-nkube-system", "-A"]), Some("default"), None),
+            args(&["-nkube-system", "-A"])
+        );
+        assert_eq!(
+            scoped_args(&args(&["run", "--", "--namespace", "x"]), Some("default"), None),
+            args(&["run", "--namespace", "default", "--", "--namespace", "x"])
+        );
+    }
+}
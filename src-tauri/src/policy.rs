@@ -0,0 +1,157 @@
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::process::Command;
+
+use crate::preferences::PolicyGate;
+use crate::types::{PolicyReport, PolicyViolation, PolicyViolations};
+
+const POLICIES_DIR: &str = "policies";
+
+/// Rego namespace conftest evaluates when the gate doesn't name one
+const DEFAULT_POLICY_NAMESPACE: &str = "main";
+
+/// Policies shipped with the app, copied into the policy directory on first use
+const BUNDLED_POLICIES: &[(&str, &str)] = &[("kubesail.rego", include_str!("../policies/kubesail.rego"))];
+
+/// The directory conftest reads policies from
+///
+/// Without a configured directory, the app data `policies/` directory is used
+/// and seeded with the bundled policies the first time it is created.
+pub fn policy_dir<R: Runtime>(app: &AppHandle<R>, gate: &PolicyGate) -> Result<PathBuf> {
+    if let Some(dir) = gate.policy_dir.as_deref().filter(|d| !d.is_empty()) {
+        return Ok(PathBuf::from(dir));
+    }
+
+    let dir = app.path().app_data_dir()?.join(POLICIES_DIR);
+    if !dir.exists() {
+        std::fs::create_dir_all(&dir)?;
+        for (name, contents) in BUNDLED_POLICIES {
+            std::fs::write(dir.join(name), contents)?;
+        }
+    }
+    Ok(dir)
+}
+
+/// Run a (possibly multi-document) manifest through conftest
+pub async fn check_manifest<R: Runtime>(
+    app: &AppHandle<R>,
+    gate: &PolicyGate,
+    manifest: &str,
+) -> Result<PolicyReport> {
+    let dir = policy_dir(app, gate)?;
+
+    // conftest picks the parser from the extension, so go through a .yaml file
+    let temp_file = std::env::temp_dir().join(format!("kubesail-policy-{}.yaml", uuid::Uuid::new_v4()));
+    tokio::fs::write(&temp_file, manifest).await?;
+
+    let output = Command::new("conftest")
+        .arg("test")
+        .arg("--no-color")
+        .arg("--output")
+        .arg("json")
+        .arg("--policy")
+        .arg(&dir)
+        .arg("--namespace")
+        .arg(gate.namespace.as_deref().unwrap_or(DEFAULT_POLICY_NAMESPACE))
+        .arg(&temp_file)
+        .output()
+        .await;
+    let _ = tokio::fs::remove_file(&temp_file).await;
+
+    let output = output.context("Failed to run conftest; is it installed and on PATH?")?;
+
+    // conftest exits non-zero when a policy fails, so only unparseable output is an error
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let results: Vec<Value> = serde_json::from_str(&stdout).map_err(|_| {
+        anyhow!(
+            "conftest failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+    })?;
+
+    let (violations, warnings) = parse_results(&results);
+    Ok(PolicyReport {
+        passed: violations.is_empty(),
+        policy_dir: dir.to_string_lossy().to_string(),
+        violations,
+        warnings,
+    })
+}
+
+/// Check a manifest when the gate is enabled, failing with [`PolicyViolations`] if enforced
+///
+/// Returns the report (warnings included) so callers can surface it; `None`
+/// means the gate is off.
+pub async fn enforce<R: Runtime>(app: &AppHandle<R>, manifest: &str) -> Result<Option<PolicyReport>> {
+    let gate = crate::preferences::get_policy_gate(app)?;
+    if !gate.enabled {
+        return Ok(None);
+    }
+
+    let report = check_manifest(app, &gate, manifest).await?;
+    if gate.enforce && !report.passed {
+        return Err(PolicyViolations {
+            error: "PolicyViolation".to_string(),
+            report,
+        }
+        .into());
+    }
+
+    Ok(Some(report))
+}
+
+/// Failures and warnings from conftest's JSON output
+fn parse_results(results: &[Value]) -> (Vec<PolicyViolation>, Vec<PolicyViolation>) {
+    let collect = |key: &str| -> Vec<PolicyViolation> {
+        results
+            .iter()
+            .flat_map(|result| {
+                let namespace = result.get("namespace").and_then(|n| n.as_str()).unwrap_or_default();
+                result
+                    .get(key)
+                    .and_then(|v| v.as_array())
+                    .into_iter()
+                    .flatten()
+                    .filter_map(move |item| {
+                        item.get("msg").and_then(|m| m.as_str()).map(|msg| PolicyViolation {
+                            namespace: namespace.to_string(),
+                            message: msg.to_string(),
+                        })
+                    })
+            })
+            .collect()
+    };
+
+    (collect("failures"), collect("warnings"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_results() {
+        let results: Vec<Value> = serde_json::from_str(
+            r#"[{
+                "filename": "/tmp/kubesail-policy.yaml",
+                "namespace": "main",
+                "successes": 3,
+                "failures": [{"msg": "Deployment/web: container \"app\" uses the :latest tag"}],
+                "warnings": [{"msg": "Deployment/web: container \"app\" has no memory limit", "metadata": {}}]
+            }, {
+                "filename": "/tmp/kubesail-policy.yaml",
+                "namespace": "main",
+                "successes": 5
+            }]"#,
+        )
+        .unwrap();
+
+        let (violations, warnings) = parse_results(&results);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].namespace, "main");
+        assert!(violations[0].message.contains(":latest"));
+        assert_eq!(warnings.len(), 1);
+    }
+}
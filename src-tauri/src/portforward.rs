@@ -1,12 +1,25 @@
 use crate::types::PortForwardInfo;
 use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+/// Lockfile in the app data dir listing the kubectl processes this session started
+pub const LOCKFILE: &str = "port-forwards.lock";
+
 pub struct PortForwardManager {
     forwards: Arc<Mutex<HashMap<String, PortForwardHandle>>>,
+    lockfile: std::sync::Mutex<Option<PathBuf>>,  // None until setup knows the app data dir
+}
+
+/// A kubectl port-forward as recorded in the lockfile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LockEntry {
+    pid: u32,
+    args: Vec<String>,  // Arguments after `kubectl`, to tell our process from a reused PID
 }
 
 struct PortForwardHandle {
@@ -18,6 +31,42 @@ impl PortForwardManager {
     pub fn new() -> Self {
         Self {
             forwards: Arc::new(Mutex::new(HashMap::new())),
+            lockfile: std::sync::Mutex::new(None),
+        }
+    }
+
+    /// Start recording forwards in `path`, so a later launch can reap them after a crash
+    pub fn set_lockfile(&self, path: PathBuf) {
+        if let Ok(mut lockfile) = self.lockfile.lock() {
+            *lockfile = Some(path);
+        }
+    }
+
+    /// Rewrite the lockfile from the live forwards; called with the forwards lock held
+    fn write_lockfile(&self, forwards: &HashMap<String, PortForwardHandle>) {
+        let Some(path) = self.lockfile.lock().ok().and_then(|p| p.clone()) else {
+            return;
+        };
+        let entries: Vec<LockEntry> = forwards
+            .values()
+            .filter_map(|h| {
+                h.process.as_ref().map(|p| LockEntry {
+                    pid: p.id(),
+                    args: port_forward_args(&h.info),
+                })
+            })
+            .collect();
+
+        let result = if entries.is_empty() {
+            match std::fs::remove_file(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+                _ => Ok(()),
+            }
+        } else {
+            write_entries(&path, &entries)
+        };
+        if let Err(e) = result {
+            tracing::warn!("Failed to update {}: {}", path.display(), e);
         }
     }
 
@@ -39,18 +88,6 @@ impl PortForwardManager {
             }
         }
 
-        // Start kubectl port-forward
-        let child = Command::new("kubectl")
-            .arg("port-forward")
-            .arg("-n")
-            .arg(namespace)
-            .arg(format!("{}/{}", resource_type, resource_name))
-            .arg(format!("{}:{}", local_port, remote_port))
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-            .context("Failed to start kubectl port-forward")?;
-
         let info = PortForwardInfo {
             id: id.clone(),
             resource_type: resource_type.to_string(),
@@ -61,6 +98,9 @@ impl PortForwardManager {
             status: "running".to_string(),
         };
 
+        // Start kubectl port-forward
+        let child = spawn_kubectl_port_forward(&info)?;
+
         let handle = PortForwardHandle {
             info: info.clone(),
             process: Some(child),
@@ -68,6 +108,7 @@ impl PortForwardManager {
 
         let mut forwards = self.forwards.lock().await;
         forwards.insert(id, handle);
+        self.write_lockfile(&forwards);
 
         Ok(info)
     }
@@ -76,6 +117,7 @@ impl PortForwardManager {
         let mut forwards = self.forwards.lock().await;
 
         if let Some(mut handle) = forwards.remove(id) {
+            self.write_lockfile(&forwards);
             if let Some(mut process) = handle.process.take() {
                 process.kill().context("Failed to kill port-forward process")?;
             }
@@ -85,6 +127,24 @@ impl PortForwardManager {
         }
     }
 
+    /// Check whether the kubectl process backing a port-forward is still alive
+    pub async fn is_running(&self, id: &str) -> bool {
+        let mut forwards = self.forwards.lock().await;
+
+        match forwards.get_mut(id).and_then(|h| h.process.as_mut()) {
+            Some(process) => matches!(process.try_wait(), Ok(None)),
+            None => false,
+        }
+    }
+
+    /// Local port of a running port-forward
+    pub async fn local_port(&self, id: &str) -> Option<u16> {
+        if !self.is_running(id).await {
+            return None;
+        }
+        self.forwards.lock().await.get(id).map(|h| h.info.local_port)
+    }
+
     pub async fn list_port_forwards(&self) -> Vec<PortForwardInfo> {
         let mut forwards = self.forwards.lock().await;
 
@@ -112,11 +172,131 @@ impl PortForwardManager {
                 let _ = process.kill();
             }
         }
+        self.write_lockfile(&forwards);
 
         Ok(())
     }
 }
 
+fn port_forward_args(info: &PortForwardInfo) -> Vec<String> {
+    vec![
+        "port-forward".to_string(),
+        "-n".to_string(),
+        info.namespace.clone(),
+        format!("{}/{}", info.resource_type, info.resource_name),
+        format!("{}:{}", info.local_port, info.remote_port),
+    ]
+}
+
+fn spawn_kubectl_port_forward(info: &PortForwardInfo) -> Result<Child> {
+    Command::new("kubectl")
+        .args(port_forward_args(info))
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to start kubectl port-forward")
+}
+
+fn write_entries(path: &Path, entries: &[LockEntry]) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    // Write then rename, so a crash mid-write can't leave a truncated lockfile
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, serde_json::to_vec(entries)?)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Whether a running process's command line is the kubectl port-forward we recorded
+///
+/// PIDs are reused, so the PID alone would risk killing an unrelated process.
+fn is_recorded_forward(command_line: &str, entry: &LockEntry) -> bool {
+    let args = format!(" {}", entry.args.join(" "));
+    match command_line.trim_end().strip_suffix(args.as_str()) {
+        // The program may be a full path, quoted on Windows
+        Some(program) => {
+            let program = program.trim().trim_matches('"');
+            let name = program.rsplit(['/', '\\']).next().unwrap_or(program);
+            name == "kubectl" || name.eq_ignore_ascii_case("kubectl.exe")
+        }
+        None => false,
+    }
+}
+
+#[cfg(unix)]
+fn process_command_line(pid: u32) -> Option<String> {
+    let output = Command::new("ps")
+        .args(["-o", "command=", "-p", &pid.to_string()])
+        .output()
+        .ok()?;
+    let line = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !line.is_empty()).then_some(line)
+}
+
+#[cfg(not(unix))]
+fn process_command_line(pid: u32) -> Option<String> {
+    let query = format!("(Get-CimInstance Win32_Process -Filter 'ProcessId={}').CommandLine", pid);
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-Command", &query])
+        .output()
+        .ok()?;
+    let line = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !line.is_empty()).then_some(line)
+}
+
+#[cfg(unix)]
+fn kill_process(pid: u32) -> Result<()> {
+    let status = Command::new("kill").arg(pid.to_string()).status()?;
+    anyhow::ensure!(status.success(), "kill exited with {}", status);
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn kill_process(pid: u32) -> Result<()> {
+    let status = Command::new("taskkill").args(["/PID", &pid.to_string(), "/F"]).status()?;
+    anyhow::ensure!(status.success(), "taskkill exited with {}", status);
+    Ok(())
+}
+
+/// Kill port-forwards left running by a session that crashed before it could stop them
+///
+/// Reads the lockfile a previous session wrote, kills each listed process whose
+/// command line still matches, and removes the file. Otherwise those processes
+/// keep their local ports and new forwards fail with "address already in use".
+/// Returns the PIDs that were killed.
+pub fn reap_orphans(path: &Path) -> Vec<u32> {
+    let entries: Vec<LockEntry> = match std::fs::read(path) {
+        Ok(content) => serde_json::from_slice(&content).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring unreadable {}: {}", path.display(), e);
+            Vec::new()
+        }),
+        Err(_) => return Vec::new(),
+    };
+
+    let mut killed = Vec::new();
+    for entry in entries {
+        let Some(command_line) = process_command_line(entry.pid) else {
+            continue;
+        };
+        if !is_recorded_forward(&command_line, &entry) {
+            continue;
+        }
+        match kill_process(entry.pid) {
+            Ok(()) => {
+                tracing::info!("Killed orphaned port-forward {} ({})", entry.pid, command_line);
+                killed.push(entry.pid);
+            }
+            Err(e) => tracing::warn!("Failed to kill orphaned port-forward {}: {}", entry.pid, e),
+        }
+    }
+
+    if let Err(e) = std::fs::remove_file(path) {
+        tracing::warn!("Failed to remove {}: {}", path.display(), e);
+    }
+    killed
+}
+
 impl Drop for PortForwardHandle {
     fn drop(&mut self) {
         if let Some(mut process) = self.process.take() {
@@ -124,3 +304,24 @@ impl Drop for PortForwardHandle {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_recorded_forward() {
+        let entry = LockEntry {
+            pid: 4242,
+            args: ["port-forward", "-n", "web", "svc/api", "8080:80"].map(String::from).to_vec(),
+        };
+        assert!(is_recorded_forward("kubectl port-forward -n web svc/api 8080:80", &entry));
+        assert!(is_recorded_forward("/usr/local/bin/kubectl port-forward -n web svc/api 8080:80", &entry));
+        assert!(is_recorded_forward(
+            r#""C:\Program Files\kubectl.exe" port-forward -n web svc/api 8080:80"#,
+            &entry
+        ));
+        assert!(!is_recorded_forward("kubectl port-forward -n web svc/api 9090:80", &entry));
+        assert!(!is_recorded_forward("/usr/bin/python3 -m http.server 8080", &entry));
+    }
+}
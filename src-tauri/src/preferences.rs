@@ -0,0 +1,364 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+
+use crate::kube::load_kubeconfig;
+
+const PREFERENCES_STORE: &str = "preferences.json";
+const CONTEXT_TAGS_KEY: &str = "context_tags";
+const RECORDING_RETENTION_KEY: &str = "recording_retention_days";
+const SHELL_POLICY_KEY: &str = "shell_policy";
+const CLUSTER_INTEGRATIONS_KEY: &str = "cluster_integrations";
+const POLICY_GATE_KEY: &str = "policy_gate";
+const USAGE_STATS_KEY: &str = "usage_stats_enabled";
+
+/// Shell recordings are kept this long unless configured otherwise
+const DEFAULT_RECORDING_RETENTION_DAYS: u32 = 30;
+
+/// User-assigned metadata for a kubeconfig context
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ContextTag {
+    pub environment: Option<String>, // dev, staging, prod, ...
+    pub color: Option<String>,       // CSS color shown next to the context
+    pub group: Option<String>,
+    pub protected: Option<bool>,     // None: protected when environment is prod
+}
+
+impl ContextTag {
+    /// Explicit `protected` wins; otherwise production contexts are protected
+    pub fn is_protected(&self) -> bool {
+        self.protected.unwrap_or_else(|| {
+            matches!(
+                self.environment.as_deref().map(|e| e.to_ascii_lowercase()).as_deref(),
+                Some("prod") | Some("production")
+            )
+        })
+    }
+}
+
+/// An in-cluster HTTP service, reached through the API server's service proxy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceEndpoint {
+    pub namespace: String,
+    pub name: String,
+    pub port: Option<u16>,       // Service port; defaults per integration
+    pub scheme: Option<String>,  // http (default) or https
+}
+
+/// A Grafana dashboard and how to fill its template variables
+///
+/// Variable values may use `{cluster}`, `{namespace}`, `{kind}` and `{name}`
+/// placeholders, e.g. `"deployment": "{name}"`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrafanaDashboard {
+    pub title: String,
+    pub uid: String,
+    #[serde(default)]
+    pub kinds: Vec<String>,  // Workload kinds it applies to; empty for namespace-level dashboards
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrafanaConfig {
+    pub base_url: String,
+    pub org_id: Option<u32>,
+    #[serde(default)]
+    pub dashboards: Vec<GrafanaDashboard>,
+}
+
+/// Monitoring tools wired up for one kubeconfig context
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClusterIntegrations {
+    pub prometheus: Option<ServiceEndpoint>,
+    pub alertmanager: Option<ServiceEndpoint>,
+    pub grafana: Option<GrafanaConfig>,
+    pub loki: Option<ServiceEndpoint>,
+    pub log_provider: Option<String>,  // kubelet (default) or loki
+}
+
+/// Auditing and restrictions applied to pod shell input
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShellPolicy {
+    pub audit_commands: bool,         // Log each command line to the audit log
+    pub restricted_mode: bool,        // Block `blocked_patterns` on protected contexts
+    pub blocked_patterns: Vec<String>,
+}
+
+impl Default for ShellPolicy {
+    fn default() -> Self {
+        Self {
+            audit_commands: false,
+            restricted_mode: false,
+            blocked_patterns: vec![
+                "rm -rf /".to_string(),
+                "rm -rf /*".to_string(),
+                "kubectl delete".to_string(),
+            ],
+        }
+    }
+}
+
+impl ShellPolicy {
+    /// The first pattern the command line contains as whole words, ignoring extra whitespace
+    ///
+    /// `rm -rf /` matches `sudo rm -rf / --no-preserve-root` but not `rm -rf /tmp/cache`.
+    pub fn blocked_pattern(&self, command: &str) -> Option<&str> {
+        let command = command.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        self.blocked_patterns.iter().map(|p| p.as_str()).find(|pattern| {
+            let pattern = pattern.split_whitespace().collect::<Vec<_>>().join(" ");
+            !pattern.is_empty()
+                && command.match_indices(&pattern).any(|(start, _)| {
+                    let before = command[..start].chars().next_back();
+                    let after = command[start + pattern.len()..].chars().next();
+                    let boundary = |c: Option<char>| {
+                        c.map(|c| c.is_whitespace() || matches!(c, ';' | '&' | '|')).unwrap_or(true)
+                    };
+                    boundary(before) && boundary(after)
+                })
+        })
+    }
+}
+
+/// Client-side policy checks run before applying manifests or upgrading releases
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyGate {
+    pub enabled: bool,
+    pub enforce: bool,                 // Block on violations; otherwise only report them
+    pub policy_dir: Option<String>,    // Defaults to the bundled policies in the app data directory
+    pub namespace: Option<String>,     // Rego package to evaluate; defaults to main
+}
+
+impl Default for PolicyGate {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            enforce: true,
+            policy_dir: None,
+            namespace: None,
+        }
+    }
+}
+
+/// Read all context tags from the preferences store
+pub fn get_context_tags<R: Runtime>(app: &AppHandle<R>) -> Result<HashMap<String, ContextTag>> {
+    let store = app.store(PREFERENCES_STORE)?;
+
+    match store.get(CONTEXT_TAGS_KEY) {
+        Some(value) => Ok(serde_json::from_value(value)?),
+        None => Ok(HashMap::new()),
+    }
+}
+
+/// Set or, with `None`, remove the tag for a context
+pub fn set_context_tag<R: Runtime>(
+    app: &AppHandle<R>,
+    context: &str,
+    tag: Option<ContextTag>,
+) -> Result<HashMap<String, ContextTag>> {
+    let mut tags = get_context_tags(app)?;
+    match tag {
+        Some(tag) => {
+            tags.insert(context.to_string(), tag);
+        }
+        None => {
+            tags.remove(context);
+        }
+    }
+
+    let store = app.store(PREFERENCES_STORE)?;
+    store.set(CONTEXT_TAGS_KEY, serde_json::to_value(&tags)?);
+    store.save()?;
+
+    Ok(tags)
+}
+
+/// Days to keep shell recordings; 0 keeps them forever
+pub fn get_recording_retention_days<R: Runtime>(app: &AppHandle<R>) -> Result<u32> {
+    let store = app.store(PREFERENCES_STORE)?;
+
+    Ok(store
+        .get(RECORDING_RETENTION_KEY)
+        .and_then(|v| v.as_u64())
+        .map(|days| days as u32)
+        .unwrap_or(DEFAULT_RECORDING_RETENTION_DAYS))
+}
+
+pub fn set_recording_retention_days<R: Runtime>(app: &AppHandle<R>, days: u32) -> Result<()> {
+    let store = app.store(PREFERENCES_STORE)?;
+    store.set(RECORDING_RETENTION_KEY, serde_json::json!(days));
+    store.save()?;
+    Ok(())
+}
+
+/// Whether local usage statistics are collected; off until the user opts in
+pub fn get_usage_stats_enabled<R: Runtime>(app: &AppHandle<R>) -> Result<bool> {
+    let store = app.store(PREFERENCES_STORE)?;
+    Ok(store.get(USAGE_STATS_KEY).and_then(|v| v.as_bool()).unwrap_or(false))
+}
+
+pub fn set_usage_stats_enabled<R: Runtime>(app: &AppHandle<R>, enabled: bool) -> Result<()> {
+    let store = app.store(PREFERENCES_STORE)?;
+    store.set(USAGE_STATS_KEY, serde_json::json!(enabled));
+    store.save()?;
+    Ok(())
+}
+
+pub fn get_shell_policy<R: Runtime>(app: &AppHandle<R>) -> Result<ShellPolicy> {
+    let store = app.store(PREFERENCES_STORE)?;
+
+    match store.get(SHELL_POLICY_KEY) {
+        Some(value) => Ok(serde_json::from_value(value)?),
+        None => Ok(ShellPolicy::default()),
+    }
+}
+
+pub fn set_shell_policy<R: Runtime>(app: &AppHandle<R>, policy: &ShellPolicy) -> Result<()> {
+    let store = app.store(PREFERENCES_STORE)?;
+    store.set(SHELL_POLICY_KEY, serde_json::to_value(policy)?);
+    store.save()?;
+    Ok(())
+}
+
+pub fn get_policy_gate<R: Runtime>(app: &AppHandle<R>) -> Result<PolicyGate> {
+    let store = app.store(PREFERENCES_STORE)?;
+
+    match store.get(POLICY_GATE_KEY) {
+        Some(value) => Ok(serde_json::from_value(value)?),
+        None => Ok(PolicyGate::default()),
+    }
+}
+
+pub fn set_policy_gate<R: Runtime>(app: &AppHandle<R>, gate: &PolicyGate) -> Result<()> {
+    let store = app.store(PREFERENCES_STORE)?;
+    store.set(POLICY_GATE_KEY, serde_json::to_value(gate)?);
+    store.save()?;
+    Ok(())
+}
+
+/// Integrations for a context, or the current context when `None`
+pub fn get_cluster_integrations<R: Runtime>(
+    app: &AppHandle<R>,
+    context: Option<&str>,
+) -> Result<ClusterIntegrations> {
+    let context = match context {
+        Some(ctx) => ctx.to_string(),
+        None => load_kubeconfig()?.current_context,
+    };
+
+    let store = app.store(PREFERENCES_STORE)?;
+    let mut all: HashMap<String, ClusterIntegrations> = match store.get(CLUSTER_INTEGRATIONS_KEY) {
+        Some(value) => serde_json::from_value(value)?,
+        None => HashMap::new(),
+    };
+
+    Ok(all.remove(&context).unwrap_or_default())
+}
+
+pub fn set_cluster_integrations<R: Runtime>(
+    app: &AppHandle<R>,
+    context: &str,
+    integrations: ClusterIntegrations,
+) -> Result<()> {
+    let store = app.store(PREFERENCES_STORE)?;
+    let mut all: HashMap<String, ClusterIntegrations> = match store.get(CLUSTER_INTEGRATIONS_KEY) {
+        Some(value) => serde_json::from_value(value)?,
+        None => HashMap::new(),
+    };
+
+    all.insert(context.to_string(), integrations);
+    store.set(CLUSTER_INTEGRATIONS_KEY, serde_json::to_value(&all)?);
+    store.save()?;
+    Ok(())
+}
+
+/// Integrations for every context, keyed by context name
+pub fn get_all_cluster_integrations<R: Runtime>(app: &AppHandle<R>) -> Result<HashMap<String, ClusterIntegrations>> {
+    let store = app.store(PREFERENCES_STORE)?;
+
+    match store.get(CLUSTER_INTEGRATIONS_KEY) {
+        Some(value) => Ok(serde_json::from_value(value)?),
+        None => Ok(HashMap::new()),
+    }
+}
+
+pub fn set_all_cluster_integrations<R: Runtime>(
+    app: &AppHandle<R>,
+    all: &HashMap<String, ClusterIntegrations>,
+) -> Result<()> {
+    let store = app.store(PREFERENCES_STORE)?;
+    store.set(CLUSTER_INTEGRATIONS_KEY, serde_json::to_value(all)?);
+    store.save()?;
+    Ok(())
+}
+
+/// Refuse a mutating action against a protected context unless it was confirmed
+///
+/// `context` defaults to the kubeconfig's current context, which is the one
+/// the shared client talks to.
+pub fn ensure_mutation_allowed<R: Runtime>(
+    app: &AppHandle<R>,
+    context: Option<&str>,
+    confirmed: bool,
+) -> Result<()> {
+    if confirmed {
+        return Ok(());
+    }
+
+    let context = match context {
+        Some(ctx) => ctx.to_string(),
+        None => load_kubeconfig()?.current_context,
+    };
+
+    let tags = get_context_tags(app)?;
+    if tags.get(&context).map(|t| t.is_protected()).unwrap_or(false) {
+        return Err(anyhow!(
+            "Context '{}' is protected; confirm the action to continue",
+            context
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prod_environment_is_protected_by_default() {
+        let tag = ContextTag {
+            environment: Some("Prod".to_string()),
+            ..Default::default()
+        };
+        assert!(tag.is_protected());
+
+        let tag = ContextTag {
+            environment: Some("prod".to_string()),
+            protected: Some(false),
+            ..Default::default()
+        };
+        assert!(!tag.is_protected());
+
+        let tag = ContextTag {
+            environment: Some("dev".to_string()),
+            ..Default::default()
+        };
+        assert!(!tag.is_protected());
+    }
+
+    #[test]
+    fn test_shell_policy_matches_whole_words() {
+        let policy = ShellPolicy::default();
+
+        assert_eq!(policy.blocked_pattern("rm  -rf /"), Some("rm -rf /"));
+        assert_eq!(policy.blocked_pattern("cd /tmp && rm -rf /"), Some("rm -rf /"));
+        assert_eq!(policy.blocked_pattern("kubectl delete pod web-0"), Some("kubectl delete"));
+        assert_eq!(policy.blocked_pattern("rm -rf /tmp/cache"), None);
+        assert_eq!(policy.blocked_pattern("kubectl get pods"), None);
+        assert_eq!(policy.blocked_pattern("echo kubectl deleted"), None);
+    }
+}
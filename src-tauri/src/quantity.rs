@@ -0,0 +1,97 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+/// A Kubernetes quantity converted to a fixed unit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NormalizedQuantity {
+    pub raw: String,
+    pub kind: String,  // cpu or memory
+    pub value: f64,    // Millicores for cpu, bytes for memory
+    pub unit: String,  // millicores or bytes
+}
+
+/// Parse a quantity string ("250m", "1.5", "3977556Ki", "2G", "1e3") into its plain numeric value
+pub fn parse_quantity(quantity: &str) -> Option<f64> {
+    let quantity = quantity.trim();
+    if quantity.is_empty() {
+        return None;
+    }
+
+    const SUFFIXES: &[(&str, f64)] = &[
+        ("Ki", 1024.0),
+        ("Mi", 1_048_576.0),
+        ("Gi", 1_073_741_824.0),
+        ("Ti", 1_099_511_627_776.0),
+        ("Pi", 1_125_899_906_842_624.0),
+        ("Ei", 1_152_921_504_606_846_976.0),
+        ("n", 1e-9),
+        ("u", 1e-6),
+        ("m", 1e-3),
+        ("k", 1e3),
+        ("M", 1e6),
+        ("G", 1e9),
+        ("T", 1e12),
+        ("P", 1e15),
+        ("E", 1e18),
+    ];
+
+    for (suffix, multiplier) in SUFFIXES {
+        if let Some(number) = quantity.strip_suffix(suffix) {
+            return number.parse::<f64>().ok().map(|n| n * multiplier);
+        }
+    }
+
+    // Plain numbers and decimal exponents ("1e3", "5E-2") parse directly
+    quantity.parse::<f64>().ok()
+}
+
+/// CPU quantity in millicores
+pub fn cpu_to_millicores(quantity: &str) -> Option<i64> {
+    parse_quantity(quantity).map(|cores| (cores * 1000.0).round() as i64)
+}
+
+/// Memory quantity in bytes
+pub fn memory_to_bytes(quantity: &str) -> Option<u64> {
+    parse_quantity(quantity).map(|bytes| bytes.round().max(0.0) as u64)
+}
+
+/// Share of `total` used by `used`, as a percentage rounded to one decimal
+pub fn percentage(used: f64, total: f64) -> Option<f64> {
+    (total > 0.0).then(|| (used / total * 1000.0).round() / 10.0)
+}
+
+/// Normalize a cpu or memory quantity for display
+pub fn normalize_quantity(quantity: &str, kind: &str) -> Result<NormalizedQuantity> {
+    let (value, unit) = match kind {
+        "cpu" => (cpu_to_millicores(quantity).map(|m| m as f64), "millicores"),
+        "memory" => (memory_to_bytes(quantity).map(|b| b as f64), "bytes"),
+        other => return Err(anyhow!("Unknown quantity kind '{}', expected cpu or memory", other)),
+    };
+
+    let value = value.ok_or_else(|| anyhow!("Invalid quantity: {}", quantity))?;
+
+    Ok(NormalizedQuantity {
+        raw: quantity.to_string(),
+        kind: kind.to_string(),
+        value,
+        unit: unit.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quantity_normalization() {
+        assert_eq!(cpu_to_millicores("250m"), Some(250));
+        assert_eq!(cpu_to_millicores("1.5"), Some(1500));
+        assert_eq!(cpu_to_millicores("1500000n"), Some(2));
+        assert_eq!(memory_to_bytes("3977556Ki"), Some(3977556 * 1024));
+        assert_eq!(memory_to_bytes("2G"), Some(2_000_000_000));
+        assert_eq!(memory_to_bytes("1e3"), Some(1000));
+        assert_eq!(memory_to_bytes("abc"), None);
+        assert_eq!(percentage(1.0, 3.0), Some(33.3));
+        assert_eq!(percentage(1.0, 0.0), None);
+    }
+}
@@ -0,0 +1,155 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt;
+
+use crate::types::ShellRecordingInfo;
+
+const RECORDINGS_DIR: &str = "recordings";
+const RECORDING_EXTENSION: &str = "cast";
+
+/// Terminal size written to the header; the shell doesn't track resizes
+const DEFAULT_WIDTH: u16 = 120;
+const DEFAULT_HEIGHT: u16 = 40;
+
+/// Directory holding shell recordings, created on first use
+pub fn recordings_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf> {
+    let dir = app.path().app_data_dir()?.join(RECORDINGS_DIR);
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Writes a shell session as an asciicast v2 file
+///
+/// The first line is a JSON header, followed by one `[elapsed, "o", data]`
+/// event per chunk of output, so files play back with `asciinema play` or
+/// the asciinema web player. Input isn't recorded since it may contain
+/// passwords; the echoed output already shows what was typed.
+pub struct ShellRecorder {
+    file: File,
+    started: Instant,
+}
+
+impl ShellRecorder {
+    pub async fn create(path: &Path, title: &str) -> Result<Self> {
+        let mut file = File::create(path)
+            .await
+            .with_context(|| format!("Failed to create recording {}", path.display()))?;
+
+        let header = serde_json::json!({
+            "version": 2,
+            "width": DEFAULT_WIDTH,
+            "height": DEFAULT_HEIGHT,
+            "timestamp": Utc::now().timestamp(),
+            "title": title,
+            "env": { "TERM": "xterm-256color" },
+        });
+        file.write_all(format!("{}\n", header).as_bytes()).await?;
+
+        Ok(Self {
+            file,
+            started: Instant::now(),
+        })
+    }
+
+    pub async fn output(&mut self, data: &str) -> Result<()> {
+        let event = serde_json::json!([self.started.elapsed().as_secs_f64(), "o", data]);
+        self.file.write_all(format!("{}\n", event).as_bytes()).await?;
+        Ok(())
+    }
+
+    pub async fn finish(mut self) -> Result<()> {
+        self.file.flush().await?;
+        Ok(())
+    }
+}
+
+/// File name for a new recording: timestamp, namespace and pod
+pub fn recording_file_name(namespace: &str, pod_name: &str) -> String {
+    format!(
+        "{}-{}-{}.{}",
+        Utc::now().format("%Y%m%dT%H%M%S"),
+        namespace,
+        pod_name,
+        RECORDING_EXTENSION
+    )
+}
+
+/// Recordings on disk, newest first
+pub fn list_recordings<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<ShellRecordingInfo>> {
+    let dir = recordings_dir(app)?;
+    let mut recordings = Vec::new();
+
+    for entry in std::fs::read_dir(&dir)?.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some(RECORDING_EXTENSION) {
+            continue;
+        }
+
+        let metadata = entry.metadata()?;
+        let header = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| content.lines().next().map(|l| l.to_string()))
+            .and_then(|line| serde_json::from_str::<serde_json::Value>(&line).ok());
+
+        recordings.push(ShellRecordingInfo {
+            file_name: entry.file_name().to_string_lossy().to_string(),
+            path: path.to_string_lossy().to_string(),
+            title: header
+                .as_ref()
+                .and_then(|h| h.get("title"))
+                .and_then(|t| t.as_str())
+                .map(|t| t.to_string()),
+            size_bytes: metadata.len(),
+            created_at: metadata
+                .modified()
+                .ok()
+                .map(|t| DateTime::<Utc>::from(t).to_rfc3339()),
+        });
+    }
+
+    recordings.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(recordings)
+}
+
+pub fn delete_recording<R: Runtime>(app: &AppHandle<R>, file_name: &str) -> Result<()> {
+    // Only bare file names, so this can't reach outside the recordings directory
+    if file_name.contains(['/', '\\']) || file_name.starts_with('.') {
+        return Err(anyhow!("Invalid recording name: {}", file_name));
+    }
+
+    let path = recordings_dir(app)?.join(file_name);
+    std::fs::remove_file(&path).with_context(|| format!("Failed to delete {}", path.display()))?;
+    Ok(())
+}
+
+/// Delete recordings older than `retention_days`; 0 keeps them forever
+pub fn prune_recordings(dir: &Path, retention_days: u32) -> Result<usize> {
+    if retention_days == 0 {
+        return Ok(0);
+    }
+
+    let cutoff = SystemTime::now() - Duration::from_secs(u64::from(retention_days) * 24 * 60 * 60);
+    let mut removed = 0;
+
+    for entry in std::fs::read_dir(dir)?.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some(RECORDING_EXTENSION) {
+            continue;
+        }
+
+        let expired = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .map(|modified| modified < cutoff)
+            .unwrap_or(false);
+        if expired && std::fs::remove_file(&path).is_ok() {
+            removed += 1;
+        }
+    }
+
+    Ok(removed)
+}
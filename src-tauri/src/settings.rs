@@ -0,0 +1,239 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+
+use crate::kube::{load_kubeconfig, KubeClientManager};
+use crate::preferences::{ClusterIntegrations, ContextTag, PolicyGate, ShellPolicy};
+
+const SETTINGS_STORE: &str = "settings.json";
+const CONTEXT_SETTINGS_KEY: &str = "contexts";
+
+/// Bumped when the export format changes incompatibly
+const EXPORT_VERSION: u32 = 1;
+
+/// Defaults applied when working in a kubeconfig context
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContextSettings {
+    pub refresh_interval_secs: Option<u64>,  // Polling interval for lists; None uses the app default
+    #[serde(default)]
+    pub qps: Option<f64>,                    // Client-side request rate limit; None uses kube::throttle::DEFAULT_QPS
+    #[serde(default)]
+    pub burst: Option<u32>,                  // Requests allowed above qps in a burst
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protected: Option<bool>,             // Stored on the context tag; see ContextTag::is_protected
+}
+
+/// Everything a team shares about its contexts, as written by `export_settings`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SettingsExport {
+    pub version: u32,
+    pub exported_at: String,
+    #[serde(default)]
+    pub contexts: HashMap<String, ContextSettings>,
+    #[serde(default)]
+    pub tags: HashMap<String, ContextTag>,
+    #[serde(default)]
+    pub integrations: HashMap<String, ClusterIntegrations>,
+    pub shell_policy: Option<ShellPolicy>,
+    pub policy_gate: Option<PolicyGate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingsImportResult {
+    pub contexts: Vec<String>,  // Contexts whose settings, tags or integrations were imported
+    pub unknown_contexts: Vec<String>,  // Imported contexts missing from the local kubeconfig
+    pub shell_policy: bool,
+    pub policy_gate: bool,
+}
+
+fn all_context_settings<R: Runtime>(app: &AppHandle<R>) -> Result<HashMap<String, ContextSettings>> {
+    let store = app.store(SETTINGS_STORE)?;
+
+    match store.get(CONTEXT_SETTINGS_KEY) {
+        Some(value) => Ok(serde_json::from_value(value)?),
+        None => Ok(HashMap::new()),
+    }
+}
+
+fn save_context_settings<R: Runtime>(app: &AppHandle<R>, all: &HashMap<String, ContextSettings>) -> Result<()> {
+    let store = app.store(SETTINGS_STORE)?;
+    store.set(CONTEXT_SETTINGS_KEY, serde_json::to_value(all)?);
+    store.save()?;
+    Ok(())
+}
+
+/// Settings for a context, or the current context when `None`
+pub fn get_context_settings<R: Runtime>(app: &AppHandle<R>, context: Option<&str>) -> Result<ContextSettings> {
+    let context = match context {
+        Some(ctx) => ctx.to_string(),
+        None => load_kubeconfig()?.current_context,
+    };
+
+    let mut settings = all_context_settings(app)?.remove(&context).unwrap_or_default();
+    let tag = crate::preferences::get_context_tags(app)?.remove(&context).unwrap_or_default();
+    settings.protected = Some(tag.is_protected());
+    Ok(settings)
+}
+
+pub fn set_context_settings<R: Runtime>(app: &AppHandle<R>, context: &str, mut settings: ContextSettings) -> Result<()> {
+    // The protected flag has one home, the context tag, so mutation checks see it
+    if let Some(protected) = settings.protected.take() {
+        let mut tag = crate::preferences::get_context_tags(app)?.remove(context).unwrap_or_default();
+        if tag.is_protected() != protected {
+            tag.protected = Some(protected);
+            crate::preferences::set_context_tag(app, context, Some(tag))?;
+        }
+    }
+
+    let mut all = all_context_settings(app)?;
+    if settings == ContextSettings::default() {
+        all.remove(context);
+    } else {
+        all.insert(context.to_string(), settings);
+    }
+    save_context_settings(app, &all)
+}
+
+/// Push every context's configured QPS and burst to the client manager, at startup and after an import
+pub fn apply_rate_limits<R: Runtime>(app: &AppHandle<R>, client_manager: &KubeClientManager) -> Result<()> {
+    for (context, settings) in all_context_settings(app)? {
+        client_manager.set_rate_limit(&context, settings.qps, settings.burst);
+    }
+    Ok(())
+}
+
+/// Serialize every context's settings, tags and integrations plus the shell
+/// policy and policy gate, as pretty JSON to share with a team
+pub fn export_settings<R: Runtime>(app: &AppHandle<R>) -> Result<String> {
+    let export = SettingsExport {
+        version: EXPORT_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        contexts: all_context_settings(app)?,
+        tags: crate::preferences::get_context_tags(app)?,
+        integrations: crate::preferences::get_all_cluster_integrations(app)?,
+        shell_policy: Some(crate::preferences::get_shell_policy(app)?),
+        policy_gate: Some(crate::preferences::get_policy_gate(app)?),
+    };
+    Ok(serde_json::to_string_pretty(&export)?)
+}
+
+/// Parse an export, rejecting files from a newer, incompatible version
+fn parse_export(content: &str) -> Result<SettingsExport> {
+    let export: SettingsExport =
+        serde_json::from_str(content).map_err(|e| anyhow!("Not a settings export: {}", e))?;
+    if export.version == 0 || export.version > EXPORT_VERSION {
+        return Err(anyhow!(
+            "Settings export version {} is not supported (expected {})",
+            export.version,
+            EXPORT_VERSION
+        ));
+    }
+    Ok(export)
+}
+
+/// Merge `imported` over `existing`, or replace it outright
+fn merge_map<T>(existing: &mut HashMap<String, T>, imported: HashMap<String, T>, replace: bool) {
+    if replace {
+        existing.clear();
+    }
+    existing.extend(imported);
+}
+
+/// Load an export written by [`export_settings`]
+///
+/// Imported contexts overwrite local entries of the same name; others are
+/// kept unless `replace` is set. Contexts absent from the local kubeconfig are
+/// imported anyway (kubeconfigs get synced later) and reported. Rate limits
+/// are pushed to `client_manager`, and reset for contexts the import removed.
+pub fn import_settings<R: Runtime>(
+    app: &AppHandle<R>,
+    client_manager: &KubeClientManager,
+    content: &str,
+    replace: bool,
+) -> Result<SettingsImportResult> {
+    let export = parse_export(content)?;
+
+    let mut contexts: Vec<String> = export
+        .contexts
+        .keys()
+        .chain(export.tags.keys())
+        .chain(export.integrations.keys())
+        .cloned()
+        .collect();
+    contexts.sort();
+    contexts.dedup();
+
+    let known: Vec<String> = load_kubeconfig()
+        .map(|kc| kc.contexts.into_iter().map(|c| c.name).collect())
+        .unwrap_or_default();
+    let unknown_contexts = contexts.iter().filter(|c| !known.contains(c)).cloned().collect();
+
+    let mut settings = all_context_settings(app)?;
+    let previous: Vec<String> = settings.keys().cloned().collect();
+    merge_map(&mut settings, export.contexts, replace);
+    for entry in settings.values_mut() {
+        entry.protected = None;
+    }
+    save_context_settings(app, &settings)?;
+
+    for context in previous.iter().filter(|c| !settings.contains_key(*c)) {
+        client_manager.set_rate_limit(context, None, None);
+    }
+    apply_rate_limits(app, client_manager)?;
+
+    if replace {
+        for context in crate::preferences::get_context_tags(app)?.keys() {
+            if !export.tags.contains_key(context) {
+                crate::preferences::set_context_tag(app, context, None)?;
+            }
+        }
+    }
+    for (context, tag) in export.tags {
+        crate::preferences::set_context_tag(app, &context, Some(tag))?;
+    }
+
+    let mut integrations = crate::preferences::get_all_cluster_integrations(app)?;
+    merge_map(&mut integrations, export.integrations, replace);
+    crate::preferences::set_all_cluster_integrations(app, &integrations)?;
+
+    if let Some(policy) = &export.shell_policy {
+        crate::preferences::set_shell_policy(app, policy)?;
+    }
+    if let Some(gate) = &export.policy_gate {
+        crate::preferences::set_policy_gate(app, gate)?;
+    }
+
+    Ok(SettingsImportResult {
+        contexts,
+        unknown_contexts,
+        shell_policy: export.shell_policy.is_some(),
+        policy_gate: export.policy_gate.is_some(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_export() {
+        let export = parse_export(
+            r#"{
+                "version": 1,
+                "exported_at": "2026-01-01T00:00:00Z",
+                "contexts": { "prod": { "refresh_interval_secs": 10, "qps": 20.0 } },
+                "tags": { "prod": { "environment": "prod" } }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(export.contexts["prod"].refresh_interval_secs, Some(10));
+        assert_eq!(export.contexts["prod"].qps, Some(20.0));
+        assert!(export.tags["prod"].is_protected());
+        assert!(export.shell_policy.is_none());
+
+        assert!(parse_export(r#"{ "version": 2, "exported_at": "" }"#).is_err());
+        assert!(parse_export("not json").is_err());
+    }
+}
@@ -7,11 +7,15 @@ use kube::{
 };
 use std::collections::HashMap;
 use std::sync::Arc;
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::sync::{Mutex, RwLock};
 use uuid::Uuid;
 
+use crate::preferences::ShellPolicy;
+use crate::recording::{prune_recordings, recording_file_name, recordings_dir, ShellRecorder};
+use crate::types::AuditEntry;
+
 type SessionId = String;
 
 #[derive(Clone)]
@@ -20,11 +24,16 @@ pub struct ShellSession {
     pub pod_name: String,
     pub namespace: String,
     pub container: Option<String>,
+    pub context: Option<String>,
+    pub protected: bool,
 }
 
 pub struct ShellManager {
     sessions: Arc<RwLock<HashMap<SessionId, Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>>>>,
     stdin_senders: Arc<RwLock<HashMap<SessionId, tokio::sync::mpsc::UnboundedSender<String>>>>,
+    session_info: Arc<RwLock<HashMap<SessionId, ShellSession>>>,
+    // Command line typed so far, reconstructed from keystrokes for auditing
+    line_buffers: Arc<Mutex<HashMap<SessionId, String>>>,
 }
 
 impl ShellManager {
@@ -32,9 +41,12 @@ impl ShellManager {
         Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
             stdin_senders: Arc::new(RwLock::new(HashMap::new())),
+            session_info: Arc::new(RwLock::new(HashMap::new())),
+            line_buffers: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn start_session(
         &self,
         app: AppHandle,
@@ -43,6 +55,7 @@ impl ShellManager {
         namespace: String,
         container: Option<String>,
         shell: Option<String>,
+        record: bool,
     ) -> Result<SessionId> {
         let session_id = Uuid::new_v4().to_string();
 
@@ -134,6 +147,21 @@ impl ShellManager {
             )
         })?;
 
+        // Start recording before any output arrives so the cast is complete
+        let recorder = if record {
+            let dir = recordings_dir(&app)?;
+            let retention = crate::preferences::get_recording_retention_days(&app)?;
+            if let Err(e) = prune_recordings(&dir, retention) {
+                eprintln!("Failed to prune old recordings: {}", e);
+            }
+
+            let path = dir.join(recording_file_name(&namespace, &pod_name));
+            let title = format!("{}/{} ({})", namespace, pod_name, target_container);
+            Some(ShellRecorder::create(&path, &title).await?)
+        } else {
+            None
+        };
+
         // Create stdin channel
         let (stdin_tx, mut stdin_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
 
@@ -172,6 +200,7 @@ impl ShellManager {
             let stdout_task = {
                 let app = app_clone.clone();
                 let session_id = session_id_clone.clone();
+                let mut recorder = recorder;
                 tokio::spawn(async move {
                     let mut buffer = vec![0u8; 4096];
                     loop {
@@ -179,6 +208,13 @@ impl ShellManager {
                             Ok(0) => break, // EOF
                             Ok(n) => {
                                 let data = String::from_utf8_lossy(&buffer[..n]).to_string();
+                                if let Some(rec) = recorder.as_mut() {
+                                    if let Err(e) = rec.output(&data).await {
+                                        // Keep the shell alive; just stop recording
+                                        eprintln!("Error writing recording: {}", e);
+                                        recorder = None;
+                                    }
+                                }
                                 let _ = app.emit(&format!("shell-output-{}", session_id), data);
                             }
                             Err(e) => {
@@ -187,6 +223,12 @@ impl ShellManager {
                             }
                         }
                     }
+
+                    if let Some(rec) = recorder {
+                        if let Err(e) = rec.finish().await {
+                            eprintln!("Error finishing recording: {}", e);
+                        }
+                    }
                 })
             };
 
@@ -203,10 +245,55 @@ impl ShellManager {
             sessions.insert(session_id.clone(), Arc::new(Mutex::new(Some(handle))));
         }
 
+        // Remember where the shell runs for auditing and restricted mode
+        let context = crate::kube::load_kubeconfig().ok().map(|c| c.current_context);
+        let protected = match context.as_deref() {
+            Some(ctx) => crate::preferences::get_context_tags(&app)
+                .map(|tags| tags.get(ctx).map(|t| t.is_protected()).unwrap_or(false))
+                .unwrap_or(false),
+            None => false,
+        };
+        self.session_info.write().await.insert(
+            session_id.clone(),
+            ShellSession {
+                session_id: session_id.clone(),
+                pod_name,
+                namespace,
+                container: Some(target_container),
+                context,
+                protected,
+            },
+        );
+
         Ok(session_id)
     }
 
-    pub async fn send_input(&self, session_id: &str, data: String) -> Result<()> {
+    /// Forward keystrokes to the shell, auditing and filtering complete command lines
+    ///
+    /// Command lines are rebuilt from what the user types (printable characters,
+    /// backspace, Ctrl-C/Ctrl-U). History recall, cursor movement and tab
+    /// completion happen inside the shell where they can't be seen, so in
+    /// restricted mode escape sequences and editing control keys are dropped
+    /// rather than let the rebuilt line drift from the real one. This is still a guard against
+    /// accidents rather than a sandbox (pasted scripts, aliases). A blocked line
+    /// is cancelled with Ctrl-C instead of being submitted, and
+    /// `shell-blocked-{session_id}` is emitted.
+    pub async fn send_input<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        session_id: &str,
+        data: String,
+    ) -> Result<()> {
+        let session = self.session_info.read().await.get(session_id).cloned();
+        let policy = crate::preferences::get_shell_policy(app)?;
+
+        let data = match session {
+            Some(session) if policy.audit_commands || (policy.restricted_mode && session.protected) => {
+                self.filter_input(app, &session, &policy, &data).await
+            }
+            _ => data,
+        };
+
         let senders = self.stdin_senders.read().await;
         if let Some(sender) = senders.get(session_id) {
             sender
@@ -218,12 +305,87 @@ impl ShellManager {
         }
     }
 
+    async fn filter_input<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        session: &ShellSession,
+        policy: &ShellPolicy,
+        data: &str,
+    ) -> String {
+        let mut buffers = self.line_buffers.lock().await;
+        let line = buffers.entry(session.session_id.clone()).or_default();
+        let mut forward = String::with_capacity(data.len());
+        let mut in_escape = false;
+        let restricted = policy.restricted_mode && session.protected;
+        let mut dropped_editing = false;
+
+        for ch in data.chars() {
+            match edit_line(line, &mut in_escape, ch, restricted) {
+                Key::Forward => forward.push(ch),
+                Key::Dropped => dropped_editing = true,
+                Key::Submit => {
+                    let command = std::mem::take(line).trim().to_string();
+                    if command.is_empty() {
+                        forward.push(ch);
+                        continue;
+                    }
+
+                    let blocked = if restricted {
+                        policy.blocked_pattern(&command).map(|p| p.to_string())
+                    } else {
+                        None
+                    };
+
+                    if policy.audit_commands || blocked.is_some() {
+                        let entry = AuditEntry {
+                            timestamp: chrono::Utc::now().to_rfc3339(),
+                            context: session.context.clone(),
+                            category: "shell".to_string(),
+                            target: format!(
+                                "{}/{}{}",
+                                session.namespace,
+                                session.pod_name,
+                                session.container.as_ref().map(|c| format!(" ({})", c)).unwrap_or_default()
+                            ),
+                            action: command.clone(),
+                            blocked: blocked.is_some(),
+                        };
+                        if let Err(e) = crate::audit::record(app, &entry) {
+                            eprintln!("Failed to write audit entry: {}", e);
+                        }
+                    }
+
+                    match blocked {
+                        Some(pattern) => {
+                            forward.push('\x03');
+                            let _ = app.emit(
+                                &format!("shell-blocked-{}", session.session_id),
+                                format!("Blocked on protected context: matches '{}'", pattern),
+                            );
+                        }
+                        None => forward.push(ch),
+                    }
+                }
+            }
+        }
+
+        if dropped_editing {
+            let _ = app.emit(
+                &format!("shell-blocked-{}", session.session_id),
+                "History, cursor and line-editing keys and tab completion are disabled on protected contexts",
+            );
+        }
+        forward
+    }
+
     pub async fn close_session(&self, session_id: &str) -> Result<()> {
         // Remove stdin sender
         {
             let mut senders = self.stdin_senders.write().await;
             senders.remove(session_id);
         }
+        self.session_info.write().await.remove(session_id);
+        self.line_buffers.lock().await.remove(session_id);
 
         // Cancel and remove session
         {
@@ -245,6 +407,8 @@ impl ShellManager {
             let mut senders = self.stdin_senders.write().await;
             senders.clear();
         }
+        self.session_info.write().await.clear();
+        self.line_buffers.lock().await.clear();
 
         // Cancel all sessions
         {
@@ -266,3 +430,85 @@ impl Default for ShellManager {
         Self::new()
     }
 }
+
+/// What a keystroke does to the shadow command line
+#[derive(Debug, PartialEq)]
+enum Key {
+    /// Pass it on to the shell
+    Forward,
+    /// Swallowed because the shadow line couldn't follow it
+    Dropped,
+    /// Enter: the line is complete and must be checked before it is sent
+    Submit,
+}
+
+/// Apply one keystroke to the shadow line
+///
+/// In restricted mode every control key other than Enter, backspace, Ctrl-C,
+/// Ctrl-D and Ctrl-U is dropped: readline uses them for history (Ctrl-P/N/R),
+/// kill and yank (Ctrl-W/K/Y) and cursor movement (Ctrl-A/E/B/F), all of which
+/// would make the shell run something other than the line that was checked.
+fn edit_line(line: &mut String, in_escape: &mut bool, ch: char, restricted: bool) -> Key {
+    if *in_escape {
+        // Arrow keys and friends: ESC [ ... final byte
+        if ch.is_ascii_alphabetic() || ch == '~' {
+            *in_escape = false;
+        }
+        return if restricted { Key::Dropped } else { Key::Forward };
+    }
+
+    match ch {
+        '\r' | '\n' => Key::Submit,
+        '\x7f' | '\x08' => {
+            line.pop();
+            Key::Forward
+        }
+        '\x03' | '\x15' => {
+            line.clear();
+            Key::Forward
+        }
+        '\x04' => Key::Forward,
+        '\x1b' => {
+            *in_escape = true;
+            if restricted { Key::Dropped } else { Key::Forward }
+        }
+        c if c.is_control() => {
+            if restricted { Key::Dropped } else { Key::Forward }
+        }
+        c => {
+            line.push(c);
+            Key::Forward
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_keys(data: &str, restricted: bool) -> (String, Vec<Key>) {
+        let mut line = String::new();
+        let mut in_escape = false;
+        let keys = data
+            .chars()
+            .map(|ch| edit_line(&mut line, &mut in_escape, ch, restricted))
+            .collect();
+        (line, keys)
+    }
+
+    #[test]
+    fn test_restricted_drops_editing_keys() {
+        // Ctrl-P recalls history; only the Enter may reach the shell, on an empty line
+        let (line, keys) = type_keys("\x10\r", true);
+        assert_eq!(line, "");
+        assert_eq!(keys, vec![Key::Dropped, Key::Submit]);
+
+        let (line, keys) = type_keys("ls\x01rm \x1b[A\t", true);
+        assert_eq!(line, "lsrm ");
+        assert!(keys.iter().filter(|k| **k == Key::Dropped).count() == 5);
+
+        let (line, keys) = type_keys("ls\x7f\x7fpwd\x10", false);
+        assert_eq!(line, "pwd");
+        assert_eq!(keys.last(), Some(&Key::Forward));
+    }
+}
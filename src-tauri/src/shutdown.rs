@@ -0,0 +1,59 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::database::ConnectionManager;
+use crate::plugins::PluginManager;
+use crate::portforward::PortForwardManager;
+use crate::shell::ShellManager;
+
+/// Upper bound on each step, so a hung process can't keep the app from quitting
+const STEP_TIMEOUT: Duration = Duration::from_secs(3);
+
+static SHUT_DOWN: AtomicBool = AtomicBool::new(false);
+
+/// Release everything that outlives the process if left alone: kubectl
+/// port-forward and plugin children, shell sessions and database pools
+///
+/// Runs once, from the exit event; later calls return immediately.
+pub async fn shutdown(app: &AppHandle) {
+    if SHUT_DOWN.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    tracing::info!("Shutting down");
+
+    // Database connections first: closing one also stops its own port-forward. A
+    // query holding the manager lock mustn't block exit, so taking it is bounded too
+    let manager = app.state::<ConnectionManager>();
+    let connections: Vec<_> = match tokio::time::timeout(STEP_TIMEOUT, manager.write()).await {
+        Ok(mut connections) => connections.drain().map(|(_, connection)| connection).collect(),
+        Err(_) => {
+            tracing::warn!("Timed out waiting for database connections; leaving them open");
+            Vec::new()
+        }
+    };
+    let port_forwards = app.state::<PortForwardManager>();
+    for connection in connections {
+        match tokio::time::timeout(STEP_TIMEOUT, connection.close(port_forwards.inner())).await {
+            Ok(Err(e)) => tracing::warn!("Failed to close database connection: {}", e),
+            Err(_) => tracing::warn!("Timed out closing a database connection"),
+            Ok(Ok(())) => {}
+        }
+    }
+
+    if let Err(e) = app.state::<ShellManager>().close_all_sessions().await {
+        tracing::warn!("Failed to close shell sessions: {}", e);
+    }
+    app.state::<PluginManager>().stop_all(STEP_TIMEOUT).await;
+    if let Err(e) = port_forwards.stop_all().await {
+        tracing::warn!("Failed to stop port-forwards: {}", e);
+    }
+
+    if let Err(e) = crate::audit::flush(app) {
+        tracing::warn!("Failed to flush the audit log: {}", e);
+    }
+    if let Err(e) = crate::usage::save() {
+        tracing::warn!("Failed to save usage statistics: {}", e);
+    }
+    tracing::info!("Shutdown complete");
+}
@@ -0,0 +1,321 @@
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+
+use crate::types::{ResourceTemplate, TemplateParam};
+
+/// A built-in manifest template
+///
+/// `body` uses `{{param}}` placeholders. Bump `version` whenever the body
+/// changes so saved drafts can tell which boilerplate they started from.
+struct TemplateDef {
+    name: &'static str,
+    title: &'static str,
+    kind: &'static str,
+    api_version: &'static str,
+    version: u32,
+    params: &'static [ParamDef],
+    body: &'static str,
+}
+
+struct ParamDef {
+    name: &'static str,
+    description: &'static str,
+    default: Option<&'static str>, // None: required
+}
+
+const fn required(name: &'static str, description: &'static str) -> ParamDef {
+    ParamDef { name, description, default: None }
+}
+
+const fn optional(name: &'static str, description: &'static str, default: &'static str) -> ParamDef {
+    ParamDef { name, description, default: Some(default) }
+}
+
+const TEMPLATES: &[TemplateDef] = &[
+    TemplateDef {
+        name: "deployment",
+        title: "Deployment",
+        kind: "Deployment",
+        api_version: "apps/v1",
+        version: 1,
+        params: &[
+            required("name", "Deployment name, also used for the app label"),
+            optional("namespace", "Target namespace", "default"),
+            required("image", "Container image, including tag"),
+            optional("replicas", "Number of replicas", "2"),
+            optional("port", "Container port", "8080"),
+            optional("cpu_request", "CPU request", "100m"),
+            optional("memory_request", "Memory request", "128Mi"),
+            optional("memory_limit", "Memory limit", "256Mi"),
+        ],
+        body: r#"apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: {{name}}
+  namespace: {{namespace}}
+  labels:
+    app: {{name}}
+spec:
+  replicas: {{replicas}}
+  selector:
+    matchLabels:
+      app: {{name}}
+  template:
+    metadata:
+      labels:
+        app: {{name}}
+    spec:
+      containers:
+        - name: {{name}}
+          image: {{image}}
+          ports:
+            - name: http
+              containerPort: {{port}}
+          resources:
+            requests:
+              cpu: {{cpu_request}}
+              memory: {{memory_request}}
+            limits:
+              memory: {{memory_limit}}
+          readinessProbe:
+            tcpSocket:
+              port: http
+          securityContext:
+            allowPrivilegeEscalation: false
+            runAsNonRoot: true
+"#,
+    },
+    TemplateDef {
+        name: "service",
+        title: "Service",
+        kind: "Service",
+        api_version: "v1",
+        version: 1,
+        params: &[
+            required("name", "Service name"),
+            optional("namespace", "Target namespace", "default"),
+            optional("app", "Value of the app label to select", "{{name}}"),
+            optional("type", "ClusterIP, NodePort or LoadBalancer", "ClusterIP"),
+            optional("port", "Service port", "80"),
+            optional("target_port", "Container port or port name", "http"),
+        ],
+        body: r#"apiVersion: v1
+kind: Service
+metadata:
+  name: {{name}}
+  namespace: {{namespace}}
+spec:
+  type: {{type}}
+  selector:
+    app: {{app}}
+  ports:
+    - name: http
+      port: {{port}}
+      targetPort: {{target_port}}
+      protocol: TCP
+"#,
+    },
+    TemplateDef {
+        name: "ingress",
+        title: "Ingress",
+        kind: "Ingress",
+        api_version: "networking.k8s.io/v1",
+        version: 1,
+        params: &[
+            required("name", "Ingress name"),
+            optional("namespace", "Target namespace", "default"),
+            required("host", "Hostname to route"),
+            optional("path", "Path prefix", "/"),
+            required("service", "Backend Service name"),
+            optional("service_port", "Backend Service port", "80"),
+            optional("ingress_class", "IngressClass name", "nginx"),
+        ],
+        body: r#"apiVersion: networking.k8s.io/v1
+kind: Ingress
+metadata:
+  name: {{name}}
+  namespace: {{namespace}}
+spec:
+  ingressClassName: {{ingress_class}}
+  rules:
+    - host: {{host}}
+      http:
+        paths:
+          - path: {{path}}
+            pathType: Prefix
+            backend:
+              service:
+                name: {{service}}
+                port:
+                  number: {{service_port}}
+"#,
+    },
+    TemplateDef {
+        name: "cronjob",
+        title: "CronJob",
+        kind: "CronJob",
+        api_version: "batch/v1",
+        version: 1,
+        params: &[
+            required("name", "CronJob name"),
+            optional("namespace", "Target namespace", "default"),
+            required("schedule", "Cron schedule, e.g. \"0 3 * * *\""),
+            required("image", "Container image, including tag"),
+            optional("command", "Command to run, as a shell line", "echo hello"),
+            optional("concurrency_policy", "Allow, Forbid or Replace", "Forbid"),
+        ],
+        body: r#"apiVersion: batch/v1
+kind: CronJob
+metadata:
+  name: {{name}}
+  namespace: {{namespace}}
+spec:
+  schedule: "{{schedule}}"
+  concurrencyPolicy: {{concurrency_policy}}
+  successfulJobsHistoryLimit: 3
+  failedJobsHistoryLimit: 3
+  jobTemplate:
+    spec:
+      backoffLimit: 2
+      template:
+        spec:
+          restartPolicy: OnFailure
+          containers:
+            - name: {{name}}
+              image: {{image}}
+              command: ["/bin/sh", "-c", "{{command}}"]
+"#,
+    },
+    TemplateDef {
+        name: "networkpolicy",
+        title: "NetworkPolicy (allow from namespace)",
+        kind: "NetworkPolicy",
+        api_version: "networking.k8s.io/v1",
+        version: 1,
+        params: &[
+            required("name", "NetworkPolicy name"),
+            optional("namespace", "Target namespace", "default"),
+            required("app", "Value of the app label of the pods to protect"),
+            required("from_namespace", "Namespace allowed to connect"),
+            optional("port", "Allowed TCP port", "8080"),
+        ],
+        body: r#"apiVersion: networking.k8s.io/v1
+kind: NetworkPolicy
+metadata:
+  name: {{name}}
+  namespace: {{namespace}}
+spec:
+  podSelector:
+    matchLabels:
+      app: {{app}}
+  policyTypes:
+    - Ingress
+  ingress:
+    - from:
+        - namespaceSelector:
+            matchLabels:
+              kubernetes.io/metadata.name: {{from_namespace}}
+      ports:
+        - protocol: TCP
+          port: {{port}}
+"#,
+    },
+];
+
+/// The built-in templates and their parameters
+pub fn list_templates() -> Vec<ResourceTemplate> {
+    TEMPLATES
+        .iter()
+        .map(|t| ResourceTemplate {
+            name: t.name.to_string(),
+            title: t.title.to_string(),
+            kind: t.kind.to_string(),
+            api_version: t.api_version.to_string(),
+            version: t.version,
+            params: t
+                .params
+                .iter()
+                .map(|p| TemplateParam {
+                    name: p.name.to_string(),
+                    description: p.description.to_string(),
+                    default: p.default.map(|d| d.to_string()),
+                    required: p.default.is_none(),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Fill a template's placeholders, returning YAML ready for the editor
+///
+/// Missing optional parameters take their defaults, which may refer to other
+/// parameters (the Service's `app` defaults to `{{name}}`). Values must be
+/// single-line so they can't inject extra YAML.
+pub fn render_template(name: &str, params: &HashMap<String, String>) -> Result<String> {
+    let template = TEMPLATES
+        .iter()
+        .find(|t| t.name == name)
+        .ok_or_else(|| anyhow!("Unknown template: {}", name))?;
+
+    if let Some(unknown) = params.keys().find(|k| !template.params.iter().any(|p| p.name == k.as_str())) {
+        return Err(anyhow!("Template '{}' has no parameter '{}'", name, unknown));
+    }
+
+    let mut values: Vec<(&str, String)> = Vec::new();
+    for param in template.params {
+        let value = match (params.get(param.name).filter(|v| !v.trim().is_empty()), param.default) {
+            (Some(value), _) => value.trim().to_string(),
+            (None, Some(default)) => default.to_string(),
+            (None, None) => return Err(anyhow!("Parameter '{}' is required", param.name)),
+        };
+        if value.contains('\n') || value.contains('\r') {
+            return Err(anyhow!("Parameter '{}' must be a single line", param.name));
+        }
+        values.push((param.name, value));
+    }
+
+    // Defaults may reference parameters given earlier, e.g. `{{name}}`
+    let resolved: Vec<(&str, String)> = values
+        .iter()
+        .map(|(name, value)| (*name, substitute(value, &values)))
+        .collect();
+    let rendered = substitute(template.body, &resolved);
+
+    serde_yaml::from_str::<serde_yaml::Value>(&rendered)
+        .map_err(|e| anyhow!("Rendered template is not valid YAML (check parameter values): {}", e))?;
+    Ok(rendered)
+}
+
+fn substitute(text: &str, values: &[(&str, String)]) -> String {
+    values.iter().fold(text.to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{{{}}}}}", name), value)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_template() {
+        let params: HashMap<String, String> = HashMap::from([("name".to_string(), "web".to_string())]);
+        let service = render_template("service", &params).unwrap();
+        assert!(service.contains("app: web"));
+        assert!(!service.contains("{{"));
+
+        assert!(render_template("deployment", &params).is_err()); // image is required
+        assert!(render_template("nope", &params).is_err());
+
+        // Every template renders to valid YAML with just its required parameters
+        for template in list_templates() {
+            let params: HashMap<String, String> = template
+                .params
+                .iter()
+                .filter(|p| p.required)
+                .map(|p| (p.name.clone(), "x".to_string()))
+                .collect();
+            let rendered = render_template(&template.name, &params).unwrap();
+            assert!(!rendered.contains("{{"), "{} left placeholders", template.name);
+        }
+    }
+}
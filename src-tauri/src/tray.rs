@@ -0,0 +1,185 @@
+use anyhow::Result;
+use std::sync::Mutex;
+use tauri::menu::{CheckMenuItemBuilder, Menu, MenuBuilder, MenuItemBuilder, PredefinedMenuItem, SubmenuBuilder};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::kube::{load_kubeconfig, KubeClientManager, PodWatchManager};
+use crate::portforward::PortForwardManager;
+use crate::types::{PortForwardInfo, TrayAction, TrayStatus};
+
+const TRAY_ID: &str = "main";
+const CONTEXT_PREFIX: &str = "context:";
+const FORWARD_PREFIX: &str = "forward:";
+
+/// What the tray remembers between menu rebuilds
+#[derive(Default)]
+pub struct TrayState {
+    last_namespace: Mutex<Option<String>>,
+}
+
+impl TrayState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn last_namespace(&self) -> Option<String> {
+        self.last_namespace.lock().ok().and_then(|n| n.clone())
+    }
+
+    pub fn set_last_namespace(&self, namespace: Option<String>) {
+        if let Ok(mut last) = self.last_namespace.lock() {
+            *last = namespace;
+        }
+    }
+}
+
+fn build_menu(app: &AppHandle, forwards: &[PortForwardInfo], last_namespace: Option<&str>) -> Result<Menu<tauri::Wry>> {
+    let (current, contexts) = match load_kubeconfig() {
+        Ok(config) => (config.current_context, config.contexts.into_iter().map(|c| c.name).collect()),
+        Err(_) => (String::new(), Vec::new()),
+    };
+
+    let header = if current.is_empty() { "No context".to_string() } else { format!("Context: {}", current) };
+    let mut switch = SubmenuBuilder::new(app, "Switch context");
+    for context in &contexts {
+        let item = CheckMenuItemBuilder::with_id(format!("{}{}", CONTEXT_PREFIX, context), context)
+            .checked(*context == current)
+            .build(app)?;
+        switch = switch.item(&item);
+    }
+
+    let forwards_title = format!("Port forwards ({})", forwards.len());
+    let mut forwards_menu = SubmenuBuilder::new(app, &forwards_title).enabled(!forwards.is_empty());
+    for forward in forwards {
+        // Clicking a forward stops it
+        let label = format!(
+            "Stop localhost:{} → {}/{}:{} ({})",
+            forward.local_port, forward.resource_type, forward.resource_name, forward.remote_port, forward.namespace
+        );
+        let item = MenuItemBuilder::with_id(format!("{}{}", FORWARD_PREFIX, forward.id), label).build(app)?;
+        forwards_menu = forwards_menu.item(&item);
+    }
+
+    let open_namespace = match last_namespace {
+        Some(ns) => format!("Open namespace {}", ns),
+        None => "Open last namespace".to_string(),
+    };
+
+    Ok(MenuBuilder::new(app)
+        .item(&MenuItemBuilder::with_id("current-context", header).enabled(false).build(app)?)
+        .item(&switch.build()?)
+        .separator()
+        .item(&forwards_menu.build()?)
+        .item(
+            &MenuItemBuilder::with_id("stop-forwards", "Stop all port forwards")
+                .enabled(!forwards.is_empty())
+                .build(app)?,
+        )
+        .item(
+            &MenuItemBuilder::with_id("open-namespace", open_namespace)
+                .enabled(last_namespace.is_some())
+                .build(app)?,
+        )
+        .separator()
+        .item(&MenuItemBuilder::with_id("show", "Show KubeSail").build(app)?)
+        .item(&PredefinedMenuItem::quit(app, None)?)
+        .build()?)
+}
+
+/// Create the tray icon; port-forwards start empty so this can run synchronously in setup
+pub fn init(app: &AppHandle) -> Result<()> {
+    let menu = build_menu(app, &[], None)?;
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("KubeSail")
+        .menu(&menu)
+        .on_menu_event(|app, event| {
+            let app = app.clone();
+            let id = event.id().as_ref().to_string();
+            tauri::async_runtime::spawn(async move {
+                handle_menu_event(&app, &id).await;
+            });
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+    Ok(())
+}
+
+/// Rebuild the menu from current state, after forwards or the context changed
+pub async fn refresh(app: &AppHandle) -> Result<()> {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return Ok(());
+    };
+    let forwards = app.state::<PortForwardManager>().list_port_forwards().await;
+    let last_namespace = app.state::<TrayState>().last_namespace();
+
+    tray.set_menu(Some(build_menu(app, &forwards, last_namespace.as_deref())?))?;
+    let context = load_kubeconfig().map(|c| c.current_context).unwrap_or_default();
+    tray.set_tooltip(Some(match forwards.len() {
+        0 => format!("KubeSail - {}", context),
+        n => format!("KubeSail - {} ({} port forwards)", context, n),
+    }))?;
+    Ok(())
+}
+
+pub async fn status(app: &AppHandle) -> TrayStatus {
+    TrayStatus {
+        context: load_kubeconfig().map(|c| c.current_context).ok(),
+        last_namespace: app.state::<TrayState>().last_namespace(),
+        port_forwards: app.state::<PortForwardManager>().list_port_forwards().await,
+    }
+}
+
+pub(crate) fn show_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// Carry out a tray click and tell the frontend with a `tray-action` event
+async fn handle_menu_event(app: &AppHandle, id: &str) {
+    let (action, target, result): (&str, Option<String>, Result<()>) =
+        if let Some(context) = id.strip_prefix(CONTEXT_PREFIX) {
+            let result = match crate::kube::switch_context(context) {
+                Ok(()) => app.state::<KubeClientManager>().reinit_client().await,
+                Err(e) => Err(e),
+            };
+            // The remembered namespace and the pod watches belonged to the old context
+            app.state::<TrayState>().set_last_namespace(None);
+            app.state::<PodWatchManager>().stop_all().await;
+            ("switch-context", Some(context.to_string()), result)
+        } else if let Some(forward) = id.strip_prefix(FORWARD_PREFIX) {
+            let result = app.state::<PortForwardManager>().stop_port_forward(forward).await;
+            ("stop-forward", Some(forward.to_string()), result)
+        } else {
+            match id {
+                "stop-forwards" => ("stop-forwards", None, app.state::<PortForwardManager>().stop_all().await),
+                "open-namespace" => {
+                    show_window(app);
+                    ("open-namespace", app.state::<TrayState>().last_namespace(), Ok(()))
+                }
+                "show" => {
+                    show_window(app);
+                    ("show", None, Ok(()))
+                }
+                _ => return,
+            }
+        };
+
+    if let Err(e) = &result {
+        tracing::warn!("Tray action {} failed: {}", action, e);
+    }
+    let _ = app.emit(
+        "tray-action",
+        &TrayAction {
+            action: action.to_string(),
+            target,
+            error: result.err().map(|e| e.to_string()),
+        },
+    );
+    let _ = refresh(app).await;
+}
@@ -14,6 +14,10 @@ pub struct ContextInfo {
     pub namespace: Option<String>,
     pub user: String,
     pub current: bool,
+    pub environment: Option<String>,  // From the user's context tags
+    pub color: Option<String>,
+    pub group: Option<String>,
+    pub protected: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +25,37 @@ pub struct NamespaceInfo {
     pub name: String,
     pub status: String,
     pub age: String,
+    pub created_at: Option<String>,  // RFC3339 creation timestamp
+}
+
+/// A write rejected because the object changed since it was read
+///
+/// Returned to the frontend as a JSON error string so it can offer to
+/// reload, force or merge instead of showing a generic failure.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceConflict {
+    pub error: String,  // Always "Conflict"
+    pub message: String,
+    pub expected_resource_version: Option<String>,
+    pub current_resource_version: Option<String>,
+    pub current_yaml: Option<String>,
+}
+
+impl std::fmt::Display for ResourceConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Conflict: {}", self.message)
+    }
+}
+
+impl std::error::Error for ResourceConflict {}
+
+/// Server-side filtering and sorting for list commands
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListQuery {
+    pub sort_by: Option<String>,  // name, namespace, age, status, or a type-specific column like restarts
+    pub descending: Option<bool>,
+    pub status: Option<Vec<String>>,  // Keep only these statuses (case-insensitive)
+    pub problems_only: Option<bool>,  // Keep only not-ready/failing items
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,11 +66,36 @@ pub struct PodInfo {
     pub ready: String,
     pub restarts: i32,
     pub age: String,
+    pub created_at: Option<String>,  // RFC3339 creation timestamp
     pub node: Option<String>,
     pub ip: Option<String>,
     pub ports: Vec<i32>,
     pub labels: Option<std::collections::HashMap<String, String>>,
     pub annotations: Option<std::collections::HashMap<String, String>>,
+    pub containers: Vec<ContainerStatusInfo>,
+    pub resources: PodResourceTotals,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PodResourceTotals {
+    pub cpu_request_millicores: Option<i64>,
+    pub cpu_limit_millicores: Option<i64>,
+    pub memory_request_bytes: Option<u64>,
+    pub memory_limit_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerStatusInfo {
+    pub name: String,
+    pub container_type: String,  // init, sidecar, container or ephemeral
+    pub image: String,
+    pub ready: bool,
+    pub restart_count: i32,
+    pub state: String,  // running, waiting, terminated or unknown
+    pub reason: Option<String>,
+    pub message: Option<String>,
+    pub exit_code: Option<i32>,
+    pub last_termination_reason: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,6 +106,7 @@ pub struct DeploymentInfo {
     pub up_to_date: i32,
     pub available: i32,
     pub age: String,
+    pub created_at: Option<String>,  // RFC3339 creation timestamp
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -58,6 +119,9 @@ pub struct ServiceInfo {
     pub ports: String,
     pub selector: Option<std::collections::HashMap<String, String>>,
     pub age: String,
+    pub created_at: Option<String>,  // RFC3339 creation timestamp
+    pub load_balancer_ingress: Vec<String>,  // assigned IPs or hostnames
+    pub load_balancer_status: Option<String>,  // Pending or Ready, LoadBalancer services only
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,6 +131,28 @@ pub struct LogEntry {
     pub pod_name: String,
 }
 
+/// A historical log search, answered by the cluster's configured log provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogSearch {
+    pub namespace: String,
+    pub pod_name: Option<String>,  // Required for kubelet logs
+    pub container: Option<String>,
+    pub query: Option<String>,     // Text to match, or a full LogQL query for Loki
+    pub since: Option<String>,     // RFC3339 or relative (30m, 2h, 7d)
+    pub until: Option<String>,
+    pub limit: Option<u32>,
+    pub provider: Option<String>,  // Override the cluster's provider: kubelet or loki
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerLogs {
+    pub container: String,
+    pub container_type: String,  // init, container or ephemeral
+    pub order: usize,  // position in the pod spec, for stable tab ordering
+    pub logs: Vec<LogEntry>,
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PortForwardRequest {
     pub namespace: String,
@@ -83,6 +169,7 @@ pub struct IngressInfo {
     pub hosts: Vec<String>,
     pub addresses: Vec<String>,
     pub age: String,
+    pub created_at: Option<String>,  // RFC3339 creation timestamp
     pub tls: bool,
     pub rules: Vec<IngressRule>,
 }
@@ -108,6 +195,7 @@ pub struct IstioVirtualServiceInfo {
     pub hosts: Vec<String>,
     pub gateways: Vec<String>,
     pub age: String,
+    pub created_at: Option<String>,  // RFC3339 creation timestamp
     pub routes: Vec<VirtualServiceRoute>,
 }
 
@@ -133,6 +221,7 @@ pub struct IstioGatewayInfo {
     pub namespace: String,
     pub servers: Vec<GatewayServer>,
     pub age: String,
+    pub created_at: Option<String>,  // RFC3339 creation timestamp
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -148,6 +237,7 @@ pub struct ConfigMapInfo {
     pub namespace: String,
     pub data: std::collections::HashMap<String, String>,
     pub age: String,
+    pub created_at: Option<String>,  // RFC3339 creation timestamp
     pub keys: usize,
 }
 
@@ -158,9 +248,20 @@ pub struct SecretInfo {
     pub secret_type: String,
     pub data: std::collections::HashMap<String, String>, // base64 decoded
     pub age: String,
+    pub created_at: Option<String>,  // RFC3339 creation timestamp
     pub keys: usize,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigDataMatch {
+    pub kind: String,  // ConfigMap or Secret
+    pub namespace: String,
+    pub name: String,
+    pub key: String,
+    pub matched_in: String,  // key or value
+    pub snippet: Option<String>,  // Only for ConfigMap values
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StatefulSetInfo {
     pub name: String,
@@ -168,6 +269,7 @@ pub struct StatefulSetInfo {
     pub ready: String,
     pub replicas: i32,
     pub age: String,
+    pub created_at: Option<String>,  // RFC3339 creation timestamp
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -180,6 +282,7 @@ pub struct DaemonSetInfo {
     pub up_to_date: i32,
     pub available: i32,
     pub age: String,
+    pub created_at: Option<String>,  // RFC3339 creation timestamp
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -189,6 +292,7 @@ pub struct JobInfo {
     pub completions: String,
     pub duration: String,
     pub age: String,
+    pub created_at: Option<String>,  // RFC3339 creation timestamp
     pub active: i32,
     pub succeeded: i32,
     pub failed: i32,
@@ -203,6 +307,7 @@ pub struct CronJobInfo {
     pub active: i32,
     pub last_schedule: Option<String>,
     pub age: String,
+    pub created_at: Option<String>,  // RFC3339 creation timestamp
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -211,6 +316,7 @@ pub struct NodeInfo {
     pub status: String,
     pub roles: Vec<String>,
     pub age: String,
+    pub created_at: Option<String>,  // RFC3339 creation timestamp
     pub version: String,
     pub internal_ip: String,
     pub external_ip: Option<String>,
@@ -221,9 +327,18 @@ pub struct NodeInfo {
     pub cpu_allocatable: String,
     pub memory_capacity: String,
     pub memory_allocatable: String,
+    pub cpu_capacity_millicores: Option<i64>,
+    pub cpu_allocatable_millicores: Option<i64>,
+    pub memory_capacity_bytes: Option<u64>,
+    pub memory_allocatable_bytes: Option<u64>,
     pub pods_capacity: String,
     pub pods_allocatable: String,
     pub gpu_capacity: Option<String>,
+    pub node_pool: Option<String>,  // From GKE/EKS/AKS/Karpenter/kops/DOKS pool labels
+    pub zone: Option<String>,
+    pub region: Option<String>,
+    pub instance_type: Option<String>,
+    pub capacity_type: String,  // spot, on-demand or unknown
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -238,6 +353,40 @@ pub struct EventInfo {
     pub count: i32,
 }
 
+// Event export types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedEvent {
+    pub timestamp: String,                // RFC3339, when the event last occurred
+    pub first_timestamp: Option<String>,
+    pub event_type: String,
+    pub reason: String,
+    pub namespace: String,
+    pub kind: String,
+    pub name: String,
+    pub owner: Option<String>,            // Top-level owning workload, Kind/name
+    pub uid: Option<String>,
+    pub message: String,
+    pub source: String,
+    pub count: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineEntry {
+    pub timestamp: String,
+    pub category: String,  // event, rollout, condition, restart
+    pub severity: String,  // Normal or Warning
+    pub object: String,    // Kind/name the entry is about
+    pub summary: String,
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventExport {
+    pub format: String,   // csv or json
+    pub count: usize,
+    pub content: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PersistentVolumeInfo {
     pub name: String,
@@ -248,6 +397,7 @@ pub struct PersistentVolumeInfo {
     pub claim: Option<String>,
     pub storage_class: Option<String>,
     pub age: String,
+    pub created_at: Option<String>,  // RFC3339 creation timestamp
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -260,6 +410,7 @@ pub struct PersistentVolumeClaimInfo {
     pub access_modes: Vec<String>,
     pub storage_class: Option<String>,
     pub age: String,
+    pub created_at: Option<String>,  // RFC3339 creation timestamp
 }
 
 // RBAC Types
@@ -268,6 +419,7 @@ pub struct RoleInfo {
     pub name: String,
     pub namespace: String,
     pub age: String,
+    pub created_at: Option<String>,  // RFC3339 creation timestamp
     pub rules_count: usize,
 }
 
@@ -279,12 +431,14 @@ pub struct RoleBindingInfo {
     pub role_kind: String,
     pub subjects: Vec<SubjectInfo>,
     pub age: String,
+    pub created_at: Option<String>,  // RFC3339 creation timestamp
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClusterRoleInfo {
     pub name: String,
     pub age: String,
+    pub created_at: Option<String>,  // RFC3339 creation timestamp
     pub rules_count: usize,
 }
 
@@ -294,6 +448,7 @@ pub struct ClusterRoleBindingInfo {
     pub role: String,
     pub subjects: Vec<SubjectInfo>,
     pub age: String,
+    pub created_at: Option<String>,  // RFC3339 creation timestamp
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -302,6 +457,7 @@ pub struct ServiceAccountInfo {
     pub namespace: String,
     pub secrets: usize,
     pub age: String,
+    pub created_at: Option<String>,  // RFC3339 creation timestamp
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -333,6 +489,7 @@ pub struct CRDInfo {
     pub singular: String,
     pub scope: String, // Namespaced or Cluster
     pub age: String,
+    pub created_at: Option<String>,  // RFC3339 creation timestamp
     pub categories: Vec<String>,
 }
 
@@ -343,6 +500,7 @@ pub struct CustomResourceInfo {
     pub kind: String,
     pub api_version: String,
     pub age: String,
+    pub created_at: Option<String>,  // RFC3339 creation timestamp
     pub metadata: serde_json::Value,
 }
 
@@ -361,4 +519,1360 @@ pub struct CNPGConnectionDetails {
     pub jdbc_uri: String,
     pub fqdn_jdbc_uri: String,
     pub pgpass: String,
+    pub password_redacted: bool,
+}
+
+// Output of an external command (kubectl, plugins)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandOutput {
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: Option<i32>,
+}
+
+// Cluster version and skew types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterVersionInfo {
+    pub server_version: String,
+    pub server_minor: Option<u32>,
+    pub platform: String,
+    pub build_date: String,
+    pub nodes: Vec<NodeVersionInfo>,
+    pub deprecated_api_usage: Vec<DeprecatedApiUsage>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeVersionInfo {
+    pub name: String,
+    pub kubelet_version: String,
+    pub container_runtime: String,
+    pub minor_skew: Option<i64>,  // API server minor minus kubelet minor
+    pub skew_supported: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeprecatedApiUsage {
+    pub group: String,
+    pub version: String,
+    pub resource: String,
+    pub subresource: String,
+    pub removed_release: Option<String>,
+}
+
+// Deprecated API scanner types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeprecatedObject {
+    pub kind: String,
+    pub name: String,
+    pub namespace: Option<String>,
+    pub api_version: String,
+    pub replacement: Option<String>,
+    pub removed_in: String,
+    pub source: String,  // managedFields or last-applied-configuration
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeprecationReport {
+    pub target_version: Option<String>,
+    pub findings: Vec<DeprecatedObject>,
+    pub scanned_resources: usize,
+    pub skipped: Vec<String>,
+}
+
+// Tekton types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineRunInfo {
+    pub name: String,
+    pub namespace: String,
+    pub pipeline: Option<String>,
+    pub status: String,  // Pending, Running, Succeeded, Failed or Cancelled
+    pub reason: Option<String>,
+    pub message: Option<String>,
+    pub started_at: Option<String>,
+    pub completed_at: Option<String>,
+    pub duration_seconds: Option<i64>,  // Elapsed so far while running
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskRunInfo {
+    pub name: String,
+    pub namespace: String,
+    pub pipeline_run: Option<String>,
+    pub task: Option<String>,
+    pub status: String,
+    pub reason: Option<String>,
+    pub message: Option<String>,
+    pub pod_name: Option<String>,
+    pub steps: Vec<TektonStepInfo>,
+    pub started_at: Option<String>,
+    pub completed_at: Option<String>,
+    pub duration_seconds: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TektonStepInfo {
+    pub name: String,
+    pub container: String,
+    pub state: String,  // Waiting, Running or Terminated
+    pub exit_code: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskRunLogLine {
+    pub step: String,
+    pub line: String,
+}
+
+// Knative types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KnativeServiceInfo {
+    pub name: String,
+    pub namespace: String,
+    pub url: Option<String>,
+    pub ready: bool,
+    pub reason: Option<String>,
+    pub latest_created_revision: Option<String>,
+    pub latest_ready_revision: Option<String>,
+    pub traffic: Vec<KnativeTrafficTarget>,
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KnativeTrafficTarget {
+    pub revision_name: Option<String>,
+    pub latest_revision: Option<bool>,
+    pub percent: Option<i64>,
+    pub tag: Option<String>,
+    pub url: Option<String>,  // Only set in status, for tagged targets
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KnativeRevisionInfo {
+    pub name: String,
+    pub namespace: String,
+    pub service: Option<String>,
+    pub ready: bool,
+    pub reason: Option<String>,
+    pub image: Option<String>,
+    pub actual_replicas: Option<i64>,
+    pub desired_replicas: Option<i64>,
+    pub scaled_to_zero: bool,
+    pub min_scale: Option<i64>,
+    pub max_scale: Option<i64>,
+    pub traffic_percent: i64,
+    pub created_at: Option<String>,
+}
+
+// CSI diagnostics types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsiDriverInfo {
+    pub name: String,
+    pub attach_required: bool,
+    pub pod_info_on_mount: bool,
+    pub storage_capacity: bool,
+    pub fs_group_policy: Option<String>,
+    pub volume_lifecycle_modes: Vec<String>,
+    pub node_count: usize,  // Nodes where the driver has registered
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsiNodeInfo {
+    pub name: String,
+    pub drivers: Vec<CsiNodeDriverInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsiNodeDriverInfo {
+    pub name: String,
+    pub node_id: String,
+    pub allocatable_count: Option<i32>,  // Max volumes the driver can attach on this node
+    pub topology_keys: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeAttachmentInfo {
+    pub name: String,
+    pub attacher: String,
+    pub node: String,
+    pub persistent_volume: Option<String>,
+    pub claim: Option<String>,  // namespace/name
+    pub attached: bool,
+    pub attach_error: Option<String>,
+    pub detach_error: Option<String>,
+    pub deleting: bool,
+    pub stuck: bool,
+    pub created_at: Option<String>,
+}
+
+// Runtime diagnostics types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeDiagnosticResult {
+    pub kind: String,     // java-threads, java-heap-histogram, java-heap-info, go-stacks
+    pub pod_name: String,
+    pub container: String,
+    pub command: String,  // What was run in the container
+    pub path: String,     // Local file the output was saved to
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileCaptureResult {
+    pub endpoint_type: String,  // pprof-cpu, pprof-heap, actuator-heapdump, ...
+    pub target: String,
+    pub request_path: String,
+    pub duration_secs: u64,
+    pub captured_at: String,
+    pub path: String,           // Saved artifact
+    pub metadata_path: String,  // JSON sidecar describing the capture
+    pub size_bytes: u64,
+}
+
+// Image inventory types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageInventory {
+    pub images: Vec<ImageUsage>,
+    pub registries: Vec<RegistrySummary>,
+    pub latest_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageUsage {
+    pub image: String,
+    pub registry: String,
+    pub repository: String,
+    pub tag: Option<String>,
+    pub digest: Option<String>,
+    pub latest: bool,  // Tagged :latest or untagged
+    pub container_count: usize,
+    pub pull_policies: Vec<String>,
+    pub pull_secrets: Vec<String>,
+    pub workloads: Vec<String>,  // namespace/Kind/name
+    pub pull_errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistrySummary {
+    pub registry: String,
+    pub image_count: usize,
+    pub pull_secrets: Vec<String>,
+    pub unreachable: bool,  // Every image from this registry is failing to pull
+    pub failing_images: Vec<String>,
+}
+
+// Access review types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AllowedActions {
+    pub kind: String,
+    pub namespace: Option<String>,
+    pub allowed: std::collections::HashMap<String, bool>,  // Action name (get, delete, scale, exec, ...) -> permitted
+}
+
+// Multi-cluster health types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextHealth {
+    pub context: String,
+    pub cluster: String,
+    pub current: bool,
+    pub reachable: bool,
+    pub version: Option<String>,
+    pub nodes_ready: Option<usize>,
+    pub nodes_total: Option<usize>,
+    pub latency_ms: Option<u64>,  // Time to answer the version request
+    pub error: Option<String>,
+}
+
+// API server metrics types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiServerMetricsSummary {
+    pub inflight_read_only: u64,
+    pub inflight_mutating: u64,
+    pub throttled_requests: u64,  // Requests answered with 429 since startup
+    pub slowest: Vec<ApiRequestStats>,  // By p99 latency
+    pub busiest: Vec<ApiRequestStats>,  // By request count
+    pub storage_objects: Vec<StorageObjectCount>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiRequestStats {
+    pub verb: String,
+    pub resource: String,
+    pub count: u64,
+    pub avg_ms: f64,
+    pub p99_ms: Option<f64>,  // Upper bound of the histogram bucket holding the 99th percentile
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageObjectCount {
+    pub resource: String,
+    pub count: u64,
+}
+
+// Control plane health types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ControlPlaneHealth {
+    pub healthy: bool,
+    pub components: Vec<ComponentHealth>,             // kube-system Deployments and DaemonSets
+    pub component_statuses: Vec<ComponentStatusInfo>, // Empty where componentstatuses isn't served
+    pub recent_restarts: Vec<RestartOffender>,        // kube-system containers restarted in the last hour
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentHealth {
+    pub kind: String,
+    pub name: String,
+    pub category: Option<String>,  // DNS, CNI, Metrics, ... for well-known components
+    pub desired: i32,
+    pub ready: i32,
+    pub healthy: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentStatusInfo {
+    pub name: String,
+    pub healthy: bool,
+    pub message: Option<String>,
+}
+
+// Cluster overview types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterOverview {
+    pub nodes_total: usize,
+    pub nodes_ready: usize,
+    pub not_ready_nodes: Vec<String>,
+    pub pods_total: usize,
+    pub pods_running: usize,
+    pub failing_pods: Vec<FailingPodInfo>,
+    pub pending_pvcs: Vec<String>,  // namespace/name
+    pub recent_warnings: Vec<EventInfo>,
+    pub top_restarts: Vec<RestartOffender>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailingPodInfo {
+    pub name: String,
+    pub namespace: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestartOffender {
+    pub pod: String,
+    pub namespace: String,
+    pub container: String,
+    pub restarts: i32,
+    pub last_termination_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterProblem {
+    pub kind: String,
+    pub reason: String,
+    pub event_count: i32,
+    pub object_count: usize,
+    pub affected_objects: Vec<String>,  // namespace/name, capped
+    pub namespaces: Vec<String>,
+    pub latest_message: String,
+    pub last_seen: String,
+}
+
+// StatefulSet PVC types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatefulSetPvcInfo {
+    pub name: String,
+    pub template: String,
+    pub ordinal: i32,
+    pub pod_name: String,
+    pub pod_exists: bool,
+    pub status: String,
+    pub capacity: Option<String>,
+    pub storage_class: Option<String>,
+    pub orphaned: bool,  // ordinal is outside [spec.ordinals.start, start + replicas)
+    pub age: String,
+    pub created_at: Option<String>,  // RFC3339 creation timestamp
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadBalancerAnnotationPreset {
+    pub provider: String,
+    pub key: String,
+    pub value: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngressRouteTestResult {
+    pub matched: bool,
+    pub rule_host: Option<String>,
+    pub matched_path: Option<String>,
+    pub path_type: Option<String>,
+    pub used_default_backend: bool,
+    pub service_name: Option<String>,
+    pub service_port: Option<String>,
+    pub service_exists: bool,
+    pub port_exists: bool,
+    pub ready_endpoints: usize,
+    pub problems: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VirtualServiceWeight {
+    pub host: String,
+    pub subset: Option<String>,
+    pub port: Option<u32>,
+    pub weight: u32,
+}
+
+// Envoy / istio-proxy diagnostics types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxyDiagnostics {
+    pub pod: String,
+    pub namespace: String,
+    pub proxy_version: Option<String>,
+    pub state: Option<String>,
+    pub clusters: Vec<ProxyCluster>,
+    pub listeners: Vec<ProxyListener>,
+    pub certificates: Vec<ProxyCertificate>,
+    pub stats: std::collections::HashMap<String, String>,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxyCluster {
+    pub name: String,
+    pub hosts: usize,
+    pub healthy_hosts: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxyListener {
+    pub name: String,
+    pub address: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxyCertificate {
+    pub serial_number: String,
+    pub subject_alt_names: Vec<String>,
+    pub valid_from: Option<String>,
+    pub expiration_time: Option<String>,
+    pub days_until_expiration: Option<i64>,
+    pub is_ca: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkScaleResult {
+    pub kind: String,
+    pub name: String,
+    pub current_replicas: i32,
+    pub target_replicas: i32,
+    pub changed: bool,
+    pub error: Option<String>,
+}
+
+// Namespace clone types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceRef {
+    pub kind: String,
+    pub namespace: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CopyResourceResult {
+    pub created: Vec<String>,  // Kind/name, dependencies first
+    pub skipped: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NamespaceCloneRules {
+    pub name_prefix: Option<String>,
+    pub name_suffix: Option<String>,
+    #[serde(default)]
+    pub labels: std::collections::HashMap<String, String>,
+    #[serde(default)]
+    pub replacements: Vec<TextReplacement>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextReplacement {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamespaceCloneResult {
+    pub created: Vec<String>,
+    pub skipped: Vec<String>,
+    pub failed: Vec<NamespaceCloneFailure>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamespaceCloneFailure {
+    pub resource: String,
+    pub error: String,
+}
+
+// Creation wizard inputs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimpleDeploymentSpec {
+    pub name: String,
+    pub namespace: String,
+    pub image: String,
+    pub replicas: Option<i32>,
+    #[serde(default)]
+    pub ports: Vec<i32>,
+    #[serde(default)]
+    pub env: std::collections::HashMap<String, String>,
+    #[serde(default)]
+    pub labels: std::collections::HashMap<String, String>,
+    pub command: Option<Vec<String>>,
+    pub cpu_request: Option<String>,
+    pub memory_request: Option<String>,
+    pub cpu_limit: Option<String>,
+    pub memory_limit: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimpleServiceSpec {
+    pub namespace: String,
+    pub workload_name: String,
+    pub name: Option<String>,  // defaults to the workload name
+    pub service_type: Option<String>,
+    #[serde(default)]
+    pub ports: Vec<i32>,
+    pub target_port: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimpleIngressSpec {
+    pub name: String,
+    pub namespace: String,
+    pub host: String,
+    pub path: Option<String>,
+    pub path_type: Option<String>,
+    pub service_name: String,
+    pub service_port: i32,
+    pub ingress_class: Option<String>,
+    pub tls_secret: Option<String>,
+    #[serde(default)]
+    pub annotations: std::collections::HashMap<String, String>,
+}
+
+// Shell recording types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShellRecordingInfo {
+    pub file_name: String,
+    pub path: String,
+    pub title: Option<String>,  // namespace/pod (container)
+    pub size_bytes: u64,
+    pub created_at: Option<String>,
+}
+
+// Audit log types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: String,
+    pub context: Option<String>,
+    pub category: String,  // shell, ...
+    pub target: String,    // What the action ran against, e.g. namespace/pod (container)
+    pub action: String,
+    pub blocked: bool,
+}
+
+// Prometheus / Alertmanager types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrometheusAlert {
+    pub name: String,
+    pub state: String,  // firing or pending
+    pub severity: Option<String>,
+    pub namespace: Option<String>,
+    pub workload: Option<String>,  // Kind/name from the alert's labels
+    pub active_at: Option<String>,
+    pub value: Option<String>,
+    pub labels: std::collections::HashMap<String, String>,
+    pub annotations: std::collections::HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertingRule {
+    pub name: String,
+    pub group: String,
+    pub query: String,
+    pub duration_secs: u64,  // `for:` clause
+    pub state: String,       // inactive, pending or firing
+    pub health: String,
+    pub last_error: Option<String>,
+    pub active_alerts: usize,
+    pub labels: std::collections::HashMap<String, String>,
+    pub annotations: std::collections::HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertSilence {
+    pub id: String,
+    pub state: String,  // active or pending
+    pub matchers: Vec<String>,  // e.g. namespace="prod"
+    pub created_by: Option<String>,
+    pub comment: Option<String>,
+    pub starts_at: Option<String>,
+    pub ends_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrafanaLink {
+    pub title: String,
+    pub url: String,
+}
+
+// DNS debugging types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DnsDebugReport {
+    pub name: String,
+    pub namespace: String,
+    pub resolved: bool,
+    pub addresses: Vec<String>,
+    pub steps: Vec<DnsDebugStep>,  // In the order they were checked
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DnsDebugStep {
+    pub name: String,
+    pub ok: bool,
+    pub detail: String,
+}
+
+// Admission webhook diagnostics types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdmissionReport {
+    pub probes: Vec<AdmissionProbeResult>,  // Failures first, then slowest first
+    pub webhooks: Vec<WebhookInfo>,  // Implicated webhooks first
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdmissionProbeResult {
+    pub namespace: String,
+    pub kind: String,  // ConfigMap or Pod
+    pub latency_ms: u64,
+    pub ok: bool,
+    pub slow: bool,
+    pub webhook: Option<String>,  // Webhook named in the error, if any
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookInfo {
+    pub name: String,
+    pub configuration: String,
+    pub webhook_type: String,  // Mutating or Validating
+    pub failure_policy: String,
+    pub timeout_seconds: i32,
+    pub service: Option<String>,  // namespace/name; None for URL webhooks
+    pub service_has_endpoints: Option<bool>,
+    pub failing_probes: usize,
+    pub implicated: bool,
+}
+
+// Policy gate types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyReport {
+    pub passed: bool,  // No deny/violation rules fired
+    pub policy_dir: String,
+    pub violations: Vec<PolicyViolation>,
+    pub warnings: Vec<PolicyViolation>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyViolation {
+    pub namespace: String,  // Rego package the rule belongs to
+    pub message: String,
+}
+
+/// A manifest rejected by the enforced policy gate
+///
+/// Returned to the frontend as a JSON error string, like [`ResourceConflict`],
+/// so the violations can be listed instead of a generic failure.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyViolations {
+    pub error: String,  // Always "PolicyViolation"
+    pub report: PolicyReport,
+}
+
+impl std::fmt::Display for PolicyViolations {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let messages: Vec<&str> = self.report.violations.iter().map(|v| v.message.as_str()).collect();
+        write!(f, "Policy check failed: {}", messages.join("; "))
+    }
+}
+
+impl std::error::Error for PolicyViolations {}
+
+// Manifest lint types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestLintResult {
+    pub document: usize,  // Position in a multi-document manifest, from 0
+    pub api_version: String,
+    pub kind: String,
+    pub name: Option<String>,
+    pub valid: bool,
+    pub errors: Vec<ManifestLintError>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestLintError {
+    pub path: String,  // e.g. .spec.template.spec.containers[0].image
+    pub message: String,
+}
+
+// Resource template types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceTemplate {
+    pub name: String,  // Identifier passed to render_template
+    pub title: String,
+    pub kind: String,
+    pub api_version: String,
+    pub version: u32,  // Bumped whenever the template body changes
+    pub params: Vec<TemplateParam>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateParam {
+    pub name: String,
+    pub description: String,
+    pub default: Option<String>,
+    pub required: bool,
+}
+
+// Scoped kubeconfig types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScopedKubeconfig {
+    pub context_name: String,
+    pub namespace: String,
+    pub service_account: String,
+    pub expires_at: String,  // RFC3339, as granted by the API server
+    pub requested_expires_at: String,
+    pub created: Vec<String>,  // Kind/name of objects created for it
+    pub kubeconfig: String,  // YAML, self-contained
+}
+
+// Registry tag browser types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistryTags {
+    pub registry: String,
+    pub repository: String,
+    pub pull_secret: Option<String>,  // namespace/name of the secret used for auth
+    pub tags: Vec<RegistryTag>,  // Newest version first
+    pub truncated: bool,  // More tags than were fetched
+    pub deployed: Vec<DeployedImageTag>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistryTag {
+    pub tag: String,
+    pub digest: Option<String>,  // Only looked up for deployed and the newest tags
+    pub deployed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeployedImageTag {
+    pub namespace: String,
+    pub pod: String,
+    pub container: String,
+    pub tag: String,
+    pub running_digest: Option<String>,
+    pub registry_digest: Option<String>,
+    pub up_to_date: Option<bool>,  // false: the tag has been re-pushed since the pod pulled it
+    pub newer_tags: Vec<String>,
+}
+
+// Rollout watch types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RolloutProgress {
+    pub kind: String,
+    pub namespace: String,
+    pub name: String,
+    pub desired: i32,
+    pub updated: i32,
+    pub ready: i32,
+    pub available: i32,
+    pub done: bool,
+    pub message: String,  // kubectl rollout status style
+    pub failure: Option<String>,  // Set when the rollout can no longer succeed
+    pub problems: Vec<RolloutProblem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RolloutProblem {
+    pub pod: String,
+    pub container: Option<String>,
+    pub reason: String,  // e.g. CrashLoopBackOff, ImagePullBackOff, Unschedulable
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RolloutResult {
+    pub outcome: String,  // success, failed, timeout or cancelled
+    pub message: String,
+    pub problem: Option<RolloutProblem>,  // The offending pod, for failures and timeouts
+}
+
+// Canary deploy types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CanaryStatus {
+    pub deployment: String,
+    pub canary: String,  // Name of the temporary canary Deployment
+    pub container: String,
+    pub stable_image: String,
+    pub canary_image: String,
+    pub stable_replicas: i32,
+    pub canary_replicas: i32,
+}
+
+// Topology spread types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpreadAnalysis {
+    pub kind: String,
+    pub namespace: String,
+    pub name: String,
+    pub replicas: i32,
+    pub scheduled_pods: usize,
+    pub nodes: Vec<DomainCount>,  // Most pods first
+    pub zones: Vec<DomainCount>,
+    pub cluster_zones: Vec<String>,  // Zones with schedulable nodes
+    pub constraints: Vec<SpreadConstraintStatus>,
+    pub anti_affinity: Vec<String>,
+    pub risks: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DomainCount {
+    pub name: String,
+    pub pods: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpreadConstraintStatus {
+    pub topology_key: String,
+    pub max_skew: i32,
+    pub when_unsatisfiable: String,
+    pub skew: i32,
+    pub satisfied: bool,
+    pub domains: Vec<DomainCount>,
+}
+
+// Node pool types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeGroupSummary {
+    pub pool: String,  // "(none)" for nodes without a recognised pool label
+    pub node_count: usize,
+    pub ready_nodes: usize,
+    pub zones: Vec<String>,
+    pub instance_types: Vec<String>,
+    pub capacity_types: Vec<String>,
+    pub pods: usize,
+    pub cpu_allocatable_millicores: i64,
+    pub memory_allocatable_bytes: u64,
+    pub cpu_requested_millicores: i64,
+    pub memory_requested_bytes: u64,
+    pub cpu_request_percent: Option<f64>,
+    pub memory_request_percent: Option<f64>,
+}
+
+// Node group drain types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeDrainProgress {
+    pub node: String,
+    pub phase: String,  // cordoned, evicting, drained or failed
+    pub pods_remaining: usize,
+    pub nodes_done: usize,
+    pub nodes_total: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeGroupDrainResult {
+    pub outcome: String,  // success, failed or cancelled
+    pub message: String,
+    pub drained: Vec<String>,
+    pub failed: Vec<String>,  // "node: reason"
+}
+
+// Autoscaler insight types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoscalerInsight {
+    pub provider: String,  // karpenter, cluster-autoscaler, both joined by '+', or none
+    pub karpenter_node_pools: Vec<KarpenterNodePoolInfo>,
+    pub karpenter_node_claims: Vec<KarpenterNodeClaimInfo>,
+    pub cluster_autoscaler: Option<ClusterAutoscalerStatus>,
+    pub pending_pods: Vec<PendingPodInsight>,
+    pub scaling_events: Vec<ScalingEvent>,  // Newest first
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KarpenterNodePoolInfo {
+    pub name: String,
+    pub ready: bool,
+    pub problem: Option<String>,
+    pub limits: Vec<String>,  // e.g. cpu=1000
+    pub resources: Vec<String>,  // What the pool currently provisions
+    pub consolidation_policy: Option<String>,
+    pub weight: Option<i64>,
+    pub node_claims: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KarpenterNodeClaimInfo {
+    pub name: String,
+    pub node_pool: Option<String>,
+    pub node_name: Option<String>,  // Unset until the node registers
+    pub instance_type: Option<String>,
+    pub capacity_type: Option<String>,
+    pub zone: Option<String>,
+    pub ready: bool,
+    pub problem: Option<String>,  // First condition that isn't True, e.g. "Launched: InsufficientCapacity"
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterAutoscalerStatus {
+    pub status: String,  // Raw contents of the status ConfigMap
+    pub last_updated: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingPodInsight {
+    pub namespace: String,
+    pub name: String,
+    pub scheduler_reason: Option<String>,
+    pub scheduler_message: Option<String>,
+    pub autoscaler_reason: Option<String>,  // e.g. TriggeredScaleUp, NotTriggerScaleUp, Nominated
+    pub autoscaler_message: Option<String>,
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScalingEvent {
+    pub time: Option<String>,
+    pub source: String,
+    pub reason: String,
+    pub kind: String,
+    pub namespace: Option<String>,
+    pub name: String,
+    pub message: String,
+    pub count: i32,
+}
+
+// Node alert types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeAlert {
+    pub node: String,
+    pub kind: String,  // MemoryPressure, DiskPressure, PIDPressure, SpotInterruption, RebalanceRecommendation, ...
+    pub active: bool,  // false when a previously raised alert clears
+    pub message: String,
+    pub since: Option<String>,
+    pub affected_workloads: Vec<String>,  // namespace/Kind/name of workloads with pods on the node
+}
+
+// Addon version types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddonVersion {
+    pub name: String,
+    pub category: String,  // cni, dns, csi, ingress, certificates, service-mesh, monitoring, autoscaling
+    pub namespace: String,
+    pub workload: String,  // Kind/name
+    pub container: String,
+    pub image: String,
+    pub version: Option<String>,  // Image tag; None when pinned by digest only
+    pub latest_known: String,
+    pub status: String,  // current, outdated, newer or unknown
+}
+
+// Storage topology types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageTopology {
+    pub pods: Vec<PodStorage>,
+    pub unmounted_claims: Vec<ClaimStorage>,  // PVCs no pod in scope references
+    pub usage_available: bool,  // false when no kubelet stats could be read
+    pub nodes_without_stats: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PodStorage {
+    pub namespace: String,
+    pub name: String,
+    pub node: Option<String>,
+    pub phase: Option<String>,
+    pub volumes: Vec<PodVolumeStorage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PodVolumeStorage {
+    pub name: String,
+    pub source: String,  // persistentVolumeClaim, ephemeral, emptyDir, hostPath, csi or nfs
+    pub mount_paths: Vec<String>,  // container:path
+    pub usage: Option<VolumeUsage>,
+    pub claim: Option<ClaimStorage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaimStorage {
+    pub namespace: String,
+    pub name: String,
+    pub phase: Option<String>,
+    pub requested: Option<String>,
+    pub capacity: Option<String>,
+    pub access_modes: Vec<String>,
+    pub storage_class_name: Option<String>,
+    pub storage_class: Option<StorageClassSummary>,
+    pub volume: Option<VolumeStorage>,
+    pub usage: Option<VolumeUsage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeStorage {
+    pub name: String,
+    pub phase: Option<String>,
+    pub capacity: Option<String>,
+    pub reclaim_policy: Option<String>,
+    pub driver: Option<String>,  // CSI driver, when CSI-backed
+    pub volume_handle: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageClassSummary {
+    pub name: String,
+    pub provisioner: String,
+    pub reclaim_policy: Option<String>,
+    pub volume_binding_mode: Option<String>,
+    pub allow_volume_expansion: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeUsage {
+    pub used_bytes: Option<u64>,
+    pub capacity_bytes: Option<u64>,
+    pub available_bytes: Option<u64>,
+    pub used_percent: Option<f64>,
+}
+
+// Volume usage types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeUsageReport {
+    pub threshold_percent: f64,
+    pub volumes: Vec<VolumeUsageEntry>,  // Fullest first
+    pub containers: Vec<EphemeralStorageUsage>,
+    pub nodes_without_stats: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeUsageEntry {
+    pub namespace: String,
+    pub pod: String,
+    pub node: String,
+    pub volume: String,
+    pub claim: Option<String>,  // None for emptyDir
+    pub usage: VolumeUsage,
+    pub over_threshold: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EphemeralStorageUsage {
+    pub namespace: String,
+    pub pod: String,
+    pub node: String,
+    pub container: String,
+    pub rootfs_used_bytes: Option<u64>,  // Writable layer
+    pub logs_used_bytes: Option<u64>,
+    pub used_bytes: u64,
+    pub limit_bytes: Option<u64>,  // ephemeral-storage limit
+    pub used_percent: Option<f64>,  // Of the limit
+    pub over_threshold: bool,
+}
+
+// API server proxy types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxyResponse {
+    pub url: String,  // API server path the request went through
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub body: String,
+    pub truncated: bool,
+    pub elapsed_ms: u64,
+}
+
+// Custom column types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceTable {
+    pub kind: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    pub csv: String,  // Header row plus rows, for export
+}
+
+// Resource query types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryResult {
+    pub kind: String,
+    pub expression: String,
+    pub object_count: usize,  // Objects the expression ran over
+    pub results: Vec<serde_json::Value>,
+    pub truncated: bool,
+}
+
+// Reference search types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceReference {
+    pub kind: String,
+    pub namespace: Option<String>,  // None for cluster-scoped referrers
+    pub name: String,
+    pub via: String,  // Where the reference is, e.g. "volume data", "env DB_PASSWORD (app)", "tls"
+}
+
+// Immutable field types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImmutableFieldChange {
+    pub path: String,  // JSON pointer, e.g. /spec/selector
+    pub live: Option<serde_json::Value>,
+    pub desired: serde_json::Value,
+    pub reason: String,
+}
+
+/// Immutable fields a manifest would change on the live object
+///
+/// Also returned to the frontend as a JSON error string, like
+/// [`ResourceConflict`], when an apply needs the object recreated.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImmutableFieldReport {
+    pub error: Option<String>,  // "RecreateRequired" when requires_recreate
+    pub resource_type: String,
+    pub name: String,
+    pub requires_recreate: bool,
+    pub changes: Vec<ImmutableFieldChange>,
+    pub message: String,
+}
+
+impl std::fmt::Display for ImmutableFieldReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ImmutableFieldReport {}
+
+// Resource recreate types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceRecreateProgress {
+    pub step: String,  // retained, deleting, deleted, claim-deleted, volume-released, applying, applied or failed
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceRecreateResult {
+    pub outcome: String,  // success or failed
+    pub message: String,
+    pub preserved_claims: Vec<String>,
+    pub deleted_claims: Vec<String>,
+}
+
+// Namespace termination types
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TerminatingObject {
+    pub kind: String,
+    pub api_version: String,
+    pub name: String,
+    pub deleting: bool,  // Has a deletionTimestamp, so only finalizers keep it
+    pub finalizers: Vec<String>,
+    pub removable_finalizers: Vec<String>,  // Known safe to remove with remove_stuck_finalizer
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamespaceTerminationStatus {
+    pub namespace: String,
+    pub phase: String,  // Active or Terminating
+    pub deleting_since: Option<String>,
+    pub elapsed_secs: Option<i64>,
+    pub namespace_finalizers: Vec<String>,  // spec.finalizers, normally just "kubernetes"
+    pub conditions: Vec<String>,  // "Type: message" for conditions that are True
+    pub remaining: Vec<TerminatingObject>,
+    pub unreadable_kinds: Vec<String>,  // Kinds that couldn't be listed, e.g. an unavailable aggregated API
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamespaceTerminationResult {
+    pub outcome: String,  // deleted, failed or cancelled
+    pub message: String,
+}
+
+// Image restart types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageRestartTarget {
+    pub kind: String,  // Deployment, StatefulSet or DaemonSet
+    pub namespace: String,
+    pub name: String,
+    pub images: Vec<String>,  // Matching images in the pod template
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageRestartProgress {
+    pub kind: String,
+    pub namespace: String,
+    pub name: String,
+    pub phase: String,  // restarting, restarted or failed
+    pub workloads_done: usize,
+    pub workloads_total: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageRestartResult {
+    pub outcome: String,  // success, failed or cancelled
+    pub message: String,
+    pub restarted: Vec<String>,  // namespace/Kind/name
+    pub failed: Vec<String>,  // "namespace/Kind/name: reason"
+}
+
+// Tray types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrayStatus {
+    pub context: Option<String>,
+    pub last_namespace: Option<String>,
+    pub port_forwards: Vec<PortForwardInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrayAction {
+    pub action: String,  // switch-context, stop-forward, stop-forwards, open-namespace or show
+    pub target: Option<String>,  // Context, forward id or namespace the action applied to
+    pub error: Option<String>,
+}
+
+// Deep link types
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeepLink {
+    pub url: String,
+    pub context: String,
+    pub namespace: Option<String>,
+    pub kind: Option<String>,  // Lowercase singular, e.g. pod, deployment
+    pub name: Option<String>,
+    pub view: Option<String>,  // logs, shell, yaml, describe, events or metrics
+    pub container: Option<String>,  // From ?container=
+}
+
+// App log types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppLogEntry {
+    pub timestamp: String,  // RFC 3339, UTC
+    pub level: String,  // TRACE, DEBUG, INFO, WARN or ERROR
+    pub target: String,  // Module that logged it, e.g. kubesail_lib::portforward
+    pub message: String,
+    pub fields: Option<serde_json::Value>,  // Structured fields besides the message
+}
+
+// Pod watch types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PodWatchEvent {
+    pub event_type: String,  // added, modified, deleted, synced or error
+    pub pod: Option<PodInfo>,  // The pod for added, modified and deleted
+    pub pods: Option<Vec<PodInfo>>,  // Full list for synced, sent first and after every re-list
+    pub message: Option<String>,  // Watch error; the watch keeps retrying
+}
+
+// Resource watch types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceWatchStarted {
+    pub watch_id: String,
+    pub kind: String,  // Resolved kind, e.g. Deployment for "deployments"
+    pub api_version: String,
+    pub namespaced: bool,  // False when the watch is cluster-wide because the kind is
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceWatchEvent {
+    pub event_type: String,  // added, modified, deleted, synced or error
+    pub object: Option<serde_json::Value>,  // The object for added, modified and deleted
+    pub objects: Option<Vec<serde_json::Value>>,  // Full list for synced
+    pub message: Option<String>,  // Watch error; the watch keeps retrying
+}
+
+// API retry types
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApiRetryStats {
+    pub retried_requests: u64,  // Requests that needed at least one retry
+    pub retries: u64,
+    pub gave_up: u64,  // Requests still failing after the last retry
+    pub last_error: Option<String>,  // Status or connection error behind the latest retry
+    pub last_retry_at: Option<String>,  // RFC 3339
+}
+
+// List cache types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceListSnapshot {
+    pub context: String,
+    pub api_version: String,
+    pub kind: String,
+    pub namespace: Option<String>,  // None for all namespaces or a cluster-scoped kind
+    pub resource_version: String,  // Of the list, for revalidation
+    pub items: Vec<serde_json::Value>,
+    pub fetched_at: String,  // RFC 3339, when the list was read from the API server
+    pub from_cache: bool,  // A resource-list-updated event follows if it changed
+}
+
+// Paginated list types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListPage<T> {
+    pub items: Vec<T>,
+    pub continue_token: Option<String>,  // Pass back for the next page; None on the last one
+    pub remaining_item_count: Option<i64>,  // Estimate from the API server, when it gives one
+}
+
+// Usage statistics types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageReport {
+    pub enabled: bool,
+    pub since: Option<String>,  // RFC 3339, when collection started or was last reset
+    pub generated_at: String,
+    pub app_version: String,
+    pub os: String,
+    pub commands: Vec<CommandUsage>,  // Most used first
+    pub requests: Vec<RequestLatency>,  // Slowest (p95) first
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandUsage {
+    pub command: String,
+    pub calls: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestLatency {
+    pub operation: String,  // Method and path with names replaced, e.g. "GET /api/v1/namespaces/{namespace}/pods"
+    pub count: u64,
+    pub errors: u64,  // Failed to connect or answered with an error status
+    pub avg_ms: u64,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    pub max_ms: u64,
+}
+
+// Automation types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutomationStepResult {
+    pub index: usize,
+    pub name: String,
+    pub action: String,  // scale, wait-for-rollout, run-job, port-forward or sql
+    pub status: String,  // succeeded, failed or skipped (after an earlier failure)
+    pub message: Option<String>,  // Why the step failed
+    pub output: Option<serde_json::Value>,  // Job name, rollout message, port-forward or query result
+    pub continue_on_error: bool,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutomationRunResult {
+    pub run_id: String,
+    pub name: String,
+    pub succeeded: bool,  // No step stopped the run; failures allowed by continue_on_error don't count
+    pub steps: Vec<AutomationStepResult>,
+    pub duration_ms: u64,
 }
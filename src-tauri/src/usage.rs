@@ -0,0 +1,349 @@
+use anyhow::Result;
+use chrono::Utc;
+use k8s_openapi::http::{Method, Request, Response, Uri};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex, OnceLock};
+use std::task::{Context, Poll};
+use std::time::Instant;
+use tauri::ipc::Invoke;
+use tauri::{AppHandle, Manager, Runtime};
+use tower::{Layer, Service};
+
+use crate::types::{CommandUsage, RequestLatency, UsageReport};
+
+const USAGE_FILE: &str = "usage.json";
+
+/// Latencies kept per operation for percentiles; the oldest is overwritten beyond this
+const MAX_SAMPLES: usize = 256;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static STATS_PATH: OnceLock<PathBuf> = OnceLock::new();
+static STATS: LazyLock<Mutex<UsageStats>> = LazyLock::new(|| Mutex::new(UsageStats::default()));
+
+/// What is kept on disk: command names, API paths with names stripped, and timings
+#[derive(Default, Serialize, Deserialize)]
+struct UsageStats {
+    since: Option<String>,
+    commands: HashMap<String, u64>,
+    requests: HashMap<String, Latency>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct Latency {
+    count: u64,
+    errors: u64,
+    total_ms: u64,
+    max_ms: u64,
+    samples: Vec<u64>,
+    next: usize,  // Ring position once samples is full
+}
+
+impl Latency {
+    fn record(&mut self, ms: u64, ok: bool) {
+        self.count += 1;
+        if !ok {
+            self.errors += 1;
+        }
+        self.total_ms += ms;
+        self.max_ms = self.max_ms.max(ms);
+        if self.samples.len() < MAX_SAMPLES {
+            self.samples.push(ms);
+        } else {
+            self.samples[self.next] = ms;
+            self.next = (self.next + 1) % MAX_SAMPLES;
+        }
+    }
+
+    fn percentile(&self, p: f64) -> u64 {
+        let mut sorted = self.samples.clone();
+        sorted.sort_unstable();
+        let index = ((sorted.len().saturating_sub(1)) as f64 * p).round() as usize;
+        sorted.get(index).copied().unwrap_or(0)
+    }
+}
+
+fn stats() -> std::sync::MutexGuard<'static, UsageStats> {
+    STATS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Load the opt-in preference and whatever was collected in earlier sessions
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> Result<()> {
+    let path = app.path().app_data_dir()?.join(USAGE_FILE);
+    let _ = STATS_PATH.set(path.clone());
+
+    let enabled = crate::preferences::get_usage_stats_enabled(app)?;
+    ENABLED.store(enabled, Ordering::SeqCst);
+    if enabled {
+        if let Ok(content) = std::fs::read_to_string(&path) {
+            match serde_json::from_str(&content) {
+                Ok(loaded) => *stats() = loaded,
+                Err(e) => tracing::warn!("Ignoring unreadable usage statistics: {}", e),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Turn collection on or off; turning it off deletes what was collected
+pub fn set_enabled<R: Runtime>(app: &AppHandle<R>, enabled: bool) -> Result<()> {
+    crate::preferences::set_usage_stats_enabled(app, enabled)?;
+    ENABLED.store(enabled, Ordering::SeqCst);
+    if enabled {
+        stats().since.get_or_insert_with(|| Utc::now().to_rfc3339());
+        Ok(())
+    } else {
+        reset()
+    }
+}
+
+/// Forget everything collected so far, on disk too
+pub fn reset() -> Result<()> {
+    let mut stats = stats();
+    *stats = UsageStats::default();
+    if ENABLED.load(Ordering::SeqCst) {
+        stats.since = Some(Utc::now().to_rfc3339());
+    }
+    if let Some(path) = STATS_PATH.get() {
+        match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Write the statistics to disk; nothing is ever sent anywhere
+pub fn save() -> Result<()> {
+    let Some(path) = STATS_PATH.get() else { return Ok(()) };
+    if !ENABLED.load(Ordering::SeqCst) {
+        return Ok(());
+    }
+    let content = serde_json::to_string(&*stats())?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    // Written aside and renamed so a crash mid-write can't leave half a file
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, content)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+pub fn record_command(command: &str) {
+    if !ENABLED.load(Ordering::SeqCst) {
+        return;
+    }
+    let mut stats = stats();
+    stats.since.get_or_insert_with(|| Utc::now().to_rfc3339());
+    *stats.commands.entry(command.to_string()).or_default() += 1;
+}
+
+fn record_request(operation: String, ms: u64, ok: bool) {
+    let mut stats = stats();
+    stats.since.get_or_insert_with(|| Utc::now().to_rfc3339());
+    stats.requests.entry(operation).or_default().record(ms, ok);
+}
+
+/// Everything collected, in a form meant to be pasted into an issue
+pub fn report<R: Runtime>(app: &AppHandle<R>) -> UsageReport {
+    let stats = stats();
+
+    let mut commands: Vec<CommandUsage> = stats
+        .commands
+        .iter()
+        .map(|(command, calls)| CommandUsage {
+            command: command.clone(),
+            calls: *calls,
+        })
+        .collect();
+    commands.sort_by(|a, b| b.calls.cmp(&a.calls).then_with(|| a.command.cmp(&b.command)));
+
+    let mut requests: Vec<RequestLatency> = stats
+        .requests
+        .iter()
+        .map(|(operation, latency)| RequestLatency {
+            operation: operation.clone(),
+            count: latency.count,
+            errors: latency.errors,
+            avg_ms: latency.total_ms / latency.count.max(1),
+            p50_ms: latency.percentile(0.5),
+            p95_ms: latency.percentile(0.95),
+            max_ms: latency.max_ms,
+        })
+        .collect();
+    requests.sort_by(|a, b| b.p95_ms.cmp(&a.p95_ms).then_with(|| a.operation.cmp(&b.operation)));
+
+    UsageReport {
+        enabled: ENABLED.load(Ordering::SeqCst),
+        since: stats.since.clone(),
+        generated_at: Utc::now().to_rfc3339(),
+        app_version: app.package_info().version.to_string(),
+        os: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        commands,
+        requests,
+    }
+}
+
+/// Wrap the generated invoke handler so each command call is counted
+pub fn counted<R: Runtime>(
+    handler: impl Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        record_command(invoke.message.command());
+        handler(invoke)
+    }
+}
+
+/// API request as it is reported: the method and the path with namespaces
+/// and object names replaced, so no cluster details end up in the report
+///
+/// Watches are left out; they stay open by design and would swamp the latencies.
+fn operation_label(method: &Method, uri: &Uri) -> Option<String> {
+    if uri.query().is_some_and(|q| q.split('&').any(|pair| pair == "watch=true" || pair == "watch=1")) {
+        return None;
+    }
+
+    let segments: Vec<&str> = uri.path().trim_matches('/').split('/').collect();
+    // /api/{version} for the core group, /apis/{group}/{version} otherwise
+    let prefix = match segments.first() {
+        Some(&"api") => 2,
+        Some(&"apis") => 3,
+        _ => return Some(format!("{} {}", method, uri.path())),
+    };
+    let mut label: Vec<&str> = segments.iter().take(prefix).copied().collect();
+    let mut rest = segments.iter().skip(prefix).copied();
+
+    let mut resource = rest.next();
+    if resource == Some("namespaces") {
+        match rest.next() {
+            Some(_) => {
+                label.extend(["namespaces", "{namespace}"]);
+                resource = rest.next();
+            }
+            None => resource = Some("namespaces"),
+        }
+        // A namespace itself, rather than something in one
+        if resource.is_none() {
+            label.truncate(label.len() - 1);
+            label.push("{name}");
+        }
+    }
+    if let Some(resource) = resource {
+        label.push(resource);
+        if rest.next().is_some() {
+            label.push("{name}");
+            // A subresource (log, exec, scale, ...); anything past it is left out
+            label.extend(rest.next());
+        }
+    }
+    Some(format!("{} /{}", method, label.join("/")))
+}
+
+/// Tower layer timing every API request while collection is enabled
+///
+/// Sits outermost, so a request's time includes throttling and retries.
+pub struct UsageLayer;
+
+impl<S> Layer<S> for UsageLayer {
+    type Service = Timed<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Timed { inner }
+    }
+}
+
+#[derive(Clone)]
+pub struct Timed<S> {
+    inner: S,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for Timed<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        let operation = ENABLED
+            .load(Ordering::SeqCst)
+            .then(|| operation_label(request.method(), request.uri()))
+            .flatten();
+        let future = self.inner.call(request);
+        let Some(operation) = operation else {
+            return Box::pin(future);
+        };
+
+        let started = Instant::now();
+        Box::pin(async move {
+            let result = future.await;
+            let ok = match &result {
+                Ok(response) => !response.status().is_client_error() && !response.status().is_server_error(),
+                Err(_) => false,
+            };
+            record_request(operation, started.elapsed().as_millis() as u64, ok);
+            result
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label(method: Method, uri: &str) -> Option<String> {
+        operation_label(&method, &uri.parse().unwrap())
+    }
+
+    #[test]
+    fn test_operation_label() {
+        assert_eq!(
+            label(Method::GET, "/api/v1/namespaces/shop/pods?limit=500").as_deref(),
+            Some("GET /api/v1/namespaces/{namespace}/pods")
+        );
+        assert_eq!(
+            label(Method::GET, "/api/v1/namespaces/shop/pods/web-1/log").as_deref(),
+            Some("GET /api/v1/namespaces/{namespace}/pods/{name}/log")
+        );
+        assert_eq!(
+            label(Method::PATCH, "/apis/apps/v1/namespaces/shop/deployments/web").as_deref(),
+            Some("PATCH /apis/apps/v1/namespaces/{namespace}/deployments/{name}")
+        );
+        assert_eq!(label(Method::GET, "/api/v1/namespaces").as_deref(), Some("GET /api/v1/namespaces"));
+        assert_eq!(
+            label(Method::DELETE, "/api/v1/namespaces/shop").as_deref(),
+            Some("DELETE /api/v1/namespaces/{name}")
+        );
+        assert_eq!(label(Method::GET, "/api/v1/nodes/node-a").as_deref(), Some("GET /api/v1/nodes/{name}"));
+        // Proxy paths carry the caller's own path after the subresource
+        assert_eq!(
+            label(Method::GET, "/api/v1/namespaces/shop/services/web:80/proxy/admin/users/42").as_deref(),
+            Some("GET /api/v1/namespaces/{namespace}/services/{name}/proxy")
+        );
+        assert_eq!(label(Method::GET, "/version").as_deref(), Some("GET /version"));
+        assert_eq!(label(Method::GET, "/api/v1/pods?watch=true&resourceVersion=10"), None);
+    }
+
+    #[test]
+    fn test_latency_percentiles() {
+        let mut latency = Latency::default();
+        for ms in 1..=100 {
+            latency.record(ms, ms % 10 != 0);
+        }
+        assert_eq!(latency.errors, 10);
+        assert_eq!(latency.max_ms, 100);
+        assert_eq!(latency.percentile(0.5), 51);
+        assert_eq!(latency.percentile(0.95), 95);
+    }
+}
@@ -16,8 +16,8 @@ export function ClusterConnectionDetails({ clusterName, namespace }: ClusterConn
   const addToast = useToastStore((state) => state.addToast);
 
   const { data: connectionDetails, isLoading, error } = useQuery({
-    queryKey: ["cnpg-connection", clusterName, namespace],
-    queryFn: () => api.getCNPGClusterConnection(clusterName, namespace),
+    queryKey: ["cnpg-connection-details", clusterName, namespace, showPassword],
+    queryFn: () => api.getCNPGConnectionDetails(clusterName, namespace, showPassword),
     retry: 1,
   });
 
@@ -39,7 +39,7 @@ export function DatabaseEditor() {
     queryKey: ["cnpg-connection", selectedCluster, currentNamespace],
     queryFn: async () => {
       if (!selectedCluster || !currentNamespace) return null;
-      return await api.getCNPGClusterConnection(selectedCluster, currentNamespace);
+      return await api.getCNPGConnectionDetails(selectedCluster, currentNamespace, true);
     },
     enabled: !!selectedCluster && !!currentNamespace,
   });
@@ -33,7 +33,7 @@ export function DatabaseEditorModal({
   const { data: fetchedDetails, isLoading: detailsLoading } = useQuery({
     queryKey: ["cnpg-connection", clusterName, namespace],
     queryFn: async () => {
-      return await api.getCNPGClusterConnection(clusterName, namespace);
+      return await api.getCNPGConnectionDetails(clusterName, namespace, true);
     },
     enabled: !!clusterName && !!namespace,
   });
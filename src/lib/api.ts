@@ -401,12 +401,14 @@ export const api = {
   async applyResourceYaml(
     resourceType: string,
     namespace: string | undefined,
-    yamlContent: string
+    yamlContent: string,
+    mode?: "check" | "force" | "latest" | "recreate"
   ): Promise<void> {
     return await invoke("apply_resource_yaml", {
       resourceType,
       namespace,
       yamlContent,
+      mode,
     });
   },
 
@@ -519,8 +521,12 @@ export const api = {
   },
 
   // CloudNativePG operations
-  async getCNPGClusterConnection(clusterName: string, namespace: string): Promise<CNPGConnectionDetails> {
-    return await invoke("get_cnpg_cluster_connection", { clusterName, namespace });
+  async getCNPGConnectionDetails(
+    clusterName: string,
+    namespace: string,
+    revealPassword = false
+  ): Promise<CNPGConnectionDetails> {
+    return await invoke("get_cnpg_connection_details", { clusterName, namespace, revealPassword });
   },
 
   // Database Editor operations
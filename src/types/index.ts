@@ -496,6 +496,7 @@ export interface CNPGConnectionDetails {
 // Database Editor Types
 export interface DbConnectionInfo {
   connection_id: string;
+  context: string;
   cluster_name: string;
   namespace: string;
   database: string;